@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::usage::collect_refs;
+use crate::*;
+
+/// Identifies a schema in a [SchemaArena]. Ids of component schemas follow their order in the spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArenaId(usize);
+
+impl ArenaId {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// The component reference, for component schemas.
+    reference: Option<String>,
+    schema: Schema,
+    dependencies: Vec<ArenaId>,
+}
+
+/// Dereferenced and flattened schemas of a spec, computed once so that repeated passes
+/// (validation, code generation) don't re-resolve references or re-merge `allOf` chains.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaArena {
+    entries: Vec<Entry>,
+    /// Ids by component reference, and by canonical JSON for inline schemas.
+    ids: HashMap<String, ArenaId>,
+    policy: RecursionPolicy,
+    /// Whether each entry reaches an external reference, directly or through its dependencies.
+    reaches_external: Vec<bool>,
+    external_references: Vec<String>,
+}
+
+/// References into other documents, which the arena can't resolve.
+fn is_external(reference: &str) -> bool {
+    !reference.starts_with('#')
+}
+
+impl SchemaArena {
+    /// Builds the arena from the spec's component schemas. Recursive references are handled
+    /// per `policy`; see [Schema::flatten].
+    ///
+    /// External references, into other documents, are not resolved. Schemas which reach one are
+    /// kept as they are rather than flattened, component schemas which are one are kept as an
+    /// empty schema, and the references are listed by [SchemaArena::external_references].
+    pub fn new(spec: &OpenAPI, policy: &RecursionPolicy) -> Result<Self> {
+        let mut arena = SchemaArena { policy: *policy, ..SchemaArena::default() };
+        for name in spec.schemas.keys() {
+            let reference = ComponentKind::Schema.reference(name);
+            let id = ArenaId(arena.entries.len());
+            arena.ids.insert(reference.clone(), id);
+            arena.entries.push(Entry { reference: Some(reference), schema: Schema::new_any(), dependencies: Vec::new() });
+        }
+        let mut resolved = Vec::with_capacity(spec.schemas.len());
+        let mut has_external = Vec::with_capacity(spec.schemas.len());
+        for (i, schema) in spec.schemas.values().enumerate() {
+            if let RefOr::Reference { reference } = schema {
+                if is_external(reference) {
+                    arena.note_external(reference);
+                    resolved.push(None);
+                    has_external.push(true);
+                    continue;
+                }
+            }
+            let schema = Resolve::resolve(schema, spec)?;
+            let (dependencies, external) = arena.dependencies_of(schema)?;
+            arena.entries[i].dependencies = dependencies;
+            resolved.push(Some(schema));
+            has_external.push(external);
+        }
+        arena.reaches_external = (0..arena.entries.len())
+            .map(|i| arena.reaches(ArenaId(i), &has_external, &mut vec![false; has_external.len()]))
+            .collect();
+        for (i, schema) in resolved.into_iter().enumerate() {
+            let Some(schema) = schema else { continue };
+            arena.entries[i].schema = if arena.reaches_external[i] { schema.clone() } else { schema.flatten(spec, policy)? };
+        }
+        Ok(arena)
+    }
+
+    /// Returns true if `id` or one of its dependencies is marked in `marked`.
+    fn reaches(&self, id: ArenaId, marked: &[bool], visited: &mut Vec<bool>) -> bool {
+        if visited[id.0] {
+            return false;
+        }
+        visited[id.0] = true;
+        marked[id.0] || self.entries[id.0].dependencies.iter().any(|d| self.reaches(*d, marked, visited))
+    }
+
+    fn note_external(&mut self, reference: &str) {
+        if !self.external_references.iter().any(|r| r == reference) {
+            self.external_references.push(reference.to_string());
+        }
+    }
+
+    /// Returns the component schemas the schema references, and whether it has external references.
+    fn dependencies_of(&mut self, schema: &Schema) -> Result<(Vec<ArenaId>, bool)> {
+        let value = serde_json::to_value(schema)?;
+        let mut dependencies = Vec::new();
+        let mut external = false;
+        for (_, reference) in collect_refs(&value) {
+            if is_external(reference) {
+                self.note_external(reference);
+                external = true;
+                continue;
+            }
+            let id = *self.ids.get(reference).ok_or_else(|| anyhow!("Schema reference {} not found", reference))?;
+            if !dependencies.contains(&id) {
+                dependencies.push(id);
+            }
+        }
+        Ok((dependencies, external))
+    }
+
+    /// Returns the id of a component reference, or of an inline schema added with [SchemaArena::intern].
+    pub fn get(&self, schema: &RefOr<Schema>) -> Option<ArenaId> {
+        match schema {
+            RefOr::Reference { reference } => self.ids.get(reference).copied(),
+            RefOr::Item(item) => self.ids.get(&serde_json::to_string(item).ok()?).copied(),
+        }
+    }
+
+    /// Returns the id of the schema, adding inline schemas to the arena as needed.
+    /// Equal inline schemas share an id.
+    pub fn intern(&mut self, schema: &RefOr<Schema>, spec: &OpenAPI) -> Result<ArenaId> {
+        if let Some(id) = self.get(schema) {
+            return Ok(id);
+        }
+        let item = match schema {
+            RefOr::Reference { reference } => return Err(anyhow!("Schema reference {} not found", reference)),
+            RefOr::Item(item) => item,
+        };
+        let id = ArenaId(self.entries.len());
+        let (dependencies, external) = self.dependencies_of(item)?;
+        let reaches_external = external || dependencies.iter().any(|d| self.reaches_external[d.0]);
+        let schema = if reaches_external { item.clone() } else { item.flatten(spec, &self.policy)? };
+        self.entries.push(Entry { reference: None, schema, dependencies });
+        self.reaches_external.push(reaches_external);
+        self.ids.insert(serde_json::to_string(item)?, id);
+        Ok(id)
+    }
+
+    /// The dereferenced, flattened schema, or the schema as is if it reaches an external reference.
+    pub fn schema(&self, id: ArenaId) -> &Schema {
+        &self.entries[id.0].schema
+    }
+
+    /// The component reference of the schema, or [None] for inline schemas.
+    pub fn reference(&self, id: ArenaId) -> Option<&str> {
+        self.entries[id.0].reference.as_deref()
+    }
+
+    /// The component schemas the schema references directly.
+    pub fn dependencies(&self, id: ArenaId) -> &[ArenaId] {
+        &self.entries[id.0].dependencies
+    }
+
+    /// The external references found in the schemas, in the order they were found.
+    pub fn external_references(&self) -> &[String] {
+        &self.external_references
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the schemas in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item=(ArenaId, &Schema)> {
+        self.entries.iter().enumerate().map(|(i, e)| (ArenaId(i), &e.schema))
+    }
+
+    /// Iterates over the schemas so that each comes after its dependencies.
+    /// Within a reference cycle, the schema reached first comes last.
+    pub fn iter_dependency_order(&self) -> impl Iterator<Item=(ArenaId, &Schema)> {
+        fn visit(arena: &SchemaArena, id: ArenaId, visited: &mut Vec<bool>, order: &mut Vec<ArenaId>) {
+            if visited[id.0] {
+                return;
+            }
+            visited[id.0] = true;
+            for dependency in arena.dependencies(id) {
+                visit(arena, *dependency, visited, order);
+            }
+            order.push(id);
+        }
+        let mut visited = vec![false; self.entries.len()];
+        let mut order = Vec::with_capacity(self.entries.len());
+        for i in 0..self.entries.len() {
+            visit(self, ArenaId(i), &mut visited, &mut order);
+        }
+        order.into_iter().map(move |id| (id, self.schema(id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_arena() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_all_of(vec![RefOr::schema_ref("Named"), RefOr::Item(Schema::new_object())]);
+        pet.title = Some("Pet".to_string());
+        spec.schemas.insert("Pet", pet);
+        let mut named = Schema::new_object();
+        named.properties_mut().insert("name", Schema::new_string());
+        named.properties_mut().insert("parent", RefOr::schema_ref("Named"));
+        spec.schemas.insert("Named", named);
+
+        let mut arena = SchemaArena::new(&spec, &RecursionPolicy::default()).unwrap();
+        let pet = arena.get(&RefOr::schema_ref("Pet")).unwrap();
+        let named = arena.get(&RefOr::schema_ref("Named")).unwrap();
+        assert!(arena.schema(pet).properties().contains_key("name"));
+        assert_eq!(arena.dependencies(pet), &[named]);
+        assert_eq!(arena.iter_dependency_order().map(|(id, _)| id).collect::<Vec<_>>(), vec![named, pet]);
+
+        let inline = RefOr::Item(Schema::new_array(RefOr::schema_ref("Pet")));
+        assert_eq!(arena.get(&inline), None);
+        let id = arena.intern(&inline, &spec).unwrap();
+        assert_eq!(arena.intern(&inline, &spec).unwrap(), id);
+        assert_eq!(arena.reference(id), None);
+        assert_eq!(arena.dependencies(id), &[pet]);
+        assert_eq!(arena.len(), 3);
+        assert!(arena.external_references().is_empty());
+    }
+
+    #[test]
+    fn test_external_and_escaped_references() {
+        let mut spec = OpenAPI::default();
+        spec.schemas.insert("Remote", RefOr::ref_("common.yaml#/Remote"));
+        spec.schemas.insert("Owner", Schema::new_all_of(vec![RefOr::ref_("common.yaml#/Person"), RefOr::Item(Schema::new_object())]));
+        spec.schemas.insert("Pet", Schema::new_all_of(vec![RefOr::schema_ref("Owner"), RefOr::Item(Schema::new_object())]));
+        spec.schemas.insert("a/b", Schema::new_string());
+        spec.schemas.insert("Tag", Schema::new_array(RefOr::ref_("#/components/schemas/a~1b")));
+
+        let arena = SchemaArena::new(&spec, &RecursionPolicy::default()).unwrap();
+        assert_eq!(arena.external_references(), &["common.yaml#/Remote", "common.yaml#/Person"]);
+        let pet = arena.get(&RefOr::schema_ref("Pet")).unwrap();
+        assert!(matches!(arena.schema(pet).kind, SchemaKind::AllOf { .. }));
+        let tag = arena.get(&RefOr::schema_ref("Tag")).unwrap();
+        let escaped = arena.get(&RefOr::ref_("#/components/schemas/a~1b")).unwrap();
+        assert_eq!(arena.dependencies(tag), &[escaped]);
+        assert_eq!(arena.reference(escaped), Some("#/components/schemas/a~1b"));
+    }
+}
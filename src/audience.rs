@@ -0,0 +1,268 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use crate::feature_flags::retain_properties;
+use crate::*;
+use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key};
+
+/// Extension restricting an operation, schema or property to audiences:
+/// either an audience name or a list of them.
+pub const AUDIENCE_EXTENSION: &str = "x-audience";
+
+/// Suffix of the schema extension mapping enum values to audiences,
+/// e.g. `x-audience-enum: {"refunded": ["internal"]}`.
+pub const ENUM_AUDIENCE_SUFFIX: &str = "-enum";
+
+fn is_visible(audiences: Option<&Value>, audience: &str) -> bool {
+    match audiences {
+        Some(Value::String(a)) => a == audience,
+        Some(Value::Array(any)) => any.iter().any(|a| a.as_str() == Some(audience)),
+        _ => true,
+    }
+}
+
+fn enum_key(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Removes enum values hidden from the audience from every schema in the JSON document, then
+/// the `key` and `<key>-enum` extensions, which would tell the audience what was hidden.
+fn retain_enum_values(value: &mut Value, key: &str, enum_key_name: &str, audience: &str, is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                if let Some(Value::Object(audiences)) = map.remove(enum_key_name) {
+                    if let Some(Value::Array(values)) = map.get_mut("enum") {
+                        values.retain(|v| is_visible(audiences.get(&enum_key(v)), audience));
+                    }
+                }
+                map.remove(key);
+            }
+            for (field, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(field, child) {
+                    continue;
+                }
+                retain_enum_values(child, key, enum_key_name, audience, !is_name_map && is_name_map_key(field));
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| retain_enum_values(item, key, enum_key_name, audience, false)),
+        _ => {}
+    }
+}
+
+fn is_hidden_ref(schema: &Value, hidden: &[String]) -> bool {
+    schema.get("$ref").and_then(|r| r.as_str()).is_some_and(|r| hidden.iter().any(|h| h == r))
+}
+
+/// Whether the schema references a hidden schema anywhere, e.g. as array items or in an allOf.
+fn references_any(schema: &Value, hidden: &[String], is_name_map: bool) -> bool {
+    match schema {
+        Value::Object(map) => (!is_name_map && is_hidden_ref(schema, hidden)) || map.iter()
+            .filter(|(key, child)| is_name_map || !is_data_field(key, child))
+            .any(|(key, child)| references_any(child, hidden, !is_name_map && is_name_map_key(key))),
+        Value::Array(items) => items.iter().any(|item| references_any(item, hidden, false)),
+        _ => false,
+    }
+}
+
+/// Removes hidden schemas from allOf, oneOf and anyOf lists, and lists left empty, as well as
+/// discriminator mappings to them.
+fn retain_alternatives(value: &mut Value, hidden: &[String], is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                if let Some(Value::Object(mapping)) = map.get_mut("discriminator").and_then(|d| d.get_mut("mapping")) {
+                    // Mappings hold either a reference or a schema name.
+                    mapping.retain(|_, target| !target.as_str().is_some_and(|target| hidden.iter()
+                        .any(|h| h == target || ComponentKind::Schema.reference(target) == *h)));
+                }
+                for key in ["allOf", "oneOf", "anyOf"] {
+                    if let Some(Value::Array(schemas)) = map.get_mut(key) {
+                        schemas.retain(|s| !is_hidden_ref(s, hidden));
+                        if schemas.is_empty() {
+                            map.remove(key);
+                        }
+                    }
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                retain_alternatives(child, hidden, !is_name_map && is_name_map_key(key));
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| retain_alternatives(item, hidden, false)),
+        _ => {}
+    }
+}
+
+/// Collects the pointers of references to hidden schemas.
+fn find_dangling(value: &Value, hidden: &[String], pointer: &str, is_name_map: bool, dangling: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map && is_hidden_ref(value, hidden) {
+                dangling.push(pointer.to_string());
+            }
+            for (key, child) in map.iter().filter(|(key, child)| is_name_map || !is_data_field(key, child)) {
+                let names = !is_name_map && is_name_map_key(key);
+                find_dangling(child, hidden, &format!("{}/{}", pointer, escape_pointer_segment(key)), names, dangling);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                find_dangling(item, hidden, &format!("{}/{}", pointer, i), false, dangling);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl OpenAPI {
+    /// Returns the view of this spec for an audience, using the [AUDIENCE_EXTENSION] key.
+    /// See [OpenAPI::for_audience_with].
+    pub fn for_audience(&self, audience: &str) -> Result<OpenAPI> {
+        self.for_audience_with(AUDIENCE_EXTENSION, audience)
+    }
+
+    /// Returns the view of this spec for an audience, where the extension `key` lists the audiences
+    /// an element is visible to. Elements without the extension are visible to everyone.
+    ///
+    /// Hidden operations and component schemas are removed, as are hidden properties, properties
+    /// referencing hidden schemas, hidden schemas in allOf, oneOf and anyOf lists, and discriminator
+    /// mappings to hidden schemas. Enum values
+    /// are hidden through the `<key>-enum` extension on their schema. Components left unused are pruned.
+    /// The `key` and `<key>-enum` extensions are removed from the view.
+    ///
+    /// Fails if a hidden schema is still referenced elsewhere, e.g. as the body of a visible operation.
+    pub fn for_audience_with(&self, key: &str, audience: &str) -> Result<OpenAPI> {
+        let mut spec = self.clone();
+        let before = spec.reachable_components();
+        spec.remove_operations_where(|op| !is_visible(op.extensions.get(key), audience));
+        let hidden: Vec<String> = spec.schemas.iter()
+            .filter(|(_, s)| s.as_item().is_some_and(|s| !is_visible(s.extensions.get(key), audience)))
+            .map(|(name, _)| ComponentKind::Schema.reference(name))
+            .collect();
+        spec.schemas.retain(|name, _| !hidden.contains(&ComponentKind::Schema.reference(name)));
+        let enum_key_name = format!("{}{}", key, ENUM_AUDIENCE_SUFFIX);
+        spec.edit_json(|doc| {
+            // Alternatives go first, so that a property composed of hidden and visible schemas stays.
+            retain_alternatives(doc, &hidden, false);
+            retain_properties(doc, &|schema| is_visible(schema.get(key), audience) && !references_any(schema, &hidden, false), false);
+            retain_enum_values(doc, key, &enum_key_name, audience, false);
+        });
+        spec.prune_newly_unreferenced(before);
+        let mut dangling = Vec::new();
+        find_dangling(&serde_json::to_value(&spec)?, &hidden, "#", false, &mut dangling);
+        if !dangling.is_empty() {
+            bail!("Schemas hidden from {} are still referenced at {}", audience, dangling.join(", "));
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_for_audience() {
+        let mut spec = OpenAPI::default();
+        let mut audit = Schema::new_object();
+        audit.extensions.insert(AUDIENCE_EXTENSION.to_string(), json!("internal"));
+        spec.schemas.insert("AuditLog", audit);
+
+        let mut status = Schema::new_str_enum(vec!["paid".to_string(), "refunded".to_string()]);
+        status.extensions.insert("x-audience-enum".to_string(), json!({"refunded": ["internal", "support"]}));
+        let mut order = Schema::new_object();
+        order.properties_mut().insert("status", status);
+        order.properties_mut().insert("audit", RefOr::schema_ref("AuditLog"));
+        let mut cost = Schema::new_number();
+        cost.extensions.insert(AUDIENCE_EXTENSION.to_string(), json!(["internal"]));
+        order.properties_mut().insert("cost", cost);
+        spec.schemas.insert("Order", order);
+
+        let mut get = Operation::default();
+        get.add_response_success_json(Some(RefOr::schema_ref("Order")));
+        spec.add_operation(PathMethod::GET, "/orders", get).unwrap();
+        let mut admin = Operation::default();
+        admin.extensions.insert(AUDIENCE_EXTENSION.to_string(), json!("internal"));
+        spec.add_operation(PathMethod::DELETE, "/orders", admin).unwrap();
+
+        let mut base = Schema::new_object();
+        base.properties_mut().insert("id", Schema::new_string());
+        spec.schemas.insert("Base", base);
+        let mut internal_fields = Schema::new_object();
+        internal_fields.extensions.insert(AUDIENCE_EXTENSION.to_string(), json!("internal"));
+        spec.schemas.insert("InternalFields", internal_fields);
+        spec.schemas.insert("Invoice", Schema::new_all_of(vec![RefOr::schema_ref("Base"), RefOr::schema_ref("InternalFields")]));
+        let mut details = Schema::new_one_of(vec![RefOr::schema_ref("Base"), RefOr::schema_ref("InternalFields")]);
+        let mut discriminator = Discriminator { property_name: "kind".to_string(), ..Discriminator::default() };
+        discriminator.mapping.insert("base".to_string(), "Base".to_string());
+        discriminator.mapping.insert("internal".to_string(), "#/components/schemas/InternalFields".to_string());
+        discriminator.mapping.insert("internal-name".to_string(), "InternalFields".to_string());
+        details.discriminator = Some(discriminator);
+        let mut payment = Schema::new_object();
+        payment.properties_mut().insert("details", details);
+        spec.schemas.insert("Payment", payment);
+        let mut invoices = Operation::default();
+        invoices.add_response_success_json(Some(RefOr::schema_ref("Payment")));
+        invoices.add_response_success_json(Some(RefOr::schema_ref("Invoice")));
+        spec.add_operation(PathMethod::GET, "/invoices", invoices).unwrap();
+
+        // Names which are also data fields, such as the default response or a schema named value,
+        // and example data which looks like an audience-scoped enum.
+        let mut error = Schema::new_object();
+        let mut trace = Schema::new_string();
+        trace.extensions.insert(AUDIENCE_EXTENSION.to_string(), json!("internal"));
+        error.properties_mut().insert("trace", trace.clone());
+        let mut fallible = Operation::default();
+        fallible.responses.default = Some(Response::new("Error").json(error).into());
+        spec.add_operation(PathMethod::POST, "/orders", fallible).unwrap();
+        let mut value = Schema::new_object();
+        value.properties_mut().insert("raw", trace);
+        value.example = Some(json!({"enum": ["paid", "refunded"], "x-audience-enum": {"refunded": ["internal"]}}));
+        spec.schemas.insert("value", value);
+
+        let public = spec.for_audience("public").unwrap();
+        assert!(!serde_json::to_string(&public).unwrap().contains("\"x-audience\""));
+        let default = public.paths.get("/orders").unwrap().as_item().unwrap().post.as_ref().unwrap().responses.default.as_ref().unwrap();
+        let error = default.as_item().unwrap().json_schema().unwrap().as_item().unwrap();
+        assert!(error.get_properties().unwrap().is_empty());
+        let value = public.schemas.get2("value").unwrap();
+        assert!(value.get_properties().unwrap().is_empty());
+        assert_eq!(value.example, spec.schemas.get2("value").unwrap().example);
+
+        let item = public.paths.get("/orders").unwrap().as_item().unwrap();
+        assert!(item.get.is_some() && item.delete.is_none());
+        assert!(public.schemas.get("AuditLog").is_none());
+        let order = public.schemas.get2("Order").unwrap();
+        assert_eq!(order.get_properties().unwrap().keys().collect::<Vec<_>>(), vec!["status"]);
+        let status = order.get_properties().unwrap().get2("status").unwrap();
+        assert_matches::assert_matches!(&status.kind, SchemaKind::Type(Type::String(s)) if s.enumeration == vec!["paid".to_string()]);
+        assert!(status.extensions.is_empty());
+
+        let invoice = serde_json::to_value(public.schemas.get2("Invoice").unwrap()).unwrap();
+        assert_eq!(invoice, json!({"allOf": [{"$ref": "#/components/schemas/Base"}]}));
+        let payment = serde_json::to_value(public.schemas.get2("Payment").unwrap()).unwrap();
+        assert_eq!(payment["properties"]["details"]["oneOf"], json!([{"$ref": "#/components/schemas/Base"}]));
+        assert_eq!(payment["properties"]["details"]["discriminator"]["mapping"], json!({"base": "Base"}));
+        let internal = spec.for_audience("internal").unwrap();
+        assert_eq!(internal.operations().count(), spec.operations().count());
+        assert_eq!(internal.schemas.len(), spec.schemas.len());
+        assert!(internal.schemas.get2("Order").unwrap().get_properties().unwrap().contains_key("cost"));
+        assert!(!serde_json::to_string(&internal.schemas).unwrap().contains("x-audience\""));
+
+        // A visible operation can't return a hidden schema.
+        let mut audits = Operation::default();
+        audits.add_response_success_json(Some(RefOr::schema_ref("AuditLog")));
+        spec.add_operation(PathMethod::GET, "/audits", audits).unwrap();
+        let error = spec.for_audience("public").unwrap_err().to_string();
+        assert!(error.contains("#/paths/~1audits/get/responses/200/content/application~1json/schema"), "{}", error);
+    }
+}
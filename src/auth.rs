@@ -0,0 +1,221 @@
+use anyhow::{bail, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use crate::*;
+use crate::client::RequestTemplate;
+use crate::util::percent_encode;
+
+/// Credentials to attach to a request with [SecurityScheme::apply].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// For `apiKey` schemes.
+    ApiKey(String),
+    /// For `http` schemes with `scheme: basic`.
+    Basic { username: String, password: String },
+    /// An access token, for `http` schemes with `scheme: bearer`, `oauth2` and `openIdConnect`.
+    Bearer(String),
+}
+
+/// The OAuth2 flows, in the order [OAuth2Flows::preferred] picks them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuth2FlowKind {
+    AuthorizationCode,
+    ClientCredentials,
+    Password,
+    Implicit,
+}
+
+impl OAuth2Flows {
+    /// The flow a client should use to obtain a token: authorization code if defined, then client
+    /// credentials, password, and implicit last, since it is deprecated.
+    pub fn preferred(&self) -> Option<OAuth2FlowKind> {
+        if self.authorization_code.is_some() {
+            Some(OAuth2FlowKind::AuthorizationCode)
+        } else if self.client_credentials.is_some() {
+            Some(OAuth2FlowKind::ClientCredentials)
+        } else if self.password.is_some() {
+            Some(OAuth2FlowKind::Password)
+        } else if self.implicit.is_some() {
+            Some(OAuth2FlowKind::Implicit)
+        } else {
+            None
+        }
+    }
+}
+
+/// The parts of a request which security schemes add to. Add them to a request with
+/// [RequestParts::apply_to] or [RequestParts::apply_to_http].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestParts {
+    pub headers: Vec<(String, String)>,
+    /// Unencoded query parameters.
+    pub query: Vec<(String, String)>,
+    pub cookies: Vec<(String, String)>,
+    /// For `oauth2` schemes, the flow the token should come from.
+    pub oauth2_flow: Option<OAuth2FlowKind>,
+}
+
+impl RequestParts {
+    /// Adds these parts to a request built by [RequestTemplate::from_operation]: headers are
+    /// appended, cookies are added to its `Cookie` header, and query parameters are encoded and
+    /// appended to its URL.
+    pub fn apply_to(&self, request: &mut RequestTemplate) {
+        request.headers.extend(self.headers.iter().cloned());
+        if let Some(cookies) = self.cookie_header() {
+            match request.headers.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case("cookie")) {
+                Some((_, existing)) => *existing = format!("{}; {}", existing, cookies),
+                None => request.headers.push(("Cookie".to_string(), cookies)),
+            }
+        }
+        request.url = append_query(&request.url, &self.query);
+    }
+
+    /// Adds these parts to the head of an [http::Request], as [RequestParts::apply_to] does.
+    /// Fails if a header name or value isn't valid, or the URI with the query isn't.
+    pub fn apply_to_http(&self, parts: &mut http::request::Parts) -> Result<()> {
+        for (name, value) in &self.headers {
+            parts.headers.append(http::HeaderName::from_bytes(name.as_bytes())?, http::HeaderValue::from_str(value)?);
+        }
+        if let Some(cookies) = self.cookie_header() {
+            let cookies = match parts.headers.get(http::header::COOKIE) {
+                Some(existing) => format!("{}; {}", existing.to_str()?, cookies),
+                None => cookies,
+            };
+            parts.headers.insert(http::header::COOKIE, http::HeaderValue::from_str(&cookies)?);
+        }
+        if !self.query.is_empty() {
+            parts.uri = append_query(&parts.uri.to_string(), &self.query).parse()?;
+        }
+        Ok(())
+    }
+
+    fn cookie_header(&self) -> Option<String> {
+        if self.cookies.is_empty() {
+            return None;
+        }
+        Some(self.cookies.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join("; "))
+    }
+}
+
+fn append_query(url: &str, query: &[(String, String)]) -> String {
+    if query.is_empty() {
+        return url.to_string();
+    }
+    let pairs: Vec<String> = query.iter()
+        .map(|(name, value)| format!("{}={}", percent_encode(name, false), percent_encode(value, false)))
+        .collect();
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", url, separator, pairs.join("&"))
+}
+
+impl SecurityScheme {
+    /// Adds the credentials to the request where the scheme expects them: an API key goes into
+    /// its header, query parameter or cookie, and tokens and passwords into an `Authorization` header.
+    ///
+    /// Fails if the credentials don't suit the scheme, for `http` schemes other than basic and bearer,
+    /// or for a basic username containing `:`, which can't be told apart from the password.
+    pub fn apply(&self, credentials: &Credentials, req: &mut RequestParts) -> Result<()> {
+        match (self, credentials) {
+            (SecurityScheme::APIKey { location, name, .. }, Credentials::ApiKey(key)) => {
+                let target = match location {
+                    APIKeyLocation::Header => &mut req.headers,
+                    APIKeyLocation::Query => &mut req.query,
+                    APIKeyLocation::Cookie => &mut req.cookies,
+                };
+                target.push((name.clone(), key.clone()));
+            }
+            (SecurityScheme::HTTP { scheme, .. }, _) => {
+                let value = match credentials {
+                    Credentials::Basic { username, .. } if username.contains(':') => bail!("Basic authentication usernames cannot contain ':'"),
+                    Credentials::Basic { username, password } if scheme.eq_ignore_ascii_case("basic") => {
+                        format!("Basic {}", BASE64.encode(format!("{}:{}", username, password)))
+                    }
+                    Credentials::Bearer(token) if scheme.eq_ignore_ascii_case("bearer") => format!("Bearer {}", token),
+                    _ if !["basic", "bearer"].iter().any(|s| scheme.eq_ignore_ascii_case(s)) => bail!("Unsupported HTTP authentication scheme {}", scheme),
+                    _ => bail!("{} authentication needs {} credentials", scheme, scheme.to_lowercase()),
+                };
+                req.headers.push(("Authorization".to_string(), value));
+            }
+            (SecurityScheme::OAuth2 { flows, .. }, Credentials::Bearer(token)) => {
+                req.oauth2_flow = flows.preferred();
+                req.headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+            }
+            (SecurityScheme::OpenIDConnect { .. }, Credentials::Bearer(token)) => {
+                req.headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+            }
+            (SecurityScheme::APIKey { .. }, _) => bail!("API key security schemes need API key credentials"),
+            _ => bail!("OAuth2 and OpenID Connect security schemes need bearer credentials"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(scheme: &str) -> SecurityScheme {
+        SecurityScheme::HTTP { scheme: scheme.to_string(), bearer_format: None, description: None, extensions: IndexMap::new() }
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut req = RequestParts::default();
+        let api_key = SecurityScheme::APIKey { location: APIKeyLocation::Query, name: "key".to_string(), description: None, extensions: IndexMap::new() };
+        api_key.apply(&Credentials::ApiKey("k1".to_string()), &mut req).unwrap();
+        let basic = Credentials::Basic { username: "Aladdin".to_string(), password: "open sesame".to_string() };
+        http("Basic").apply(&basic, &mut req).unwrap();
+        let flows = OAuth2Flows {
+            implicit: Some(ImplicitOAuth2Flow { authorization_url: "https://example.com/auth".to_string(), refresh_url: None, scopes: IndexMap::new() }),
+            client_credentials: Some(OAuth2Flow { refresh_url: None, token_url: "https://example.com/token".to_string(), scopes: IndexMap::new() }),
+            ..OAuth2Flows::default()
+        };
+        let oauth2 = SecurityScheme::OAuth2 { flows, description: None, extensions: IndexMap::new() };
+        oauth2.apply(&Credentials::Bearer("t".to_string()), &mut req).unwrap();
+        assert_eq!(req, RequestParts {
+            headers: vec![
+                ("Authorization".to_string(), "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".to_string()),
+                ("Authorization".to_string(), "Bearer t".to_string()),
+            ],
+            query: vec![("key".to_string(), "k1".to_string())],
+            cookies: vec![],
+            oauth2_flow: Some(OAuth2FlowKind::ClientCredentials),
+        });
+
+        assert_eq!(http("bearer").apply(&basic, &mut req).unwrap_err().to_string(), "bearer authentication needs bearer credentials");
+        assert_eq!(http("digest").apply(&basic, &mut req).unwrap_err().to_string(), "Unsupported HTTP authentication scheme digest");
+        assert!(api_key.apply(&Credentials::Bearer("t".to_string()), &mut req).is_err());
+        let colon = Credentials::Basic { username: "a:b".to_string(), password: "c".to_string() };
+        assert!(http("basic").apply(&colon, &mut req).is_err());
+    }
+
+    #[test]
+    fn test_apply_to_request() {
+        let mut req = RequestParts::default();
+        let cookie = SecurityScheme::APIKey { location: APIKeyLocation::Cookie, name: "session".to_string(), description: None, extensions: IndexMap::new() };
+        cookie.apply(&Credentials::ApiKey("s1".to_string()), &mut req).unwrap();
+        let query = SecurityScheme::APIKey { location: APIKeyLocation::Query, name: "api key".to_string(), description: None, extensions: IndexMap::new() };
+        query.apply(&Credentials::ApiKey("k&1".to_string()), &mut req).unwrap();
+        http("bearer").apply(&Credentials::Bearer("t".to_string()), &mut req).unwrap();
+
+        let mut template = RequestTemplate {
+            method: PathMethod::GET,
+            url: "https://api.example.com/pets?limit=1".to_string(),
+            headers: vec![("Cookie".to_string(), "theme=dark".to_string())],
+            body: None,
+        };
+        req.apply_to(&mut template);
+        assert_eq!(template.url, "https://api.example.com/pets?limit=1&api%20key=k%261");
+        assert_eq!(template.headers, vec![
+            ("Cookie".to_string(), "theme=dark; session=s1".to_string()),
+            ("Authorization".to_string(), "Bearer t".to_string()),
+        ]);
+
+        let (mut parts, ()) = http::Request::get("https://api.example.com/pets").body(()).unwrap().into_parts();
+        req.apply_to_http(&mut parts).unwrap();
+        assert_eq!(parts.uri, "https://api.example.com/pets?api%20key=k%261");
+        assert_eq!(parts.headers["authorization"], "Bearer t");
+        assert_eq!(parts.headers["cookie"], "session=s1");
+    }
+}
@@ -0,0 +1,235 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{read_extension, write_extension};
+
+const INTEGRATION_EXTENSION: &str = "x-amazon-apigateway-integration";
+const AUTHORIZER_EXTENSION: &str = "x-amazon-apigateway-authorizer";
+const AUTH_TYPE_EXTENSION: &str = "x-amazon-apigateway-authtype";
+const REQUEST_VALIDATORS_EXTENSION: &str = "x-amazon-apigateway-request-validators";
+const REQUEST_VALIDATOR_EXTENSION: &str = "x-amazon-apigateway-request-validator";
+
+/// The backend type of an [AwsIntegration].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AwsIntegrationType {
+    Aws,
+    AwsProxy,
+    Http,
+    HttpProxy,
+    Mock,
+}
+
+/// The `x-amazon-apigateway-integration` extension of an [Operation]. Fields not modeled
+/// here are kept in `other`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsIntegration {
+    #[serde(rename = "type")]
+    pub typ: AwsIntegrationType,
+    /// The backend endpoint, e.g. a Lambda invocation ARN or an HTTP URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// The method used to call the backend, which may differ from the operation's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passthrough_behavior: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_in_millis: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_format_version: Option<String>,
+    /// Maps backend request parameters to their source, e.g. `integration.request.header.x-id`
+    /// to `method.request.path.id`.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub request_parameters: IndexMap<String, String>,
+    /// Mapping templates by content type.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub request_templates: IndexMap<String, String>,
+    /// Integration responses, keyed by a regex matched against the backend status or error.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub responses: IndexMap<String, AwsIntegrationResponse>,
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+impl AwsIntegration {
+    pub fn new(typ: AwsIntegrationType, uri: impl Into<String>) -> Self {
+        AwsIntegration {
+            typ,
+            uri: Some(uri.into()),
+            http_method: None,
+            credentials: None,
+            connection_type: None,
+            connection_id: None,
+            passthrough_behavior: None,
+            timeout_in_millis: None,
+            payload_format_version: None,
+            request_parameters: IndexMap::new(),
+            request_templates: IndexMap::new(),
+            responses: IndexMap::new(),
+            other: IndexMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsIntegrationResponse {
+    pub status_code: String,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub response_parameters: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub response_templates: IndexMap<String, String>,
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+/// The `x-amazon-apigateway-authorizer` extension of a [SecurityScheme]. Fields not modeled
+/// here are kept in `other`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsAuthorizer {
+    /// `token`, `request`, `jwt` or `cognito_user_pools`.
+    #[serde(rename = "type")]
+    pub typ: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorizer_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorizer_credentials: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorizer_result_ttl_in_seconds: Option<u64>,
+    #[serde(default, rename = "providerARNs", skip_serializing_if = "Vec::is_empty")]
+    pub provider_arns: Vec<String>,
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+/// An entry of `x-amazon-apigateway-request-validators`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsRequestValidator {
+    #[serde(default)]
+    pub validate_request_body: bool,
+    #[serde(default)]
+    pub validate_request_parameters: bool,
+}
+
+impl Operation {
+    /// Returns the `x-amazon-apigateway-integration` of this operation, if it exists and is well-formed.
+    pub fn aws_integration(&self) -> Option<AwsIntegration> {
+        read_extension(&self.extensions, INTEGRATION_EXTENSION)?.ok()
+    }
+
+    pub fn set_aws_integration(&mut self, integration: &AwsIntegration) {
+        write_extension(&mut self.extensions, INTEGRATION_EXTENSION, integration)
+    }
+
+    /// Returns the `x-amazon-apigateway-request-validator` name of this operation.
+    pub fn aws_request_validator(&self) -> Option<String> {
+        read_extension(&self.extensions, REQUEST_VALIDATOR_EXTENSION)?.ok()
+    }
+
+    pub fn set_aws_request_validator(&mut self, name: &str) {
+        write_extension(&mut self.extensions, REQUEST_VALIDATOR_EXTENSION, name)
+    }
+}
+
+impl SecurityScheme {
+    /// Returns the `x-amazon-apigateway-authorizer` of this scheme, if it exists and is well-formed.
+    pub fn aws_authorizer(&self) -> Option<AwsAuthorizer> {
+        read_extension(self.extensions(), AUTHORIZER_EXTENSION)?.ok()
+    }
+
+    pub fn set_aws_authorizer(&mut self, authorizer: &AwsAuthorizer) {
+        write_extension(self.extensions_mut(), AUTHORIZER_EXTENSION, authorizer)
+    }
+
+    /// Returns the `x-amazon-apigateway-authtype` of this scheme, e.g. `custom` or `awsSigv4`.
+    pub fn aws_auth_type(&self) -> Option<String> {
+        read_extension(self.extensions(), AUTH_TYPE_EXTENSION)?.ok()
+    }
+
+    pub fn set_aws_auth_type(&mut self, auth_type: &str) {
+        write_extension(self.extensions_mut(), AUTH_TYPE_EXTENSION, auth_type)
+    }
+}
+
+impl OpenAPI {
+    /// Returns the `x-amazon-apigateway-request-validators` of this document. Empty if missing or malformed.
+    pub fn aws_request_validators(&self) -> IndexMap<String, AwsRequestValidator> {
+        read_extension(&self.extensions, REQUEST_VALIDATORS_EXTENSION).and_then(|r| r.ok()).unwrap_or_default()
+    }
+
+    pub fn set_aws_request_validators(&mut self, validators: &IndexMap<String, AwsRequestValidator>) {
+        write_extension(&mut self.extensions, REQUEST_VALIDATORS_EXTENSION, validators)
+    }
+
+    /// Returns the document's default `x-amazon-apigateway-request-validator` name.
+    pub fn aws_request_validator(&self) -> Option<String> {
+        read_extension(&self.extensions, REQUEST_VALIDATOR_EXTENSION)?.ok()
+    }
+
+    /// Returns the request validator which applies to the operation: its own, else the document's.
+    pub fn aws_request_validator_for(&self, operation: &Operation) -> Option<AwsRequestValidator> {
+        let name = operation.aws_request_validator().or_else(|| self.aws_request_validator())?;
+        self.aws_request_validators().swap_remove(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aws_extensions() {
+        let spec: OpenAPI = serde_yaml::from_str(r#"
+openapi: 3.0.0
+info: { title: Test, version: "1" }
+x-amazon-apigateway-request-validators:
+  all: { validateRequestBody: true, validateRequestParameters: true }
+x-amazon-apigateway-request-validator: all
+paths:
+  /pets:
+    get:
+      responses: {}
+      x-amazon-apigateway-integration:
+        type: aws_proxy
+        httpMethod: POST
+        uri: arn:aws:apigateway:us-east-1:lambda:path/functions/pets/invocations
+        contentHandling: CONVERT_TO_TEXT
+components:
+  securitySchemes:
+    lambda:
+      type: apiKey
+      name: Authorization
+      in: header
+      x-amazon-apigateway-authtype: custom
+      x-amazon-apigateway-authorizer:
+        type: token
+        authorizerUri: arn:aws:lambda:auth
+        authorizerResultTtlInSeconds: 300
+"#).unwrap();
+        let op = spec.paths.get("/pets").unwrap().as_item().unwrap().get.as_ref().unwrap();
+        let integration = op.aws_integration().unwrap();
+        assert_eq!(integration.typ, AwsIntegrationType::AwsProxy);
+        assert_eq!(integration.other["contentHandling"], "CONVERT_TO_TEXT");
+        assert!(spec.aws_request_validator_for(op).unwrap().validate_request_body);
+
+        let scheme = spec.components.security_schemes["lambda"].as_item().unwrap();
+        assert_eq!(scheme.aws_auth_type().as_deref(), Some("custom"));
+        assert_eq!(scheme.aws_authorizer().unwrap().authorizer_result_ttl_in_seconds, Some(300));
+        let json = serde_json::to_value(scheme).unwrap();
+        assert_eq!(json["x-amazon-apigateway-authorizer"]["authorizerUri"], "arn:aws:lambda:auth");
+    }
+}
@@ -0,0 +1,228 @@
+//! Command line access to the library's document-wide operations.
+//!
+//! Documents are read from a file, or from stdin when the path is `-`. Files ending in `.json`
+//! are read and written as JSON, everything else as YAML.
+use std::io::Read;
+use std::process::exit;
+
+use anyhow::{anyhow, bail, Result};
+use openapiv3::{ChangelogOptions, ExternalResolver, OpenAPI, OperationFilter, PathMethod, VersionedOpenAPI};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const USAGE: &str = "\
+usage: openapiv3 <command> [options]
+
+commands:
+  validate <spec>                         report validation errors
+  stats <spec>                            print a summary of operations and components
+  diff <old> <new> [--markdown]           list changes, classified by severity
+  filter <spec> [--path-prefix P] [--method M] [--tag T] [--operation-id ID]
+                                          keep only matching operations
+  bundle <spec>                           move external references into components
+  dereference <spec>                      inline all references
+  convert <spec> --to 3.0|3.1             convert between OpenAPI versions
+
+options:
+  -o, --output <file>                     write the resulting document to a file
+  --format json|yaml                      output format, defaults to the input's";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn of_path(path: &str) -> Format {
+        if path.ends_with(".json") { Format::Json } else { Format::Yaml }
+    }
+}
+
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item=String>) -> Result<Args> {
+        const FLAGS: [&str; 1] = ["--markdown"];
+        let mut parsed = Args { positional: Vec::new(), options: Vec::new(), flags: Vec::new() };
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            if FLAGS.contains(&arg.as_str()) {
+                parsed.flags.push(arg);
+            } else if arg.starts_with('-') && arg != "-" {
+                let name = if arg == "-o" { "--output".to_string() } else { arg };
+                let value = args.next().ok_or_else(|| anyhow!("{} requires a value", name))?;
+                parsed.options.push((name, value));
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn options(&self, name: &str) -> impl Iterator<Item=&str> {
+        let name = name.to_string();
+        self.options.iter().filter(move |(n, _)| *n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    fn input(&self, index: usize) -> Result<&str> {
+        self.positional.get(index).map(String::as_str).ok_or_else(|| anyhow!("missing input file\n\n{}", USAGE))
+    }
+}
+
+/// Reads a document of any supported OpenAPI version as an [OpenAPI].
+fn read_spec(path: &str) -> Result<OpenAPI> {
+    Ok(read_document::<VersionedOpenAPI>(path)?.upgrade())
+}
+
+fn read_document<T: DeserializeOwned>(path: &str) -> Result<T> {
+    let mut text = String::new();
+    if path == "-" {
+        std::io::stdin().read_to_string(&mut text)?;
+    } else {
+        text = std::fs::read_to_string(path).map_err(|e| anyhow!("{}: {}", path, e))?;
+    }
+    let spec = match Format::of_path(path) {
+        Format::Json => serde_json::from_str(&text)?,
+        Format::Yaml => serde_yaml::from_str(&text)?,
+    };
+    Ok(spec)
+}
+
+fn write_spec(spec: &impl Serialize, args: &Args, input: &str) -> Result<()> {
+    let output = args.option("--output");
+    let format = match args.option("--format") {
+        Some("json") => Format::Json,
+        Some("yaml") => Format::Yaml,
+        Some(other) => bail!("unknown format: {}", other),
+        None => Format::of_path(output.unwrap_or(input)),
+    };
+    let text = match format {
+        Format::Json => serde_json::to_string_pretty(spec)? + "\n",
+        Format::Yaml => serde_yaml::to_string(spec)?,
+    };
+    match output {
+        Some(path) => std::fs::write(path, text).map_err(|e| anyhow!("{}: {}", path, e))?,
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Runs the command, returning the process exit code.
+fn run(command: &str, args: Args) -> Result<i32> {
+    match command {
+        "validate" => {
+            let spec = read_spec(args.input(0)?)?;
+            let errors = spec.validate();
+            for error in &errors {
+                println!("{}", error);
+            }
+            Ok(if errors.is_empty() { 0 } else { 1 })
+        }
+        "stats" => {
+            let spec = read_spec(args.input(0)?)?;
+            print!("{}", spec.summary());
+            Ok(0)
+        }
+        "diff" => {
+            let old = read_spec(args.input(0)?)?;
+            let new = read_spec(args.input(1)?)?;
+            let diff = old.diff(&new);
+            if args.flag("--markdown") {
+                print!("{}", diff.render_markdown(&ChangelogOptions::default()));
+            } else {
+                for change in &diff.changes {
+                    println!("{:?}\t{}", change.severity, change);
+                }
+            }
+            Ok(if diff.has_breaking_changes() { 1 } else { 0 })
+        }
+        "filter" => {
+            let input = args.input(0)?;
+            let mut spec = read_spec(input)?;
+            let mut filter = OperationFilter::all();
+            if let Some(prefix) = args.option("--path-prefix") {
+                filter = filter.path_prefix(prefix);
+            }
+            for method in args.options("--method") {
+                filter = filter.method(PathMethod::from_bytes(method.to_uppercase().as_bytes())?);
+            }
+            for tag in args.options("--tag") {
+                filter = filter.tag(tag);
+            }
+            for id in args.options("--operation-id") {
+                filter = filter.operation_id(id);
+            }
+            spec.retain_operations(&filter, true);
+            write_spec(&spec, &args, input)?;
+            Ok(0)
+        }
+        "bundle" | "dereference" => {
+            let input = args.input(0)?;
+            let spec = read_spec(input)?;
+            // References in a document from stdin are relative to the current directory.
+            let mut resolver = ExternalResolver::new(if input == "-" { "./" } else { input });
+            let spec = if command == "bundle" {
+                spec.bundle(&mut resolver)?
+            } else {
+                spec.dereference(&mut resolver)?
+            };
+            write_spec(&spec, &args, input)?;
+            Ok(0)
+        }
+        "convert" => {
+            let input = args.input(0)?;
+            let mut spec = read_spec(input)?;
+            let spec = match args.option("--to") {
+                Some("3.0") => {
+                    spec.openapi = "3.0.3".to_string();
+                    VersionedOpenAPI::V3(spec)
+                }
+                Some("3.1") => {
+                    spec.openapi = "3.1.0".to_string();
+                    VersionedOpenAPI::V31(spec)
+                }
+                Some(other) => bail!("unsupported version: {}", other),
+                None => bail!("convert requires --to\n\n{}", USAGE),
+            };
+            write_spec(&spec, &args, input)?;
+            Ok(0)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        _ => bail!("unknown command: {}\n\n{}", command, USAGE),
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let command = match args.next() {
+        Some(command) => command,
+        None => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+    let result = Args::parse(args).and_then(|args| run(&command, args));
+    match result {
+        Ok(code) => exit(code),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            exit(2);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+use crate::*;
+use crate::trace::Phase;
+use crate::util::{is_data_field, unescape_pointer_segment};
+
+/// Where a value sits in the document, and so what kind of component a reference there points to.
+#[derive(Clone, Copy)]
+enum Slot {
+    Item(Option<ComponentKind>),
+    /// A map or list whose entries are all of one kind.
+    Collection(ComponentKind),
+}
+
+fn slot_for(key: &str) -> Slot {
+    use ComponentKind::*;
+    match key {
+        "schema" | "items" | "not" | "additionalProperties" => Slot::Item(Some(Schema)),
+        "requestBody" => Slot::Item(Some(RequestBody)),
+        "allOf" | "anyOf" | "oneOf" | "properties" | "schemas" => Slot::Collection(Schema),
+        "parameters" => Slot::Collection(Parameter),
+        "responses" => Slot::Collection(Response),
+        "requestBodies" => Slot::Collection(RequestBody),
+        "headers" => Slot::Collection(Header),
+        "examples" => Slot::Collection(Example),
+        "links" => Slot::Collection(Link),
+        "callbacks" => Slot::Collection(Callback),
+        "securitySchemes" => Slot::Collection(SecurityScheme),
+        _ => Slot::Item(None),
+    }
+}
+
+struct Bundler<'a> {
+    resolver: &'a mut ExternalResolver,
+    root: String,
+    /// Inline every reference, rather than only collecting external ones into `components`.
+    inline_all: bool,
+    /// Local references of the components collected so far, by the absolute reference they came from.
+    bundled: HashMap<String, String>,
+    collected: Vec<(ComponentKind, String, Value)>,
+    names: HashSet<(ComponentKind, String)>,
+    /// References being inlined, to detect cycles.
+    stack: Vec<String>,
+    count: usize,
+}
+
+impl Bundler<'_> {
+    fn walk(&mut self, value: &mut Value, slot: Slot, from: &str) -> Result<()> {
+        if let Slot::Item(kind) = slot {
+            if let Some(reference) = value.get("$ref").and_then(Value::as_str) {
+                let reference = reference.to_string();
+                return self.reference(value, &reference, kind, from);
+            }
+        }
+        match (value, slot) {
+            (Value::Object(map), Slot::Collection(kind)) => {
+                map.values_mut().try_for_each(|v| self.walk(v, Slot::Item(Some(kind)), from))
+            }
+            (Value::Array(items), Slot::Collection(kind)) => {
+                items.iter_mut().try_for_each(|v| self.walk(v, Slot::Item(Some(kind)), from))
+            }
+            (Value::Object(map), Slot::Item(_)) => {
+                for (k, v) in map.iter_mut() {
+                    if is_data_field(k, v) {
+                        continue;
+                    }
+                    match v.get_mut("mapping").and_then(Value::as_object_mut) {
+                        Some(mapping) if k == "discriminator" => {
+                            for target in mapping.values_mut() {
+                                let Some(reference) = target.as_str() else { continue };
+                                if let Some(local) = self.mapping_target(reference, from)? {
+                                    *target = Value::String(local);
+                                }
+                            }
+                        }
+                        _ => self.walk(v, slot_for(k), from)?,
+                    }
+                }
+                Ok(())
+            }
+            (Value::Array(items), Slot::Item(_)) => {
+                items.iter_mut().try_for_each(|v| self.walk(v, Slot::Item(None), from))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn reference(&mut self, value: &mut Value, reference: &str, kind: Option<ComponentKind>, from: &str) -> Result<()> {
+        let is_local = reference.starts_with('#') && from == self.root;
+        if is_local && !self.inline_all {
+            return Ok(());
+        }
+        let absolute = ExternalResolver::absolute(reference, from);
+        if let Some(local) = self.bundled.get(&absolute) {
+            *value = serde_json::json!({"$ref": local});
+            return Ok(());
+        }
+        let (target, mut content) = self.resolver.lookup(reference, from)?;
+        let (location, fragment) = target.split_once('#').unwrap_or((&target, ""));
+        let location = location.to_string();
+        let root_component = (location == self.root && fragment.starts_with("/components/"))
+            .then(|| format!("#{}", fragment));
+        self.count += 1;
+        if self.inline_all || (root_component.is_none() && kind.is_none()) {
+            if self.stack.contains(&target) {
+                // A recursive structure can't be inlined; it keeps pointing into `components`.
+                let local = root_component.ok_or_else(|| anyhow!("{} is circular.", target))?;
+                *value = serde_json::json!({"$ref": local});
+                return Ok(());
+            }
+            self.stack.push(target.clone());
+            self.walk(&mut content, Slot::Item(kind), &location)?;
+            self.stack.pop();
+            *value = content;
+        } else if let Some(local) = root_component {
+            // Points back into the root document.
+            *value = serde_json::json!({"$ref": local});
+        } else if let Some(kind) = kind {
+            let name = self.unique_name(kind, fragment, &location);
+            let local = kind.reference(&name);
+            self.bundled.insert(absolute, local.clone());
+            self.bundled.insert(target, local.clone());
+            self.walk(&mut content, Slot::Item(Some(kind)), &location)?;
+            self.collected.push((kind, name, content));
+            *value = serde_json::json!({"$ref": local});
+        }
+        Ok(())
+    }
+
+    /// Returns the local reference a discriminator mapping value should have, collecting its
+    /// target into `components` if needed. Mapping values must stay references, so they are
+    /// never inlined. Bare schema names and local references of the root are kept.
+    fn mapping_target(&mut self, reference: &str, from: &str) -> Result<Option<String>> {
+        let is_name = !reference.contains('/') && !reference.contains('#');
+        if is_name || (reference.starts_with('#') && from == self.root) {
+            return Ok(None);
+        }
+        let mut value = serde_json::json!({"$ref": reference});
+        let inline_all = std::mem::replace(&mut self.inline_all, false);
+        let result = self.reference(&mut value, reference, Some(ComponentKind::Schema), from);
+        self.inline_all = inline_all;
+        result?;
+        Ok(value.get("$ref").and_then(Value::as_str).map(str::to_string))
+    }
+
+    /// Names a collected component after the last segment of its pointer, or its file.
+    fn unique_name(&mut self, kind: ComponentKind, fragment: &str, location: &str) -> String {
+        let last = fragment.rsplit('/').next().filter(|s| !s.is_empty()).map(unescape_pointer_segment);
+        let base = last.unwrap_or_else(|| {
+            let file = location.rsplit('/').next().unwrap_or(location);
+            file.split('.').next().unwrap_or(file).to_string()
+        });
+        let base: String = base.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+            .collect();
+        let mut name = base.clone();
+        let mut i = 2;
+        while !self.names.insert((kind, name.clone())) {
+            name = format!("{}_{}", base, i);
+            i += 1;
+        }
+        name
+    }
+}
+
+impl OpenAPI {
+    /// Produces a self-contained copy of this document: every reference into another file or
+    /// URL is loaded with `resolver`, and its target added to `components` and referenced from
+    /// there. References which are already local are kept. Targets whose kind of component can't
+    /// be told from their position, such as path items, are inlined instead.
+    pub fn bundle(&self, resolver: &mut ExternalResolver) -> Result<OpenAPI> {
+        self.rewrite_references(resolver, false, "bundle")
+    }
+
+    /// Like [OpenAPI::bundle], but inlines every reference, local ones included. References
+    /// which would recurse forever are kept, pointing into `components`, and are an error if the
+    /// recursion runs through another document.
+    pub fn dereference(&self, resolver: &mut ExternalResolver) -> Result<OpenAPI> {
+        self.rewrite_references(resolver, true, "dereference document")
+    }
+
+    fn rewrite_references(&self, resolver: &mut ExternalResolver, inline_all: bool, phase: &'static str) -> Result<OpenAPI> {
+        let phase = Phase::start(phase);
+        let mut doc = serde_json::to_value(self)?;
+        let root = resolver.base().to_string();
+        resolver.insert_document(&root, doc.clone());
+        let names = ComponentKind::ALL.iter()
+            .flat_map(|kind| self.components.keys(*kind).map(move |name| (*kind, name.clone())))
+            .collect();
+        let mut bundler = Bundler {
+            resolver,
+            root: root.clone(),
+            inline_all,
+            bundled: HashMap::new(),
+            collected: Vec::new(),
+            names,
+            stack: Vec::new(),
+            count: 0,
+        };
+        bundler.walk(&mut doc, Slot::Item(None), &root)?;
+        if !bundler.collected.is_empty() {
+            let components = doc.as_object_mut().expect("OpenAPI serializes to an object")
+                .entry("components").or_insert_with(|| Value::Object(Map::new()));
+            for (kind, name, value) in bundler.collected {
+                let map = components.as_object_mut().expect("Components serialize to an object")
+                    .entry(kind.as_str()).or_insert_with(|| Value::Object(Map::new()));
+                map.as_object_mut().expect("Component maps serialize to objects").insert(name, value);
+            }
+        }
+        phase.finish(bundler.count);
+        Ok(serde_json::from_value(doc)?)
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_and_dereference() {
+        let dir = std::env::temp_dir().join(format!("openapiv3-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("pet.yaml"), r##"
+Pet:
+  type: object
+  properties:
+    name:
+      type: string
+    owner:
+      $ref: "#/Owner"
+    kind:
+      type: string
+  discriminator:
+    propertyName: kind
+    mapping:
+      cat: "#/Cat"
+Cat:
+  type: object
+Owner:
+  type: object
+  properties:
+    pets:
+      type: array
+      items:
+        $ref: "#/Pet"
+"##).unwrap();
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: "#/components/parameters/Limit"
+      responses:
+        "200":
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: "./pet.yaml#/Pet"
+components:
+  parameters:
+    Limit:
+      name: limit
+      in: query
+      schema:
+        type: integer
+"##).unwrap();
+
+        let mut resolver = ExternalResolver::new(format!("{}/openapi.yaml", dir.display()));
+        let bundled = spec.bundle(&mut resolver).unwrap();
+        let json = serde_json::to_value(&bundled).unwrap();
+        let get = &json["paths"]["/pets"]["get"];
+        assert_eq!(get["parameters"][0]["$ref"], "#/components/parameters/Limit");
+        assert_eq!(get["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Pet");
+        let schemas = &json["components"]["schemas"];
+        assert_eq!(schemas["Pet"]["properties"]["owner"]["$ref"], "#/components/schemas/Owner");
+        assert_eq!(schemas["Owner"]["properties"]["pets"]["items"]["$ref"], "#/components/schemas/Pet");
+        assert_eq!(schemas["Pet"]["discriminator"]["mapping"]["cat"], "#/components/schemas/Cat");
+        assert_eq!(schemas["Cat"]["type"], "object");
+        assert!(bundled.validate().is_empty(), "{:?}", bundled.validate());
+
+        let dereferenced = bundled.dereference(&mut resolver).unwrap();
+        let json = serde_json::to_value(&dereferenced).unwrap();
+        let get = &json["paths"]["/pets"]["get"];
+        assert_eq!(get["parameters"][0]["name"], "limit");
+        let pet = &get["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(pet["properties"]["name"]["type"], "string");
+        assert_eq!(pet["properties"]["owner"]["properties"]["pets"]["items"]["$ref"], "#/components/schemas/Pet");
+
+        // The cycle runs through pet.yaml, so can't be kept as a local reference.
+        assert!(spec.dereference(&mut resolver).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
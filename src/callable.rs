@@ -0,0 +1,102 @@
+use crate::*;
+use crate::util::escape_pointer_segment;
+use crate::validate::path_pointer;
+
+/// Where a [CallableOperation] is declared.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationOrigin<'a> {
+    /// An operation under `paths`.
+    Path,
+    /// An operation of a callback, either declared on an operation or in `components.callbacks`.
+    Callback {
+        /// The name of the callback.
+        name: &'a str,
+        /// The operation declaring the callback, or [None] for callbacks in `components.callbacks`.
+        parent: Option<&'a Operation>,
+    },
+    /// An operation under the top-level `webhooks` of an OpenAPI 3.1 document.
+    Webhook {
+        /// The name of the webhook.
+        name: &'a str,
+    },
+}
+
+/// A uniform view of an operation, regardless of whether it is declared under `paths`,
+/// in a callback, or as a webhook. See [OpenAPI::callable_operations].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallableOperation<'a> {
+    pub origin: OperationOrigin<'a>,
+    /// The path, or for callbacks the runtime expression identifying the callback URL.
+    pub path: &'a str,
+    /// The lowercase method, e.g. `get`.
+    pub method: &'a str,
+    pub operation: &'a Operation,
+    pub path_item: &'a PathItem,
+    /// A JSON pointer to the operation.
+    pub pointer: String,
+}
+
+impl OpenAPI {
+    /// Iterates over every operation of the document: path operations, callback operations
+    /// (including nested callbacks and those in `components.callbacks`), and webhooks.
+    pub fn callable_operations(&self) -> Vec<CallableOperation<'_>> {
+        let mut out = Vec::new();
+        for (path, item) in self.paths.iter() {
+            let Some(item) = item.as_item() else { continue };
+            collect_operations(path, item, OperationOrigin::Path, &path_pointer(path), &mut out);
+        }
+        for (name, callback) in self.components.callbacks.iter() {
+            let Some(callback) = callback.as_item() else { continue };
+            let pointer = format!("#/components/callbacks/{}", escape_pointer_segment(name));
+            collect_callback(name, None, callback, &pointer, &mut out);
+        }
+        for (name, item) in self.webhooks.iter() {
+            let Some(item) = item.as_item() else { continue };
+            let pointer = format!("#/webhooks/{}", escape_pointer_segment(name));
+            collect_operations(name, item, OperationOrigin::Webhook { name }, &pointer, &mut out);
+        }
+        out
+    }
+}
+
+fn collect_operations<'a>(path: &'a str, item: &'a PathItem, origin: OperationOrigin<'a>, pointer: &str, out: &mut Vec<CallableOperation<'a>>) {
+    for (method, operation) in item.iter() {
+        let op_pointer = format!("{}/{}", pointer, method);
+        out.push(CallableOperation {
+            origin: origin.clone(),
+            path,
+            method,
+            operation,
+            path_item: item,
+            pointer: op_pointer.clone(),
+        });
+        for (name, callback) in operation.callbacks.iter() {
+            let Some(callback) = callback.as_item() else { continue };
+            let pointer = format!("{}/callbacks/{}", op_pointer, escape_pointer_segment(name));
+            collect_callback(name, Some(operation), callback, &pointer, out);
+        }
+    }
+}
+
+fn collect_callback<'a>(name: &'a str, parent: Option<&'a Operation>, callback: &'a Callback, pointer: &str, out: &mut Vec<CallableOperation<'a>>) {
+    for (expression, item) in callback {
+        let pointer = format!("{}/{}", pointer, escape_pointer_segment(expression));
+        collect_operations(expression, item, OperationOrigin::Callback { name, parent }, &pointer, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callable_operations() {
+        let api: OpenAPI = serde_yaml::from_str(include_str!("../fixtures/callback-example.yaml")).unwrap();
+        let ops = api.callable_operations();
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0].origin, OperationOrigin::Path);
+        assert_eq!(ops[1].path, "{$request.query.callbackUrl}/data");
+        assert!(matches!(ops[1].origin, OperationOrigin::Callback { name: "onData", parent: Some(_) }));
+        assert_eq!(ops[1].pointer, "#/paths/~1streams/post/callbacks/onData/{$request.query.callbackUrl}~1data/post");
+    }
+}
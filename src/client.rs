@@ -0,0 +1,495 @@
+//! Builds HTTP requests from the spec, without performing any I/O.
+//!
+//! ```
+//! # use openapiv3::*;
+//! # use openapiv3::client::RequestTemplate;
+//! # use serde_json::json;
+//! let mut spec = OpenAPI::new("Petstore", "1.0.0").with_server("https://api.example.com/v1");
+//! let mut op = Operation { operation_id: Some("getPet".to_string()), ..Operation::default() };
+//! op.add_path_param("id", Schema::new_integer());
+//! op.add_query_param("fields", Schema::new_array(Schema::new_string()));
+//! spec.add_operation(PathMethod::GET, "/pets/{id}", op).unwrap();
+//!
+//! let params = vec![("id", json!(7)), ("fields", json!(["name", "age"]))];
+//! let request = RequestTemplate::from_operation("getPet", &spec, params, None).unwrap();
+//! assert_eq!(request.url, "https://api.example.com/v1/pets/7?fields=name&fields=age");
+//! ```
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+use crate::*;
+use crate::media_type::find_media_type;
+use crate::param_codec::{primitive, serialize_cookie, serialize_path, serialize_query, Shape};
+use crate::util::percent_encode;
+
+/// Identifies an operation in the spec, either by operationId or by method and path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationRef<'a> {
+    Id(&'a str),
+    Path { method: PathMethod, path: &'a str },
+}
+
+impl<'a> From<&'a str> for OperationRef<'a> {
+    fn from(id: &'a str) -> Self {
+        OperationRef::Id(id)
+    }
+}
+
+impl<'a> From<(PathMethod, &'a str)> for OperationRef<'a> {
+    fn from((method, path): (PathMethod, &'a str)) -> Self {
+        OperationRef::Path { method, path }
+    }
+}
+
+/// An operation as found by [OperationRef::find].
+pub(crate) struct FoundOperation<'a> {
+    pub method: PathMethod,
+    pub path: &'a str,
+    pub operation: &'a Operation,
+    pub path_item: &'a PathItem,
+}
+
+impl FoundOperation<'_> {
+    /// The servers of the operation, its path item, or the document, whichever is set first.
+    pub(crate) fn servers<'s>(&'s self, spec: &'s OpenAPI) -> &'s [Server] {
+        [&self.operation.servers, &self.path_item.servers]
+            .iter()
+            .copied()
+            .find(|s| !s.is_empty())
+            .unwrap_or(&spec.servers)
+    }
+}
+
+impl<'a> OperationRef<'a> {
+    pub(crate) fn find<'s>(&self, spec: &'s OpenAPI) -> Result<FoundOperation<'s>> {
+        let found = spec.operations().find(|(path, method, op, _)| match self {
+            OperationRef::Id(id) => op.operation_id.as_deref() == Some(*id),
+            OperationRef::Path { method: m, path: p } => p == path && m.as_str().eq_ignore_ascii_case(method),
+        });
+        let (path, method, operation, path_item) = found.ok_or_else(|| anyhow!("Operation {:?} not found.", self))?;
+        Ok(FoundOperation {
+            method: PathMethod::from_bytes(method.to_uppercase().as_bytes())?,
+            path,
+            operation,
+            path_item,
+        })
+    }
+}
+
+/// A fully-built HTTP request: method, URL, headers and encoded body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestTemplate {
+    pub method: PathMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl RequestTemplate {
+    /// Builds the request for an operation from parameter values keyed by parameter name,
+    /// and an optional body. Parameters of the same name in different locations are told apart
+    /// with a `location:name` key, such as `header:id`.
+    ///
+    /// The URL uses the first server of the operation, its path item, or the document, with
+    /// server variables set to their defaults. Parameters are serialized according to their
+    /// `style` and `explode` settings. The body is encoded as JSON, form data or text,
+    /// depending on the first supported content type of the request body, preferring JSON.
+    ///
+    /// Fails if the operation doesn't exist, a parameter reference doesn't resolve, a required
+    /// parameter or body is missing, a value is given for a parameter the operation doesn't
+    /// define, or a plain name is given for a name used in several locations.
+    pub fn from_operation<'a, K: AsRef<str>>(
+        operation: impl Into<OperationRef<'a>>,
+        spec: &OpenAPI,
+        params: impl IntoIterator<Item=(K, Value)>,
+        body: Option<&Value>,
+    ) -> Result<Self> {
+        let found = operation.into().find(spec)?;
+        let op = found.operation;
+        let mut values: IndexMap<String, Value> = params.into_iter().map(|(k, v)| (k.as_ref().to_string(), v)).collect();
+
+        let mut path = found.path.to_string();
+        let mut query: Vec<(String, String)> = Vec::new();
+        let mut headers: Vec<(String, String)> = Vec::new();
+        let mut cookies: Vec<String> = Vec::new();
+        let params = op.try_effective_parameters(found.path_item, spec)?;
+        for param in params.iter().copied() {
+            let value = match take_value(&mut values, param, &params)? {
+                Some(value) => value,
+                None if param.required => bail!("Missing required {} parameter {}", param.kind.location(), param.name),
+                None => continue,
+            };
+            match &param.kind {
+                ParameterKind::Path { style } => {
+                    path = path.replace(&format!("{{{}}}", param.name), &serialize_path(param, style, &value));
+                }
+                ParameterKind::Query { style, allow_reserved, .. } => {
+                    query.extend(serialize_query(param, style, *allow_reserved, &value));
+                }
+                ParameterKind::Header { .. } => {
+                    let explode = param.effective_explode();
+                    headers.push((param.name.clone(), Shape::of(&value, str::to_string).join(",", explode)));
+                }
+                ParameterKind::Cookie { .. } => cookies.extend(serialize_cookie(param, &value)),
+            }
+        }
+        if let Some(name) = values.keys().next() {
+            bail!("Unknown parameter {}", name);
+        }
+        if !cookies.is_empty() {
+            headers.push(("Cookie".to_string(), cookies.join("; ")));
+        }
+
+        let server = found.servers(spec).first()
+            .map(Server::default_url)
+            .unwrap_or_default();
+        let url = join_url(&server, &path, query);
+
+        let request_body = op.request_body.as_ref().map(|b| b.resolve(spec)).transpose()?;
+        let body = match (request_body, body) {
+            (Some(request_body), Some(body)) => {
+                let (content_type, bytes) = encode_body(request_body, body)?;
+                headers.push(("Content-Type".to_string(), content_type));
+                Some(bytes)
+            }
+            (Some(request_body), None) if request_body.required => bail!("Missing required request body"),
+            (None, Some(_)) => bail!("Operation does not accept a request body"),
+            _ => None,
+        };
+        Ok(RequestTemplate {
+            method: found.method,
+            url,
+            headers,
+            body,
+        })
+    }
+}
+
+impl OpenAPI {
+    /// Builds the absolute URL of an operation: picks server `server_index` of the operation, its
+    /// path item, or the document, expands its variables with `server_variables` or else their
+    /// defaults, and fills in the path and query parameters as [RequestTemplate::from_operation] does.
+    /// Relative server URLs are resolved against `base`, the URL the document was retrieved from.
+    ///
+    /// Fails if the operation or server doesn't exist, a server variable isn't defined or its value
+    /// isn't allowed, a parameter reference doesn't resolve, a required path or query parameter is
+    /// missing, a value is given for a parameter that isn't a path or query parameter of the
+    /// operation, or the result isn't a valid absolute URL, e.g. for a relative server without `base`.
+    pub fn full_url_for<'a, S: AsRef<str>, V: Into<String>, K: AsRef<str>, Q: AsRef<str>>(
+        &self,
+        op_ref: impl Into<OperationRef<'a>>,
+        server_index: usize,
+        server_variables: impl IntoIterator<Item=(S, V)>,
+        path_params: impl IntoIterator<Item=(K, Value)>,
+        query: impl IntoIterator<Item=(Q, Value)>,
+        base: Option<&http::Uri>,
+    ) -> Result<http::Uri> {
+        let found = op_ref.into().find(self)?;
+        let servers = found.servers(self);
+        let server = servers.get(server_index)
+            .ok_or_else(|| anyhow!("Server index {} out of range, the operation has {} servers", server_index, servers.len()))?;
+        let server = crate::server::absolute_url(&server.url_with(server_variables)?, base)?.to_string();
+
+        let mut path_values: IndexMap<String, Value> = path_params.into_iter().map(|(k, v)| (k.as_ref().to_string(), v)).collect();
+        let mut query_values: IndexMap<String, Value> = query.into_iter().map(|(k, v)| (k.as_ref().to_string(), v)).collect();
+        let mut path = found.path.to_string();
+        let mut pairs = Vec::new();
+        for param in found.operation.try_effective_parameters(found.path_item, self)? {
+            let value = match &param.kind {
+                ParameterKind::Path { .. } => path_values.shift_remove(&param.name),
+                ParameterKind::Query { .. } => query_values.shift_remove(&param.name),
+                _ => continue,
+            };
+            let value = match value {
+                Some(value) => value,
+                None if param.required => bail!("Missing required {} parameter {}", param.kind.location(), param.name),
+                None => continue,
+            };
+            match &param.kind {
+                ParameterKind::Path { style } => {
+                    path = path.replace(&format!("{{{}}}", param.name), &serialize_path(param, style, &value));
+                }
+                ParameterKind::Query { style, allow_reserved, .. } => {
+                    pairs.extend(serialize_query(param, style, *allow_reserved, &value));
+                }
+                _ => unreachable!(),
+            }
+        }
+        if let Some(name) = path_values.keys().chain(query_values.keys()).next() {
+            bail!("Unknown parameter {}", name);
+        }
+        Ok(join_url(&server, &path, pairs).parse()?)
+    }
+}
+
+/// Takes the value of a parameter, given as `location:name` or by its name alone, which must then
+/// be unique among `params`.
+fn take_value(values: &mut IndexMap<String, Value>, param: &Parameter, params: &[&Parameter]) -> Result<Option<Value>> {
+    let location = param.kind.location();
+    if let Some(value) = values.shift_remove(&format!("{}:{}", location, param.name)) {
+        return Ok(Some(value));
+    }
+    if values.contains_key(&param.name) && params.iter().filter(|p| p.name == param.name).count() > 1 {
+        bail!("Parameter {} is defined in several locations, use a key such as {}:{}", param.name, location, param.name);
+    }
+    Ok(values.shift_remove(&param.name))
+}
+
+fn join_url(server: &str, path: &str, query: Vec<(String, String)>) -> String {
+    let url = format!("{}{}", server.trim_end_matches('/'), path);
+    if query.is_empty() {
+        return url;
+    }
+    let query: Vec<String> = query.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    format!("{}?{}", url, query.join("&"))
+}
+
+fn encode_body(request_body: &RequestBody, body: &Value) -> Result<(String, Vec<u8>)> {
+    for mime in ["application/json", "application/x-www-form-urlencoded", "text/plain"].iter() {
+        let Some((content_type, _)) = find_media_type(&request_body.content, mime) else { continue };
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        let content_type = if essence.contains('*') { mime.to_string() } else { content_type.clone() };
+        let bytes = match *mime {
+            "application/json" => serde_json::to_vec(body)?,
+            "application/x-www-form-urlencoded" => {
+                let map = body.as_object().ok_or_else(|| anyhow!("Form bodies must be objects"))?;
+                map.iter()
+                    .map(|(k, v)| format!("{}={}", percent_encode(k, false), percent_encode(&primitive(v), false)))
+                    .collect::<Vec<_>>()
+                    .join("&")
+                    .into_bytes()
+            }
+            _ => primitive(body).into_bytes(),
+        };
+        return Ok((content_type, bytes));
+    }
+    match (request_body.content.keys().next(), body) {
+        (Some(content_type), Value::String(s)) => Ok((content_type.clone(), s.clone().into_bytes())),
+        (Some(content_type), _) => bail!("Cannot encode a JSON value as {}", content_type),
+        (None, _) => bail!("Request body has no content types"),
+    }
+}
+
+/// A response matched to its definition in the spec. See [decode_response].
+#[derive(Debug, Clone)]
+pub struct DecodedResponse<'a> {
+    pub status: u16,
+    /// The matching response definition, by exact status code, status range, or `default`.
+    pub response: &'a Response,
+    /// The `Content-Type` header, if present.
+    pub content_type: Option<String>,
+    /// The media type of the response matching the content type.
+    pub media_type: Option<&'a MediaType>,
+    /// The schema of the matched media type, resolved.
+    pub schema: Option<&'a Schema>,
+    /// The parsed body, for JSON (as a value) and text (as a string) content types. For event
+    /// streams, an array of the event data, each validated against its `x-sse-events` schema.
+    /// For newline-delimited JSON, an array of the lines, each validated against the schema.
+    pub body: Option<Value>,
+    /// Problems found validating the body against the schema. Empty if the body wasn't parsed.
+    pub validation_errors: Vec<ValidationError>,
+}
+
+impl<'a> DecodedResponse<'a> {
+    pub fn is_valid(&self) -> bool {
+        self.validation_errors.is_empty()
+    }
+}
+
+fn is_json(essence: &str) -> bool {
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// Matches a response to its definition in the spec, checks its content type, and parses and
+/// validates the body.
+///
+/// Fails if the operation doesn't exist, no response is defined for the status, the content
+/// type isn't one the response declares, or a JSON body doesn't parse. Schema violations don't
+/// fail; they are returned in [DecodedResponse::validation_errors].
+pub fn decode_response<'a, 's, K: AsRef<str>, V: AsRef<str>>(
+    operation: impl Into<OperationRef<'a>>,
+    spec: &'s OpenAPI,
+    status: u16,
+    headers: impl IntoIterator<Item=(K, V)>,
+    body: &[u8],
+) -> Result<DecodedResponse<'s>> {
+    let found = operation.into().find(spec)?;
+    let response = found.operation.responses.for_status(status)
+        .ok_or_else(|| anyhow!("No response defined for status {}", status))?
+        .resolve(spec)?;
+    let content_type = headers.into_iter()
+        .find(|(k, _)| k.as_ref().eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.as_ref().to_string());
+
+    let mut decoded = DecodedResponse {
+        status,
+        response,
+        content_type: content_type.clone(),
+        media_type: None,
+        schema: None,
+        body: None,
+        validation_errors: Vec::new(),
+    };
+    if response.content.is_empty() {
+        return Ok(decoded);
+    }
+    let content_type = match content_type {
+        Some(content_type) => content_type,
+        None if body.is_empty() => return Ok(decoded),
+        None => bail!("Response has a body but no Content-Type"),
+    };
+    let (_, media_type) = find_media_type(&response.content, &content_type)
+        .ok_or_else(|| anyhow!("Content-Type {} is not declared for status {}", content_type, status))?;
+    decoded.media_type = Some(media_type);
+    decoded.schema = media_type.schema.as_ref().map(|s| Resolve::resolve(s, spec)).transpose()?;
+
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    if is_streaming_media_type(&essence) {
+        let (values, errors) = crate::sse::validate_event_stream(media_type, &String::from_utf8_lossy(body), spec);
+        decoded.body = Some(values);
+        decoded.validation_errors = errors;
+        return Ok(decoded);
+    }
+    if is_ndjson_media_type(&essence) {
+        let (values, errors) = media_type.validate_ndjson(&String::from_utf8_lossy(body), spec);
+        decoded.body = Some(values);
+        decoded.validation_errors = errors;
+        return Ok(decoded);
+    }
+    decoded.body = if is_json(&essence) {
+        Some(serde_json::from_slice(body).map_err(|e| anyhow!("Invalid JSON body: {}", e))?)
+    } else if essence.starts_with("text/") {
+        Some(Value::String(String::from_utf8_lossy(body).into_owned()))
+    } else {
+        None
+    };
+    if let (Some(schema), Some(value)) = (decoded.schema, &decoded.body) {
+        decoded.validation_errors = schema.validate_value(value, spec);
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_request_template() {
+        let mut spec = OpenAPI::new("Petstore", "1.0.0").with_server("https://api.example.com/");
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::path_required("id", Schema::new_array(Schema::new_integer())).style(PathStyle::Matrix).unwrap().explode(true).into());
+        op.parameters.push(Parameter::query("filter", Schema::new_object()).style(QueryStyle::DeepObject).unwrap().into());
+        op.parameters.push(Parameter::query("tags", Schema::new_array(Schema::new_string())).explode(false).into());
+        op.parameters.push(Parameter::header("X-Trace", Schema::new_string()).into());
+        op.parameters.push(Parameter::cookie("session", Schema::new_string()).required().into());
+        op.request_body = Some(RequestBody::form(Schema::new_object()).into());
+        spec.add_operation(PathMethod::POST, "/pets/{id}", op).unwrap();
+
+        let params = vec![
+            ("id", json!([1, 2])),
+            ("filter", json!({"kind": "cat dog"})),
+            ("tags", json!(["a", "b/c"])),
+            ("X-Trace", json!("abc")),
+            ("session", json!("s1")),
+        ];
+        let body = json!({"name": "Tom & Jerry"});
+        let request = RequestTemplate::from_operation((PathMethod::POST, "/pets/{id}"), &spec, params, Some(&body)).unwrap();
+        assert_eq!(request.method, PathMethod::POST);
+        assert_eq!(request.url, "https://api.example.com/pets/;id=1;id=2?filter%5Bkind%5D=cat%20dog&tags=a,b%2Fc");
+        assert_eq!(request.headers, vec![
+            ("X-Trace".to_string(), "abc".to_string()),
+            ("Cookie".to_string(), "session=s1".to_string()),
+            ("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()),
+        ]);
+        assert_eq!(request.body.unwrap(), b"name=Tom%20%26%20Jerry");
+
+        let missing = RequestTemplate::from_operation((PathMethod::POST, "/pets/{id}"), &spec, vec![("id", json!(1))], None);
+        assert!(missing.unwrap_err().to_string().contains("session"));
+        let unknown = RequestTemplate::from_operation((PathMethod::POST, "/pets/{id}"), &spec, vec![("id", json!(1)), ("session", json!("s")), ("nope", json!(1))], None);
+        assert_eq!(unknown.unwrap_err().to_string(), "Unknown parameter nope");
+
+        let mut op = Operation { operation_id: Some("patchPet".to_string()), ..Operation::default() };
+        op.add_query_param("id", Schema::new_string());
+        op.parameters.push(Parameter::header("id", Schema::new_string()).into());
+        op.request_body = Some(RequestBody {
+            content: vec![("application/merge-patch+json".to_string(), MediaType::default())].into_iter().collect(),
+            ..RequestBody::default()
+        }.into());
+        spec.add_operation(PathMethod::PATCH, "/pets", op).unwrap();
+        let params = vec![("query:id", json!("q")), ("header:id", json!("h"))];
+        let request = RequestTemplate::from_operation("patchPet", &spec, params, Some(&json!({"name": null}))).unwrap();
+        assert_eq!(request.url, "https://api.example.com/pets?id=q");
+        assert_eq!(request.headers, vec![
+            ("id".to_string(), "h".to_string()),
+            ("Content-Type".to_string(), "application/merge-patch+json".to_string()),
+        ]);
+        let ambiguous = RequestTemplate::from_operation("patchPet", &spec, vec![("id", json!("q"))], None);
+        assert_eq!(ambiguous.unwrap_err().to_string(), "Parameter id is defined in several locations, use a key such as query:id");
+
+        spec.paths.get_mut("/pets").unwrap().as_mut().unwrap().patch.as_mut().unwrap()
+            .parameters.push(RefOr::ref_("#/components/parameters/Missing"));
+        assert!(RequestTemplate::from_operation("patchPet", &spec, vec![("query:id", json!("q"))], None).is_err());
+    }
+
+    #[test]
+    fn test_full_url_for() {
+        let mut spec = OpenAPI::new("Petstore", "1.0.0");
+        spec.servers.push(Server { url: "https://{region}.example.com/v1/".to_string(), ..Server::default() });
+        spec.servers[0].variables = Some(vec![("region".to_string(), ServerVariable { default: "eu".to_string(), ..ServerVariable::default() })].into_iter().collect());
+        spec.servers.push(Server { url: "/v2".to_string(), ..Server::default() });
+        let mut op = Operation { operation_id: Some("getPet".to_string()), ..Operation::default() };
+        op.add_path_param("id", Schema::new_string());
+        op.add_query_param("fields", Schema::new_array(Schema::new_string()));
+        op.parameters.push(Parameter::header("X-Trace", Schema::new_string()).into());
+        spec.add_operation(PathMethod::GET, "/pets/{id}", op).unwrap();
+
+        let url = spec.full_url_for("getPet", 0, Vec::<(&str, &str)>::new(), [("id", json!("a b"))], [("fields", json!(["name", "age"]))], None).unwrap();
+        assert_eq!(url, "https://eu.example.com/v1/pets/a%20b?fields=name&fields=age");
+        let url = spec.full_url_for((PathMethod::GET, "/pets/{id}"), 0, Vec::<(&str, &str)>::new(), [("id", json!(1))], Vec::<(&str, Value)>::new(), None).unwrap();
+        assert_eq!(url, "https://eu.example.com/v1/pets/1");
+        let url = spec.full_url_for("getPet", 0, [("region", "us")], [("id", json!(1))], Vec::<(&str, Value)>::new(), None).unwrap();
+        assert_eq!(url, "https://us.example.com/v1/pets/1");
+        let unknown = spec.full_url_for("getPet", 0, [("stage", "dev")], [("id", json!(1))], Vec::<(&str, Value)>::new(), None);
+        assert_eq!(unknown.unwrap_err().to_string(), "Unknown server variable stage");
+
+        assert!(spec.full_url_for("getPet", 1, Vec::<(&str, &str)>::new(), [("id", json!(1))], Vec::<(&str, Value)>::new(), None).is_err());
+        let base = "https://docs.example.com/api/openapi.json".parse().unwrap();
+        let url = spec.full_url_for("getPet", 1, Vec::<(&str, &str)>::new(), [("id", json!(1))], Vec::<(&str, Value)>::new(), Some(&base)).unwrap();
+        assert_eq!(url, "https://docs.example.com/v2/pets/1");
+
+        assert!(spec.full_url_for("getPet", 2, Vec::<(&str, &str)>::new(), [("id", json!(1))], Vec::<(&str, Value)>::new(), None).is_err());
+        let header = spec.full_url_for("getPet", 0, Vec::<(&str, &str)>::new(), [("id", json!(1))], [("X-Trace", json!("t"))], None);
+        assert_eq!(header.unwrap_err().to_string(), "Unknown parameter X-Trace");
+        let missing = spec.full_url_for("getPet", 0, Vec::<(&str, &str)>::new(), Vec::<(&str, Value)>::new(), Vec::<(&str, Value)>::new(), None);
+        assert_eq!(missing.unwrap_err().to_string(), "Missing required path parameter id");
+    }
+
+    #[test]
+    fn test_decode_response() {
+        let mut spec = OpenAPI::new("Petstore", "1.0.0");
+        let mut op = Operation { operation_id: Some("getPet".to_string()), ..Operation::default() };
+        let mut pet = Schema::new_object();
+        pet.properties_mut().insert("name", Schema::new_string());
+        pet.add_required("name");
+        op.responses.responses.insert(StatusCode::Code(200), Response::new("OK").json(pet).into());
+        op.responses.responses.insert(StatusCode::Range(4), Response::new("Client error").into());
+        spec.add_operation(PathMethod::GET, "/pet", op).unwrap();
+
+        let json = [("content-type", "application/json; charset=utf-8")];
+        let decoded = decode_response("getPet", &spec, 200, json, br#"{"name": "Tom"}"#).unwrap();
+        assert!(decoded.is_valid());
+        assert_eq!(decoded.body, Some(json!({"name": "Tom"})));
+        assert!(decoded.schema.unwrap().get_properties().is_some());
+
+        let decoded = decode_response("getPet", &spec, 200, json, br#"{}"#).unwrap();
+        assert_eq!(decoded.validation_errors[0].message, "Missing required property name");
+
+        let decoded = decode_response("getPet", &spec, 404, Vec::<(&str, &str)>::new(), b"").unwrap();
+        assert_eq!(decoded.response.description, "Client error");
+
+        assert!(decode_response("getPet", &spec, 200, [("Content-Type", "text/html")], b"<p>").is_err());
+        assert!(decode_response("getPet", &spec, 500, json, b"{}").is_err());
+    }
+}
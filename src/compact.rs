@@ -0,0 +1,97 @@
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{is_data_field, is_name_map_key};
+
+/// Serializes an [OpenAPI] document without descriptions, summaries and examples,
+/// leaving only the structure needed at runtime. Created by [OpenAPI::compact].
+///
+/// Responses keep an empty `description`, since the field is required.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactSerializer<'a>(pub &'a OpenAPI);
+
+impl Serialize for CompactSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut doc = serde_json::to_value(self.0).map_err(serde::ser::Error::custom)?;
+        compact(&mut doc, false);
+        doc.serialize(serializer)
+    }
+}
+
+impl OpenAPI {
+    /// Wraps the document so that it serializes compactly, e.g. `serde_json::to_string(&spec.compact())`.
+    pub fn compact(&self) -> CompactSerializer<'_> {
+        CompactSerializer(self)
+    }
+}
+
+fn compact(value: &mut Value, is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                for key in ["description", "summary", "example", "examples"] {
+                    map.remove(key);
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                let names = !is_name_map && is_name_map_key(key);
+                compact(child, names);
+                if names && key == "responses" {
+                    let Value::Object(responses) = child else { continue };
+                    for response in responses.values_mut().filter_map(|r| r.as_object_mut()) {
+                        if !response.contains_key("$ref") {
+                            response.insert("description".to_string(), Value::String(String::new()));
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                compact(item, false);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_compact() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.info.description = Some("All about pets".to_string());
+        let mut schema = Schema::new_object();
+        let mut name = Schema::new_string();
+        name.description = Some("The name".to_string());
+        name.example = Some(json!("Tom"));
+        schema.properties_mut().insert("name", name);
+        schema.properties_mut().insert("description", Schema::new_string());
+        api.schemas.insert("Pet", schema);
+        let mut op = Operation {
+            summary: Some("Get a pet".to_string()),
+            ..Operation::default()
+        };
+        op.responses.responses.insert(StatusCode::Code(200), Response::new("The pet").json(RefOr::schema_ref("Pet")).into());
+        api.paths.insert_operation("/pet".to_string(), PathMethod::GET, op);
+
+        let doc = serde_json::to_value(api.compact()).unwrap();
+        assert_eq!(doc["info"], json!({"title": "Petstore", "version": "1.0.0"}));
+        assert_eq!(doc["components"]["schemas"]["Pet"]["properties"], json!({
+            "name": {"type": "string"},
+            "description": {"type": "string"},
+        }));
+        assert_eq!(doc["paths"]["/pet"]["get"].get("summary"), None);
+        assert_eq!(doc["paths"]["/pet"]["get"]["responses"]["200"]["description"], "");
+        let parsed: OpenAPI = serde_json::from_value(doc).unwrap();
+        assert_eq!(parsed.paths.len(), 1);
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::*;
+
+/// Which direction of schema evolution [Schema::compatible_with] checks, as in schema registries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompatMode {
+    /// Consumers using the new schema can read data written with the old one.
+    Backward,
+    /// Consumers using the old schema can read data written with the new one.
+    Forward,
+    /// Both backward and forward.
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncompatibilityKind {
+    /// The reader requires a field the writer may omit.
+    RequiredField,
+    /// The reader doesn't accept the writer's type.
+    TypeChanged,
+    /// The reader doesn't accept some of the writer's enum values.
+    EnumNarrowed,
+    /// A length, range, count or pattern constraint is stricter for the reader.
+    ConstraintTightened,
+    /// The writer may send null, which the reader doesn't accept.
+    NullableRemoved,
+    /// The schemas couldn't be compared, e.g. a `not` schema or an `allOf` which can't be merged.
+    Unknown,
+}
+
+/// A reason data written with one schema version may be rejected by another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incompatibility {
+    /// JSON pointer into the payload, e.g. `#/items/name`.
+    pub pointer: String,
+    pub kind: IncompatibilityKind,
+    pub message: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Checks that a reader schema accepts everything a writer schema produces.
+struct Checker<'a> {
+    reader_spec: &'a OpenAPI,
+    writer_spec: &'a OpenAPI,
+    reader: &'static str,
+    writer: &'static str,
+    /// Pairs of referenced schemas already checked.
+    seen: HashSet<(String, String)>,
+    /// Addresses of the schema pairs being checked, which alternatives may lead back to.
+    active: HashSet<(usize, usize)>,
+    out: Vec<Incompatibility>,
+}
+
+/// Returns `schema` with its `allOf` parts merged, or None if they can't be merged.
+fn merged(schema: &Schema, spec: &OpenAPI) -> Option<Schema> {
+    let SchemaKind::AllOf { all_of } = &schema.kind else { return Some(schema.clone()) };
+    let mut parts = all_of.iter().map(|part| Resolve::resolve(part, spec));
+    let first = parts.next()?.ok()?.clone();
+    let merged = parts.try_fold(first, |merged, part| merged.intersect(part?, spec)).ok()?;
+    (!matches!(merged.kind, SchemaKind::AllOf { .. })).then_some(merged)
+}
+
+impl<'a> Checker<'a> {
+    fn push(&mut self, pointer: &str, kind: IncompatibilityKind, message: String) {
+        self.out.push(Incompatibility { pointer: pointer.to_string(), kind, message });
+    }
+
+    /// Reports when the reader limits a lower bound more strictly than the writer.
+    fn lower<T: PartialOrd + fmt::Display>(&mut self, pointer: &str, what: &str, reader: Option<T>, writer: Option<T>) {
+        match (reader, writer) {
+            (Some(r), Some(w)) if r > w => {
+                let message = format!("{} raised from {} to {}", what, w, r);
+                self.tightened(pointer, message);
+            }
+            (Some(r), None) => {
+                let message = format!("{} {} added", what, r);
+                self.tightened(pointer, message);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports when the reader limits an upper bound more strictly than the writer.
+    fn upper<T: PartialOrd + fmt::Display>(&mut self, pointer: &str, what: &str, reader: Option<T>, writer: Option<T>) {
+        match (reader, writer) {
+            (Some(r), Some(w)) if r < w => {
+                let message = format!("{} lowered from {} to {}", what, w, r);
+                self.tightened(pointer, message);
+            }
+            (Some(r), None) => {
+                let message = format!("{} {} added", what, r);
+                self.tightened(pointer, message);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports when only the reader excludes a bound which both share.
+    fn exclusive<T: PartialEq>(&mut self, pointer: &str, what: &str, reader: (Option<T>, bool), writer: (Option<T>, bool)) {
+        if reader.1 && !writer.1 && reader.0.is_some() && reader.0 == writer.0 {
+            self.tightened(pointer, format!("{} added", what));
+        }
+    }
+
+    /// Reports when the writer's values aren't all multiples of the reader's `multipleOf`.
+    fn multiple_of(&mut self, pointer: &str, reader: Option<f64>, writer: Option<f64>) {
+        match (reader, writer) {
+            (Some(r), Some(w)) if ((w / r) - (w / r).round()).abs() > 1e-9 => {
+                self.tightened(pointer, format!("multipleOf changed from {} to {}", w, r));
+            }
+            (Some(r), None) => self.tightened(pointer, format!("multipleOf {} added", r)),
+            _ => {}
+        }
+    }
+
+    fn tightened(&mut self, pointer: &str, message: String) {
+        let message = format!("{} in the {} schema", message, self.reader);
+        self.push(pointer, IncompatibilityKind::ConstraintTightened, message);
+    }
+
+    fn enums<T: PartialEq + fmt::Debug>(&mut self, pointer: &str, reader: &[T], writer: &[T]) {
+        if reader.is_empty() {
+            return;
+        }
+        if writer.is_empty() {
+            let message = format!("The {} schema restricts values to an enum", self.reader);
+            self.push(pointer, IncompatibilityKind::EnumNarrowed, message);
+            return;
+        }
+        let missing: Vec<&T> = writer.iter().filter(|w| !reader.contains(w)).collect();
+        if !missing.is_empty() {
+            let message = format!("The {} schema doesn't accept {:?}", self.reader, missing);
+            self.push(pointer, IncompatibilityKind::EnumNarrowed, message);
+        }
+    }
+
+    fn unknown(&mut self, pointer: &str, what: &str) {
+        let message = format!("Can't compare {} in the {} and {} schemas", what, self.reader, self.writer);
+        self.push(pointer, IncompatibilityKind::Unknown, message);
+    }
+
+    fn check_ref(&mut self, pointer: &str, reader: &RefOr<Schema>, writer: &RefOr<Schema>) {
+        if let (RefOr::Reference { reference: r, .. }, RefOr::Reference { reference: w, .. }) = (reader, writer) {
+            if !self.seen.insert((r.clone(), w.clone())) {
+                return;
+            }
+        }
+        let (Ok(r), Ok(w)) = (Resolve::resolve(reader, self.reader_spec), Resolve::resolve(writer, self.writer_spec)) else {
+            return;
+        };
+        self.check(pointer, r, w);
+    }
+
+    fn check(&mut self, pointer: &str, reader: &Schema, writer: &Schema) {
+        let key = (reader as *const Schema as usize, writer as *const Schema as usize);
+        if !self.active.insert(key) {
+            return;
+        }
+        self.check_kinds(pointer, reader, writer);
+        self.active.remove(&key);
+    }
+
+    fn check_kinds(&mut self, pointer: &str, reader: &Schema, writer: &Schema) {
+        if writer.nullable && !reader.nullable {
+            let message = format!("The {} schema allows null but the {} doesn't", self.writer, self.reader);
+            self.push(pointer, IncompatibilityKind::NullableRemoved, message);
+        }
+        match (&reader.kind, &writer.kind) {
+            (SchemaKind::Type(r), SchemaKind::Type(w)) => self.check_types(pointer, r, w),
+            (SchemaKind::AllOf { .. }, _) | (_, SchemaKind::AllOf { .. }) => {
+                match (merged(reader, self.reader_spec), merged(writer, self.writer_spec)) {
+                    (Some(r), Some(w)) => self.check(pointer, &r, &w),
+                    _ => self.unknown(pointer, "allOf which can't be merged into one schema"),
+                }
+            }
+            // The writer may produce any of its alternatives.
+            (_, SchemaKind::OneOf { one_of: alternatives }) | (_, SchemaKind::AnyOf { any_of: alternatives }) => {
+                for alternative in alternatives {
+                    if let Ok(w) = Resolve::resolve(alternative, self.writer_spec) {
+                        self.check(pointer, reader, w);
+                    }
+                }
+            }
+            // The reader accepts what one of its alternatives accepts.
+            (SchemaKind::OneOf { one_of: alternatives }, _) | (SchemaKind::AnyOf { any_of: alternatives }, _) => {
+                let accepted = alternatives.iter()
+                    .filter_map(|alternative| Resolve::resolve(alternative, self.reader_spec).ok())
+                    .any(|r| {
+                        let mut checker = Checker {
+                            seen: self.seen.clone(),
+                            active: self.active.clone(),
+                            out: Vec::new(),
+                            ..*self
+                        };
+                        checker.check(pointer, r, writer);
+                        checker.out.is_empty()
+                    });
+                if !accepted {
+                    let message = format!("No alternative of the {} schema accepts everything the {} allows", self.reader, self.writer);
+                    self.push(pointer, IncompatibilityKind::TypeChanged, message);
+                }
+            }
+            (SchemaKind::Any(r), _) if *r == AnySchema::default() => {}
+            (_, SchemaKind::Any(w)) if *w == AnySchema::default() => {
+                let message = format!("The {} schema allows any value but the {} doesn't", self.writer, self.reader);
+                self.push(pointer, IncompatibilityKind::TypeChanged, message);
+            }
+            (SchemaKind::Not { .. }, _) | (_, SchemaKind::Not { .. }) => self.unknown(pointer, "not"),
+            _ => self.unknown(pointer, "untyped schemas with constraints"),
+        }
+    }
+
+    fn check_types(&mut self, pointer: &str, r: &Type, w: &Type) {
+        match (r, w) {
+            (Type::String(r), Type::String(w)) => {
+                self.lower(pointer, "minLength", r.min_length, w.min_length);
+                self.upper(pointer, "maxLength", r.max_length, w.max_length);
+                if r.pattern.is_some() && r.pattern != w.pattern {
+                    self.tightened(pointer, format!("pattern {} added", r.pattern.as_deref().unwrap_or_default()));
+                }
+                self.enums(pointer, &r.enumeration, &w.enumeration);
+            }
+            (Type::Number(r), Type::Number(w)) => {
+                self.lower(pointer, "minimum", r.minimum, w.minimum);
+                self.upper(pointer, "maximum", r.maximum, w.maximum);
+                self.exclusive(pointer, "exclusiveMinimum", (r.minimum, r.exclusive_minimum), (w.minimum, w.exclusive_minimum));
+                self.exclusive(pointer, "exclusiveMaximum", (r.maximum, r.exclusive_maximum), (w.maximum, w.exclusive_maximum));
+                self.multiple_of(pointer, r.multiple_of, w.multiple_of);
+                self.enums(pointer, &r.enumeration, &w.enumeration);
+            }
+            (Type::Number(r), Type::Integer(w)) => {
+                let (minimum, maximum) = (w.minimum.map(|m| m as f64), w.maximum.map(|m| m as f64));
+                self.lower(pointer, "minimum", r.minimum, minimum);
+                self.upper(pointer, "maximum", r.maximum, maximum);
+                self.exclusive(pointer, "exclusiveMinimum", (r.minimum, r.exclusive_minimum), (minimum, w.exclusive_minimum));
+                self.exclusive(pointer, "exclusiveMaximum", (r.maximum, r.exclusive_maximum), (maximum, w.exclusive_maximum));
+                // Integers are multiples of 1.
+                self.multiple_of(pointer, r.multiple_of, Some(w.multiple_of.unwrap_or(1) as f64));
+            }
+            (Type::Integer(r), Type::Integer(w)) => {
+                self.lower(pointer, "minimum", r.minimum, w.minimum);
+                self.upper(pointer, "maximum", r.maximum, w.maximum);
+                self.exclusive(pointer, "exclusiveMinimum", (r.minimum, r.exclusive_minimum), (w.minimum, w.exclusive_minimum));
+                self.exclusive(pointer, "exclusiveMaximum", (r.maximum, r.exclusive_maximum), (w.maximum, w.exclusive_maximum));
+                self.multiple_of(pointer, r.multiple_of.map(|m| m as f64), w.multiple_of.map(|m| m as f64));
+                self.enums(pointer, &r.enumeration, &w.enumeration);
+            }
+            (Type::Array(r), Type::Array(w)) => {
+                self.lower(pointer, "minItems", r.min_items, w.min_items);
+                self.upper(pointer, "maxItems", r.max_items, w.max_items);
+                if let (Some(ri), Some(wi)) = (&r.items, &w.items) {
+                    self.check_ref(&format!("{}/items", pointer), ri, wi);
+                }
+            }
+            (Type::Object(r), Type::Object(w)) => {
+                self.lower(pointer, "minProperties", r.min_properties, w.min_properties);
+                self.upper(pointer, "maxProperties", r.max_properties, w.max_properties);
+                for name in r.required.iter().filter(|n| !w.required.contains(n)) {
+                    let message = format!("{} is required by the {} schema but not the {}", name, self.reader, self.writer);
+                    self.push(&format!("{}/{}", pointer, name), IncompatibilityKind::RequiredField, message);
+                }
+                for (name, rp) in r.properties.iter() {
+                    if let Some(wp) = w.properties.get(name) {
+                        self.check_ref(&format!("{}/{}", pointer, name), rp, wp);
+                    }
+                }
+                // Properties only the writer declares are additional properties to the reader.
+                for (name, wp) in w.properties.iter().filter(|(name, _)| !r.properties.contains_key(*name)) {
+                    match &r.additional_properties {
+                        Some(AdditionalProperties::Any(false)) => {
+                            let message = format!("{} is not allowed by the {} schema", name, self.reader);
+                            self.push(&format!("{}/{}", pointer, name), IncompatibilityKind::ConstraintTightened, message);
+                        }
+                        Some(AdditionalProperties::Schema(rs)) => self.check_ref(&format!("{}/{}", pointer, name), rs, wp),
+                        _ => {}
+                    }
+                }
+                match (&r.additional_properties, &w.additional_properties) {
+                    (Some(AdditionalProperties::Any(false)), Some(AdditionalProperties::Any(false))) => {}
+                    (Some(AdditionalProperties::Any(false)), _) => self.tightened(pointer, "additionalProperties false added".to_string()),
+                    (Some(AdditionalProperties::Schema(rs)), Some(AdditionalProperties::Schema(ws))) => {
+                        self.check_ref(&format!("{}/additionalProperties", pointer), rs, ws);
+                    }
+                    (Some(AdditionalProperties::Schema(_)), Some(AdditionalProperties::Any(false))) => {}
+                    (Some(AdditionalProperties::Schema(_)), _) => {
+                        self.tightened(pointer, "additionalProperties schema added".to_string());
+                    }
+                    _ => {}
+                }
+            }
+            (Type::Boolean {}, Type::Boolean {}) => {}
+            (r, w) => {
+                let message = format!("Type changed from {} in the {} schema to {} in the {}", w.as_str(), self.writer, r.as_str(), self.reader);
+                self.push(pointer, IncompatibilityKind::TypeChanged, message);
+            }
+        }
+    }
+}
+
+impl Schema {
+    /// Checks whether payloads remain compatible between an old version of a schema and this one,
+    /// with references resolved in each version's spec. Backward compatibility means readers of
+    /// this schema accept data valid under `old`; forward compatibility is the reverse.
+    ///
+    /// `allOf` parts are merged before comparing. A writer's `oneOf` or `anyOf` alternatives must each
+    /// be accepted, while a reader's need one alternative accepting the writer. Schemas which can't
+    /// be compared, such as `not`, are reported as [IncompatibilityKind::Unknown].
+    pub fn compatible_with(&self, old: &Schema, spec_new: &OpenAPI, spec_old: &OpenAPI, mode: CompatMode) -> Vec<Incompatibility> {
+        let mut out = Vec::new();
+        if matches!(mode, CompatMode::Backward | CompatMode::Full) {
+            let mut checker = Checker { reader_spec: spec_new, writer_spec: spec_old, reader: "new", writer: "old", seen: HashSet::new(), active: HashSet::new(), out: Vec::new() };
+            checker.check("#", self, old);
+            out.extend(checker.out);
+        }
+        if matches!(mode, CompatMode::Forward | CompatMode::Full) {
+            let mut checker = Checker { reader_spec: spec_old, writer_spec: spec_new, reader: "old", writer: "new", seen: HashSet::new(), active: HashSet::new(), out: Vec::new() };
+            checker.check("#", old, self);
+            out.extend(checker.out);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatible_with() {
+        let spec = OpenAPI::default();
+        let mut old = Schema::new_object();
+        old.properties_mut().insert("id", Schema::new_string());
+        old.properties_mut().insert("nickname", Schema::new_string());
+        old.properties_mut().insert("status", Schema::new_str_enum(vec!["active".to_string(), "banned".to_string()]));
+        old.required_mut().push("nickname".to_string());
+
+        let mut new = Schema::new_object();
+        new.properties_mut().insert("id", Schema::new_integer());
+        new.properties_mut().insert("status", Schema::new_str_enum(vec!["active".to_string()]));
+        new.required_mut().push("id".to_string());
+
+        let messages = |mode| new.compatible_with(&old, &spec, &spec, mode).iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(messages(CompatMode::Backward), vec![
+            "#/id: id is required by the new schema but not the old",
+            "#/id: Type changed from string in the old schema to integer in the new",
+            "#/status: The new schema doesn't accept [\"banned\"]",
+        ]);
+        assert_eq!(messages(CompatMode::Forward), vec![
+            "#/nickname: nickname is required by the old schema but not the new",
+            "#/id: Type changed from integer in the new schema to string in the old",
+        ]);
+        assert_eq!(messages(CompatMode::Full).len(), 5);
+        assert!(old.compatible_with(&old, &spec, &spec, CompatMode::Full).is_empty());
+    }
+
+    #[test]
+    fn test_compatible_compositions() {
+        let mut spec = OpenAPI::default();
+        let mut base = Schema::new_object();
+        base.properties_mut().insert("id", Schema::new_integer());
+        spec.schemas.insert("Base", base);
+        let messages = |new: &Schema, old: &Schema| new.compatible_with(old, &spec, &spec, CompatMode::Backward)
+            .iter().map(|i| i.to_string()).collect::<Vec<_>>();
+
+        let mut own = Schema::new_object();
+        own.properties_mut().insert("id", Schema::new_string());
+        let old = Schema::new_all_of(vec![RefOr::schema_ref("Base")]);
+        let new = Schema::new_all_of(vec![RefOr::schema_ref("Base"), own.into()]);
+        assert_eq!(messages(&new, &old), vec!["#: Can't compare allOf which can't be merged into one schema in the new and old schemas"]);
+
+        let old = Schema::new_one_of(vec![Schema::new_string().into(), Schema::new_integer().into()]);
+        let new = Schema::new_one_of(vec![Schema::new_string().into(), Schema::new_number().into()]);
+        assert!(messages(&new, &old).is_empty());
+        assert_eq!(messages(&old, &new), vec!["#: No alternative of the new schema accepts everything the old allows"]);
+        assert!(messages(&Schema::new_any(), &old).is_empty());
+        assert_eq!(messages(&Schema::new_string(), &Schema::new_any()), vec!["#: The old schema allows any value but the new doesn't"]);
+
+        let mut old = Schema::new_number();
+        let mut new = Schema::new_number();
+        if let (SchemaKind::Type(Type::Number(o)), SchemaKind::Type(Type::Number(n))) = (&mut old.kind, &mut new.kind) {
+            o.minimum = Some(0.0);
+            o.multiple_of = Some(0.5);
+            n.minimum = Some(0.0);
+            n.exclusive_minimum = true;
+            n.multiple_of = Some(0.25);
+        }
+        assert_eq!(messages(&new, &old), vec!["#: exclusiveMinimum added in the new schema"]);
+        assert_eq!(messages(&old, &new), vec!["#: multipleOf changed from 0.25 to 0.5 in the new schema"]);
+
+        let mut old = Schema::new_object();
+        old.properties_mut().insert("id", Schema::new_integer());
+        old.properties_mut().insert("name", Schema::new_string());
+        let mut new = Schema::new_object();
+        new.properties_mut().insert("id", Schema::new_integer());
+        if let SchemaKind::Type(Type::Object(o)) = &mut new.kind {
+            o.additional_properties = Some(AdditionalProperties::Any(false));
+        }
+        assert_eq!(messages(&new, &old), vec![
+            "#/name: name is not allowed by the new schema",
+            "#: additionalProperties false added in the new schema",
+        ]);
+    }
+}
@@ -2,6 +2,7 @@ use crate::*;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use crate::map::RefOrMap;
+use crate::util::escape_pointer_segment;
 
 /// Holds a set of reusable objects for different aspects of the OAS.
 /// All objects defined within the components object will have no effect
@@ -55,4 +56,237 @@ impl Components {
             && self.callbacks.is_empty()
             && self.extensions.is_empty()
     }
-}
\ No newline at end of file
+}
+/// The kinds of objects which can be held in [Components].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    SecurityScheme,
+    Response,
+    Parameter,
+    Example,
+    RequestBody,
+    Header,
+    Schema,
+    Link,
+    Callback,
+}
+
+impl ComponentKind {
+    pub const ALL: [ComponentKind; 9] = [
+        ComponentKind::SecurityScheme,
+        ComponentKind::Response,
+        ComponentKind::Parameter,
+        ComponentKind::Example,
+        ComponentKind::RequestBody,
+        ComponentKind::Header,
+        ComponentKind::Schema,
+        ComponentKind::Link,
+        ComponentKind::Callback,
+    ];
+
+    /// Returns the key of this kind in the components object, e.g. `requestBodies`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentKind::SecurityScheme => "securitySchemes",
+            ComponentKind::Response => "responses",
+            ComponentKind::Parameter => "parameters",
+            ComponentKind::Example => "examples",
+            ComponentKind::RequestBody => "requestBodies",
+            ComponentKind::Header => "headers",
+            ComponentKind::Schema => "schemas",
+            ComponentKind::Link => "links",
+            ComponentKind::Callback => "callbacks",
+        }
+    }
+
+    /// Returns the reference to the component with the given name, e.g. `#/components/schemas/Pet`.
+    /// `~` and `/` in the name are escaped.
+    pub fn reference(&self, name: &str) -> String {
+        format!("#/components/{}/{}", self.as_str(), escape_pointer_segment(name))
+    }
+
+    /// Parses a `#/components/<kind>/<name>` reference into its kind and name.
+    pub fn parse_reference(reference: &str) -> Option<(ComponentKind, &str)> {
+        let rest = reference.strip_prefix("#/components/")?;
+        let (kind, name) = rest.split_once('/')?;
+        let kind = ComponentKind::ALL.iter().find(|k| k.as_str() == kind)?;
+        if name.is_empty() || name.contains('/') {
+            return None;
+        }
+        Some((*kind, name))
+    }
+}
+
+impl std::fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A borrowed component of any kind. See [Components::get].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentRef<'a> {
+    SecurityScheme(&'a RefOr<SecurityScheme>),
+    Response(&'a RefOr<Response>),
+    Parameter(&'a RefOr<Parameter>),
+    Example(&'a RefOr<Example>),
+    RequestBody(&'a RefOr<RequestBody>),
+    Header(&'a RefOr<Header>),
+    Schema(&'a RefOr<Schema>),
+    Link(&'a RefOr<Link>),
+    Callback(&'a RefOr<Callback>),
+}
+
+impl ComponentRef<'_> {
+    /// Returns an owned copy of the component.
+    pub fn cloned(&self) -> Component {
+        match *self {
+            ComponentRef::SecurityScheme(c) => Component::SecurityScheme(c.clone()),
+            ComponentRef::Response(c) => Component::Response(c.clone()),
+            ComponentRef::Parameter(c) => Component::Parameter(c.clone()),
+            ComponentRef::Example(c) => Component::Example(c.clone()),
+            ComponentRef::RequestBody(c) => Component::RequestBody(c.clone()),
+            ComponentRef::Header(c) => Component::Header(c.clone()),
+            ComponentRef::Schema(c) => Component::Schema(c.clone()),
+            ComponentRef::Link(c) => Component::Link(c.clone()),
+            ComponentRef::Callback(c) => Component::Callback(c.clone()),
+        }
+    }
+
+    pub(crate) fn to_json(self) -> serde_json::Value {
+        let value = match self {
+            ComponentRef::SecurityScheme(c) => serde_json::to_value(c),
+            ComponentRef::Response(c) => serde_json::to_value(c),
+            ComponentRef::Parameter(c) => serde_json::to_value(c),
+            ComponentRef::Example(c) => serde_json::to_value(c),
+            ComponentRef::RequestBody(c) => serde_json::to_value(c),
+            ComponentRef::Header(c) => serde_json::to_value(c),
+            ComponentRef::Schema(c) => serde_json::to_value(c),
+            ComponentRef::Link(c) => serde_json::to_value(c),
+            ComponentRef::Callback(c) => serde_json::to_value(c),
+        };
+        value.expect("Components serialize to JSON")
+    }
+}
+
+/// An owned component of any kind. See [Components::insert].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Component {
+    SecurityScheme(RefOr<SecurityScheme>),
+    Response(RefOr<Response>),
+    Parameter(RefOr<Parameter>),
+    Example(RefOr<Example>),
+    RequestBody(RefOr<RequestBody>),
+    Header(RefOr<Header>),
+    Schema(RefOr<Schema>),
+    Link(RefOr<Link>),
+    Callback(RefOr<Callback>),
+}
+
+impl Component {
+    pub fn kind(&self) -> ComponentKind {
+        match self {
+            Component::SecurityScheme(_) => ComponentKind::SecurityScheme,
+            Component::Response(_) => ComponentKind::Response,
+            Component::Parameter(_) => ComponentKind::Parameter,
+            Component::Example(_) => ComponentKind::Example,
+            Component::RequestBody(_) => ComponentKind::RequestBody,
+            Component::Header(_) => ComponentKind::Header,
+            Component::Schema(_) => ComponentKind::Schema,
+            Component::Link(_) => ComponentKind::Link,
+            Component::Callback(_) => ComponentKind::Callback,
+        }
+    }
+}
+
+impl Components {
+    /// Returns the component of the given kind and name.
+    pub fn get(&self, kind: ComponentKind, name: &str) -> Option<ComponentRef<'_>> {
+        Some(match kind {
+            ComponentKind::SecurityScheme => ComponentRef::SecurityScheme(self.security_schemes.get(name)?),
+            ComponentKind::Response => ComponentRef::Response(self.responses.get(name)?),
+            ComponentKind::Parameter => ComponentRef::Parameter(self.parameters.get(name)?),
+            ComponentKind::Example => ComponentRef::Example(self.examples.get(name)?),
+            ComponentKind::RequestBody => ComponentRef::RequestBody(self.request_bodies.get(name)?),
+            ComponentKind::Header => ComponentRef::Header(self.headers.get(name)?),
+            ComponentKind::Schema => ComponentRef::Schema(self.schemas.get(name)?),
+            ComponentKind::Link => ComponentRef::Link(self.links.get(name)?),
+            ComponentKind::Callback => ComponentRef::Callback(self.callbacks.get(name)?),
+        })
+    }
+
+    /// Returns true if a component of the given kind and name exists.
+    pub fn contains(&self, kind: ComponentKind, name: &str) -> bool {
+        self.get(kind, name).is_some()
+    }
+
+    /// Inserts a component under the given name, returning the previous component if there was one.
+    /// The kind is determined by the variant of `value`.
+    pub fn insert(&mut self, name: impl Into<String>, value: Component) -> Option<Component> {
+        let name = name.into();
+        match value {
+            Component::SecurityScheme(v) => self.security_schemes.insert(name, v).map(Component::SecurityScheme),
+            Component::Response(v) => self.responses.insert(name, v).map(Component::Response),
+            Component::Parameter(v) => self.parameters.insert(name, v).map(Component::Parameter),
+            Component::Example(v) => self.examples.insert(name, v).map(Component::Example),
+            Component::RequestBody(v) => self.request_bodies.insert(name, v).map(Component::RequestBody),
+            Component::Header(v) => self.headers.insert(name, v).map(Component::Header),
+            Component::Schema(v) => self.schemas.insert(name, v).map(Component::Schema),
+            Component::Link(v) => self.links.insert(name, v).map(Component::Link),
+            Component::Callback(v) => self.callbacks.insert(name, v).map(Component::Callback),
+        }
+    }
+
+    /// Removes the component of the given kind and name, preserving the order of the remaining components.
+    pub fn remove(&mut self, kind: ComponentKind, name: &str) -> Option<Component> {
+        Some(match kind {
+            ComponentKind::SecurityScheme => Component::SecurityScheme(self.security_schemes.shift_remove(name)?),
+            ComponentKind::Response => Component::Response(self.responses.shift_remove(name)?),
+            ComponentKind::Parameter => Component::Parameter(self.parameters.shift_remove(name)?),
+            ComponentKind::Example => Component::Example(self.examples.shift_remove(name)?),
+            ComponentKind::RequestBody => Component::RequestBody(self.request_bodies.shift_remove(name)?),
+            ComponentKind::Header => Component::Header(self.headers.shift_remove(name)?),
+            ComponentKind::Schema => Component::Schema(self.schemas.shift_remove(name)?),
+            ComponentKind::Link => Component::Link(self.links.shift_remove(name)?),
+            ComponentKind::Callback => Component::Callback(self.callbacks.shift_remove(name)?),
+        })
+    }
+
+    /// Iterates over the names of the components of the given kind.
+    pub fn keys(&self, kind: ComponentKind) -> Box<dyn Iterator<Item=&String> + '_> {
+        match kind {
+            ComponentKind::SecurityScheme => Box::new(self.security_schemes.keys()),
+            ComponentKind::Response => Box::new(self.responses.keys()),
+            ComponentKind::Parameter => Box::new(self.parameters.keys()),
+            ComponentKind::Example => Box::new(self.examples.keys()),
+            ComponentKind::RequestBody => Box::new(self.request_bodies.keys()),
+            ComponentKind::Header => Box::new(self.headers.keys()),
+            ComponentKind::Schema => Box::new(self.schemas.keys()),
+            ComponentKind::Link => Box::new(self.links.keys()),
+            ComponentKind::Callback => Box::new(self.callbacks.keys()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generic_access() {
+        let mut components = Components::default();
+        assert!(components.insert("Pet", Component::Schema(Schema::new_object().into())).is_none());
+        components.insert("Limit", Component::Parameter(Parameter::query("limit", Schema::new_integer()).into()));
+
+        assert!(matches!(components.get(ComponentKind::Schema, "Pet"), Some(ComponentRef::Schema(_))));
+        assert!(components.get(ComponentKind::Parameter, "Pet").is_none());
+        assert_eq!(components.keys(ComponentKind::Parameter).collect::<Vec<_>>(), vec!["Limit"]);
+        assert!(matches!(components.remove(ComponentKind::Schema, "Pet"), Some(Component::Schema(_))));
+        assert!(!components.contains(ComponentKind::Schema, "Pet"));
+
+        assert_eq!(ComponentKind::RequestBody.reference("Pet"), "#/components/requestBodies/Pet");
+        assert_eq!(ComponentKind::Schema.reference("a/b~c"), "#/components/schemas/a~1b~0c");
+        assert_eq!(ComponentKind::parse_reference("#/components/requestBodies/Pet"), Some((ComponentKind::RequestBody, "Pet")));
+        assert_eq!(ComponentKind::parse_reference("#/components/schemas/Pet/properties/id"), None);
+    }
+}
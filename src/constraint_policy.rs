@@ -0,0 +1,140 @@
+use serde_json::{Map, Value};
+
+use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key};
+use crate::*;
+
+/// Schema keywords removed by [ConstraintPolicy::strip].
+const CONSTRAINTS: [&str; 13] = [
+    "pattern", "minLength", "maxLength", "minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum",
+    "multipleOf", "minItems", "maxItems", "uniqueItems", "minProperties", "maxProperties",
+];
+
+/// What [OpenAPI::apply_constraint_policy] changes on every schema in the document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConstraintPolicy {
+    /// Remove all value constraints (lengths, ranges, patterns, counts) before applying limits.
+    pub strip: bool,
+    /// Give every string schema a `maxLength` of at most this value.
+    /// Enums and `format: binary` strings are left alone.
+    pub max_string_length: Option<usize>,
+    /// Give every array schema a `maxItems` of at most this value.
+    pub max_array_items: Option<usize>,
+}
+
+impl ConstraintPolicy {
+    /// A policy bounding every string and array, as security reviews commonly require.
+    pub fn bounded(max_string_length: usize, max_array_items: usize) -> Self {
+        ConstraintPolicy {
+            strip: false,
+            max_string_length: Some(max_string_length),
+            max_array_items: Some(max_array_items),
+        }
+    }
+}
+
+/// A change made by [OpenAPI::apply_constraint_policy].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintChange {
+    /// JSON pointer to the changed schema.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConstraintChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+fn cap(map: &mut Map<String, Value>, key: &str, limit: usize, pointer: &str, changes: &mut Vec<ConstraintChange>) {
+    let message = match map.get(key).and_then(|v| v.as_u64()) {
+        Some(existing) if existing <= limit as u64 => return,
+        Some(existing) => format!("{} lowered from {} to {}", key, existing, limit),
+        None => format!("{} {} added", key, limit),
+    };
+    map.insert(key.to_string(), Value::from(limit));
+    changes.push(ConstraintChange { pointer: pointer.to_string(), message });
+}
+
+fn apply(value: &mut Value, policy: &ConstraintPolicy, pointer: &str, is_name_map: bool, changes: &mut Vec<ConstraintChange>) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                if policy.strip {
+                    let removed: Vec<&str> = CONSTRAINTS.iter().copied().filter(|k| map.remove(*k).is_some()).collect();
+                    if !removed.is_empty() {
+                        changes.push(ConstraintChange { pointer: pointer.to_string(), message: format!("removed {}", removed.join(", ")) });
+                    }
+                }
+                match map.get("type").and_then(|t| t.as_str()) {
+                    Some("string") if !map.contains_key("enum") && map.get("format") != Some(&Value::from("binary")) => {
+                        if let Some(limit) = policy.max_string_length {
+                            cap(map, "maxLength", limit, pointer, changes);
+                        }
+                    }
+                    Some("array") => {
+                        if let Some(limit) = policy.max_array_items {
+                            cap(map, "maxItems", limit, pointer, changes);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                let names = !is_name_map && is_name_map_key(key);
+                apply(child, policy, &format!("{}/{}", pointer, escape_pointer_segment(key)), names, changes);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                apply(item, policy, &format!("{}/{}", pointer, i), false, changes);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl OpenAPI {
+    /// Applies the policy to every schema in the document, inline or in components,
+    /// and returns what changed.
+    pub fn apply_constraint_policy(&mut self, policy: &ConstraintPolicy) -> Vec<ConstraintChange> {
+        let mut changes = Vec::new();
+        self.edit_json(|doc| apply(doc, policy, "#", false, &mut changes));
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_constraint_policy() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        pet.properties_mut().insert("name", serde_json::from_value::<Schema>(json!({"type": "string", "maxLength": 500})).unwrap());
+        pet.properties_mut().insert("kind", Schema::new_str_enum(vec!["cat".to_string()]));
+        pet.properties_mut().insert("tags", Schema::new_array(Schema::new_string()));
+        pet.example = Some(json!({"type": "string"}));
+        spec.schemas.insert("Pet", pet);
+
+        let changes = spec.apply_constraint_policy(&ConstraintPolicy::bounded(100, 50));
+        let messages: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(messages, vec![
+            "#/components/schemas/Pet/properties/name: maxLength lowered from 500 to 100",
+            "#/components/schemas/Pet/properties/tags: maxItems 50 added",
+            "#/components/schemas/Pet/properties/tags/items: maxLength 100 added",
+        ]);
+        assert!(spec.apply_constraint_policy(&ConstraintPolicy::bounded(100, 50)).is_empty());
+
+        let changes = spec.apply_constraint_policy(&ConstraintPolicy { strip: true, ..ConstraintPolicy::default() });
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].message, "removed maxLength");
+        assert_eq!(spec.schemas.get2("Pet").unwrap().example, Some(json!({"type": "string"})));
+    }
+}
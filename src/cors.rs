@@ -0,0 +1,174 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+use crate::util::{is_false, read_extension, write_extension};
+use crate::validate::path_pointer;
+
+const CORS_EXTENSION: &str = "x-cors";
+
+/// Cross-origin resource sharing behavior, stored in an `x-cors` extension on a [Server] or [PathItem].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CorsPolicy {
+    /// Origins allowed to make requests, or `*` for any origin.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed in cross-origin requests, e.g. `GET`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_methods: Vec<String>,
+    /// Request headers allowed in cross-origin requests.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_headers: Vec<String>,
+    /// Response headers exposed to the requesting script.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exposed_headers: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_credentials: bool,
+    /// How long, in seconds, preflight responses may be cached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+}
+
+impl CorsPolicy {
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins.iter().any(|o| o == "*" || o == origin)
+    }
+
+    pub fn allows_method(&self, method: &PathMethod) -> bool {
+        self.allowed_methods.iter().any(|m| m == "*" || m.eq_ignore_ascii_case(method.as_str()))
+    }
+
+    /// Problems with the policy itself, independent of where it's used.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.allow_credentials && self.allowed_origins.iter().any(|o| o == "*") {
+            problems.push("allowCredentials cannot be combined with a wildcard origin".to_string());
+        }
+        for method in &self.allowed_methods {
+            if method != "*" && PathMethod::from_bytes(method.as_bytes()).is_err() {
+                problems.push(format!("{} is not a valid method", method));
+            }
+        }
+        problems
+    }
+}
+
+fn read_cors(extensions: &IndexMap<String, serde_json::Value>) -> Option<serde_json::Result<CorsPolicy>> {
+    read_extension(extensions, CORS_EXTENSION)
+}
+
+fn write_cors(extensions: &mut IndexMap<String, serde_json::Value>, policy: &CorsPolicy) {
+    write_extension(extensions, CORS_EXTENSION, policy)
+}
+
+impl PathItem {
+    /// Returns the `x-cors` policy of this path, if it exists and is well-formed.
+    pub fn cors(&self) -> Option<CorsPolicy> {
+        read_cors(&self.extensions)?.ok()
+    }
+
+    pub fn set_cors(&mut self, policy: &CorsPolicy) {
+        write_cors(&mut self.extensions, policy)
+    }
+}
+
+impl Server {
+    /// Returns the `x-cors` policy of this server, if it exists and is well-formed.
+    pub fn cors(&self) -> Option<CorsPolicy> {
+        read_cors(&self.extensions)?.ok()
+    }
+
+    pub fn set_cors(&mut self, policy: &CorsPolicy) {
+        write_cors(&mut self.extensions, policy)
+    }
+}
+
+impl OpenAPI {
+    /// Returns the CORS policy which applies to the given path.
+    ///
+    /// A policy on the path item takes precedence, followed by the path item's servers,
+    /// then the document's servers. Among servers, the first with a policy wins.
+    pub fn cors_policy_for(&self, path: &str) -> Option<CorsPolicy> {
+        let item = self.paths.get(path).and_then(|item| item.as_item());
+        if let Some(policy) = item.and_then(PathItem::cors) {
+            return Some(policy);
+        }
+        item.into_iter()
+            .flat_map(|item| item.servers.iter())
+            .chain(self.servers.iter())
+            .find_map(Server::cors)
+    }
+}
+
+/// Reports malformed `x-cors` extensions, invalid policies, and path policies allowing
+/// methods the path doesn't define.
+pub(crate) fn validate_cors(spec: &OpenAPI, errors: &mut Vec<ValidationError>) {
+    for (i, server) in spec.servers.iter().enumerate() {
+        check_cors(format!("#/servers/{}", i), &server.extensions, None, errors);
+    }
+}
+
+pub(crate) fn validate_cors_path(path: &str, item: &PathItem, errors: &mut Vec<ValidationError>) {
+    check_cors(path_pointer(path), &item.extensions, Some(item), errors);
+    for (i, server) in item.servers.iter().enumerate() {
+        check_cors(format!("{}/servers/{}", path_pointer(path), i), &server.extensions, None, errors);
+    }
+}
+
+fn check_cors(pointer: String, extensions: &IndexMap<String, serde_json::Value>, item: Option<&PathItem>, errors: &mut Vec<ValidationError>) {
+    let pointer = format!("{}/{}", pointer, CORS_EXTENSION);
+    let policy = match read_cors(extensions) {
+        None => return,
+        Some(Err(e)) => return errors.push(ValidationError::new(pointer, format!("Invalid CORS policy: {}", e))),
+        Some(Ok(policy)) => policy,
+    };
+    for problem in policy.problems() {
+        errors.push(ValidationError::new(&pointer, problem));
+    }
+    if let Some(item) = item {
+        let defined: Vec<PathMethod> = item.methods().collect();
+        for method in &policy.allowed_methods {
+            let undefined = PathMethod::from_bytes(method.to_uppercase().as_bytes())
+                .map(|m| m != PathMethod::OPTIONS && !defined.contains(&m))
+                .unwrap_or(false);
+            if undefined {
+                errors.push(ValidationError::new(&pointer, format!("Allows {} which the path does not define", method)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cors_policy_for() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0").with_server("https://api.example.com");
+        api.servers[0].set_cors(&CorsPolicy {
+            allowed_origins: vec!["*".to_string()],
+            ..CorsPolicy::default()
+        });
+        api.add_operation(PathMethod::GET, "/pets", Operation::default()).unwrap();
+        api.add_operation(PathMethod::GET, "/admin", Operation::default()).unwrap();
+        let admin = CorsPolicy {
+            allowed_origins: vec!["https://admin.example.com".to_string()],
+            allowed_methods: vec!["GET".to_string(), "DELETE".to_string()],
+            allow_credentials: true,
+            ..CorsPolicy::default()
+        };
+        api.paths.get_mut("/admin").unwrap().as_mut().unwrap().set_cors(&admin);
+
+        assert_eq!(api.cors_policy_for("/admin"), Some(admin));
+        assert!(api.cors_policy_for("/pets").unwrap().allows_origin("https://anywhere.example.com"));
+
+        let errors = api.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pointer, "#/paths/~1admin/x-cors");
+        assert_eq!(errors[0].message, "Allows DELETE which the path does not define");
+
+        api.servers[0].extensions.insert("x-cors".to_string(), serde_json::json!({"allowCredentials": true, "allowedOrigins": "*"}));
+        assert!(api.validate().iter().any(|e| e.pointer == "#/servers/0/x-cors" && e.message.starts_with("Invalid CORS policy")));
+    }
+}
@@ -0,0 +1,254 @@
+use serde_json::{json, Value};
+
+use crate::util::escape_pointer_segment;
+use crate::*;
+
+/// Deeper nesting isn't searched for constraints to violate.
+const MAX_DEPTH: usize = 8;
+
+/// The constraint a value from [Schema::generate_counterexamples] violates. Pointers point into
+/// the value, as in [ValidationError]s, e.g. `#/owner/name`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpectedViolation {
+    WrongType { pointer: String, expected: &'static str },
+    MissingRequired { pointer: String, property: String },
+    /// A bound of `minimum`, `maximum`, `minLength`, `maxLength`, `minItems` or `maxItems`, named by `constraint`.
+    OutOfRange { pointer: String, constraint: &'static str },
+    NotInEnum { pointer: String },
+}
+
+impl ExpectedViolation {
+    /// Where the violation is in the value, e.g. `#/owner/name`, or `#` for the value itself.
+    pub fn pointer(&self) -> &str {
+        match self {
+            ExpectedViolation::WrongType { pointer, .. }
+            | ExpectedViolation::MissingRequired { pointer, .. }
+            | ExpectedViolation::OutOfRange { pointer, .. }
+            | ExpectedViolation::NotInEnum { pointer } => pointer,
+        }
+    }
+}
+
+struct Mutator<'a> {
+    spec: &'a OpenAPI,
+    /// References being searched.
+    stack: Vec<String>,
+    /// Replacements for the value at a pointer, each breaking one constraint.
+    mutations: Vec<(String, Value, ExpectedViolation)>,
+}
+
+impl Mutator<'_> {
+    fn visit(&mut self, schema: &RefOr<Schema>, value: &Value, pointer: &str, depth: usize) {
+        match schema {
+            RefOr::Reference { reference } => {
+                if self.stack.contains(reference) {
+                    return;
+                }
+                let Ok(resolved) = Resolve::resolve(schema, self.spec) else { return };
+                self.stack.push(reference.clone());
+                self.visit_item(resolved, value, pointer, depth);
+                self.stack.pop();
+            }
+            RefOr::Item(schema) => self.visit_item(schema, value, pointer, depth),
+        }
+    }
+
+    fn push(&mut self, pointer: &str, value: Value, violation: ExpectedViolation) {
+        self.mutations.push((pointer.to_string(), value, violation));
+    }
+
+    fn wrong_type(&mut self, pointer: &str, expected: &'static str) {
+        let value = match expected {
+            "string" => json!(0),
+            "boolean" => json!("true"),
+            "object" => json!([]),
+            _ => json!("string"),
+        };
+        self.push(pointer, value, ExpectedViolation::WrongType { pointer: pointer.to_string(), expected });
+    }
+
+    fn out_of_range(&mut self, pointer: &str, value: Value, constraint: &'static str) {
+        self.push(pointer, value, ExpectedViolation::OutOfRange { pointer: pointer.to_string(), constraint });
+    }
+
+    fn visit_item(&mut self, schema: &Schema, value: &Value, pointer: &str, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let SchemaKind::Type(typ) = &schema.kind else { return };
+        let not_in_enum = ExpectedViolation::NotInEnum { pointer: pointer.to_string() };
+        match typ {
+            Type::String(s) => {
+                self.wrong_type(pointer, "string");
+                if let Some(first) = s.enumeration.first() {
+                    self.push(pointer, json!(format!("{}-invalid", first)), not_in_enum);
+                    return;
+                }
+                if let Some(min) = s.min_length.filter(|m| *m > 0) {
+                    self.out_of_range(pointer, json!("x".repeat(min - 1)), "minLength");
+                }
+                if let Some(max) = s.max_length.and_then(|m| m.checked_add(1)) {
+                    self.out_of_range(pointer, json!("x".repeat(max)), "maxLength");
+                }
+            }
+            Type::Integer(n) => {
+                self.wrong_type(pointer, "integer");
+                if let Some(max) = n.enumeration.iter().flatten().max() {
+                    // At the limit of i64, go below the smallest value instead.
+                    let min = n.enumeration.iter().flatten().min().copied().unwrap_or(*max);
+                    if let Some(outside) = max.checked_add(1).or_else(|| min.checked_sub(1)) {
+                        self.push(pointer, json!(outside), not_in_enum);
+                    }
+                    return;
+                }
+                // Bounds at the limit of i64 can't be broken, since the value wouldn't be an i64.
+                if let Some(below) = n.minimum.and_then(|min| if n.exclusive_minimum { Some(min) } else { min.checked_sub(1) }) {
+                    self.out_of_range(pointer, json!(below), "minimum");
+                }
+                if let Some(above) = n.maximum.and_then(|max| if n.exclusive_maximum { Some(max) } else { max.checked_add(1) }) {
+                    self.out_of_range(pointer, json!(above), "maximum");
+                }
+            }
+            Type::Number(n) => {
+                self.wrong_type(pointer, "number");
+                if let Some(max) = n.enumeration.iter().flatten().copied().reduce(f64::max) {
+                    self.push(pointer, json!(max + 1.0), not_in_enum);
+                    return;
+                }
+                if let Some(min) = n.minimum {
+                    self.out_of_range(pointer, json!(if n.exclusive_minimum { min } else { min - 1.0 }), "minimum");
+                }
+                if let Some(max) = n.maximum {
+                    self.out_of_range(pointer, json!(if n.exclusive_maximum { max } else { max + 1.0 }), "maximum");
+                }
+            }
+            Type::Boolean {} => self.wrong_type(pointer, "boolean"),
+            Type::Object(o) => {
+                self.wrong_type(pointer, "object");
+                let Some(map) = value.as_object() else { return };
+                for name in &o.required {
+                    let mut without = map.clone();
+                    if without.remove(name).is_some() {
+                        self.push(pointer, Value::Object(without), ExpectedViolation::MissingRequired { pointer: pointer.to_string(), property: name.clone() });
+                    }
+                }
+                for (name, property) in o.properties.iter() {
+                    if let Some(child) = map.get(name) {
+                        self.visit(property, child, &format!("{}/{}", pointer, escape_pointer_segment(name)), depth + 1);
+                    }
+                }
+            }
+            Type::Array(a) => {
+                self.wrong_type(pointer, "array");
+                let Some(items) = value.as_array() else { return };
+                if let (Some(min), Some(first)) = (a.min_items.filter(|m| *m > 0), items.first()) {
+                    self.out_of_range(pointer, Value::Array(vec![first.clone(); min - 1]), "minItems");
+                }
+                if let (Some(max), Some(first)) = (a.max_items, items.first()) {
+                    // Distinct items, so that uniqueItems isn't violated as well.
+                    let more = (0..=max).map(|i| if i == 0 { first.clone() } else { json!(i) }).collect();
+                    self.out_of_range(pointer, Value::Array(more), "maxItems");
+                }
+                if let (Some(schema), Some(first)) = (&a.items, items.first()) {
+                    self.visit(schema, first, &format!("{}/0", pointer), depth + 1);
+                }
+            }
+        }
+    }
+}
+
+impl Schema {
+    /// Generates up to `n` values which each violate exactly one constraint of the schema, for
+    /// negative contract tests: a wrong type, a missing required property, a value outside a
+    /// range or length bound, or a value outside an enum.
+    ///
+    /// Values are derived from [Schema::generate_example], changing one place at a time, and
+    /// only kept if [Schema::validate_value] reports exactly one error. Returns nothing if the
+    /// example itself isn't valid.
+    pub fn generate_counterexamples(&self, spec: &OpenAPI, n: usize) -> Vec<(Value, ExpectedViolation)> {
+        let example = self.generate_example(spec);
+        if !self.validate_value(&example, spec).is_empty() {
+            return Vec::new();
+        }
+        let mut mutator = Mutator { spec, stack: Vec::new(), mutations: Vec::new() };
+        mutator.visit_item(self, &example, "#", 0);
+        mutator.mutations.into_iter()
+            .filter_map(|(pointer, replacement, violation)| {
+                let mut value = example.clone();
+                *value.pointer_mut(&pointer[1..])? = replacement;
+                (self.validate_value(&value, spec).len() == 1).then_some((value, violation))
+            })
+            .take(n)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_counterexamples() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    Owner:
+      type: object
+      required: [name]
+      properties:
+        name: {type: string, minLength: 1}
+"##).unwrap();
+        let pet: Schema = serde_yaml::from_str(r##"
+type: object
+required: [age, kind]
+properties:
+  age: {type: integer, minimum: 0, maximum: 30}
+  kind: {type: string, enum: [cat, dog]}
+  owner: {$ref: "#/components/schemas/Owner"}
+"##).unwrap();
+
+        let counterexamples = pet.generate_counterexamples(&spec, 100);
+        let violations: Vec<&ExpectedViolation> = counterexamples.iter().map(|(_, v)| v).collect();
+        assert_eq!(violations, vec![
+            &ExpectedViolation::WrongType { pointer: "#".to_string(), expected: "object" },
+            &ExpectedViolation::MissingRequired { pointer: "#".to_string(), property: "age".to_string() },
+            &ExpectedViolation::MissingRequired { pointer: "#".to_string(), property: "kind".to_string() },
+            &ExpectedViolation::WrongType { pointer: "#/age".to_string(), expected: "integer" },
+            &ExpectedViolation::OutOfRange { pointer: "#/age".to_string(), constraint: "minimum" },
+            &ExpectedViolation::OutOfRange { pointer: "#/age".to_string(), constraint: "maximum" },
+            &ExpectedViolation::WrongType { pointer: "#/kind".to_string(), expected: "string" },
+            &ExpectedViolation::NotInEnum { pointer: "#/kind".to_string() },
+            &ExpectedViolation::WrongType { pointer: "#/owner".to_string(), expected: "object" },
+            &ExpectedViolation::MissingRequired { pointer: "#/owner".to_string(), property: "name".to_string() },
+            &ExpectedViolation::WrongType { pointer: "#/owner/name".to_string(), expected: "string" },
+            &ExpectedViolation::OutOfRange { pointer: "#/owner/name".to_string(), constraint: "minLength" },
+        ]);
+        assert_eq!(counterexamples[4].0, json!({"age": -1, "kind": "cat", "owner": {"name": "string"}}));
+        for (value, violation) in &counterexamples {
+            assert_eq!(pet.validate_value(value, &spec)[0].pointer, violation.pointer());
+        }
+        assert_eq!(pet.generate_counterexamples(&spec, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_counterexamples_at_i64_limits() {
+        let spec = OpenAPI::default();
+        let mut full = Schema::new_integer();
+        if let SchemaKind::Type(Type::Integer(n)) = &mut full.kind {
+            n.minimum = Some(i64::MIN);
+            n.maximum = Some(i64::MAX);
+        }
+        let violations: Vec<ExpectedViolation> = full.generate_counterexamples(&spec, 10).into_iter().map(|(_, v)| v).collect();
+        assert_eq!(violations, vec![ExpectedViolation::WrongType { pointer: "#".to_string(), expected: "integer" }]);
+
+        let mut top = Schema::new_integer();
+        if let SchemaKind::Type(Type::Integer(n)) = &mut top.kind {
+            n.enumeration = vec![Some(0), Some(i64::MAX)];
+        }
+        let counterexamples = top.generate_counterexamples(&spec, 10);
+        assert_eq!(counterexamples[1], (json!(-1), ExpectedViolation::NotInEnum { pointer: "#".to_string() }));
+    }
+}
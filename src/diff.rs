@@ -0,0 +1,642 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::*;
+use crate::trace::Phase;
+use crate::util::{escape_pointer_segment, is_name_map_key, remove_matching, to_json, unescape_pointer_segment};
+
+/// How a change affects existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Existing clients may stop working.
+    Breaking,
+    /// Additive, or loosens a constraint.
+    NonBreaking,
+    /// Only descriptions, summaries, examples, or document metadata changed.
+    Docs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A single difference between two documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// JSON pointer of the changed value, e.g. `#/paths/~1pets/get/parameters/0/required`.
+    /// Points into the new document, or the old one for removals.
+    pub pointer: String,
+    pub kind: ChangeKind,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// What a [Change] belongs to, derived from its pointer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChangeSubject {
+    Operation { method: String, path: String },
+    Path(String),
+    Component { kind: ComponentKind, name: String },
+    Document,
+}
+
+impl ChangeSubject {
+    /// A markdown anchor for the subject, e.g. `get-pets-id` for `GET /pets/{id}`.
+    pub fn anchor(&self) -> String {
+        let text = match self {
+            ChangeSubject::Operation { method, path } => format!("{} {}", method, path),
+            ChangeSubject::Path(path) => path.clone(),
+            ChangeSubject::Component { kind, name } => format!("{} {}", kind, name),
+            ChangeSubject::Document => "document".to_string(),
+        };
+        let mut anchor = String::new();
+        for c in text.chars() {
+            if c.is_alphanumeric() {
+                anchor.extend(c.to_lowercase());
+            } else if !anchor.is_empty() && !anchor.ends_with('-') {
+                anchor.push('-');
+            }
+        }
+        anchor.trim_end_matches('-').to_string()
+    }
+}
+
+impl fmt::Display for ChangeSubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeSubject::Operation { method, path } => write!(f, "{} {}", method, path),
+            ChangeSubject::Path(path) => write!(f, "{}", path),
+            ChangeSubject::Component { kind, name } => write!(f, "{} {}", kind, name),
+            ChangeSubject::Document => write!(f, "document"),
+        }
+    }
+}
+
+impl Change {
+    fn new(pointer: &str, kind: ChangeKind, severity: Severity, message: impl Into<String>) -> Self {
+        Change {
+            pointer: pointer.to_string(),
+            kind,
+            severity,
+            message: message.into(),
+        }
+    }
+
+    fn segments(&self) -> Vec<String> {
+        self.pointer.trim_start_matches('#')
+            .split('/')
+            .skip(1)
+            .map(unescape_pointer_segment)
+            .collect()
+    }
+
+    /// The operation, path, or component this change belongs to, and the number of
+    /// pointer segments that identify it.
+    fn subject_and_depth(&self) -> (ChangeSubject, usize) {
+        let segments = self.segments();
+        match segments.as_slice() {
+            [paths, path, method, ..] if paths == "paths" && METHODS.contains(&method.as_str()) => (ChangeSubject::Operation {
+                method: method.to_uppercase(),
+                path: path.clone(),
+            }, 3),
+            [paths, path, ..] if paths == "paths" => (ChangeSubject::Path(path.clone()), 2),
+            [components, kind, name, ..] if components == "components" => {
+                match ComponentKind::ALL.iter().find(|k| k.as_str() == kind) {
+                    Some(kind) => (ChangeSubject::Component { kind: *kind, name: name.clone() }, 3),
+                    None => (ChangeSubject::Document, 0),
+                }
+            }
+            _ => (ChangeSubject::Document, 0),
+        }
+    }
+
+    pub fn subject(&self) -> ChangeSubject {
+        self.subject_and_depth().0
+    }
+
+    /// The pointer relative to [Change::subject], e.g. `parameters/0/required`. Empty if the
+    /// change is to the subject itself.
+    pub fn location(&self) -> String {
+        let depth = self.subject_and_depth().1;
+        self.pointer.trim_start_matches('#')
+            .split('/')
+            .skip(1 + depth)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// The differences between two documents, as computed by [OpenAPI::diff].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecDiff {
+    pub changes: Vec<Change>,
+}
+
+impl SpecDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|c| c.severity == Severity::Breaking)
+    }
+
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item=&Change> {
+        self.changes.iter().filter(move |c| c.severity == severity)
+    }
+
+    /// The semantic version increment implied by the changes.
+    pub fn suggested_version_bump(&self) -> VersionBump {
+        self.changes.iter()
+            .map(|c| match c.severity {
+                Severity::Breaking => VersionBump::Major,
+                Severity::NonBreaking => VersionBump::Minor,
+                Severity::Docs => VersionBump::Patch,
+            })
+            .min()
+            .unwrap_or(VersionBump::None)
+    }
+
+    /// Renders the diff as a markdown changelog, grouped by severity.
+    pub fn render_markdown(&self, opts: &ChangelogOptions) -> String {
+        let mut out = format!("# {}\n", opts.title);
+        if self.is_empty() {
+            out.push_str("\nNo changes.\n");
+            return out;
+        }
+        let sections = [
+            (Severity::Breaking, "Breaking changes"),
+            (Severity::NonBreaking, "Non-breaking changes"),
+            (Severity::Docs, "Documentation changes"),
+        ];
+        for (severity, heading) in sections.iter() {
+            if *severity == Severity::Docs && !opts.include_docs {
+                continue;
+            }
+            let mut changes = self.with_severity(*severity).peekable();
+            if changes.peek().is_none() {
+                continue;
+            }
+            out.push_str(&format!("\n## {}\n\n", heading));
+            for change in changes {
+                let subject = change.subject();
+                let subject = if opts.link_anchors {
+                    format!("[{}](#{}{})", subject, opts.anchor_prefix, subject.anchor())
+                } else {
+                    format!("**{}**", subject)
+                };
+                let location = change.location();
+                if location.is_empty() {
+                    out.push_str(&format!("- {} {}\n", subject, change.message));
+                } else {
+                    out.push_str(&format!("- {}: `{}` {}\n", subject, location, change.message));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A semantic version increment, see [SpecDiff::suggested_version_bump].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+    None,
+}
+
+impl OpenAPI {
+    /// Increments `info.version`, which must be a semantic version such as `1.4.2` or `v1.4.2`.
+    /// Pre-release and build metadata are dropped.
+    pub fn bump_version(&mut self, bump: VersionBump) -> anyhow::Result<()> {
+        let version = self.info.version.trim();
+        let (prefix, version) = match version.strip_prefix('v') {
+            Some(rest) => ("v", rest),
+            None => ("", version),
+        };
+        let core = version.split(['-', '+']).next().unwrap_or_default();
+        let parts = core.split('.')
+            .map(|p| p.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|p| p.len() == 3)
+            .ok_or_else(|| anyhow::anyhow!("info.version is not a semantic version: {}", self.info.version))?;
+        let (major, minor, patch) = (parts[0], parts[1], parts[2]);
+        let (major, minor, patch) = match bump {
+            VersionBump::Major => (major + 1, 0, 0),
+            VersionBump::Minor => (major, minor + 1, 0),
+            VersionBump::Patch => (major, minor, patch + 1),
+            VersionBump::None => (major, minor, patch),
+        };
+        self.info.version = format!("{}{}.{}.{}", prefix, major, minor, patch);
+        Ok(())
+    }
+}
+
+/// Options for [SpecDiff::render_markdown].
+#[derive(Debug, Clone)]
+pub struct ChangelogOptions {
+    /// Top-level heading of the changelog.
+    pub title: String,
+    /// Whether to include a section for documentation-only changes.
+    pub include_docs: bool,
+    /// Whether to link operations and components to anchors in the rendered API docs.
+    pub link_anchors: bool,
+    /// Prepended to every anchor, e.g. `operation-`.
+    pub anchor_prefix: String,
+}
+
+impl Default for ChangelogOptions {
+    fn default() -> Self {
+        ChangelogOptions {
+            title: "Changelog".to_string(),
+            include_docs: true,
+            link_anchors: true,
+            anchor_prefix: String::new(),
+        }
+    }
+}
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+/// Fields whose changes only affect documentation.
+const DOC_FIELDS: [&str; 6] = ["description", "summary", "title", "example", "examples", "externalDocs"];
+/// Schema keywords which restrict the accepted values when added.
+const CONSTRAINT_FIELDS: [&str; 16] = [
+    "type", "format", "pattern", "enum", "const", "multipleOf",
+    "maximum", "exclusiveMaximum", "minimum", "exclusiveMinimum",
+    "maxLength", "minLength", "maxItems", "minItems", "maxProperties", "minProperties",
+];
+
+fn render(value: &Value) -> String {
+    format!("`{}`", value)
+}
+
+fn is_required(value: &Value) -> bool {
+    value.get("required") == Some(&Value::Bool(true))
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(&mut self, pointer: &str, kind: ChangeKind, severity: Severity, message: impl Into<String>) {
+        self.changes.push(Change::new(pointer, kind, severity, message));
+    }
+
+    fn is_doc_field(pointer: &str, key: &str) -> bool {
+        DOC_FIELDS.contains(&key) || (pointer == "#" && (key == "info" || key == "tags"))
+    }
+
+    fn object(&mut self, pointer: &str, is_name_map: bool, old: &serde_json::Map<String, Value>, new: &serde_json::Map<String, Value>) {
+        let keys = old.keys().chain(new.keys().filter(|k| !old.contains_key(*k)));
+        for key in keys {
+            let child = format!("{}/{}", pointer, escape_pointer_segment(key));
+            match (old.get(key), new.get(key)) {
+                (Some(_), None) => self.removed(&child, pointer, key, is_name_map),
+                (None, Some(value)) => self.added(&child, pointer, key, is_name_map, value),
+                (Some(a), Some(b)) if a != b => self.value(&child, pointer, key, is_name_map, a, b),
+                _ => {}
+            }
+        }
+    }
+
+    fn added(&mut self, pointer: &str, parent: &str, key: &str, in_name_map: bool, value: &Value) {
+        let severity = if in_name_map {
+            if is_required(value) { Severity::Breaking } else { Severity::NonBreaking }
+        } else if Self::is_doc_field(parent, key) {
+            Severity::Docs
+        } else if key.starts_with("x-") {
+            Severity::NonBreaking
+        } else if CONSTRAINT_FIELDS.contains(&key) || (key == "requestBody" && is_required(value)) {
+            Severity::Breaking
+        } else if key == "required" {
+            if value == &Value::Bool(false) { Severity::NonBreaking } else { Severity::Breaking }
+        } else {
+            Severity::NonBreaking
+        };
+        let message = if in_name_map && severity == Severity::Breaking { "added as required" } else { "added" };
+        self.push(pointer, ChangeKind::Added, severity, message);
+    }
+
+    fn removed(&mut self, pointer: &str, parent: &str, key: &str, in_name_map: bool) {
+        let severity = if in_name_map {
+            Severity::Breaking
+        } else if Self::is_doc_field(parent, key) {
+            Severity::Docs
+        } else if key.starts_with("x-") || CONSTRAINT_FIELDS.contains(&key) || key == "required" || key == "deprecated" {
+            Severity::NonBreaking
+        } else {
+            Severity::Breaking
+        };
+        self.push(pointer, ChangeKind::Removed, severity, "removed");
+    }
+
+    fn value(&mut self, pointer: &str, parent: &str, key: &str, in_name_map: bool, old: &Value, new: &Value) {
+        if !in_name_map && parent == "#" && (key == "info" || key == "tags") {
+            // Document metadata: report the individual changes, but only as documentation.
+            let start = self.changes.len();
+            self.value(pointer, parent, "", false, old, new);
+            for change in &mut self.changes[start..] {
+                change.severity = Severity::Docs;
+            }
+            return;
+        }
+        if !in_name_map && Self::is_doc_field(parent, key) {
+            self.push(pointer, ChangeKind::Modified, Severity::Docs, "changed");
+            return;
+        }
+        match (old, new) {
+            (Value::Object(a), Value::Object(b)) => {
+                let is_name_map = !in_name_map && is_name_map_key(key);
+                self.object(pointer, is_name_map, a, b)
+            }
+            (Value::Array(a), Value::Array(b)) => self.array(pointer, key, a, b),
+            _ => {
+                let severity = Self::scalar_severity(key, old, new);
+                self.push(pointer, ChangeKind::Modified, severity, format!("changed from {} to {}", render(old), render(new)));
+            }
+        }
+    }
+
+    fn scalar_severity(key: &str, old: &Value, new: &Value) -> Severity {
+        let loosened = match (key, old.as_f64(), new.as_f64()) {
+            ("maximum" | "maxLength" | "maxItems" | "maxProperties", Some(a), Some(b)) => b > a,
+            ("minimum" | "minLength" | "minItems" | "minProperties", Some(a), Some(b)) => b < a,
+            _ => false,
+        };
+        if loosened || key.starts_with("x-") || key == "deprecated" {
+            Severity::NonBreaking
+        } else if key == "required" {
+            if new == &Value::Bool(true) { Severity::Breaking } else { Severity::NonBreaking }
+        } else if key == "nullable" {
+            if new == &Value::Bool(false) { Severity::Breaking } else { Severity::NonBreaking }
+        } else {
+            Severity::Breaking
+        }
+    }
+
+    fn array(&mut self, pointer: &str, key: &str, old: &[Value], new: &[Value]) {
+        match key {
+            "parameters" => self.parameters(pointer, old, new),
+            "enum" | "required" => {
+                let is_enum = key == "enum";
+                // A value replaced in place is reported as a rename rather than a removal and an addition.
+                let renamed: Vec<(&Value, &Value)> = if is_enum && old.len() == new.len() {
+                    old.iter().zip(new)
+                        .filter(|(a, b)| a != b && !new.contains(a) && !old.contains(b))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                for (a, b) in &renamed {
+                    self.push(pointer, ChangeKind::Modified, Severity::Breaking, format!("enum value {} renamed to {}", render(a), render(b)));
+                }
+                for value in old.iter().filter(|v| !new.contains(v) && !renamed.iter().any(|(a, _)| a == v)) {
+                    let (severity, message) = if is_enum {
+                        (Severity::Breaking, format!("enum value {} removed", render(value)))
+                    } else {
+                        (Severity::NonBreaking, format!("{} is no longer required", render(value)))
+                    };
+                    self.push(pointer, ChangeKind::Removed, severity, message);
+                }
+                for value in new.iter().filter(|v| !old.contains(v) && !renamed.iter().any(|(_, b)| b == v)) {
+                    let (severity, message) = if is_enum {
+                        (Severity::NonBreaking, format!("enum value {} added", render(value)))
+                    } else {
+                        (Severity::Breaking, format!("{} is now required", render(value)))
+                    };
+                    self.push(pointer, ChangeKind::Added, severity, message);
+                }
+            }
+            "tags" | "servers" => self.push(pointer, ChangeKind::Modified, Severity::NonBreaking, "changed"),
+            _ if old.len() == new.len() => {
+                for (i, (a, b)) in old.iter().zip(new).enumerate() {
+                    if a != b {
+                        self.value(&format!("{}/{}", pointer, i), pointer, "", false, a, b);
+                    }
+                }
+            }
+            _ => self.push(pointer, ChangeKind::Modified, Severity::Breaking, "changed"),
+        }
+    }
+
+    /// Parameters are matched by name and location, or by reference, rather than by index.
+    fn parameters(&mut self, pointer: &str, old: &[Value], new: &[Value]) {
+        fn key(v: &Value) -> Option<String> {
+            if let Some(r) = v.get("$ref").and_then(Value::as_str) {
+                return Some(r.to_string());
+            }
+            Some(format!("{}:{}", v.get("in")?.as_str()?, v.get("name")?.as_str()?))
+        }
+        let find = |list: &[Value], k: &Option<String>| list.iter().position(|v| &key(v) == k);
+        for (i, a) in old.iter().enumerate() {
+            let child = format!("{}/{}", pointer, i);
+            match find(new, &key(a)) {
+                None => self.push(&child, ChangeKind::Removed, Severity::Breaking, "removed"),
+                Some(j) if a != &new[j] => {
+                    self.value(&format!("{}/{}", pointer, j), pointer, "", true, a, &new[j])
+                }
+                _ => {}
+            }
+        }
+        for (j, b) in new.iter().enumerate() {
+            if find(old, &key(b)).is_none() {
+                self.added(&format!("{}/{}", pointer, j), pointer, "", true, b);
+            }
+        }
+    }
+}
+
+impl OpenAPI {
+    /// Compares this document against a newer version of it, classifying each change by
+    /// its impact on existing clients.
+    ///
+    /// The classification is conservative: any change that may break a client, such as
+    /// modifying a type or tightening a constraint, is reported as [Severity::Breaking].
+    /// References are compared as written and not resolved; changes to a component are
+    /// reported once, under `#/components`.
+    pub fn diff(&self, new: &OpenAPI) -> SpecDiff {
+        self.diff_ignoring(new, &[])
+    }
+
+    /// Like [OpenAPI::diff], but values at the given locations are excluded from both documents.
+    ///
+    /// Each selector is a JSON pointer such as `/info/version`, where a `*` segment matches any
+    /// single key or index and `**` matches any number of segments, e.g. `**/description`.
+    pub fn diff_ignoring(&self, new: &OpenAPI, ignore: &[&str]) -> SpecDiff {
+        let phase = Phase::start("diff serialize");
+        let mut old = to_json(self);
+        let mut new = to_json(new);
+        let removed = remove_matching(&mut old, ignore) + remove_matching(&mut new, ignore);
+        phase.finish(removed);
+        let phase = Phase::start("diff compare");
+        let mut differ = Differ::default();
+        if let (Value::Object(a), Value::Object(b)) = (&old, &new) {
+            differ.object("#", false, a, b);
+        }
+        phase.finish(differ.changes.len());
+        SpecDiff { changes: differ.changes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> OpenAPI {
+        serde_yaml::from_str(r#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+            maximum: 100
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+        kind:
+          type: string
+          enum: [cat, dog]
+"#).unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = spec();
+        let mut new = spec();
+        assert!(old.diff(&new).is_empty());
+
+        new.info.description = Some("All about pets".to_string());
+        let (_, _, op) = new.operations_mut().next().unwrap();
+        op.add_query_param("offset", Schema::new_integer());
+        op.parameters.swap(0, 1);
+        let pet = new.schemas.get_mut2("Pet").unwrap();
+        pet.required_mut().push("kind".to_string());
+        new.schemas.insert("Owner", Schema::new_object());
+
+        let diff = old.diff(&new);
+        let changes: Vec<_> = diff.changes.iter()
+            .map(|c| (c.pointer.as_str(), c.severity))
+            .collect();
+        assert_eq!(changes, vec![
+            ("#/components/schemas/Pet/required", Severity::Breaking),
+            ("#/components/schemas/Owner", Severity::NonBreaking),
+            ("#/info/description", Severity::Docs),
+            ("#/paths/~1pets/get/parameters/0", Severity::NonBreaking),
+        ]);
+        assert!(diff.has_breaking_changes());
+        assert_eq!(diff.suggested_version_bump(), VersionBump::Major);
+        let change = &diff.changes[3];
+        assert_eq!(change.subject(), ChangeSubject::Operation { method: "GET".to_string(), path: "/pets".to_string() });
+        assert_eq!(change.location(), "parameters/0");
+        assert_eq!(change.subject().anchor(), "get-pets");
+
+        let opts = ChangelogOptions { include_docs: false, ..ChangelogOptions::default() };
+        assert_eq!(diff.render_markdown(&opts), "\
+# Changelog
+
+## Breaking changes
+
+- [schemas Pet](#schemas-pet): `required` `\"kind\"` is now required
+
+## Non-breaking changes
+
+- [schemas Owner](#schemas-owner) added
+- [GET /pets](#get-pets): `parameters/0` added
+");
+    }
+
+    #[test]
+    fn test_diff_ignoring() {
+        let old = spec();
+        let mut new = spec();
+        new.info.version = "1.1.0".to_string();
+        new.info.description = Some("All about pets".to_string());
+        new.schemas.get_mut2("Pet").unwrap().properties_mut().get_mut2("name").unwrap().description = Some("Name".to_string());
+        new.schemas.get_mut2("Pet").unwrap().properties_mut().get_mut2("kind").unwrap().description = Some("Kind".to_string());
+        assert_eq!(old.diff(&new).changes.len(), 4);
+
+        let diff = old.diff_ignoring(&new, &["#/info/version", "**/description"]);
+        assert!(diff.is_empty(), "{:?}", diff.changes);
+        let diff = old.diff_ignoring(&new, &["/info", "/components/schemas/*/properties/name"]);
+        let pointers: Vec<_> = diff.changes.iter().map(|c| c.pointer.as_str()).collect();
+        assert_eq!(pointers, vec!["#/components/schemas/Pet/properties/kind/description"]);
+
+        // A property named like an ignored field is still compared.
+        let mut new = spec();
+        new.schemas.get_mut2("Pet").unwrap().properties_mut().insert("description", Schema::new_string());
+        let diff = old.diff_ignoring(&new, &["**/description"]);
+        let pointers: Vec<_> = diff.changes.iter().map(|c| c.pointer.as_str()).collect();
+        assert_eq!(pointers, vec!["#/components/schemas/Pet/properties/description"]);
+        assert!(old.diff_ignoring(&new, &["**/properties/description"]).is_empty());
+    }
+
+    #[test]
+    fn test_diff_constraints() {
+        let old = spec();
+        let mut new = spec();
+        let mut json = serde_json::to_value(&new).unwrap();
+        json["paths"]["/pets"]["get"]["parameters"][0]["schema"]["maximum"] = 200.into();
+        json["components"]["schemas"]["Pet"]["properties"]["kind"]["enum"] = serde_json::json!(["cat"]);
+        new = serde_json::from_value(json).unwrap();
+        let diff = old.diff(&new);
+        let changes: Vec<_> = diff.changes.iter()
+            .map(|c| (c.message.as_str(), c.severity))
+            .collect();
+        assert_eq!(changes, vec![
+            ("enum value `\"dog\"` removed", Severity::Breaking),
+            ("changed from `100` to `200`", Severity::NonBreaking),
+        ]);
+
+        let mut renamed = spec();
+        renamed.schemas.get_mut2("Pet").unwrap().properties_mut().get_mut2("kind").unwrap()
+            .rename_enum_value(&serde_json::json!("dog"), serde_json::json!("canine")).unwrap();
+        let diff = old.diff(&renamed);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].message, "enum value `\"dog\"` renamed to `\"canine\"`");
+        assert_eq!(diff.changes[0].severity, Severity::Breaking);
+    }
+
+    #[test]
+    fn test_bump_version() {
+        let mut api = spec();
+        assert_eq!(api.diff(&api).suggested_version_bump(), VersionBump::None);
+        api.bump_version(VersionBump::Minor).unwrap();
+        assert_eq!(api.info.version, "1.1.0");
+        api.info.version = "v2.3.4-beta.1".to_string();
+        api.bump_version(VersionBump::Patch).unwrap();
+        assert_eq!(api.info.version, "v2.3.5");
+        api.bump_version(VersionBump::Major).unwrap();
+        assert_eq!(api.info.version, "v3.0.0");
+        api.info.version = "2023-01-01".to_string();
+        assert!(api.bump_version(VersionBump::Patch).is_err());
+    }
+}
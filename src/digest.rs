@@ -0,0 +1,134 @@
+use std::fmt;
+
+use anyhow::{anyhow, bail, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::*;
+use crate::versioned::walk_structure;
+
+const DIGEST_EXTENSION: &str = "x-content-digest";
+
+/// A hash algorithm for [OpenAPI::content_digest].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DigestAlgorithm {
+    Sha256,
+}
+
+impl DigestAlgorithm {
+    /// The algorithm's name in the HTTP Digest Algorithm registry (RFC 9530).
+    pub fn name(&self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha256 => "sha-256",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sha-256" => Some(DigestAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            DigestAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl OpenAPI {
+    /// Returns the canonical form of the document: minified JSON with object keys sorted,
+    /// excluding the `x-content-digest` extension. Parameter styles are left out where they are
+    /// the default for the parameter's location, whether or not `skip_serializing_defaults` is on.
+    pub fn canonical_json(&self) -> Result<String> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(root) = value.as_object_mut() {
+            root.remove(DIGEST_EXTENSION);
+        }
+        walk_structure(&mut value, false, &remove_default_style);
+        Ok(serde_json::to_string(&value)?)
+    }
+
+    /// Computes a digest of the [canonical form](OpenAPI::canonical_json) of the document, in the
+    /// `Content-Digest` format of RFC 9530, e.g. `sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:`.
+    pub fn content_digest(&self, algo: DigestAlgorithm) -> String {
+        let canonical = self.canonical_json().expect("OpenAPI serializes to JSON");
+        format!("{}=:{}:", algo.name(), BASE64.encode(algo.hash(canonical.as_bytes())))
+    }
+
+    /// Stores the [content digest](OpenAPI::content_digest) in the `x-content-digest` extension.
+    pub fn embed_content_digest(&mut self, algo: DigestAlgorithm) {
+        let digest = self.content_digest(algo);
+        self.extensions.insert(DIGEST_EXTENSION.to_string(), digest.into());
+    }
+
+    /// Checks the `x-content-digest` extension against the document's content.
+    /// Fails if it's missing, uses an unknown algorithm, or doesn't match.
+    pub fn verify_content_digest(&self) -> Result<()> {
+        let embedded = self.extensions.get(DIGEST_EXTENSION)
+            .ok_or_else(|| anyhow!("Document has no {} extension", DIGEST_EXTENSION))?
+            .as_str()
+            .ok_or_else(|| anyhow!("{} must be a string", DIGEST_EXTENSION))?;
+        let (name, _) = embedded.split_once('=')
+            .ok_or_else(|| anyhow!("Malformed digest {}", embedded))?;
+        let algo = DigestAlgorithm::from_name(name)
+            .ok_or_else(|| anyhow!("Unsupported digest algorithm {}", name))?;
+        let actual = self.content_digest(algo);
+        if actual != embedded {
+            bail!("Content digest mismatch: document has {}, content is {}", embedded, actual);
+        }
+        Ok(())
+    }
+}
+
+fn remove_default_style(map: &mut Map<String, Value>) {
+    let default = match map.get("in").and_then(Value::as_str) {
+        Some("query" | "cookie") => "form",
+        Some("path" | "header") => "simple",
+        _ => return,
+    };
+    if map.contains_key("name") && map.get("style").and_then(Value::as_str) == Some(default) {
+        map.remove("style");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_digest() {
+        let mut spec = OpenAPI::new("Pets", "1.0.0");
+        spec.embed_content_digest(DigestAlgorithm::Sha256);
+        assert!(spec.extensions["x-content-digest"].as_str().unwrap().starts_with("sha-256=:"));
+        spec.verify_content_digest().unwrap();
+
+        let yaml = serde_yaml::to_string(&spec).unwrap();
+        let reparsed: OpenAPI = serde_yaml::from_str(&yaml).unwrap();
+        reparsed.verify_content_digest().unwrap();
+
+        // Default styles are omitted, as with skip_serializing_defaults.
+        let mut op = Operation::default();
+        op.add_query_param("q", Schema::new_string());
+        spec.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        spec.embed_content_digest(DigestAlgorithm::Sha256);
+        assert!(!spec.canonical_json().unwrap().contains("style"));
+        let mut value = serde_json::to_value(&spec).unwrap();
+        value["paths"]["/pets"]["get"]["parameters"][0]["style"] = "form".into();
+        let with_style: OpenAPI = serde_json::from_value(value).unwrap();
+        with_style.verify_content_digest().unwrap();
+
+        spec.info.title = "Tampered".to_string();
+        assert!(spec.verify_content_digest().unwrap_err().to_string().starts_with("Content digest mismatch"));
+    }
+}
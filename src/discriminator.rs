@@ -1,5 +1,9 @@
+use anyhow::{anyhow, Result};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::*;
 
 /// When request bodies or response payloads may be one of a number of different schemas,
 /// a discriminator object can be used to aid in serialization, deserialization,
@@ -21,3 +25,168 @@ pub struct Discriminator {
     #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
     pub extensions: IndexMap<String, serde_json::Value>,
 }
+
+impl Discriminator {
+    /// The discriminator values which select the schema `reference`: its keys in `mapping`,
+    /// or else the schema name.
+    pub fn values_for(&self, reference: &str) -> Vec<String> {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+        let mapped: Vec<String> = self.mapping.iter()
+            .filter(|(_, target)| *target == reference || *target == name)
+            .map(|(value, _)| value.clone())
+            .collect();
+        if mapped.is_empty() {
+            vec![name.to_string()]
+        } else {
+            mapped
+        }
+    }
+}
+
+impl Schema {
+    /// For a `oneOf` with a discriminator, makes the discriminator property a required string of every
+    /// variant, inserting it where it's missing. Referenced variants are updated in `spec`.
+    ///
+    /// With `restrict_values`, the property of each referenced variant is limited to an enum of its
+    /// discriminator values. For `allOf` variants, the property goes into the inline object part.
+    /// Fails if a variant isn't an object, or declares the property with a type other than string.
+    pub fn ensure_discriminator_property(&mut self, spec: &mut OpenAPI, restrict_values: bool) -> Result<()> {
+        let discriminator = self.data.discriminator.as_ref().ok_or_else(|| anyhow!("Schema has no discriminator"))?;
+        let SchemaKind::OneOf { one_of } = &self.kind else {
+            return Err(anyhow!("Discriminator of {} requires oneOf", discriminator.property_name));
+        };
+        let property = &discriminator.property_name;
+        // Changes are made to copies, and only applied once every variant succeeded.
+        let mut variants = one_of.clone();
+        let mut referenced = Vec::new();
+        for variant in variants.iter_mut() {
+            match variant {
+                RefOr::Item(schema) => ensure_property(schema, property, None, spec)
+                    .map_err(|e| anyhow!("Inline variant: {}", e))?,
+                RefOr::Reference { reference } => {
+                    let name = reference.strip_prefix("#/components/schemas/")
+                        .ok_or_else(|| anyhow!("Variant {} is not a component schema", reference))?;
+                    let mut schema = spec.schemas.get2(name)
+                        .ok_or_else(|| anyhow!("Variant {} not found", reference))?
+                        .clone();
+                    let values = restrict_values.then(|| discriminator.values_for(reference));
+                    ensure_property(&mut schema, property, values, spec)
+                        .map_err(|e| anyhow!("Variant {}: {}", name, e))?;
+                    referenced.push((name.to_string(), schema));
+                }
+            }
+        }
+        for (name, schema) in referenced {
+            spec.schemas.insert(name, schema);
+        }
+        self.kind = SchemaKind::OneOf { one_of: variants };
+        Ok(())
+    }
+}
+
+fn check_string(schema: &Schema, property: &str) -> Result<()> {
+    match schema.kind {
+        SchemaKind::Type(Type::String(_)) | SchemaKind::Any(_) => Ok(()),
+        _ => Err(anyhow!("property {} is not a string", property)),
+    }
+}
+
+fn ensure_property(schema: &mut Schema, property: &str, values: Option<Vec<String>>, spec: &OpenAPI) -> Result<()> {
+    if let SchemaKind::AllOf { all_of } = &mut schema.kind {
+        let mut inherited = false;
+        for part in all_of.iter().filter(|part| part.as_ref_str().is_some()) {
+            let part = Resolve::resolve(part, spec)?;
+            if let Some(existing) = part.get_properties().and_then(|properties| properties.get(property)) {
+                check_string(Resolve::resolve(existing, spec)?, property)?;
+                inherited = true;
+            }
+        }
+        let index = match all_of.iter().position(|part| matches!(part, RefOr::Item(s) if s.get_properties().is_some())) {
+            Some(index) => index,
+            None => {
+                all_of.push(Schema::new_object().into());
+                all_of.len() - 1
+            }
+        };
+        let RefOr::Item(part) = &mut all_of[index] else { unreachable!() };
+        // An inherited property only needs to be required, unless its values are restricted.
+        if inherited && values.is_none() {
+            part.add_required(property);
+            return Ok(());
+        }
+        return ensure_property(part, property, values, spec);
+    }
+    let properties = schema.get_properties_mut().ok_or_else(|| anyhow!("not an object"))?;
+    match properties.get_mut(property) {
+        Some(RefOr::Item(existing)) => {
+            check_string(existing, property)?;
+            if let Some(values) = values {
+                existing.set_enum_values(values.into_iter().map(Value::from).collect())?;
+            }
+        }
+        // A referenced property may be shared, so it's only checked.
+        Some(existing @ RefOr::Reference { .. }) => check_string(Resolve::resolve(&*existing, spec)?, property)?,
+        None => {
+            let schema = match values {
+                Some(values) => Schema::new_str_enum(values),
+                None => Schema::new_string(),
+            };
+            properties.insert(property, schema);
+        }
+    }
+    schema.add_required(property);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_discriminator_property() {
+        let mut spec = OpenAPI::default();
+        spec.schemas.insert("Cat", Schema::new_object());
+        let mut base = Schema::new_object();
+        base.properties_mut().insert("petType", Schema::new_string());
+        spec.schemas.insert("Pet", base);
+        spec.schemas.insert("Dog", Schema::new_all_of(vec![RefOr::schema_ref("Pet")]));
+
+        let mut pet = Schema::new_one_of(vec![RefOr::schema_ref("Cat"), RefOr::schema_ref("Dog")]);
+        let mut discriminator = Discriminator {
+            property_name: "petType".to_string(),
+            ..Discriminator::default()
+        };
+        discriminator.mapping.insert("dog".to_string(), "#/components/schemas/Dog".to_string());
+        pet.discriminator = Some(discriminator);
+        pet.ensure_discriminator_property(&mut spec, true).unwrap();
+
+        let cat = spec.schemas.get2("Cat").unwrap();
+        assert!(cat.is_required("petType"));
+        assert_eq!(cat.properties().get2("petType").unwrap().enum_values(), vec![Value::from("Cat")]);
+        let SchemaKind::AllOf { all_of } = &spec.schemas.get2("Dog").unwrap().kind else { panic!() };
+        let own = all_of[1].as_item().unwrap();
+        assert_eq!(own.properties().get2("petType").unwrap().enum_values(), vec![Value::from("dog")]);
+
+        spec.schemas.get_mut2("Cat").unwrap().properties_mut().insert("petType", Schema::new_integer());
+        let error = pet.ensure_discriminator_property(&mut spec, false).unwrap_err();
+        assert_eq!(error.to_string(), "Variant Cat: property petType is not a string");
+
+        // Failing leaves the spec and the schema unchanged.
+        spec.schemas.insert("Cat", Schema::new_object());
+        spec.schemas.get_mut2("Pet").unwrap().properties_mut().insert("petType", Schema::new_integer());
+        let before = (spec.clone(), pet.clone());
+        let error = pet.ensure_discriminator_property(&mut spec, false).unwrap_err();
+        assert_eq!(error.to_string(), "Variant Dog: property petType is not a string");
+        assert_eq!((spec.clone(), pet.clone()), before);
+
+        // An inherited property is made required in the own part.
+        spec.schemas.get_mut2("Pet").unwrap().properties_mut().insert("petType", Schema::new_string());
+        spec.schemas.insert("Dog", Schema::new_all_of(vec![RefOr::schema_ref("Pet")]));
+        pet.ensure_discriminator_property(&mut spec, false).unwrap();
+        let SchemaKind::AllOf { all_of } = &spec.schemas.get2("Dog").unwrap().kind else { panic!() };
+        assert!(all_of[1].as_item().unwrap().is_required("petType"));
+
+        spec.schemas.insert("Dog", Schema::new_all_of(vec![RefOr::schema_ref("Missing")]));
+        assert!(pet.ensure_discriminator_property(&mut spec, false).is_err());
+    }
+}
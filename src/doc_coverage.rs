@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+
+use crate::*;
+
+/// How many items of one category have a description.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub documented: usize,
+    pub total: usize,
+}
+
+impl Coverage {
+    /// The documented fraction, 1.0 if there is nothing to document.
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.documented as f64 / self.total as f64
+        }
+    }
+
+    fn add(&mut self, text: Option<&str>) {
+        self.total += 1;
+        if text.is_some_and(|t| !t.trim().is_empty()) {
+            self.documented += 1;
+        }
+    }
+}
+
+/// [Coverage] for each kind of documented item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoverageBreakdown {
+    /// Operations with a summary or description.
+    pub operations: Coverage,
+    pub parameters: Coverage,
+    pub properties: Coverage,
+    pub responses: Coverage,
+}
+
+impl CoverageBreakdown {
+    fn categories(&self) -> [(&'static str, Coverage); 4] {
+        [
+            ("operation", self.operations),
+            ("parameter", self.parameters),
+            ("property", self.properties),
+            ("response", self.responses),
+        ]
+    }
+}
+
+/// Documentation coverage of a document, see [OpenAPI::doc_coverage].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocCoverage {
+    /// Properties are counted for every component schema and inline request or response schema.
+    pub total: CoverageBreakdown,
+    /// Properties are counted for the schemas each tag's operations use, following references.
+    /// Untagged operations only count towards the total.
+    pub by_tag: IndexMap<String, CoverageBreakdown>,
+}
+
+impl DocCoverage {
+    /// Fails, listing each category of the total whose coverage is below `min_ratio`. For CI checks.
+    pub fn check(&self, min_ratio: f64) -> Result<()> {
+        let failures: Vec<String> = self.total.categories().iter()
+            .filter(|(_, coverage)| coverage.ratio() < min_ratio)
+            .map(|(name, coverage)| format!("{} documentation coverage {:.1}% is below {:.1}%", name, coverage.ratio() * 100.0, min_ratio * 100.0))
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("{}", failures.join("; ")))
+        }
+    }
+}
+
+impl OpenAPI {
+    /// Measures how many operations, parameters, schema properties and responses have a description.
+    /// References which don't resolve are skipped.
+    pub fn doc_coverage(&self) -> DocCoverage {
+        let mut coverage = DocCoverage::default();
+        for schema in self.schemas.values() {
+            count_properties(schema, self, None, &mut coverage.total.properties);
+        }
+        for (_, _, op, item) in self.operations() {
+            let mut own = CoverageBreakdown::default();
+            own.operations.add(op.description.as_deref().or(op.summary.as_deref()));
+            for param in op.effective_parameters(item, self) {
+                own.parameters.add(param.description.as_deref());
+            }
+            let responses = op.responses.responses.values().chain(&op.responses.default)
+                .filter_map(|r| r.resolve(self).ok());
+            let mut schemas = Vec::new();
+            for response in responses {
+                own.responses.add(Some(&response.description));
+                schemas.extend(response.content.values().filter_map(|m| m.schema.as_ref()));
+            }
+            if let Some(body) = op.request_body.as_ref().and_then(|b| b.resolve(self).ok()) {
+                schemas.extend(body.content.values().filter_map(|m| m.schema.as_ref()));
+            }
+            let mut total = own;
+            for schema in &schemas {
+                count_properties(schema, self, None, &mut total.properties);
+            }
+            add(&mut coverage.total, &total);
+            for tag in &op.tags {
+                let tagged = coverage.by_tag.entry(tag.clone()).or_default();
+                let mut seen = HashSet::new();
+                let mut own = own;
+                for schema in &schemas {
+                    count_properties(schema, self, Some(&mut seen), &mut own.properties);
+                }
+                add(tagged, &own);
+            }
+        }
+        coverage
+    }
+}
+
+fn add(into: &mut CoverageBreakdown, from: &CoverageBreakdown) {
+    for (into, from) in [
+        (&mut into.operations, from.operations),
+        (&mut into.parameters, from.parameters),
+        (&mut into.properties, from.properties),
+        (&mut into.responses, from.responses),
+    ] {
+        into.documented += from.documented;
+        into.total += from.total;
+    }
+}
+
+/// Counts the properties of a schema and its subschemas. References are only followed given
+/// `seen`, the references already counted.
+fn count_properties(schema: &RefOr<Schema>, spec: &OpenAPI, mut seen: Option<&mut HashSet<String>>, coverage: &mut Coverage) {
+    let schema = match schema {
+        RefOr::Item(schema) => schema,
+        RefOr::Reference { reference } => {
+            let Some(seen) = seen.as_deref_mut() else { return };
+            if !seen.insert(reference.clone()) {
+                return;
+            }
+            let Ok(resolved) = Resolve::resolve(schema, spec) else { return };
+            resolved
+        }
+    };
+    let children: Vec<&RefOr<Schema>> = match &schema.kind {
+        SchemaKind::Type(Type::Object(o)) => {
+            for property in o.properties.values() {
+                let description = match property {
+                    RefOr::Item(p) => p.description.as_deref(),
+                    RefOr::Reference { .. } => Resolve::resolve(property, spec).ok().and_then(|p| p.description.as_deref()),
+                };
+                coverage.add(description);
+            }
+            o.properties.values().collect()
+        }
+        SchemaKind::Type(Type::Array(a)) => a.items.iter().map(|i| &**i).collect(),
+        SchemaKind::OneOf { one_of: parts } | SchemaKind::AllOf { all_of: parts } | SchemaKind::AnyOf { any_of: parts } => parts.iter().collect(),
+        _ => Vec::new(),
+    };
+    for child in children {
+        count_properties(child, spec, seen.as_deref_mut(), coverage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_coverage() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        let mut name = Schema::new_string();
+        name.description = Some("The pet's name".to_string());
+        pet.properties_mut().insert("name", name);
+        pet.properties_mut().insert("age", Schema::new_integer());
+        spec.schemas.insert("Pet", pet);
+        let mut op = Operation {
+            tags: vec!["pets".to_string()],
+            summary: Some("Get a pet".to_string()),
+            ..Operation::default()
+        };
+        op.add_path_param("id", Schema::new_string());
+        op.responses.responses.insert(StatusCode::Code(200), Response::new("The pet").json(RefOr::schema_ref("Pet")).into());
+        op.responses.responses.insert(StatusCode::Code(404), Response::default().into());
+        spec.paths.insert_operation("/pets/{id}".to_string(), PathMethod::GET, op);
+        spec.paths.insert_operation("/health".to_string(), PathMethod::GET, Operation::default());
+
+        let coverage = spec.doc_coverage();
+        assert_eq!(coverage.total.operations, Coverage { documented: 1, total: 2 });
+        assert_eq!(coverage.total.parameters, Coverage { documented: 0, total: 1 });
+        assert_eq!(coverage.total.properties, Coverage { documented: 1, total: 2 });
+        assert_eq!(coverage.total.responses, Coverage { documented: 1, total: 2 });
+        let pets = &coverage.by_tag["pets"];
+        assert_eq!(pets.operations.ratio(), 1.0);
+        assert_eq!(pets.properties, Coverage { documented: 1, total: 2 });
+        assert!(coverage.check(0.0).is_ok());
+        assert_eq!(coverage.check(0.5).unwrap_err().to_string(), "parameter documentation coverage 0.0% is below 50.0%");
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::{bail, Result};
+
+use crate::export::fragment::escape_json;
+use crate::OpenAPI;
+
+/// A browser documentation viewer, loaded from a CDN by [OpenAPI::docs_html].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsRenderer {
+    SwaggerUi,
+    Redoc,
+    Scalar,
+}
+
+const SWAGGER_UI_CSS: &str = "https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui.css";
+const SWAGGER_UI_JS: &str = "https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui-bundle.js";
+const REDOC_JS: &str = "https://cdn.jsdelivr.net/npm/redoc@2.1.5/bundles/redoc.standalone.js";
+const SCALAR_JS: &str = "https://cdn.jsdelivr.net/npm/@scalar/api-reference@1.25.0";
+
+impl DocsRenderer {
+    /// The URLs of the stylesheets and scripts the page loads, pinned to exact versions, in the
+    /// order [OpenAPI::docs_html_with_integrity] takes their hashes.
+    pub fn assets(&self) -> &'static [&'static str] {
+        match self {
+            DocsRenderer::SwaggerUi => &[SWAGGER_UI_CSS, SWAGGER_UI_JS],
+            DocsRenderer::Redoc => &[REDOC_JS],
+            DocsRenderer::Scalar => &[SCALAR_JS],
+        }
+    }
+}
+
+/// Escapes text for HTML content and double-quoted attributes.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(title: &str, head: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n{}</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title), head, body,
+    )
+}
+
+/// Where the page gets the spec from: a URL, or JSON embedded in the page.
+enum Source<'a> {
+    Url(&'a str),
+    Inline(String),
+}
+
+/// The attributes loading `url`, with an `integrity` check if a hash is given.
+fn asset(attribute: &str, url: &str, integrity: Option<&str>) -> String {
+    match integrity {
+        Some(hash) => format!("{}=\"{}\" integrity=\"{}\" crossorigin=\"anonymous\"", attribute, url, escape_html(hash)),
+        None => format!("{}=\"{}\"", attribute, url),
+    }
+}
+
+impl OpenAPI {
+    /// Returns a standalone HTML page rendering this spec, which is embedded inline.
+    pub fn docs_html(&self, renderer: DocsRenderer) -> String {
+        let json = self.to_minified_json_escaped().expect("OpenAPI serializes to JSON");
+        self.render_docs(renderer, Source::Inline(json), &[])
+    }
+
+    /// Like [OpenAPI::docs_html], but the page fetches the spec from `spec_url` instead of embedding it.
+    pub fn docs_html_for_url(&self, renderer: DocsRenderer, spec_url: &str) -> String {
+        self.render_docs(renderer, Source::Url(spec_url), &[])
+    }
+
+    /// Like [OpenAPI::docs_html], or [OpenAPI::docs_html_for_url] if `spec_url` is given, with
+    /// Subresource Integrity checks: `integrity` holds a hash such as `sha384-…` for each of
+    /// [DocsRenderer::assets], in order, and browsers refuse assets which don't match.
+    /// Fails if the number of hashes is wrong.
+    pub fn docs_html_with_integrity(&self, renderer: DocsRenderer, spec_url: Option<&str>, integrity: &[&str]) -> Result<String> {
+        if integrity.len() != renderer.assets().len() {
+            bail!("Expected {} integrity hashes, got {}", renderer.assets().len(), integrity.len());
+        }
+        let source = match spec_url {
+            Some(url) => Source::Url(url),
+            None => Source::Inline(self.to_minified_json_escaped()?),
+        };
+        Ok(self.render_docs(renderer, source, integrity))
+    }
+
+    fn render_docs(&self, renderer: DocsRenderer, source: Source, integrity: &[&str]) -> String {
+        let title = &self.info.title;
+        // A JSON string literal, escaped so it can't close the script element.
+        let js_string = |s: &str| escape_json(&serde_json::Value::from(s).to_string());
+        let hash = |i: usize| integrity.get(i).copied();
+        match renderer {
+            DocsRenderer::SwaggerUi => {
+                let spec = match source {
+                    Source::Url(url) => format!("url: {}", js_string(url)),
+                    Source::Inline(json) => format!("spec: {}", json),
+                };
+                page(
+                    title,
+                    &format!("<link rel=\"stylesheet\" {}>\n", asset("href", SWAGGER_UI_CSS, hash(0))),
+                    &format!("<div id=\"swagger-ui\"></div>\n<script {}></script>\n<script>\nSwaggerUIBundle({{ dom_id: \"#swagger-ui\", {} }});\n</script>\n", asset("src", SWAGGER_UI_JS, hash(1)), spec),
+                )
+            }
+            DocsRenderer::Redoc => {
+                let spec = match source {
+                    Source::Url(url) => js_string(url),
+                    Source::Inline(json) => json,
+                };
+                page(
+                    title,
+                    "",
+                    &format!("<div id=\"redoc\"></div>\n<script {}></script>\n<script>\nRedoc.init({}, {{}}, document.getElementById(\"redoc\"));\n</script>\n", asset("src", REDOC_JS, hash(0)), spec),
+                )
+            }
+            DocsRenderer::Scalar => {
+                let reference = match source {
+                    Source::Url(url) => format!("<script id=\"api-reference\" data-url=\"{}\"></script>\n", escape_html(url)),
+                    Source::Inline(json) => format!("<script id=\"api-reference\" type=\"application/json\">{}</script>\n", json),
+                };
+                page(
+                    title,
+                    "",
+                    &format!("{}<script {}></script>\n", reference, asset("src", SCALAR_JS, hash(0))),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_html() {
+        let mut spec = OpenAPI::default();
+        spec.info.title = "Pets <beta>".to_string();
+        spec.info.description = Some("</script><script>alert(1)</script>".to_string());
+
+        let html = spec.docs_html(DocsRenderer::SwaggerUi);
+        assert!(html.contains("<title>Pets &lt;beta&gt;</title>"));
+        assert!(html.contains("SwaggerUIBundle({ dom_id: \"#swagger-ui\", spec: {"));
+        assert!(!html.contains("</script><script>alert"));
+
+        let html = spec.docs_html_for_url(DocsRenderer::Redoc, "/openapi.json");
+        assert!(html.contains("Redoc.init(\"/openapi.json\", {}"));
+
+        let html = spec.docs_html_for_url(DocsRenderer::Scalar, "/openapi.json?a=1&b=2");
+        assert!(html.contains("data-url=\"/openapi.json?a=1&amp;b=2\""));
+        assert!(html.contains("<script src=\"https://cdn.jsdelivr.net/npm/@scalar/api-reference@1.25.0\"></script>"));
+
+        let html = spec.docs_html_with_integrity(DocsRenderer::SwaggerUi, None, &["sha384-css", "sha384-js"]).unwrap();
+        assert!(html.contains("href=\"https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui.css\" integrity=\"sha384-css\" crossorigin=\"anonymous\""));
+        assert!(html.contains("swagger-ui-bundle.js\" integrity=\"sha384-js\" crossorigin=\"anonymous\""));
+        assert!(spec.docs_html_with_integrity(DocsRenderer::Redoc, Some("/openapi.json"), &[]).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::*;
+
+const GENERATOR: &str = "x-generator";
+const GENERATOR_VERSION: &str = "x-generator-version";
+const GENERATED_AT: &str = "x-generated-at";
+const SOURCE_COMMIT: &str = "x-source-commit";
+
+/// Provenance of a generated document, stored as root extensions: `x-generator`,
+/// `x-generator-version`, `x-generated-at` and `x-source-commit`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentMeta {
+    /// The tool which generated the document.
+    pub generator: Option<String>,
+    pub generator_version: Option<String>,
+    /// When the document was generated, as an RFC 3339 timestamp.
+    pub generated_at: Option<String>,
+    /// The commit of the source the document was generated from.
+    pub source_commit: Option<String>,
+}
+
+impl DocumentMeta {
+    pub fn new(generator: impl Into<String>, generator_version: impl Into<String>) -> Self {
+        DocumentMeta {
+            generator: Some(generator.into()),
+            generator_version: Some(generator_version.into()),
+            ..DocumentMeta::default()
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, &Option<String>); 4] {
+        [
+            (GENERATOR, &self.generator),
+            (GENERATOR_VERSION, &self.generator_version),
+            (GENERATED_AT, &self.generated_at),
+            (SOURCE_COMMIT, &self.source_commit),
+        ]
+    }
+}
+
+impl OpenAPI {
+    /// Replaces the document metadata extensions. Fields which are `None` are removed, so
+    /// metadata from a previous generator doesn't linger.
+    pub fn set_document_metadata(&mut self, meta: DocumentMeta) {
+        for (key, value) in meta.fields().iter() {
+            match value {
+                Some(value) => {
+                    self.extensions.insert(key.to_string(), value.clone().into());
+                }
+                None => {
+                    self.extensions.shift_remove(*key);
+                }
+            }
+        }
+    }
+
+    /// Reads the document metadata extensions. Non-string values are ignored.
+    pub fn document_metadata(&self) -> DocumentMeta {
+        let get = |key: &str| self.extensions.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        DocumentMeta {
+            generator: get(GENERATOR),
+            generator_version: get(GENERATOR_VERSION),
+            generated_at: get(GENERATED_AT),
+            source_commit: get(SOURCE_COMMIT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_metadata() {
+        let mut spec = OpenAPI::default();
+        spec.extensions.insert("x-source-commit".to_string(), "old".into());
+        let meta = DocumentMeta {
+            generated_at: Some("2024-01-01T00:00:00Z".to_string()),
+            ..DocumentMeta::new("petgen", "1.2.0")
+        };
+        spec.set_document_metadata(meta.clone());
+        assert_eq!(spec.document_metadata(), meta);
+        assert!(!spec.extensions.contains_key("x-source-commit"));
+        assert_eq!(spec.extensions["x-generator"], "petgen");
+    }
+}
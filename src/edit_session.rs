@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+
+use crate::*;
+
+/// Transactional editing of an [OpenAPI] document with undo and redo.
+///
+/// Documents are changed by applying [Mutation]s, which are grouped into transactions with
+/// [EditSession::begin] and [EditSession::commit]. Mutations applied outside of a transaction are
+/// committed immediately. Each committed transaction is one undo step.
+///
+/// Undo history is kept as the inverse of each mutation, which holds only the path item or schema
+/// the mutation changed. The oldest steps are dropped past [EditSession::history_limit].
+#[derive(Debug, Clone)]
+pub struct EditSession {
+    spec: OpenAPI,
+    transaction: Option<Vec<Inverse>>,
+    undo: VecDeque<Vec<Inverse>>,
+    redo: Vec<Vec<Inverse>>,
+    history_limit: usize,
+}
+
+/// Undoes one [Mutation].
+#[derive(Debug, Clone)]
+enum Inverse {
+    /// Restores a path item to its position, or removes it if it didn't exist.
+    Path { path: String, index: usize, item: Option<Box<RefOr<PathItem>>> },
+    /// Restores a component schema to its position, or removes it if it didn't exist.
+    Schema { name: String, index: usize, schema: Option<Box<RefOr<Schema>>> },
+    /// Renames are undone by renaming back.
+    Mutation(Mutation),
+}
+
+impl Inverse {
+    /// Records what `mutation` will change in `spec`.
+    fn of(mutation: &Mutation, spec: &OpenAPI) -> Inverse {
+        match mutation {
+            Mutation::AddOperation { path, .. } | Mutation::RemoveOperation { path, .. } => Inverse::Path {
+                path: path.clone(),
+                index: spec.paths.paths.get_index_of(path).unwrap_or(spec.paths.paths.len()),
+                item: spec.paths.paths.get(path).cloned().map(Box::new),
+            },
+            Mutation::SetSchema { name, .. } | Mutation::RemoveSchema { name } => Inverse::Schema {
+                name: name.clone(),
+                index: spec.schemas.get_index_of(name).unwrap_or(spec.schemas.len()),
+                schema: spec.schemas.get(name).cloned().map(Box::new),
+            },
+            Mutation::RenameOperation { from, to } => Inverse::Mutation(Mutation::RenameOperation { from: to.clone(), to: from.clone() }),
+            Mutation::RenameSchema { from, to } => Inverse::Mutation(Mutation::RenameSchema { from: to.clone(), to: from.clone() }),
+        }
+    }
+
+    /// Undoes the mutation, returning the inverse which redoes it.
+    fn revert(self, spec: &mut OpenAPI) -> Result<Inverse> {
+        Ok(match self {
+            Inverse::Path { path, index, item } => {
+                let current = Inverse::Path {
+                    index: spec.paths.paths.get_index_of(&path).unwrap_or(spec.paths.paths.len()),
+                    item: spec.paths.paths.get(&path).cloned().map(Box::new),
+                    path: path.clone(),
+                };
+                restore(&mut spec.paths.paths, path, index, item.map(|item| *item));
+                current
+            }
+            Inverse::Schema { name, index, schema } => {
+                let current = Inverse::Schema {
+                    index: spec.schemas.get_index_of(&name).unwrap_or(spec.schemas.len()),
+                    schema: spec.schemas.get(&name).cloned().map(Box::new),
+                    name: name.clone(),
+                };
+                restore(&mut spec.components.schemas, name, index, schema.map(|schema| *schema));
+                current
+            }
+            Inverse::Mutation(mutation) => {
+                let inverse = Inverse::of(&mutation, spec);
+                mutation.apply(spec)?;
+                inverse
+            }
+        })
+    }
+}
+
+/// Puts `value` back under `key` at `index`, or removes `key` if `value` is `None`.
+fn restore<V>(map: &mut IndexMap<String, V>, key: String, index: usize, value: Option<V>) {
+    match value {
+        Some(value) => {
+            let (current, _) = map.insert_full(key, value);
+            map.move_index(current, index.min(map.len() - 1));
+        }
+        None => {
+            map.shift_remove(&key);
+        }
+    }
+}
+
+impl EditSession {
+    /// The default number of undo steps kept.
+    pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+    pub fn new(spec: OpenAPI) -> Self {
+        EditSession {
+            spec,
+            transaction: None,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            history_limit: Self::DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Sets the number of undo steps kept, dropping the oldest ones past it.
+    pub fn history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self.truncate_history();
+        self
+    }
+
+    fn truncate_history(&mut self) {
+        while self.undo.len() > self.history_limit {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Starts a transaction. Fails if one is already in progress.
+    pub fn begin(&mut self) -> Result<()> {
+        if self.transaction.is_some() {
+            return Err(anyhow!("A transaction is already in progress."));
+        }
+        self.transaction = Some(Vec::new());
+        Ok(())
+    }
+
+    /// Ends the transaction, keeping its changes as one undo step. Transactions without changes add no step.
+    pub fn commit(&mut self) -> Result<()> {
+        let inverses = self.transaction.take().ok_or_else(|| anyhow!("No transaction in progress."))?;
+        if !inverses.is_empty() {
+            self.undo.push_back(inverses);
+            self.truncate_history();
+            self.redo.clear();
+        }
+        Ok(())
+    }
+
+    /// Ends the transaction, discarding its changes.
+    pub fn rollback(&mut self) -> Result<()> {
+        let inverses = self.transaction.take().ok_or_else(|| anyhow!("No transaction in progress."))?;
+        self.revert(inverses)?;
+        Ok(())
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Applies a [Mutation]. A failed mutation leaves the document unchanged.
+    pub fn apply(&mut self, mutation: &Mutation) -> Result<()> {
+        let inverse = Inverse::of(mutation, &self.spec);
+        mutation.apply(&mut self.spec)?;
+        match &mut self.transaction {
+            Some(inverses) => inverses.push(inverse),
+            None => {
+                self.transaction = Some(vec![inverse]);
+                self.commit().expect("Transaction was started");
+            }
+        }
+        Ok(())
+    }
+
+    /// Reverts the last committed transaction. Returns false if there is nothing to undo.
+    /// Fails during a transaction.
+    pub fn undo(&mut self) -> Result<bool> {
+        self.check_no_transaction()?;
+        let Some(inverses) = self.undo.pop_back() else { return Ok(false) };
+        let redo = self.revert(inverses)?;
+        self.redo.push(redo);
+        Ok(true)
+    }
+
+    /// Reapplies the last undone transaction. Returns false if there is nothing to redo.
+    /// Fails during a transaction.
+    pub fn redo(&mut self) -> Result<bool> {
+        self.check_no_transaction()?;
+        let Some(inverses) = self.redo.pop() else { return Ok(false) };
+        let undo = self.revert(inverses)?;
+        self.undo.push_back(undo);
+        Ok(true)
+    }
+
+    fn check_no_transaction(&self) -> Result<()> {
+        if self.transaction.is_some() {
+            return Err(anyhow!("Cannot undo or redo during a transaction."));
+        }
+        Ok(())
+    }
+
+    /// Reverts `inverses` newest first, returning the inverses which redo them.
+    fn revert(&mut self, inverses: Vec<Inverse>) -> Result<Vec<Inverse>> {
+        let mut redo = inverses.into_iter().rev()
+            .map(|inverse| inverse.revert(&mut self.spec))
+            .collect::<Result<Vec<_>>>()?;
+        redo.reverse();
+        Ok(redo)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Returns the document, including the changes of an uncommitted transaction.
+    pub fn into_inner(self) -> OpenAPI {
+        self.spec
+    }
+}
+
+impl Default for EditSession {
+    fn default() -> Self {
+        EditSession::new(OpenAPI::default())
+    }
+}
+
+impl std::ops::Deref for EditSession {
+    type Target = OpenAPI;
+
+    fn deref(&self) -> &Self::Target {
+        &self.spec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_schema(name: &str, schema: Schema) -> Mutation {
+        Mutation::SetSchema { name: name.to_string(), schema: Box::new(schema) }
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut session = EditSession::new(OpenAPI::default());
+        session.apply(&set_schema("Pet", Schema::new_object())).unwrap();
+        assert!(session.apply(&Mutation::RemoveSchema { name: "User".to_string() }).is_err());
+
+        session.begin().unwrap();
+        session.apply(&set_schema("User", Schema::new_object())).unwrap();
+        session.apply(&Mutation::RenameSchema { from: "Pet".to_string(), to: "Animal".to_string() }).unwrap();
+        assert!(session.undo().is_err());
+        session.rollback().unwrap();
+        assert_eq!(session.schemas.keys().collect::<Vec<_>>(), vec!["Pet"]);
+
+        session.begin().unwrap();
+        session.apply(&set_schema("User", Schema::new_object())).unwrap();
+        session.apply(&set_schema("Tag", Schema::new_string())).unwrap();
+        session.commit().unwrap();
+        assert_eq!(session.schemas.len(), 3);
+
+        assert!(session.undo().unwrap());
+        assert_eq!(session.schemas.len(), 1);
+        assert!(session.undo().unwrap());
+        assert!(session.schemas.is_empty());
+        assert!(!session.undo().unwrap());
+        assert!(session.redo().unwrap());
+        assert!(session.redo().unwrap());
+        assert_eq!(session.schemas.keys().collect::<Vec<_>>(), vec!["Pet", "User", "Tag"]);
+
+        session.apply(&set_schema("User", Schema::new_object())).unwrap();
+        assert!(!session.can_redo());
+    }
+
+    #[test]
+    fn test_undo_restores_position() {
+        let mut spec = OpenAPI::default();
+        spec.schemas.insert("A", Schema::new_string());
+        spec.schemas.insert("B", Schema::new_string());
+        let mut item = PathItem::get(Operation::default());
+        item.summary = Some("Pets".to_string());
+        spec.paths.insert("/pets".to_string(), item);
+        spec.paths.insert_operation("/users".to_string(), PathMethod::GET, Operation::default());
+        let original = spec.clone();
+
+        let mut session = EditSession::new(spec).history_limit(2);
+        session.apply(&Mutation::RemoveSchema { name: "A".to_string() }).unwrap();
+        session.apply(&Mutation::RemoveOperation { path: "/pets".to_string(), method: "GET".to_string() }).unwrap();
+        assert!(session.undo().unwrap());
+        assert!(session.undo().unwrap());
+        assert_eq!(&*session, &original);
+
+        session.apply(&set_schema("C", Schema::new_string())).unwrap();
+        session.apply(&set_schema("D", Schema::new_string())).unwrap();
+        session.apply(&set_schema("E", Schema::new_string())).unwrap();
+        assert!(session.undo().unwrap());
+        assert!(session.undo().unwrap());
+        assert!(!session.undo().unwrap());
+        assert_eq!(session.schemas.len(), 3);
+    }
+}
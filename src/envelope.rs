@@ -0,0 +1,101 @@
+use crate::*;
+
+/// The media type of JSON:API documents.
+pub const JSONAPI_JSON: &str = "application/vnd.api+json";
+/// The media type of HAL documents.
+pub const HAL_JSON: &str = "application/hal+json";
+
+fn required_object(props: Vec<(&str, RefOr<Schema>)>, required: &[&str]) -> Schema {
+    let mut schema = Schema::new_object();
+    for (name, prop) in props {
+        schema.properties_mut().insert(name, prop);
+    }
+    for name in required {
+        schema.add_required(name);
+    }
+    schema
+}
+
+fn jsonapi_links() -> Schema {
+    Schema::new_map(Schema::new_string().with_format("uri"))
+}
+
+impl Schema {
+    /// Wraps `attributes` in a JSON:API resource object, `{ type, id, attributes, relationships, links }`,
+    /// with `type` fixed to `type_name`.
+    pub fn jsonapi_resource_object(type_name: &str, attributes: impl Into<RefOr<Schema>>) -> Schema {
+        required_object(vec![
+            ("type", Schema::new_str_enum(vec![type_name.to_string()]).into()),
+            ("id", Schema::new_string().into()),
+            ("attributes", attributes.into()),
+            ("relationships", Schema::new_map_any().into()),
+            ("links", jsonapi_links().into()),
+        ], &["type", "id"])
+    }
+
+    /// Creates a JSON:API top-level document whose primary `data` is a single resource
+    /// of type `type_name` with the given attributes. See [Schema::jsonapi_collection] for lists.
+    pub fn jsonapi_resource(type_name: &str, attributes: impl Into<RefOr<Schema>>) -> Schema {
+        Self::jsonapi_document(Self::jsonapi_resource_object(type_name, attributes).into())
+    }
+
+    /// Like [Schema::jsonapi_resource], but `data` is an array of resources.
+    pub fn jsonapi_collection(type_name: &str, attributes: impl Into<RefOr<Schema>>) -> Schema {
+        Self::jsonapi_document(Schema::new_array(Self::jsonapi_resource_object(type_name, attributes)).into())
+    }
+
+    fn jsonapi_document(data: RefOr<Schema>) -> Schema {
+        required_object(vec![
+            ("data", data),
+            ("included", Schema::new_array(Schema::new_map_any()).into()),
+            ("meta", Schema::new_map_any().into()),
+            ("links", jsonapi_links().into()),
+        ], &["data"])
+    }
+
+    /// Wraps `resource` in a HAL envelope, adding `_links` (requiring a `self` link) and, if
+    /// `embedded` is non-empty, an `_embedded` object with the given relations.
+    pub fn hal_resource(resource: impl Into<RefOr<Schema>>, embedded: RefOrMap<Schema>) -> Schema {
+        let link = required_object(vec![
+            ("href", Schema::new_string().into()),
+            ("templated", Schema::new_bool().into()),
+            ("title", Schema::new_string().into()),
+        ], &["href"]);
+        let mut links = Schema::new_map(link.clone());
+        links.properties_mut().insert("self", link);
+        links.add_required("self");
+        let mut envelope = required_object(vec![("_links", links.into())], &["_links"]);
+        if !embedded.is_empty() {
+            let mut embedded_schema = Schema::new_object();
+            *embedded_schema.properties_mut() = embedded;
+            envelope.properties_mut().insert("_embedded", embedded_schema);
+        }
+        Schema::new_all_of(vec![resource.into(), envelope.into()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_envelopes() {
+        let spec = OpenAPI::default();
+        let mut attributes = Schema::new_object();
+        attributes.properties_mut().insert("title", Schema::new_string());
+        let doc = Schema::jsonapi_resource("articles", attributes.clone());
+        let valid = json!({"data": {"type": "articles", "id": "1", "attributes": {"title": "Hi"}}});
+        assert!(doc.validate_value(&valid, &spec).is_empty());
+        let wrong_type = json!({"data": {"type": "people", "id": "1"}});
+        assert!(!doc.validate_value(&wrong_type, &spec).is_empty());
+
+        let mut embedded = RefOrMap::new();
+        embedded.insert("author", Schema::new_object());
+        let hal = Schema::hal_resource(attributes, embedded);
+        let valid = json!({"title": "Hi", "_links": {"self": {"href": "/articles/1"}}, "_embedded": {"author": {}}});
+        assert!(hal.validate_value(&valid, &spec).is_empty());
+        assert!(!hal.validate_value(&json!({"title": "Hi", "_links": {}}), &spec).is_empty());
+    }
+}
@@ -14,6 +14,8 @@ pub struct Example {
     /// field are mutually exclusive. To represent examples of
     /// media types that cannot naturally represented in JSON or YAML,
     /// use a string value to contain the example, escaping where necessary.
+    /// An explicit `null` is kept as `Some(Value::Null)`.
+    #[serde(default, deserialize_with = "crate::util::deserialize_present")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<serde_json::Value>,
     /// A URL that points to the literal example.
@@ -0,0 +1,162 @@
+use serde_json::Value;
+
+use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key, to_json};
+use crate::*;
+
+/// The naming convention for the keys of `examples` maps, see [ExampleLintOptions].
+#[derive(Debug, Clone, Copy)]
+pub enum ExampleKeyPattern {
+    Any,
+    /// e.g. `largeOrder`
+    CamelCase,
+    /// e.g. `large_order`
+    SnakeCase,
+    /// e.g. `large-order`
+    KebabCase,
+    Custom(fn(&str) -> bool),
+}
+
+impl ExampleKeyPattern {
+    pub fn matches(&self, key: &str) -> bool {
+        let lower_alnum = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+        let separated = |sep: char| !key.is_empty() && key.split(sep).all(|word| word.starts_with(|c: char| c.is_ascii_lowercase()) && word.chars().all(lower_alnum));
+        match self {
+            ExampleKeyPattern::Any => true,
+            ExampleKeyPattern::CamelCase => key.starts_with(|c: char| c.is_ascii_lowercase()) && key.chars().all(|c| c.is_ascii_alphanumeric()),
+            ExampleKeyPattern::SnakeCase => separated('_'),
+            ExampleKeyPattern::KebabCase => separated('-'),
+            ExampleKeyPattern::Custom(f) => f(key),
+        }
+    }
+}
+
+/// Options for [OpenAPI::lint_examples].
+#[derive(Debug, Clone)]
+pub struct ExampleLintOptions {
+    pub key_pattern: ExampleKeyPattern,
+    /// Request bodies of operations with this tag must have an example.
+    pub public_tag: String,
+}
+
+impl Default for ExampleLintOptions {
+    fn default() -> Self {
+        ExampleLintOptions {
+            key_pattern: ExampleKeyPattern::Any,
+            public_tag: "public".to_string(),
+        }
+    }
+}
+
+impl OpenAPI {
+    /// Checks the `examples` maps of the document:
+    /// - `example-key`: keys follow [ExampleLintOptions::key_pattern].
+    /// - `example-value`: each example has exactly one of `value` and `externalValue`.
+    /// - `missing-example`: request bodies of operations tagged [ExampleLintOptions::public_tag]
+    ///   have an `example` or `examples` for some media type.
+    pub fn lint_examples(&self, options: &ExampleLintOptions) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let doc = to_json(self);
+        walk(&doc, &mut String::from("#"), false, options, &mut lints);
+        for op in self.callable_operations() {
+            if !op.operation.tags.contains(&options.public_tag) {
+                continue;
+            }
+            let Some(body) = op.operation.request_body.as_ref().and_then(|b| b.resolve(self).ok()) else { continue };
+            if !body.content.values().any(|media| media.example.is_some() || !media.examples.is_empty()) {
+                lints.push(Lint::new("missing-example", format!("{}/requestBody", op.pointer), "Request body of a public operation has no example."));
+            }
+        }
+        lints
+    }
+}
+
+fn walk(value: &Value, pointer: &mut String, is_name_map: bool, options: &ExampleLintOptions, lints: &mut Vec<Lint>) {
+    let len = pointer.len();
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                pointer.push('/');
+                pointer.push_str(&escape_pointer_segment(key));
+                match child {
+                    Value::Object(examples) if !is_name_map && key == "examples" => check_examples(examples, pointer, options, lints),
+                    _ => {
+                        let names = !is_name_map && is_name_map_key(key);
+                        walk(child, pointer, names, options, lints);
+                    }
+                }
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                pointer.push_str(&format!("/{}", i));
+                walk(item, pointer, false, options, lints);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_examples(examples: &serde_json::Map<String, Value>, pointer: &str, options: &ExampleLintOptions, lints: &mut Vec<Lint>) {
+    for (key, example) in examples {
+        let pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+        if !options.key_pattern.matches(key) {
+            lints.push(Lint::new("example-key", pointer.as_str(), format!("Example name {} doesn't follow the naming convention.", key)));
+        }
+        if example.get("$ref").is_some() {
+            continue;
+        }
+        match (example.get("value").is_some(), example.get("externalValue").is_some()) {
+            (true, true) => lints.push(Lint::new("example-value", pointer, "Example has both value and externalValue.")),
+            (false, false) => lints.push(Lint::new("example-value", pointer, "Example has neither value nor externalValue.")),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_lint_examples() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let example = |value: Value| serde_json::from_value::<Example>(value).unwrap();
+        api.examples.insert("smallPet", example(json!({"externalValue": "https://example.com/pet.json", "value": {}})));
+        let mut media = MediaType::default();
+        media.examples.insert("largePet".to_string(), example(json!({"value": {"name": "Rex"}})).into());
+        media.examples.insert("pet_ref".to_string(), RefOr::ref_("#/components/examples/smallPet"));
+        let mut response = Response::new("OK");
+        response.content.insert("application/json".to_string(), media);
+        let mut op = Operation {
+            tags: vec!["public".to_string()],
+            request_body: Some(RequestBody::json(Schema::new_object()).into()),
+            ..Operation::default()
+        };
+        op.responses.responses.insert(StatusCode::Code(200), response.into());
+        let mut param = Parameter::query("q", Schema::new_string());
+        param.examples.insert("empty".to_string(), example(json!({"summary": "Nothing"})).into());
+        op.parameters.push(param.into());
+        let mut param = Parameter::query("limit", Schema::new_integer());
+        param.examples.insert("none".to_string(), example(json!({"value": null})).into());
+        op.parameters.push(param.into());
+        api.add_operation(PathMethod::POST, "/pets", op).unwrap();
+
+        let options = ExampleLintOptions { key_pattern: ExampleKeyPattern::CamelCase, ..ExampleLintOptions::default() };
+        let lints: Vec<String> = api.lint_examples(&options).into_iter().map(|l| l.to_string()).collect();
+        assert_eq!(lints, vec![
+            "#/components/examples/smallPet: Example has both value and externalValue. [example-value]",
+            "#/paths/~1pets/post/parameters/0/examples/empty: Example has neither value nor externalValue. [example-value]",
+            "#/paths/~1pets/post/responses/200/content/application~1json/examples/pet_ref: Example name pet_ref doesn't follow the naming convention. [example-key]",
+            "#/paths/~1pets/post/requestBody: Request body of a public operation has no example. [missing-example]",
+        ]);
+        assert!(ExampleKeyPattern::KebabCase.matches("large-pet2"));
+        assert!(!ExampleKeyPattern::SnakeCase.matches("large__pet"));
+    }
+}
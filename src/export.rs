@@ -0,0 +1,35 @@
+//! Converts specs into configuration and formats for other tools.
+pub mod fragment;
+pub mod gateway;
+pub mod proto;
+pub mod sql;
+
+/// Converts a name such as `petOwner` or `Pet-Owner` to `pet_owner`. Characters other than ASCII
+/// letters and digits separate words, and a leading digit gets an underscore.
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            prev_lower = true;
+        } else {
+            if !out.ends_with('_') {
+                out.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    let out = out.trim_matches('_').to_string();
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", out)
+    } else {
+        out
+    }
+}
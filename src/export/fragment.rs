@@ -0,0 +1,93 @@
+//! Minified, escape-safe JSON for embedding a spec in other documents, such as infrastructure
+//! definitions with size limits on string fields.
+use anyhow::{bail, Result};
+
+use crate::OpenAPI;
+
+/// Escapes a minified JSON document so it only contains printable ASCII, and none of the
+/// characters which template languages or HTML treat specially: `<`, `>`, `&`, `'` and `$`
+/// become `\u` escapes. Since those characters can only occur within JSON strings, the result
+/// is equivalent JSON.
+pub fn escape_json(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' | '>' | '&' | '\'' | '$' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            ' '..='~' => out.push(c),
+            _ => {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Splits ASCII JSON text into chunks of at most `max_bytes`, never splitting an escape
+/// sequence. Concatenating the chunks gives back the input.
+pub fn chunk_json(json: &str, max_bytes: usize) -> Result<Vec<String>> {
+    if max_bytes < 6 {
+        bail!("Chunk size must be at least 6 bytes to hold an escape sequence");
+    }
+    if !json.is_ascii() {
+        bail!("JSON must be ASCII to be chunked; escape it first");
+    }
+    let bytes = json.as_bytes();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let token = match (bytes[i], bytes.get(i + 1)) {
+            (b'\\', Some(b'u')) => 6,
+            (b'\\', _) => 2,
+            _ => 1,
+        };
+        if i + token - start > max_bytes {
+            chunks.push(json[start..i].to_string());
+            start = i;
+        }
+        i = (i + token).min(bytes.len());
+    }
+    if start < bytes.len() {
+        chunks.push(json[start..].to_string());
+    }
+    Ok(chunks)
+}
+
+impl OpenAPI {
+    /// Serializes the document as minified JSON, escaped by [escape_json]. The output is
+    /// deterministic: fields and map entries keep their document order.
+    pub fn to_minified_json_escaped(&self) -> Result<String> {
+        Ok(escape_json(&serde_json::to_string(self)?))
+    }
+
+    /// Like [OpenAPI::to_minified_json_escaped], split by [chunk_json] into chunks of at most `max_bytes`.
+    pub fn to_minified_json_chunks(&self, max_bytes: usize) -> Result<Vec<String>> {
+        chunk_json(&self.to_minified_json_escaped()?, max_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minified_chunks() {
+        let spec = OpenAPI::new("Pets <café> ${env} 🐈", "1.0.0");
+        let json = spec.to_minified_json_escaped().unwrap();
+        assert!(json.is_ascii());
+        assert!(json.contains(r"Pets \u003ccaf\u00e9\u003e \u0024{env} \ud83d\udc08"));
+        let parsed: OpenAPI = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.info.title, spec.info.title);
+
+        for size in 6..20 {
+            let chunks = spec.to_minified_json_chunks(size).unwrap();
+            assert!(chunks.iter().all(|c| c.len() <= size));
+            assert!(chunks.iter().all(|c| !c.ends_with('\\') || c.ends_with("\\\\")));
+            assert_eq!(chunks.concat(), json);
+        }
+        assert!(chunk_json(&json, 5).is_err());
+    }
+}
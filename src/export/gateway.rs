@@ -0,0 +1,281 @@
+//! Declarative API gateway configuration generated from a spec.
+//!
+//! The export is a skeleton: routes, upstreams and authentication plugins are derived from the
+//! document, while plugin credentials and anything the spec can't express are left to the operator.
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::*;
+
+/// A Kong declarative configuration (`_format_version: "3.0"`).
+/// Serialize it with `serde_yaml` or `serde_json` to produce a `kong.yml`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KongConfig {
+    #[serde(rename = "_format_version")]
+    pub format_version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub upstreams: Vec<KongUpstream>,
+    pub services: Vec<KongService>,
+}
+
+/// A load-balanced set of targets, generated when the document lists several hosts.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KongUpstream {
+    pub name: String,
+    pub targets: Vec<KongTarget>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KongTarget {
+    /// `host:port`
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KongService {
+    pub name: String,
+    /// `scheme://host`, or for a service backed by a [KongUpstream], `scheme://upstream-name`.
+    /// Routes keep the servers' base paths, which are forwarded as is.
+    pub url: String,
+    pub routes: Vec<KongRoute>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KongRoute {
+    pub name: String,
+    /// Regex paths, prefixed with `~` as Kong 3 requires.
+    pub paths: Vec<String>,
+    pub methods: Vec<String>,
+    pub strip_path: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<KongPlugin>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KongPlugin {
+    pub name: String,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub config: Value,
+}
+
+fn slug(s: &str) -> String {
+    let slug: String = s.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    slug.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+fn regex_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Converts a path template to an anchored Kong regex path, e.g. `/pets/{id}.json` to
+/// `~/pets/[^/]+\.json$`.
+fn route_path(base: &str, path: &str) -> String {
+    let mut regex = String::from("~");
+    regex.push_str(&regex_escape(base.trim_end_matches('/')));
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        regex.push_str(&regex_escape(&rest[..start]));
+        regex.push_str("[^/]+");
+        rest = rest[start..].split_once('}').map(|(_, r)| r).unwrap_or_default();
+    }
+    regex.push_str(&regex_escape(rest));
+    regex.push('$');
+    regex
+}
+
+/// The servers sharing a scheme, which become one service.
+#[derive(Default)]
+struct ServerGroup {
+    /// `host:port`
+    authorities: Vec<String>,
+    base_paths: Vec<String>,
+}
+
+impl OpenAPI {
+    /// Groups the servers by scheme. Relative server URLs are resolved against `base`, the
+    /// document's URL, or else served from localhost.
+    fn server_groups(&self, base: Option<&http::Uri>) -> IndexMap<String, ServerGroup> {
+        let mut groups: IndexMap<String, ServerGroup> = IndexMap::new();
+        for server in &self.servers {
+            let (scheme, authority, path) = match crate::server::absolute_url(&server.default_url(), base) {
+                Ok(uri) => {
+                    let scheme = uri.scheme_str().unwrap_or("http").to_string();
+                    let host = uri.host().unwrap_or("localhost");
+                    let port = uri.port_u16().unwrap_or(if scheme == "https" { 443 } else { 80 });
+                    (scheme, format!("{}:{}", host, port), uri.path().to_string())
+                }
+                Err(_) => {
+                    let url = server.default_url();
+                    ("http".to_string(), "localhost:80".to_string(), url.split(['?', '#']).next().unwrap_or_default().to_string())
+                }
+            };
+            let group = groups.entry(scheme).or_default();
+            if !group.authorities.contains(&authority) {
+                group.authorities.push(authority);
+            }
+            let path = path.trim_end_matches('/').to_string();
+            if !group.base_paths.contains(&path) {
+                group.base_paths.push(path);
+            }
+        }
+        if groups.is_empty() {
+            groups.insert("http".to_string(), ServerGroup {
+                authorities: vec!["localhost:80".to_string()],
+                base_paths: vec![String::new()],
+            });
+        }
+        groups
+    }
+}
+
+fn security_plugin(scheme: &SecurityScheme, scopes: &[String]) -> Option<KongPlugin> {
+    let (name, config) = match scheme {
+        SecurityScheme::APIKey { location: APIKeyLocation::Cookie, .. } => return None,
+        SecurityScheme::APIKey { location, name, .. } => ("key-auth", json!({
+            "key_names": [name],
+            "key_in_header": *location == APIKeyLocation::Header,
+            "key_in_query": *location == APIKeyLocation::Query,
+        })),
+        SecurityScheme::HTTP { scheme, .. } if scheme.eq_ignore_ascii_case("basic") => ("basic-auth", Value::Null),
+        SecurityScheme::HTTP { scheme, .. } if scheme.eq_ignore_ascii_case("bearer") => ("jwt", Value::Null),
+        SecurityScheme::HTTP { .. } => return None,
+        SecurityScheme::OAuth2 { .. } => ("oauth2", json!({"scopes": scopes, "mandatory_scope": !scopes.is_empty()})),
+        SecurityScheme::OpenIDConnect { open_id_connect_url, .. } => ("openid-connect", json!({
+            "issuer": open_id_connect_url,
+            "scopes_required": scopes,
+        })),
+    };
+    Some(KongPlugin { name: name.to_string(), config })
+}
+
+impl OpenAPI {
+    /// Exports the document as a Kong declarative configuration, with a service for each scheme
+    /// of the servers.
+    ///
+    /// A service points at the host of its servers. With several hosts, an upstream targeting each
+    /// of them is added and the service routes through it. Relative server URLs are resolved
+    /// against `base`, the URL the document was retrieved from, or else served from localhost. Each operation becomes
+    /// a route of every service, named by its operationId, which matches the path under each of
+    /// the servers' base paths. Authentication plugins are derived from the first security
+    /// requirement of each operation, since Kong can't express alternatives; cookie API keys and
+    /// HTTP schemes other than basic and bearer are skipped.
+    pub fn to_kong_config(&self, base: Option<&http::Uri>) -> KongConfig {
+        let service_name = match slug(&self.info.title) {
+            s if s.is_empty() => "api".to_string(),
+            s => s,
+        };
+        let groups = self.server_groups(base);
+        let mut upstreams = Vec::new();
+        let mut services = Vec::new();
+        for (scheme, group) in &groups {
+            // Kong names are global, so with several services names carry the scheme.
+            let name = if groups.len() > 1 { format!("{}-{}", service_name, scheme) } else { service_name.clone() };
+            let url = if group.authorities.len() > 1 {
+                let upstream = format!("{}-upstream", name);
+                upstreams.push(KongUpstream {
+                    name: upstream.clone(),
+                    targets: group.authorities.iter().map(|a| KongTarget { target: a.clone() }).collect(),
+                });
+                format!("{}://{}", scheme, upstream)
+            } else {
+                format!("{}://{}", scheme, group.authorities[0])
+            };
+            let routes = self.operations()
+                .map(|(path, method, op, _)| {
+                    let route_name = op.operation_id.clone()
+                        .unwrap_or_else(|| slug(&format!("{} {}", method, path)));
+                    let plugins = op.security.as_ref().unwrap_or(&self.security).first()
+                        .map(|requirement| requirement.iter()
+                            .filter_map(|(name, scopes)| security_plugin(self.security_schemes.get(name)?.as_item()?, scopes))
+                            .collect())
+                        .unwrap_or_default();
+                    KongRoute {
+                        name: if groups.len() > 1 { format!("{}-{}", route_name, scheme) } else { route_name },
+                        paths: group.base_paths.iter().map(|base| route_path(base, path)).collect(),
+                        methods: vec![method.to_uppercase()],
+                        strip_path: false,
+                        tags: op.tags.clone(),
+                        plugins,
+                    }
+                })
+                .collect();
+            services.push(KongService { name, url, routes });
+        }
+
+        KongConfig {
+            format_version: "3.0".to_string(),
+            upstreams,
+            services,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_kong_config() {
+        let mut spec = OpenAPI::new("Pet Store", "1.0.0")
+            .with_server("https://a.example.com/v1")
+            .with_server("https://b.example.com:8443/v1");
+        spec.security_schemes.insert("key", SecurityScheme::APIKey {
+            location: APIKeyLocation::Header,
+            name: "X-API-Key".to_string(),
+            description: None,
+            extensions: IndexMap::new(),
+        });
+        let mut requirement = SecurityRequirement::new();
+        requirement.insert("key".to_string(), vec![]);
+        spec.security = vec![requirement];
+        let mut op = Operation { operation_id: Some("getPet".to_string()), ..Operation::default() };
+        op.add_path_param("id", Schema::new_string());
+        spec.add_operation(PathMethod::GET, "/pets/{id}", op).unwrap();
+
+        let config = spec.to_kong_config(None);
+        assert_eq!(config.upstreams[0].targets[1].target, "b.example.com:8443");
+        let service = &config.services[0];
+        assert_eq!(service.name, "pet-store");
+        assert_eq!(service.url, "https://pet-store-upstream");
+        let route = &service.routes[0];
+        assert_eq!(route.paths, vec!["~/v1/pets/[^/]+$"]);
+        assert_eq!(route.methods, vec!["GET"]);
+        assert_eq!(route.plugins[0].name, "key-auth");
+        assert_eq!(route.plugins[0].config["key_names"][0], "X-API-Key");
+    }
+
+    #[test]
+    fn test_kong_servers() {
+        let mut spec = OpenAPI::new("Pets", "1.0.0")
+            .with_server("/api/v1")
+            .with_server("https://pets.example.com/v2")
+            .with_server("http://localhost:8080/");
+        let mut op = Operation::default();
+        op.add_path_param("id", Schema::new_string());
+        spec.add_operation(PathMethod::GET, "/pets/{id}.json", op).unwrap();
+
+        let base = "https://pets.example.com/openapi.yaml".parse().unwrap();
+        let config = spec.to_kong_config(Some(&base));
+        let https = &config.services[0];
+        assert_eq!(https.name, "pets-https");
+        assert_eq!(https.url, "https://pets.example.com:443");
+        assert_eq!(https.routes[0].name, "get-pets-id-json-https");
+        assert_eq!(https.routes[0].paths, vec![r"~/api/v1/pets/[^/]+\.json$", r"~/v2/pets/[^/]+\.json$"]);
+        let http = &config.services[1];
+        assert_eq!(http.url, "http://localhost:8080");
+        assert_eq!(http.routes[0].paths, vec![r"~/pets/[^/]+\.json$"]);
+        assert!(config.upstreams.is_empty());
+    }
+}
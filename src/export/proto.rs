@@ -0,0 +1,418 @@
+//! proto3 message sketches generated from schemas, for comparing OpenAPI and protobuf definitions.
+use std::collections::HashSet;
+
+use crate::*;
+use crate::export::snake_case;
+
+fn pascal_case(name: &str) -> String {
+    snake_case(name).split('_').map(|word| {
+        let mut chars = word.chars();
+        chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+    }).collect()
+}
+
+/// A field type, and whether it is `repeated` or a `map`.
+enum FieldType {
+    Single(String),
+    Repeated(String),
+    Map(String),
+}
+
+/// The nested messages and enums of a message.
+struct Nested {
+    definitions: Vec<String>,
+    /// The type names taken in the message, including its own.
+    names: HashSet<String>,
+}
+
+struct ProtoWriter<'a> {
+    spec: &'a OpenAPI,
+    /// Constructs which proto3 can't express, by JSON pointer relative to the schema.
+    report: Vec<String>,
+}
+
+impl ProtoWriter<'_> {
+    fn unrepresentable(&mut self, pointer: &str, what: &str) -> String {
+        self.report.push(format!("{}: {}", pointer, what));
+        "google.protobuf.Value".to_string()
+    }
+
+    /// Returns `name`, or a numbered variant if it is taken. Names which are taken or empty are
+    /// reported.
+    fn unique_name(&mut self, name: String, fallback: &str, used: &mut HashSet<String>, pointer: &str) -> String {
+        let base = if name.is_empty() {
+            self.report.push(format!("{}: name without ASCII letters or digits", pointer));
+            fallback.to_string()
+        } else {
+            name
+        };
+        let mut unique = base.clone();
+        let mut n = 1;
+        while used.contains(&unique) {
+            n += 1;
+            unique = format!("{}{}", base, n);
+        }
+        if n > 1 {
+            self.report.push(format!("{}: name {} which is already taken", pointer, base));
+        }
+        used.insert(unique.clone());
+        unique
+    }
+
+    /// Merges `allOf` parts, resolved one level so that nested references keep their names.
+    fn merged(&mut self, schema: &Schema, pointer: &str) -> Schema {
+        let SchemaKind::AllOf { all_of } = &schema.kind else { return schema.clone() };
+        let mut parts = all_of.iter().map(|part| Resolve::resolve(part, self.spec));
+        let merged = match parts.next() {
+            Some(Ok(first)) => parts.try_fold(first.clone(), |merged, part| merged.intersect(part?, self.spec)),
+            Some(Err(e)) => Err(e),
+            None => Ok(Schema::new_object()),
+        };
+        match merged {
+            Ok(merged) if !matches!(merged.kind, SchemaKind::AllOf { .. }) => merged,
+            _ => {
+                self.unrepresentable(pointer, "allOf which can't be merged into one object");
+                schema.clone()
+            }
+        }
+    }
+
+    fn scalar(&mut self, schema: &Schema, pointer: &str) -> Option<String> {
+        Some(match &schema.kind {
+            SchemaKind::Type(Type::String(s)) => match s.format.as_str() {
+                "byte" | "binary" => "bytes",
+                "date-time" => "google.protobuf.Timestamp",
+                _ => "string",
+            }.to_string(),
+            SchemaKind::Type(Type::Integer(i)) => match (&i.format, i.minimum.is_some_and(|m| m >= 0)) {
+                (VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32), false) => "int32",
+                (VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32), true) => "uint32",
+                (_, false) => "int64",
+                (_, true) => "uint64",
+            }.to_string(),
+            SchemaKind::Type(Type::Number(n)) => match n.format {
+                VariantOrUnknownOrEmpty::Item(NumberFormat::Float) => "float",
+                _ => "double",
+            }.to_string(),
+            SchemaKind::Type(Type::Boolean {}) => "bool".to_string(),
+            SchemaKind::AnyOf { .. } => self.unrepresentable(pointer, "anyOf"),
+            SchemaKind::Not { .. } => self.unrepresentable(pointer, "not"),
+            SchemaKind::Any(_) => self.unrepresentable(pointer, "schema without a type"),
+            _ => return None,
+        })
+    }
+
+    /// Returns the type of a field, adding nested messages and enums to `nested`.
+    fn field_type(&mut self, schema: &RefOr<Schema>, name: &str, pointer: &str, nested: &mut Nested, indent: usize) -> FieldType {
+        if let RefOr::Reference { reference } = schema {
+            let Ok(resolved) = Resolve::resolve(schema, self.spec) else {
+                return FieldType::Single(self.unrepresentable(pointer, "unresolvable reference"));
+            };
+            return match self.scalar(resolved, pointer) {
+                Some(scalar) if resolved.enum_values().is_empty() => FieldType::Single(scalar),
+                _ if matches!(resolved.kind, SchemaKind::Type(Type::Array(_))) => self.field_type(&RefOr::Item(resolved.clone()), name, pointer, nested, indent),
+                _ => FieldType::Single(pascal_case(reference.rsplit('/').next().unwrap_or(reference))),
+            };
+        }
+        let schema = self.merged(schema.as_item().expect("References are handled above"), pointer);
+        match &schema.kind {
+            SchemaKind::Type(Type::String(s)) if !s.enumeration.is_empty() => {
+                let typ = self.unique_name(pascal_case(name), "Enum", &mut nested.names, pointer);
+                let definition = self.enum_definition(&typ, &s.enumeration, pointer, indent);
+                nested.definitions.push(definition);
+                FieldType::Single(typ)
+            }
+            SchemaKind::Type(Type::Array(a)) => {
+                let Some(items) = &a.items else {
+                    return FieldType::Repeated(self.unrepresentable(pointer, "array without items"));
+                };
+                match self.field_type(items, &format!("{}_item", name), &format!("{}/items", pointer), nested, indent) {
+                    FieldType::Single(typ) => FieldType::Repeated(typ),
+                    _ => FieldType::Repeated(self.unrepresentable(pointer, "nested array or map")),
+                }
+            }
+            SchemaKind::Type(Type::Object(o)) if o.properties.is_empty() => match &o.additional_properties {
+                Some(AdditionalProperties::Schema(values)) => match self.field_type(values, &format!("{}_value", name), &format!("{}/additionalProperties", pointer), nested, indent) {
+                    FieldType::Single(typ) => FieldType::Map(typ),
+                    _ => FieldType::Map(self.unrepresentable(pointer, "map of arrays or maps")),
+                },
+                _ => FieldType::Single("google.protobuf.Struct".to_string()),
+            },
+            SchemaKind::Type(Type::Object(_)) | SchemaKind::OneOf { .. } => {
+                let typ = self.unique_name(pascal_case(name), "Message", &mut nested.names, pointer);
+                let definition = self.message(&typ, &schema, pointer, indent);
+                nested.definitions.push(definition);
+                FieldType::Single(typ)
+            }
+            _ => FieldType::Single(self.scalar(&schema, pointer).unwrap_or_else(|| self.unrepresentable(pointer, "schema"))),
+        }
+    }
+
+    fn message(&mut self, name: &str, schema: &Schema, pointer: &str, indent: usize) -> String {
+        let schema = self.merged(schema, pointer);
+        let pad = "  ".repeat(indent + 1);
+        let mut nested = Nested { definitions: Vec::new(), names: HashSet::from([name.to_string()]) };
+        let mut field_names = HashSet::new();
+        let mut fields = Vec::new();
+        // The last field number used; proto3 field numbers start at 1.
+        let mut number = 0;
+        match &schema.kind {
+            SchemaKind::Type(Type::Object(o)) => {
+                for (property, property_schema) in o.properties.iter() {
+                    let property_pointer = format!("{}/properties/{}", pointer, crate::util::escape_pointer_segment(property));
+                    let field = self.unique_name(snake_case(property), "field", &mut field_names, &property_pointer);
+                    if let Some(SchemaKind::OneOf { one_of }) = property_schema.as_item().map(|s| &s.kind) {
+                        fields.push(self.oneof(&field, one_of, &property_pointer, &mut number, &mut nested, indent));
+                        continue;
+                    }
+                    number += 1;
+                    let nullable = Resolve::resolve(property_schema, self.spec).is_ok_and(|s| s.nullable);
+                    let line = match self.field_type(property_schema, property, &property_pointer, &mut nested, indent + 1) {
+                        FieldType::Single(typ) if nullable => format!("optional {} {} = {};", typ, field, number),
+                        FieldType::Single(typ) => format!("{} {} = {};", typ, field, number),
+                        FieldType::Repeated(typ) => format!("repeated {} {} = {};", typ, field, number),
+                        FieldType::Map(typ) => format!("map<string, {}> {} = {};", typ, field, number),
+                    };
+                    fields.push(format!("{}{}", pad, line));
+                }
+                if matches!(o.additional_properties, Some(AdditionalProperties::Schema(_))) && !o.properties.is_empty() {
+                    self.unrepresentable(pointer, "additionalProperties next to properties");
+                }
+            }
+            SchemaKind::OneOf { one_of } => fields.push(self.oneof("value", one_of, pointer, &mut number, &mut nested, indent)),
+            _ => {
+                self.unrepresentable(pointer, "message which isn't an object or oneOf");
+            }
+        }
+        let outer = "  ".repeat(indent);
+        let body: Vec<String> = nested.definitions.into_iter().chain(fields).collect();
+        if body.is_empty() {
+            format!("{}message {} {{}}", outer, name)
+        } else {
+            format!("{}message {} {{\n{}\n{}}}", outer, name, body.join("\n"), outer)
+        }
+    }
+
+    fn oneof(&mut self, name: &str, options: &[RefOr<Schema>], pointer: &str, number: &mut usize, nested: &mut Nested, indent: usize) -> String {
+        let pad = "  ".repeat(indent + 1);
+        let mut lines = vec![format!("{}oneof {} {{", pad, name)];
+        for (i, option) in options.iter().enumerate() {
+            let option_name = match option.as_ref_str() {
+                Some(reference) => snake_case(reference.rsplit('/').next().unwrap_or(reference)),
+                None => format!("{}_{}", name, i + 1),
+            };
+            let option_pointer = format!("{}/oneOf/{}", pointer, i);
+            let typ = match self.field_type(option, &option_name, &option_pointer, nested, indent + 1) {
+                FieldType::Single(typ) => typ,
+                _ => self.unrepresentable(&option_pointer, "repeated or map field in a oneof"),
+            };
+            *number += 1;
+            lines.push(format!("{}  {} {} = {};", pad, typ, option_name, number));
+        }
+        lines.push(format!("{}}}", pad));
+        lines.join("\n")
+    }
+
+    /// Values without a name, or whose name is taken by an earlier value, are left out.
+    fn enum_definition(&mut self, name: &str, values: &[String], pointer: &str, indent: usize) -> String {
+        let pad = "  ".repeat(indent);
+        let prefix = snake_case(name).to_uppercase();
+        let mut lines = vec![format!("{}enum {} {{", pad, name), format!("{}  {}_UNSPECIFIED = 0;", pad, prefix)];
+        let mut taken = HashSet::from(["UNSPECIFIED".to_string()]);
+        for (i, value) in values.iter().enumerate() {
+            let value_name = snake_case(value).to_uppercase();
+            let value_pointer = format!("{}/enum/{}", pointer, i);
+            if value_name.is_empty() {
+                self.unrepresentable(&value_pointer, "enum value without ASCII letters or digits");
+            } else if !taken.insert(value_name.clone()) {
+                self.unrepresentable(&value_pointer, &format!("enum value whose name {} is already taken", value_name));
+            } else {
+                lines.push(format!("{}  {}_{} = {};", pad, prefix, value_name, i + 1));
+            }
+        }
+        lines.push(format!("{}}}", pad));
+        lines.join("\n")
+    }
+}
+
+impl Schema {
+    /// Returns a proto3 message sketch for the schema. See [Schema::to_proto_message_with_report].
+    pub fn to_proto_message(&self, name: &str, spec: &OpenAPI) -> String {
+        self.to_proto_message_with_report(name, spec).0
+    }
+
+    /// Converts an object schema to a proto3 message, numbering fields in property order.
+    /// Referenced schemas become message types of the same name, inline objects and string enums
+    /// nested messages and enums, `oneOf` a `oneof`, arrays `repeated` fields, maps `map` fields,
+    /// and nullable properties `optional` ones. Formats pick the scalar type, e.g. `int32`,
+    /// `bytes` or `google.protobuf.Timestamp`, with unsigned types for integers with a
+    /// non-negative minimum.
+    ///
+    /// Nested types are named after their property, with an `Item` suffix for array items and
+    /// `Value` for map values. Names which are taken get a number, and enum values whose names
+    /// collide are left out.
+    ///
+    /// Also returns the constructs which have no proto3 equivalent, such as `anyOf`, `not`,
+    /// nested arrays or colliding names, as `pointer: construct`. Unrepresentable schemas become
+    /// `google.protobuf.Value` fields.
+    pub fn to_proto_message_with_report(&self, name: &str, spec: &OpenAPI) -> (String, Vec<String>) {
+        let mut writer = ProtoWriter { spec, report: Vec::new() };
+        let message = writer.message(name, self, "#", 0);
+        (message, writer.report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_proto_message() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    Owner:
+      type: object
+      properties:
+        name: {type: string}
+    Cat: {type: object}
+    Dog: {type: object}
+"##).unwrap();
+        let pet: Schema = serde_yaml::from_str(r##"
+type: object
+properties:
+  id: {type: integer, format: int64, minimum: 1}
+  name: {type: string}
+  kind: {type: string, enum: [cat, dog]}
+  born: {type: string, format: date-time}
+  weight: {type: number, format: float, nullable: true}
+  owners: {type: array, items: {$ref: "#/components/schemas/Owner"}}
+  labels: {type: object, additionalProperties: {type: string}}
+  details:
+    oneOf:
+      - $ref: "#/components/schemas/Cat"
+      - $ref: "#/components/schemas/Dog"
+  location:
+    type: object
+    properties:
+      lat: {type: number}
+  grid: {type: array, items: {type: array, items: {type: integer}}}
+  extra: {anyOf: [{type: string}, {type: integer}]}
+"##).unwrap();
+        let (message, report) = pet.to_proto_message_with_report("Pet", &spec);
+        assert_eq!(message, "message Pet {
+  enum Kind {
+    KIND_UNSPECIFIED = 0;
+    KIND_CAT = 1;
+    KIND_DOG = 2;
+  }
+  message Location {
+    double lat = 1;
+  }
+  uint64 id = 1;
+  string name = 2;
+  Kind kind = 3;
+  google.protobuf.Timestamp born = 4;
+  optional float weight = 5;
+  repeated Owner owners = 6;
+  map<string, string> labels = 7;
+  oneof details {
+    Cat cat = 8;
+    Dog dog = 9;
+  }
+  Location location = 10;
+  repeated google.protobuf.Value grid = 11;
+  google.protobuf.Value extra = 12;
+}");
+        assert_eq!(report, vec!["#/properties/grid: nested array or map", "#/properties/extra: anyOf"]);
+        assert_eq!(pet.to_proto_message("Pet", &spec), message);
+    }
+
+    #[test]
+    fn test_oneof_field_numbers() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    Cat: {type: object}
+    Dog: {type: object}
+"##).unwrap();
+        let pet: Schema = serde_yaml::from_str(r##"
+oneOf:
+  - $ref: "#/components/schemas/Cat"
+  - $ref: "#/components/schemas/Dog"
+"##).unwrap();
+        assert_eq!(pet.to_proto_message("Pet", &spec), "message Pet {
+  oneof value {
+    Cat cat = 1;
+    Dog dog = 2;
+  }
+}");
+        let pets: Schema = serde_yaml::from_str(r##"
+type: object
+properties:
+  pets:
+    type: array
+    items:
+      oneOf:
+        - $ref: "#/components/schemas/Cat"
+        - $ref: "#/components/schemas/Dog"
+"##).unwrap();
+        let (message, _) = pets.to_proto_message_with_report("Pets", &spec);
+        assert_eq!(message, "message Pets {
+  message PetsItem {
+    oneof value {
+      Cat cat = 1;
+      Dog dog = 2;
+    }
+  }
+  repeated PetsItem pets = 1;
+}");
+    }
+
+    #[test]
+    fn test_name_collisions() {
+        let spec = OpenAPI::default();
+        let pet: Schema = serde_yaml::from_str(r##"
+type: object
+properties:
+  kind: {type: string, enum: [a-b, a_b, "", UNSPECIFIED]}
+  fooBar: {type: object, properties: {id: {type: string}}}
+  foo_bar: {type: object, properties: {id: {type: string}}}
+  pet: {type: object, properties: {id: {type: string}}}
+"##).unwrap();
+        let (message, report) = pet.to_proto_message_with_report("Pet", &spec);
+        assert_eq!(message, "message Pet {
+  enum Kind {
+    KIND_UNSPECIFIED = 0;
+    KIND_A_B = 1;
+  }
+  message FooBar {
+    string id = 1;
+  }
+  message FooBar2 {
+    string id = 1;
+  }
+  message Pet2 {
+    string id = 1;
+  }
+  Kind kind = 1;
+  FooBar foo_bar = 2;
+  FooBar2 foo_bar2 = 3;
+  Pet2 pet = 4;
+}");
+        assert_eq!(report, vec![
+            "#/properties/kind/enum/1: enum value whose name A_B is already taken",
+            "#/properties/kind/enum/2: enum value without ASCII letters or digits",
+            "#/properties/kind/enum/3: enum value whose name UNSPECIFIED is already taken",
+            "#/properties/foo_bar: name foo_bar which is already taken",
+            "#/properties/foo_bar: name FooBar which is already taken",
+            "#/properties/pet: name Pet which is already taken",
+        ]);
+    }
+}
@@ -0,0 +1,210 @@
+//! `CREATE TABLE` sketches generated from component schemas, in PostgreSQL syntax.
+//!
+//! The export is best-effort: it jump-starts a storage model from API models, but keys, indexes,
+//! relations and normalization are left to whoever reviews it. Nested objects and arrays become
+//! `JSONB` columns.
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::*;
+use crate::export::snake_case;
+
+/// Returns the snake case identifier for `name`, double-quoted. Names without ASCII letters or
+/// digits use `fallback`, and names whose identifier is in `used` get a numeric suffix. A note
+/// explaining the identifier is pushed to `notes` whenever it isn't just the snake case name.
+fn identifier(name: &str, fallback: &str, used: &mut HashSet<String>, notes: &mut Vec<String>) -> String {
+    let base = snake_case(name);
+    let base = if base.is_empty() { fallback.to_string() } else { base };
+    let mut unique = base.clone();
+    let mut n = 1;
+    while used.contains(&unique) {
+        n += 1;
+        unique = format!("{}_{}", base, n);
+    }
+    if unique != snake_case(name) {
+        notes.push(format!("-- {} {} is named {}.", fallback, single_line(name), unique));
+    }
+    used.insert(unique.clone());
+    format!("\"{}\"", unique)
+}
+
+/// Replaces line breaks, which would end a `--` comment.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// The column type of a property. References to object schemas are stored as `JSONB`.
+fn column_type(schema: &RefOr<Schema>, spec: &OpenAPI) -> &'static str {
+    let Ok(schema) = Resolve::resolve(schema, spec) else { return "JSONB" };
+    match &schema.kind {
+        SchemaKind::Type(Type::String(s)) => match s.format.as_str() {
+            "date" => "DATE",
+            "date-time" => "TIMESTAMPTZ",
+            "time" => "TIME",
+            "uuid" => "UUID",
+            "byte" | "binary" => "BYTEA",
+            _ => "TEXT",
+        },
+        SchemaKind::Type(Type::Integer(i)) => match i.format {
+            VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32) => "INTEGER",
+            _ => "BIGINT",
+        },
+        SchemaKind::Type(Type::Number(n)) => match n.format {
+            VariantOrUnknownOrEmpty::Item(NumberFormat::Float) => "REAL",
+            VariantOrUnknownOrEmpty::Item(NumberFormat::Double) => "DOUBLE PRECISION",
+            _ => "NUMERIC",
+        },
+        SchemaKind::Type(Type::Boolean {}) => "BOOLEAN",
+        _ => "JSONB",
+    }
+}
+
+impl OpenAPI {
+    /// Returns `CREATE TABLE` statements for the object schemas in `#/components/schemas`, with
+    /// `allOf` inheritance flattened. Tables and columns are named in snake case and quoted. Names which
+    /// collide, or have no ASCII letters or digits, get a numeric suffix noted in a comment. Formats pick the
+    /// column type (`VARCHAR(n)` for strings with a `maxLength`), required non-nullable properties
+    /// are `NOT NULL`, and descriptions become `COMMENT ON` statements. A required `id` property
+    /// becomes the primary key. Schemas which aren't objects are skipped.
+    pub fn to_sql_ddl(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "-- Best-effort table sketch generated from {} {}.", single_line(&self.info.title), single_line(&self.info.version));
+        let _ = writeln!(out, "-- Review types, keys, indexes and relations before use.");
+        let mut tables = HashSet::new();
+        for (name, schema) in self.schemas.iter() {
+            let Ok(schema) = Resolve::resolve(schema, self) else { continue };
+            let Ok(flat) = schema.flatten(self, &RecursionPolicy::default()) else { continue };
+            let SchemaKind::Type(Type::Object(object)) = &flat.kind else { continue };
+            let mut notes = Vec::new();
+            let table = identifier(name, "table", &mut tables, &mut notes);
+            let mut column_names = HashSet::new();
+            let mut columns = Vec::new();
+            let mut comments = Vec::new();
+            if let Some(description) = &flat.description {
+                comments.push(format!("COMMENT ON TABLE {} IS {};", table, quote(description)));
+            }
+            for (property, schema) in object.properties.iter() {
+                let column = identifier(property, "column", &mut column_names, &mut notes);
+                let resolved = Resolve::resolve(schema, self).ok();
+                let max_length = match resolved.map(|s| &s.kind) {
+                    Some(SchemaKind::Type(Type::String(s))) => s.max_length,
+                    _ => None,
+                };
+                let mut definition = match (column_type(schema, self), max_length) {
+                    ("TEXT", Some(max)) => format!("{} VARCHAR({})", column, max),
+                    (typ, _) => format!("{} {}", column, typ),
+                };
+                let required = object.required.contains(property) && !resolved.is_some_and(|s| s.nullable);
+                if required && property == "id" {
+                    definition.push_str(" PRIMARY KEY");
+                } else if required {
+                    definition.push_str(" NOT NULL");
+                }
+                columns.push(definition);
+                if let Some(description) = resolved.and_then(|s| s.description.as_ref()) {
+                    comments.push(format!("COMMENT ON COLUMN {}.{} IS {};", table, column, quote(description)));
+                }
+            }
+            let _ = writeln!(out);
+            for note in notes {
+                let _ = writeln!(out, "{}", note);
+            }
+            let _ = write!(out, "CREATE TABLE {} (\n    {}\n);\n", table, columns.join(",\n    "));
+            for comment in comments {
+                let _ = writeln!(out, "{}", comment);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_sql_ddl() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    Entity:
+      type: object
+      required: [id]
+      properties:
+        id: {type: string, format: uuid}
+        createdAt: {type: string, format: date-time}
+    PetOwner:
+      description: Someone's pet owner
+      allOf:
+        - $ref: "#/components/schemas/Entity"
+        - type: object
+          required: [name]
+          properties:
+            name: {type: string, maxLength: 80, description: Full name}
+            age: {type: integer, format: int32, nullable: true}
+            tags: {type: array, items: {type: string}}
+    Name:
+      type: string
+"##).unwrap();
+        let ddl = spec.to_sql_ddl();
+        assert!(ddl.starts_with("-- Best-effort table sketch generated from Pets 1.0.0.\n"));
+        assert!(ddl.contains(r#"
+CREATE TABLE "pet_owner" (
+    "id" UUID PRIMARY KEY,
+    "created_at" TIMESTAMPTZ,
+    "name" VARCHAR(80) NOT NULL,
+    "age" INTEGER,
+    "tags" JSONB
+);
+COMMENT ON TABLE "pet_owner" IS 'Someone''s pet owner';
+COMMENT ON COLUMN "pet_owner"."name" IS 'Full name';
+"#), "{}", ddl);
+        assert!(ddl.contains("CREATE TABLE \"entity\" ("));
+        assert!(!ddl.contains("CREATE TABLE \"name\""));
+    }
+
+    #[test]
+    fn test_sql_identifiers() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: "Pets\nDROP TABLE pets;", version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    PetOwner:
+      type: object
+      properties:
+        fooBar: {type: string}
+        foo_bar: {type: string}
+        名前: {type: string}
+    pet_owner:
+      type: object
+      properties:
+        id: {type: integer}
+"##).unwrap();
+        let ddl = spec.to_sql_ddl();
+        assert_eq!(ddl, r#"-- Best-effort table sketch generated from Pets DROP TABLE pets; 1.0.0.
+-- Review types, keys, indexes and relations before use.
+
+-- column foo_bar is named foo_bar_2.
+-- column 名前 is named column.
+CREATE TABLE "pet_owner" (
+    "foo_bar" TEXT,
+    "foo_bar_2" TEXT,
+    "column" TEXT
+);
+
+-- table pet_owner is named pet_owner_2.
+CREATE TABLE "pet_owner_2" (
+    "id" BIGINT
+);
+"#);
+    }
+}
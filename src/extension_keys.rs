@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key, to_json};
+
+/// Extension prefixes reserved by the OpenAPI Initiative.
+pub const RESERVED_EXTENSION_PREFIXES: [&str; 2] = ["x-oai-", "x-oas-"];
+
+impl OpenAPI {
+    /// Checks the keys of every extension in the document. Keys must start with `x-` and must not
+    /// use a [RESERVED_EXTENSION_PREFIXES] prefix. Keys without `x-` can only be inserted in code,
+    /// and are dropped when the document is parsed again.
+    pub fn validate_extension_keys(&self) -> Vec<ValidationError> {
+        let doc = to_json(self);
+        let mut errors = Vec::new();
+        walk_extensions(&doc, "#", false, &mut |pointer, key| {
+            if let Some(prefix) = RESERVED_EXTENSION_PREFIXES.iter().find(|p| key.starts_with(*p)) {
+                errors.push(ValidationError::new(format!("{}/{}", pointer, key), format!("Extension {} uses the reserved prefix {}", key, prefix)));
+            }
+        });
+        let Ok(reparsed) = serde_json::from_value::<OpenAPI>(doc.clone()) else { return errors };
+        let reparsed = to_json(&reparsed);
+        let mut dropped = Vec::new();
+        crate::parse::find_unknown_keys(&doc, &reparsed, &mut String::from("#"), &mut dropped);
+        for pointer in dropped {
+            errors.push(ValidationError::new(pointer, "Extension keys must start with x-"));
+        }
+        errors
+    }
+
+    /// Moves the extensions in the namespace `old_prefix` into `new_prefix`, e.g. `x-owner` becomes
+    /// `x-acme-owner` for the prefixes `x-` and `x-acme-`. Extensions already in the new namespace
+    /// are kept. Extensions read by this crate, such as `x-cors`, are only moved if `old_prefix`
+    /// covers them. Returns the number of renamed keys.
+    pub fn prefix_extensions(&mut self, old_prefix: &str, new_prefix: &str) -> Result<usize> {
+        if let Some(prefix) = [old_prefix, new_prefix].iter().find(|p| !p.starts_with("x-")) {
+            return Err(anyhow!("Extension prefix {} must start with x-", prefix));
+        }
+        self.rename_extensions(|key| match key.strip_prefix(old_prefix) {
+            Some(_) if key.starts_with(new_prefix) => None,
+            Some(rest) => Some(format!("{}{}", new_prefix, rest)),
+            None => None,
+        })
+    }
+
+    /// Renames extension keys throughout the document according to `migrations` of `(old, new)` keys.
+    /// Returns the number of renamed keys.
+    pub fn migrate_extension_keys(&mut self, migrations: &[(&str, &str)]) -> Result<usize> {
+        if let Some((_, new)) = migrations.iter().find(|(_, new)| !new.starts_with("x-")) {
+            return Err(anyhow!("Extension key {} must start with x-", new));
+        }
+        self.rename_extensions(|key| migrations.iter().find(|(old, _)| *old == key).map(|(_, new)| new.to_string()))
+    }
+
+    /// Fails without changes if a renamed key would replace another extension.
+    fn rename_extensions(&mut self, rename: impl Fn(&str) -> Option<String>) -> Result<usize> {
+        let mut doc = to_json(&*self);
+        let count = rename_in(&mut doc, "#", false, &rename)?;
+        if count > 0 {
+            self.edit_json(|value| *value = doc);
+        }
+        Ok(count)
+    }
+}
+
+/// Calls `visit` with the pointer of the containing object and the key of each extension.
+fn walk_extensions(value: &Value, pointer: &str, is_name_map: bool, visit: &mut dyn FnMut(&str, &str)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if !is_name_map && key.starts_with("x-") {
+                    visit(pointer, key);
+                    continue;
+                }
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                let child_pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+                walk_extensions(child, &child_pointer, !is_name_map && is_name_map_key(key), visit);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                walk_extensions(item, &format!("{}/{}", pointer, i), false, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rename_in(value: &mut Value, pointer: &str, is_name_map: bool, rename: &dyn Fn(&str) -> Option<String>) -> Result<usize> {
+    let mut count = 0;
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                let renames: Vec<(String, String)> = map.keys()
+                    .filter(|key| key.starts_with("x-"))
+                    .filter_map(|key| Some((key.clone(), rename(key)?)))
+                    .filter(|(old, new)| old != new)
+                    .collect();
+                for (old, new) in renames {
+                    if map.contains_key(&new) {
+                        return Err(anyhow!("{}: renaming {} would replace {}", pointer, old, new));
+                    }
+                    let v = map.remove(&old).expect("Key exists");
+                    map.insert(new, v);
+                    count += 1;
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                let child_pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+                count += rename_in(child, &child_pointer, !is_name_map && is_name_map_key(key), rename)?;
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                count += rename_in(item, &format!("{}/{}", pointer, i), false, rename)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_extension_keys() {
+        let mut spec = OpenAPI::default();
+        spec.info.extensions.insert("x-owner".to_string(), json!("pets"));
+        spec.info.extensions.insert("x-oai-status".to_string(), json!("draft"));
+        let mut schema = Schema::new_object();
+        schema.properties_mut().insert("x-id", Schema::new_string());
+        schema.extensions.insert("owner".to_string(), json!("pets"));
+        schema.extensions.insert("x-table".to_string(), json!({"x-nested": true}));
+        spec.schemas.insert("Pet", schema);
+
+        let errors = spec.validate_extension_keys();
+        assert_eq!(errors, vec![
+            ValidationError::new("#/info/x-oai-status", "Extension x-oai-status uses the reserved prefix x-oai-"),
+            ValidationError::new("#/components/schemas/Pet/owner", "Extension keys must start with x-"),
+        ]);
+
+        spec.schemas.get_mut2("Pet").unwrap().extensions.shift_remove("owner");
+        assert_eq!(spec.migrate_extension_keys(&[("x-oai-status", "x-status")]).unwrap(), 1);
+        assert_eq!(spec.prefix_extensions("x-", "x-acme-").unwrap(), 3);
+        assert_eq!(spec.info.extensions.keys().collect::<Vec<_>>(), vec!["x-acme-owner", "x-acme-status"]);
+        let pet = spec.schemas.get2("Pet").unwrap();
+        assert_eq!(pet.extensions["x-acme-table"], json!({"x-nested": true}));
+        assert!(pet.properties().contains_key("x-id"));
+        assert!(spec.migrate_extension_keys(&[("x-acme-owner", "x-acme-status")]).is_err());
+
+        spec.extensions.insert("x-cors".to_string(), json!({"allowOrigins": ["*"]}));
+        assert_eq!(spec.prefix_extensions("x-acme-", "x-pets-").unwrap(), 3);
+        assert_eq!(spec.info.extensions.keys().collect::<Vec<_>>(), vec!["x-pets-owner", "x-pets-status"]);
+        assert!(spec.extensions.contains_key("x-cors"));
+        assert!(spec.prefix_extensions("acme-", "x-acme-").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{is_data_field, is_name_map_key, to_json};
+
+/// Extension marking an operation or schema property as part of a feature:
+/// either a flag name, or a list of flag names of which any enables it.
+pub const FEATURE_EXTENSION: &str = "x-feature";
+
+fn is_enabled(feature: Option<&Value>, flags: &HashSet<String>) -> bool {
+    match feature {
+        Some(Value::String(flag)) => flags.contains(flag),
+        Some(Value::Array(any)) => any.iter().any(|f| f.as_str().is_some_and(|f| flags.contains(f))),
+        _ => true,
+    }
+}
+
+/// Removes properties for which `keep` is false from every schema in the JSON document,
+/// and from their `required` lists. `is_name_map` tells whether `value` is a map keyed by
+/// names, such as properties or responses, whose keys are never data fields.
+pub(crate) fn retain_properties(value: &mut Value, keep: &dyn Fn(&Value) -> bool, is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                let mut removed = Vec::new();
+                if let Some(Value::Object(properties)) = map.get_mut("properties") {
+                    properties.retain(|name, schema| {
+                        let kept = keep(schema);
+                        if !kept {
+                            removed.push(name.clone());
+                        }
+                        kept
+                    });
+                }
+                if let Some(Value::Array(required)) = map.get_mut("required") {
+                    required.retain(|r| !r.as_str().is_some_and(|r| removed.iter().any(|name| name == r)));
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                retain_properties(child, keep, !is_name_map && is_name_map_key(key));
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| retain_properties(item, keep, false)),
+        _ => {}
+    }
+}
+
+impl OpenAPI {
+    /// Removes every operation for which `remove` is true, and paths left without operations.
+    pub(crate) fn remove_operations_where(&mut self, remove: impl Fn(&Operation) -> bool) {
+        let removed: Vec<(String, String)> = self.operations()
+            .filter(|(_, _, op, _)| remove(op))
+            .map(|(path, method, _, _)| (path.to_string(), method.to_string()))
+            .collect();
+        for (path, method) in removed {
+            let method = PathMethod::from_bytes(method.to_uppercase().as_bytes()).expect("PathItem methods are valid");
+            self.remove_operation(method, &path, false);
+        }
+    }
+
+    /// Rebuilds the spec from its JSON form after `edit`, for changes which apply throughout the document.
+    pub(crate) fn edit_json(&mut self, edit: impl FnOnce(&mut Value)) {
+        let mut doc = to_json(&*self);
+        edit(&mut doc);
+        *self = serde_json::from_value(doc).expect("Edited document deserializes");
+    }
+}
+
+impl OpenAPI {
+    /// Returns the variant of this spec for the given enabled feature flags. Operations and schema
+    /// properties whose [FEATURE_EXTENSION] names no enabled flag are removed, then components which
+    /// only they used are pruned. Unannotated elements are always kept.
+    pub fn apply_feature_flags(&self, flags: &HashSet<String>) -> OpenAPI {
+        let mut spec = self.clone();
+        let before = spec.reachable_components();
+        spec.remove_operations_where(|op| !is_enabled(op.extensions.get(FEATURE_EXTENSION), flags));
+        spec.edit_json(|doc| retain_properties(doc, &|schema| is_enabled(schema.get(FEATURE_EXTENSION), flags), false));
+        spec.prune_newly_unreferenced(before);
+        spec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_feature_flags() {
+        let mut spec = OpenAPI::default();
+        let mut account = Schema::new_object();
+        account.properties_mut().insert("id", Schema::new_string());
+        let mut sso = Schema::new_string();
+        sso.extensions.insert(FEATURE_EXTENSION.to_string(), json!(["sso", "enterprise"]));
+        account.properties_mut().insert("ssoDomain", sso);
+        account.required_mut().push("ssoDomain".to_string());
+        spec.schemas.insert("Account", account);
+        spec.schemas.insert("Report", Schema::new_object());
+
+        spec.add_operation(PathMethod::GET, "/account", Operation::default()).unwrap();
+        let mut reports = Operation::default();
+        reports.add_response_success_json(Some(RefOr::schema_ref("Report")));
+        reports.extensions.insert(FEATURE_EXTENSION.to_string(), json!("reports"));
+        spec.add_operation(PathMethod::GET, "/reports", reports).unwrap();
+
+        // Names which are also data fields, such as the default response or a schema named value.
+        let gated = || {
+            let mut schema = Schema::new_string();
+            schema.extensions.insert(FEATURE_EXTENSION.to_string(), json!("debug"));
+            schema
+        };
+        let mut error = Schema::new_object();
+        error.properties_mut().insert("trace", gated());
+        let mut fallible = Operation::default();
+        fallible.responses.default = Some(Response::new("Error").json(error).into());
+        spec.add_operation(PathMethod::POST, "/account", fallible).unwrap();
+        let mut value = Schema::new_object();
+        value.properties_mut().insert("raw", gated());
+        spec.schemas.insert("value", value);
+
+        let basic = spec.apply_feature_flags(&HashSet::new());
+        let default = basic.paths.get("/account").unwrap().as_item().unwrap().post.as_ref().unwrap().responses.default.as_ref().unwrap();
+        let error = default.as_item().unwrap().json_schema().unwrap().as_item().unwrap();
+        assert!(error.get_properties().unwrap().is_empty());
+        assert!(basic.schemas.get2("value").unwrap().get_properties().unwrap().is_empty());
+        assert_eq!(basic.paths.keys().collect::<Vec<_>>(), vec!["/account"]);
+        assert!(basic.schemas.get("Report").is_none());
+        let account = basic.schemas.get2("Account").unwrap();
+        assert_eq!(account.get_properties().unwrap().keys().collect::<Vec<_>>(), vec!["id"]);
+        assert!(account.get_required().unwrap().is_empty());
+
+        let flags = vec!["reports".to_string(), "enterprise".to_string(), "debug".to_string()].into_iter().collect();
+        assert_eq!(spec.apply_feature_flags(&flags), spec);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::*;
+
+/// Selects operations by path, method, tag or operationId.
+///
+/// Empty criteria match everything, so `OperationFilter::default()` matches every operation.
+/// When several criteria are set, an operation must satisfy all of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationFilter {
+    /// Match operations whose path starts with this prefix.
+    pub path_prefix: Option<String>,
+    /// Match operations with one of these methods.
+    pub methods: Vec<PathMethod>,
+    /// Match operations with at least one of these tags.
+    pub tags: Vec<String>,
+    /// Match operations with one of these operationIds.
+    pub operation_ids: Vec<String>,
+}
+
+impl OperationFilter {
+    /// A filter which matches every operation.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn method(mut self, method: PathMethod) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation_ids.push(operation_id.into());
+        self
+    }
+
+    /// Returns true if the operation at the given path and method (e.g. `get`) matches this filter.
+    pub fn matches(&self, path: &str, method: &str, operation: &Operation) -> bool {
+        if let Some(prefix) = &self.path_prefix {
+            if !path.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if !self.methods.is_empty() && !self.methods.iter().any(|m| m.as_str().eq_ignore_ascii_case(method)) {
+            return false;
+        }
+        if !self.tags.is_empty() && !operation.tags.iter().any(|t| self.tags.contains(t)) {
+            return false;
+        }
+        if !self.operation_ids.is_empty() && !operation.operation_id.as_ref().map(|id| self.operation_ids.contains(id)).unwrap_or(false) {
+            return false;
+        }
+        true
+    }
+}
+
+impl OpenAPI {
+    /// Removes every operation not matched by the filter, and paths left without operations.
+    /// See [OpenAPI::remove_operation] for `prune_components`.
+    pub fn retain_operations(&mut self, filter: &OperationFilter, prune_components: bool) {
+        let before = prune_components.then(|| self.reachable_components());
+        let removed: Vec<(String, String)> = self.operations()
+            .filter(|(path, method, op, _)| !filter.matches(path, method, op))
+            .map(|(path, method, _, _)| (path.to_string(), method.to_string()))
+            .collect();
+        for (path, method) in removed {
+            let method = PathMethod::from_bytes(method.to_uppercase().as_bytes()).expect("PathItem methods are valid");
+            self.remove_operation(method, &path, false);
+        }
+        if let Some(before) = before {
+            self.prune_newly_unreferenced(before);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let op = Operation {
+            tags: vec!["pets".to_string()],
+            operation_id: Some("listPets".to_string()),
+            ..Operation::default()
+        };
+        assert!(OperationFilter::all().matches("/pets", "get", &op));
+        assert!(OperationFilter::all().path_prefix("/pets").method(PathMethod::GET).tag("pets").matches("/pets", "get", &op));
+        assert!(!OperationFilter::all().method(PathMethod::POST).matches("/pets", "get", &op));
+        assert!(!OperationFilter::all().operation_id("createPet").matches("/pets", "get", &op));
+    }
+
+    #[test]
+    fn test_retain_operations() {
+        let mut api = OpenAPI::default();
+        let mut op = Operation::default();
+        op.add_query_param("filter", RefOr::schema_ref("Filter"));
+        api.schemas.insert("Filter", Schema::new_string());
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        api.add_operation(PathMethod::GET, "/users", Operation::default()).unwrap();
+        api.retain_operations(&OperationFilter::all().path_prefix("/users"), true);
+        assert_eq!(api.paths.keys().collect::<Vec<_>>(), vec!["/users"]);
+        assert!(api.schemas.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::*;
+
+fn graphql_request() -> Schema {
+    let mut schema = Schema::new_object();
+    let props = schema.properties_mut();
+    props.insert("query", Schema::new_string());
+    props.insert("operationName", Schema::new_string());
+    props.insert("variables", Schema::new_map_any());
+    props.insert("extensions", Schema::new_map_any());
+    schema.add_required("query");
+    schema
+}
+
+fn graphql_error() -> Schema {
+    let mut location = Schema::new_object();
+    location.properties_mut().insert("line", Schema::new_integer());
+    location.properties_mut().insert("column", Schema::new_integer());
+
+    let mut schema = Schema::new_object();
+    let props = schema.properties_mut();
+    props.insert("message", Schema::new_string());
+    props.insert("locations", Schema::new_array(location));
+    props.insert("path", Schema::new_array_any());
+    props.insert("extensions", Schema::new_map_any());
+    schema.add_required("message");
+    schema
+}
+
+fn graphql_response() -> Schema {
+    let mut data = Schema::new_map_any();
+    data.nullable = true;
+    let mut schema = Schema::new_object();
+    schema.properties_mut().insert("data", data);
+    schema.properties_mut().insert("errors", Schema::new_array(graphql_error()));
+    schema.properties_mut().insert("extensions", Schema::new_map_any());
+    schema
+}
+
+impl Operation {
+    /// Creates an operation modeling the standard GraphQL-over-HTTP `POST` endpoint: a JSON body
+    /// with `query`, `operationName` and `variables`, and a JSON response with `data` and `errors`.
+    /// Add it at the gateway's path, conventionally `/graphql`.
+    pub fn graphql_default() -> Self {
+        let mut op = Operation {
+            operation_id: Some("graphql".to_string()),
+            summary: Some("Execute a GraphQL query or mutation".to_string()),
+            tags: vec!["GraphQL".to_string()],
+            request_body: Some(RequestBody::json(graphql_request()).required().into()),
+            ..Operation::default()
+        };
+        op.responses.responses.insert(StatusCode::Code(200), Response::new("GraphQL result").json(graphql_response()).into());
+        op.responses.responses.insert(StatusCode::Code(400), Response::new("Malformed GraphQL request").json(graphql_response()).into());
+        op
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_graphql_default() {
+        let mut spec = OpenAPI::default();
+        spec.add_operation(PathMethod::POST, "/graphql", Operation::graphql_default()).unwrap();
+        let op = spec.get_operation("graphql").unwrap().0;
+        let request = op.request_body.as_ref().unwrap().as_item().unwrap().schema_for("application/json").unwrap();
+        assert!(request.validate_value(&json!({"query": "{ me { id } }", "variables": {}}), &spec).is_empty());
+        assert!(!request.validate_value(&json!({"variables": {}}), &spec).is_empty());
+        let response = op.responses.responses[&StatusCode::Code(200)].as_item().unwrap().json_schema().unwrap();
+        let result = json!({"data": null, "errors": [{"message": "Not found", "path": ["me"]}]});
+        assert!(response.validate_value(&result, &spec).is_empty());
+    }
+}
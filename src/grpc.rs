@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+use crate::util::{read_extension, write_extension};
+use crate::validate::path_pointer;
+
+const GOOGLE_BACKEND_EXTENSION: &str = "x-google-backend";
+const HTTP_EXTENSION: &str = "x-google-api-http";
+
+/// How the request path is forwarded to a [GoogleBackend] address.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PathTranslation {
+    AppendPathToAddress,
+    ConstantAddress,
+}
+
+/// The `x-google-backend` extension, routing requests to a backend service. Set on the document
+/// for a default backend, or on an [Operation] to override it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GoogleBackend {
+    /// The backend URL, e.g. `grpcs://books-abc.a.run.app`.
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_translation: Option<PathTranslation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt_audience: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub disable_auth: bool,
+    /// Request deadline in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<f64>,
+    /// The backend protocol, e.g. `h2`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+}
+
+/// A custom HTTP method binding of an [HttpRule].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomHttpPattern {
+    pub kind: String,
+    pub path: String,
+}
+
+/// The `google.api.Http` message, in its JSON form: the HTTP mapping of a gRPC service, as in the
+/// `http` section of a service configuration. Stored in the document's `x-google-api-http` extension.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleApiHttp {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<HttpRule>,
+    #[serde(default, skip_serializing_if = "is_false", alias = "fully_decode_reserved_expansion")]
+    pub fully_decode_reserved_expansion: bool,
+}
+
+/// A `google.api.HttpRule`, mapping a gRPC method to an HTTP method and path template.
+/// Fields are written in camelCase, as in the JSON form of the message; the snake_case proto
+/// field names are also read.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpRule {
+    /// The fully qualified gRPC method, e.g. `library.v1.Library.GetBook`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub selector: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub get: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub put: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom: Option<CustomHttpPattern>,
+    /// The request field mapped to the HTTP body, or `*` for all fields not bound by the path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", alias = "response_body")]
+    pub response_body: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", alias = "additional_bindings")]
+    pub additional_bindings: Vec<HttpRule>,
+}
+
+impl HttpRule {
+    /// Returns the HTTP method and path template this rule binds to, or `None` if no pattern is set.
+    pub fn binding(&self) -> Option<(PathMethod, &str)> {
+        let patterns = vec![
+            (PathMethod::GET, &self.get),
+            (PathMethod::PUT, &self.put),
+            (PathMethod::POST, &self.post),
+            (PathMethod::DELETE, &self.delete),
+            (PathMethod::PATCH, &self.patch),
+        ];
+        if let Some((method, path)) = patterns.into_iter().find_map(|(m, p)| p.as_deref().map(|p| (m, p))) {
+            return Some((method, path));
+        }
+        let custom = self.custom.as_ref()?;
+        let method = PathMethod::from_bytes(custom.kind.to_uppercase().as_bytes()).ok()?;
+        Some((method, custom.path.as_str()))
+    }
+
+    /// Returns the bindings of this rule followed by its `additional_bindings`.
+    pub fn bindings(&self) -> Vec<(PathMethod, &str)> {
+        self.binding().into_iter()
+            .chain(self.additional_bindings.iter().flat_map(|b| b.binding()))
+            .collect()
+    }
+}
+
+/// Converts a gRPC path template to an OpenAPI path, dropping variable patterns:
+/// `/v1/{name=shelves/*}/books` becomes `/v1/{name}/books`.
+pub fn grpc_template_to_path(template: &str) -> String {
+    let mut path = String::with_capacity(template.len());
+    let mut in_pattern = false;
+    for c in template.chars() {
+        match c {
+            '=' if path.rfind('{') > path.rfind('}') => in_pattern = true,
+            '}' => {
+                in_pattern = false;
+                path.push(c);
+            }
+            _ if !in_pattern => path.push(c),
+            _ => {}
+        }
+    }
+    path
+}
+
+impl Operation {
+    /// Returns the `x-google-backend` of this operation, if it exists and is well-formed.
+    pub fn google_backend(&self) -> Option<GoogleBackend> {
+        read_extension(&self.extensions, GOOGLE_BACKEND_EXTENSION)?.ok()
+    }
+
+    pub fn set_google_backend(&mut self, backend: &GoogleBackend) {
+        write_extension(&mut self.extensions, GOOGLE_BACKEND_EXTENSION, backend)
+    }
+}
+
+impl OpenAPI {
+    /// Returns the document-level `x-google-backend`, if it exists and is well-formed.
+    pub fn google_backend(&self) -> Option<GoogleBackend> {
+        read_extension(&self.extensions, GOOGLE_BACKEND_EXTENSION)?.ok()
+    }
+
+    pub fn set_google_backend(&mut self, backend: &GoogleBackend) {
+        write_extension(&mut self.extensions, GOOGLE_BACKEND_EXTENSION, backend)
+    }
+
+    /// Returns the backend for an operation: its own `x-google-backend`, else the document's.
+    pub fn google_backend_for(&self, operation: &Operation) -> Option<GoogleBackend> {
+        operation.google_backend().or_else(|| self.google_backend())
+    }
+
+    /// Returns the `x-google-api-http` mapping of this document, if it exists and is well-formed.
+    pub fn google_api_http(&self) -> Option<GoogleApiHttp> {
+        read_extension(&self.extensions, HTTP_EXTENSION)?.ok()
+    }
+
+    pub fn set_google_api_http(&mut self, http: &GoogleApiHttp) {
+        write_extension(&mut self.extensions, HTTP_EXTENSION, http)
+    }
+
+    /// Returns the rules of the `x-google-api-http` mapping. Empty if missing or malformed.
+    pub fn grpc_http_rules(&self) -> Vec<HttpRule> {
+        self.google_api_http().map(|http| http.rules).unwrap_or_default()
+    }
+
+    /// Sets the rules of the `x-google-api-http` mapping, keeping its other fields.
+    pub fn set_grpc_http_rules(&mut self, rules: &[HttpRule]) {
+        let mut http = self.google_api_http().unwrap_or_default();
+        http.rules = rules.to_vec();
+        self.set_google_api_http(&http)
+    }
+}
+
+/// Reports malformed gRPC extensions, and HTTP rules whose bindings don't match a path and
+/// method of the spec.
+pub(crate) fn validate_grpc(spec: &OpenAPI, errors: &mut Vec<ValidationError>) {
+    if let Some(Err(e)) = read_extension::<GoogleBackend>(&spec.extensions, GOOGLE_BACKEND_EXTENSION) {
+        errors.push(ValidationError::new(format!("#/{}", GOOGLE_BACKEND_EXTENSION), format!("Invalid backend: {}", e)));
+    }
+    let pointer = format!("#/{}/rules", HTTP_EXTENSION);
+    let http: GoogleApiHttp = match read_extension(&spec.extensions, HTTP_EXTENSION) {
+        None => return,
+        Some(Err(e)) => return errors.push(ValidationError::new(format!("#/{}", HTTP_EXTENSION), format!("Invalid HTTP mapping: {}", e))),
+        Some(Ok(http)) => http,
+    };
+    for (i, rule) in http.rules.iter().enumerate() {
+        let pointer = format!("{}/{}", pointer, i);
+        let bindings = rule.bindings();
+        if bindings.is_empty() {
+            errors.push(ValidationError::new(&pointer, format!("Rule for {} has no HTTP binding", rule.selector)));
+        }
+        for (method, template) in bindings {
+            let path = grpc_template_to_path(template);
+            let defined = spec.paths.get(&path)
+                .and_then(|item| item.as_item())
+                .map(|item| item.operation(&method).is_some());
+            match defined {
+                None => errors.push(ValidationError::new(&pointer, format!("Template {} of {} matches no path", template, rule.selector))),
+                Some(false) => errors.push(ValidationError::new(&pointer, format!("Path {} does not define {} for {}", path, method, rule.selector))),
+                Some(true) => {}
+            }
+        }
+    }
+}
+
+pub(crate) fn validate_grpc_path(path: &str, item: &PathItem, errors: &mut Vec<ValidationError>) {
+    for (method, op) in item.iter() {
+        if let Some(Err(e)) = read_extension::<GoogleBackend>(&op.extensions, GOOGLE_BACKEND_EXTENSION) {
+            let pointer = format!("{}/{}/{}", path_pointer(path), method, GOOGLE_BACKEND_EXTENSION);
+            errors.push(ValidationError::new(pointer, format!("Invalid backend: {}", e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_rules() {
+        let mut spec = OpenAPI::default();
+        let mut op = Operation::default();
+        op.add_path_param("name", Schema::new_string());
+        spec.add_operation(PathMethod::GET, "/v1/{name}", op).unwrap();
+        assert_eq!(grpc_template_to_path("/v1/{name=shelves/*/books/*}"), "/v1/{name}");
+        spec.set_grpc_http_rules(&[HttpRule {
+            selector: "library.v1.Library.GetBook".to_string(),
+            get: Some("/v1/{name=shelves/*/books/*}".to_string()),
+            additional_bindings: vec![HttpRule {
+                delete: Some("/v1/{name=shelves/*/books/*}".to_string()),
+                ..HttpRule::default()
+            }],
+            ..HttpRule::default()
+        }]);
+        spec.set_google_backend(&GoogleBackend {
+            address: "grpcs://library.example.com".to_string(),
+            path_translation: Some(PathTranslation::AppendPathToAddress),
+            ..GoogleBackend::default()
+        });
+        assert_eq!(spec.grpc_http_rules()[0].bindings().len(), 2);
+        assert_eq!(spec.extensions["x-google-backend"]["path_translation"], "APPEND_PATH_TO_ADDRESS");
+        assert_eq!(spec.extensions["x-google-api-http"]["rules"][0]["additionalBindings"][0]["delete"], "/v1/{name=shelves/*/books/*}");
+        let errors = spec.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Path /v1/{name} does not define DELETE for library.v1.Library.GetBook");
+        assert_eq!(errors[0].pointer, "#/x-google-api-http/rules/0");
+
+        let http: GoogleApiHttp = serde_json::from_value(serde_json::json!({
+            "rules": [{"selector": "library.v1.Library.GetBook", "get": "/v1/{name}", "response_body": "book"}],
+            "fully_decode_reserved_expansion": true,
+        })).unwrap();
+        assert!(http.fully_decode_reserved_expansion);
+        assert_eq!(http.rules[0].response_body.as_deref(), Some("book"));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::*;
+
+/// Controls which operations [OpenAPI::generate_head_options] synthesizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadOptionsPolicy {
+    /// Add a HEAD operation to every path with a GET operation.
+    pub head: bool,
+    /// Add an OPTIONS operation to every path, advertising its methods in an `Allow` header.
+    pub options: bool,
+    /// Only consider paths with at least one operation matching this filter.
+    pub filter: OperationFilter,
+}
+
+impl Default for HeadOptionsPolicy {
+    fn default() -> Self {
+        HeadOptionsPolicy {
+            head: true,
+            options: true,
+            filter: OperationFilter::all(),
+        }
+    }
+}
+
+/// Copies a GET operation for use as HEAD: same parameters and response headers, no bodies.
+fn head_from_get(get: &Operation, spec: &OpenAPI) -> Operation {
+    let mut head = get.clone();
+    head.operation_id = get.operation_id.as_ref().map(|id| format!("{}Head", id));
+    head.request_body = None;
+    let responses = head.responses.responses.values_mut().chain(head.responses.default.as_mut());
+    for response in responses {
+        let mut item = match response.resolve(spec) {
+            Ok(item) => item.clone(),
+            Err(_) => continue,
+        };
+        item.content.clear();
+        *response = RefOr::Item(item);
+    }
+    head
+}
+
+fn options_for(item: &PathItem) -> Operation {
+    let mut allowed: Vec<String> = item.methods().map(|m| m.to_string()).collect();
+    if !allowed.iter().any(|m| m == "OPTIONS") {
+        allowed.push("OPTIONS".to_string());
+    }
+    let mut allow = Header::new(Schema::new_string());
+    allow.description = Some("The methods supported by this path.".to_string());
+    allow.example = Some(allowed.join(", ").into());
+    let mut response = Response::new("The allowed methods.");
+    response.headers.insert("Allow".to_string(), RefOr::Item(allow));
+    let mut options = Operation {
+        summary: Some("Describe the allowed methods".to_string()),
+        ..Operation::default()
+    };
+    options.responses.responses.insert(StatusCode::Code(204), RefOr::Item(response));
+    options
+}
+
+impl OpenAPI {
+    /// Synthesizes HEAD operations mirroring GET, and OPTIONS operations advertising the allowed
+    /// methods of each path. Existing HEAD and OPTIONS operations are left untouched.
+    ///
+    /// HEAD operations copy the GET operation without response bodies, with `Head` appended to the
+    /// operationId. Referenced responses are inlined so that their content can be dropped.
+    pub fn generate_head_options(&mut self, policy: &HeadOptionsPolicy) {
+        let mut heads = Vec::new();
+        for (path, item) in self.paths.iter() {
+            let item = match item.as_item() {
+                Some(item) => item,
+                None => continue,
+            };
+            if !item.iter().any(|(method, op)| policy.filter.matches(path, method, op)) {
+                continue;
+            }
+            if let (true, Some(get), None) = (policy.head, &item.get, &item.head) {
+                heads.push((path.clone(), head_from_get(get, self)));
+            }
+        }
+        for (path, head) in heads {
+            if let Some(item) = self.paths.get_mut(&path).and_then(RefOr::as_mut) {
+                item.head = Some(head);
+            }
+        }
+        if !policy.options {
+            return;
+        }
+        for (path, item) in self.paths.iter_mut() {
+            let item = match item.as_mut() {
+                Some(item) => item,
+                None => continue,
+            };
+            if item.options.is_some() || !item.iter().any(|(method, op)| policy.filter.matches(path, method, op)) {
+                continue;
+            }
+            item.options = Some(options_for(item));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_head_options() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.responses.insert("Pets", Response::new("Pets").json(Schema::new_string()).header("X-Total", Schema::new_integer()));
+        let mut get = Operation {
+            operation_id: Some("listPets".to_string()),
+            ..Operation::default()
+        };
+        get.responses.responses.insert(StatusCode::Code(200), RefOr::ref_("#/components/responses/Pets"));
+        api.add_operation(PathMethod::GET, "/pets", get).unwrap();
+        api.add_operation(PathMethod::POST, "/pets", Operation::default()).unwrap();
+        api.add_operation(PathMethod::POST, "/admin", Operation::default()).unwrap();
+
+        api.generate_head_options(&HeadOptionsPolicy {
+            filter: OperationFilter::all().path_prefix("/pets"),
+            ..HeadOptionsPolicy::default()
+        });
+        let item = api.paths["/pets"].as_item().unwrap();
+        let head = item.head.as_ref().unwrap();
+        assert_eq!(head.operation_id.as_deref(), Some("listPetsHead"));
+        let response = head.responses.responses[&StatusCode::Code(200)].as_item().unwrap();
+        assert!(response.content.is_empty());
+        assert!(response.headers.contains_key("X-Total"));
+
+        let options = item.options.as_ref().unwrap();
+        let allow = options.responses.responses[&StatusCode::Code(204)].as_item().unwrap().headers["Allow"].as_item().unwrap();
+        assert_eq!(allow.example, Some("GET, POST, HEAD, OPTIONS".into()));
+        assert!(api.paths["/admin"].as_item().unwrap().options.is_none());
+    }
+}
@@ -38,3 +38,26 @@ pub struct Header {
     #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
     pub extensions: IndexMap<String, serde_json::Value>,
 }
+
+impl Header {
+    pub fn new(schema: impl Into<RefOr<Schema>>) -> Self {
+        Header {
+            description: None,
+            style: HeaderStyle::Simple,
+            required: false,
+            deprecated: None,
+            format: ParameterSchemaOrContent::Schema(schema.into()),
+            example: None,
+            examples: IndexMap::new(),
+            extensions: IndexMap::new(),
+        }
+    }
+
+    /// Returns the header schema if it exists.
+    pub fn schema(&self) -> Option<&RefOr<Schema>> {
+        match self.format {
+            ParameterSchemaOrContent::Schema(ref schema) => Some(schema),
+            ParameterSchemaOrContent::Content(_) => None
+        }
+    }
+}
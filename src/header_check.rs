@@ -0,0 +1,230 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::*;
+use crate::param_codec::{coerce, ValueKind};
+
+/// Request headers defined by HTTP and common infrastructure, which are never flagged as undeclared.
+const STANDARD_HEADERS: &[&str] = &[
+    "accept", "accept-charset", "accept-encoding", "accept-language", "authorization",
+    "cache-control", "connection", "content-encoding", "content-length", "content-type", "cookie",
+    "date", "expect", "forwarded", "host", "if-match", "if-modified-since", "if-none-match",
+    "if-range", "if-unmodified-since", "keep-alive", "origin", "pragma", "range", "referer",
+    "te", "trailer", "transfer-encoding", "upgrade", "user-agent", "via",
+    "access-control-request-method", "access-control-request-headers",
+    "x-forwarded-for", "x-forwarded-host", "x-forwarded-proto", "x-request-id",
+];
+
+/// Header parameters which the spec says SHALL be ignored.
+const IGNORED_PARAMS: &[&str] = &["accept", "content-type", "authorization"];
+
+/// What [Operation::check_request_headers] does with headers the operation doesn't declare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UndeclaredHeaders {
+    /// Accept any header.
+    #[default]
+    Allow,
+    /// Flag headers which are neither declared nor standard HTTP headers.
+    Flag,
+}
+
+/// A problem found by [Operation::check_request_headers].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeaderViolation {
+    /// A required header parameter is missing.
+    Missing { name: String },
+    /// The header value doesn't match the parameter schema.
+    Invalid { name: String, errors: Vec<ValidationError> },
+    /// The header isn't valid UTF-8 text.
+    Unreadable { name: String },
+    /// The header isn't declared by the operation.
+    Undeclared { name: String },
+}
+
+impl HeaderViolation {
+    pub fn name(&self) -> &str {
+        match self {
+            HeaderViolation::Missing { name }
+            | HeaderViolation::Invalid { name, .. }
+            | HeaderViolation::Unreadable { name }
+            | HeaderViolation::Undeclared { name } => name,
+        }
+    }
+}
+
+impl fmt::Display for HeaderViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderViolation::Missing { name } => write!(f, "Missing required header {}", name),
+            HeaderViolation::Invalid { name, errors } => {
+                let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "Invalid header {}: {}", name, messages.join("; "))
+            }
+            HeaderViolation::Unreadable { name } => write!(f, "Header {} is not valid text", name),
+            HeaderViolation::Undeclared { name } => write!(f, "Undeclared header {}", name),
+        }
+    }
+}
+
+/// Parses a `simple` style header value into the type of the schema.
+fn parse_header(schema: Option<&RefOr<Schema>>, explode: bool, raw: &str, spec: &OpenAPI) -> Value {
+    let parts = || raw.split(',').map(|s| s.trim());
+    match ValueKind::of_schema(schema, spec) {
+        ValueKind::Primitive(schema) => coerce(raw.trim(), schema),
+        ValueKind::Array(items) => Value::Array(parts().map(|v| coerce(v, items)).collect()),
+        ValueKind::Object(object) => {
+            let property = |k: &str| object.get_properties()
+                .and_then(|p| p.get(k))
+                .and_then(|s| Resolve::resolve(s, spec).ok());
+            let mut map = Map::new();
+            if explode {
+                for (k, v) in parts().filter_map(|p| p.split_once('=')) {
+                    map.insert(k.to_string(), coerce(v, property(k)));
+                }
+            } else {
+                let items: Vec<&str> = parts().collect();
+                for pair in items.chunks(2) {
+                    if let [k, v] = pair {
+                        map.insert(k.to_string(), coerce(v, property(k)));
+                    }
+                }
+            }
+            Value::Object(map)
+        }
+    }
+}
+
+fn check_values(name: &str, values: &[&http::HeaderValue], schema: Option<&RefOr<Schema>>, explode: bool, spec: &OpenAPI) -> Option<HeaderViolation> {
+    let Ok(raw) = values.iter().map(|v| v.to_str()).collect::<Result<Vec<&str>, _>>() else {
+        return Some(HeaderViolation::Unreadable { name: name.to_string() });
+    };
+    let errors = schema?.validate_value(&parse_header(schema, explode, &raw.join(","), spec), spec);
+    if errors.is_empty() {
+        None
+    } else {
+        Some(HeaderViolation::Invalid { name: name.to_string(), errors })
+    }
+}
+
+impl Operation {
+    /// Checks request headers against the header parameters of the operation and its path item:
+    /// required headers must be present and values must match their schemas. Per `undeclared`, headers which are neither
+    /// declared, standard, nor used by an API key security scheme are flagged.
+    ///
+    /// `Accept`, `Content-Type` and `Authorization` parameters are ignored, as the spec requires.
+    pub fn check_request_headers(&self, headers: &http::HeaderMap, path_item: &PathItem, spec: &OpenAPI, undeclared: UndeclaredHeaders) -> Vec<HeaderViolation> {
+        let mut violations = Vec::new();
+        let params: Vec<&Parameter> = self.effective_parameters(path_item, spec).into_iter()
+            .filter(|p| matches!(p.kind, ParameterKind::Header { .. }))
+            .filter(|p| !IGNORED_PARAMS.contains(&p.name.to_ascii_lowercase().as_str()))
+            .collect();
+        for param in &params {
+            let values: Vec<&http::HeaderValue> = headers.get_all(param.name.as_str()).iter().collect();
+            if values.is_empty() {
+                if param.required {
+                    violations.push(HeaderViolation::Missing { name: param.name.clone() });
+                }
+                continue;
+            }
+            if let Some(violation) = check_values(&param.name, &values, param.schema(), param.effective_explode(), spec) {
+                violations.push(violation);
+            }
+        }
+        if undeclared == UndeclaredHeaders::Flag {
+            let api_keys: Vec<String> = spec.security_schemes.values()
+                .filter_map(|s| s.as_item())
+                .filter_map(|s| match s {
+                    SecurityScheme::APIKey { location: APIKeyLocation::Header, name, .. } => Some(name.to_ascii_lowercase()),
+                    _ => None,
+                })
+                .collect();
+            for name in headers.keys() {
+                let name = name.as_str();
+                let declared = params.iter().any(|p| p.name.eq_ignore_ascii_case(name))
+                    || STANDARD_HEADERS.contains(&name)
+                    || api_keys.iter().any(|k| k == name);
+                if !declared {
+                    violations.push(HeaderViolation::Undeclared { name: name.to_string() });
+                }
+            }
+        }
+        violations
+    }
+
+    /// Checks the headers of an [http::Request] with [Operation::check_request_headers].
+    pub fn check_request<B>(&self, request: &http::Request<B>, path_item: &PathItem, spec: &OpenAPI, undeclared: UndeclaredHeaders) -> Vec<HeaderViolation> {
+        self.check_request_headers(request.headers(), path_item, spec, undeclared)
+    }
+
+    /// Checks the headers of an [http::Response] against the headers declared for its status:
+    /// required headers must be present and values must match their schemas.
+    /// `Content-Type` is ignored, as the spec requires.
+    pub fn check_response<B>(&self, response: &http::Response<B>, spec: &OpenAPI) -> Vec<HeaderViolation> {
+        let Some(declared) = self.responses.for_status(response.status().as_u16())
+            .and_then(|r| r.resolve(spec).ok()) else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+        for (name, header) in &declared.headers {
+            if name.eq_ignore_ascii_case("content-type") {
+                continue;
+            }
+            let Ok(header) = header.resolve(spec) else { continue };
+            let values: Vec<&http::HeaderValue> = response.headers().get_all(name.as_str()).iter().collect();
+            if values.is_empty() {
+                if header.required {
+                    violations.push(HeaderViolation::Missing { name: name.clone() });
+                }
+            } else if let Some(violation) = check_values(name, &values, header.schema(), false, spec) {
+                violations.push(violation);
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue};
+
+    use super::*;
+
+    #[test]
+    fn test_check_request_headers() {
+        let spec = OpenAPI::default();
+        let mut item = PathItem::default();
+        item.parameters.push(Parameter::header("X-Tenant", Schema::new_string()).required().into());
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::header("X-Page-Size", Schema::new_integer()).into());
+        op.parameters.push(Parameter::header("X-Tags", Schema::new_array(Schema::new_string())).into());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-page-size", HeaderValue::from_static("ten"));
+        headers.insert("x-tags", HeaderValue::from_static("a, b"));
+        headers.insert("user-agent", HeaderValue::from_static("test"));
+        headers.insert("x-debug", HeaderValue::from_static("1"));
+
+        let violations = op.check_request_headers(&headers, &item, &spec, UndeclaredHeaders::Flag);
+        let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        assert_eq!(messages, vec![
+            "Missing required header X-Tenant",
+            "Invalid header X-Page-Size: #: Expected integer, found string",
+            "Undeclared header x-debug",
+        ]);
+        assert_eq!(op.check_request_headers(&headers, &item, &spec, UndeclaredHeaders::Allow).len(), 2);
+
+        let request = http::Request::builder().header("X-Tenant", "acme").body(()).unwrap();
+        assert!(op.check_request(&request, &item, &spec, UndeclaredHeaders::Flag).is_empty());
+
+        let mut limited = Response::new("OK").header("X-Rate-Limit", Schema::new_integer());
+        if let Some(RefOr::Item(h)) = limited.headers.get_mut("X-Rate-Limit") {
+            h.required = true;
+        }
+        op.responses.responses.insert(StatusCode::Range(2), limited.into());
+        let response = http::Response::builder().status(204).body(()).unwrap();
+        assert_eq!(op.check_response(&response, &spec), vec![HeaderViolation::Missing { name: "X-Rate-Limit".to_string() }]);
+        let response = http::Response::builder().status(200).header("x-rate-limit", "10").body(()).unwrap();
+        assert!(op.check_response(&response, &spec).is_empty());
+    }
+}
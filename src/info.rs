@@ -9,6 +9,9 @@ use serde::{Deserialize, Serialize};
 pub struct Info {
     /// REQUIRED. The title of the application.
     pub title: String,
+    /// OpenAPI 3.1: a short summary of the API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
     /// A short description of the application.
     /// CommonMark syntax MAY be used for rich text representation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -30,3 +33,11 @@ pub struct Info {
     #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
     pub extensions: IndexMap<String, serde_json::Value>,
 }
+
+impl Info {
+    /// Sets the OpenAPI 3.1 `summary`.
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::*;
+use crate::usage::collect_refs;
+use crate::util::to_json;
+
+impl OpenAPI {
+    /// Replaces references to components used at most `threshold` times with copies of the component,
+    /// and removes those components. Returns the references of the inlined components.
+    ///
+    /// Unreferenced components, security schemes, recursive components, and schemas which take part in
+    /// a discriminator are kept, since they can't be inlined.
+    pub fn inline_single_use_components(&mut self, threshold: usize) -> Vec<String> {
+        let doc = to_json(&*self);
+        let refs = collect_refs(&doc);
+        let mut counts: IndexMap<&str, usize> = IndexMap::new();
+        let mut edges: IndexMap<&str, Vec<&str>> = IndexMap::new();
+        let mut pinned: HashSet<&str> = HashSet::new();
+        for (pointer, reference) in &refs {
+            *counts.entry(reference).or_default() += 1;
+            if let Some(source) = component_pointer(pointer) {
+                edges.entry(source).or_default().push(reference);
+            }
+            let mut segments = pointer.rsplitn(3, '/').skip(1);
+            if let (Some("oneOf" | "anyOf"), Some(parent)) = (segments.next(), segments.next()) {
+                if doc.pointer(&parent[1..]).is_some_and(|p| p.get("discriminator").is_some()) {
+                    pinned.insert(reference);
+                }
+            }
+        }
+        let mut inline: IndexMap<String, Value> = IndexMap::new();
+        for (reference, count) in counts {
+            let Some((kind, name)) = ComponentKind::parse_reference(reference) else { continue };
+            let Some(component) = self.components.get(kind, name) else { continue };
+            if count > threshold || kind == ComponentKind::SecurityScheme || pinned.contains(reference)
+                || is_discriminator_target(&doc, reference) || reaches(&edges, reference, reference) {
+                continue;
+            }
+            inline.insert(reference.to_string(), component.to_json());
+        }
+        if inline.is_empty() {
+            return Vec::new();
+        }
+        self.edit_json(|doc| {
+            if let Some(Value::Object(components)) = doc.get_mut("components") {
+                for reference in inline.keys() {
+                    let (kind, name) = ComponentKind::parse_reference(reference).expect("Parsed above");
+                    if let Some(Value::Object(map)) = components.get_mut(kind.as_str()) {
+                        map.remove(name);
+                    }
+                }
+            }
+            substitute(doc, &inline);
+        });
+        let mut inlined: Vec<String> = inline.into_keys().collect();
+        inlined.sort();
+        inlined
+    }
+}
+
+/// Replaces references to inlined components, including those within the inlined copies.
+fn substitute(value: &mut Value, inline: &IndexMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(component) = map.get("$ref").and_then(|r| r.as_str()).and_then(|r| inline.get(r)) {
+                *value = component.clone();
+                return substitute(value, inline);
+            }
+            map.values_mut().for_each(|v| substitute(v, inline));
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| substitute(v, inline)),
+        _ => {}
+    }
+}
+
+/// Returns the reference of the component containing `pointer`, e.g. `#/components/schemas/Pet`.
+fn component_pointer(pointer: &str) -> Option<&str> {
+    let rest = pointer.strip_prefix("#/components/")?;
+    let mut end = rest.find('/')? + 1;
+    end += rest[end..].find('/').unwrap_or(rest.len() - end);
+    Some(&pointer[..("#/components/".len() + end)])
+}
+
+fn reaches(edges: &IndexMap<&str, Vec<&str>>, from: &str, target: &str) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = vec![from];
+    while let Some(current) = stack.pop() {
+        for &next in edges.get(current).into_iter().flatten() {
+            if next == target {
+                return true;
+            }
+            if seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    false
+}
+
+/// Whether a discriminator mapping names the schema.
+fn is_discriminator_target(doc: &Value, reference: &str) -> bool {
+    let name = reference.rsplit('/').next().unwrap_or(reference);
+    match doc {
+        Value::Object(map) => {
+            let mapped = map.get("discriminator")
+                .and_then(|d| d.get("mapping"))
+                .and_then(|m| m.as_object())
+                .is_some_and(|m| m.values().any(|v| v == reference || v == name));
+            mapped || map.values().any(|v| is_discriminator_target(v, reference))
+        }
+        Value::Array(items) => items.iter().any(|v| is_discriminator_target(v, reference)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_single_use() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        pet.properties_mut().insert("tag", RefOr::schema_ref("Tag"));
+        pet.properties_mut().insert("owner", RefOr::schema_ref("User"));
+        spec.schemas.insert("Pet", pet);
+        spec.schemas.insert("Tag", Schema::new_string());
+        let mut user = Schema::new_object();
+        user.properties_mut().insert("friend", RefOr::schema_ref("User"));
+        spec.schemas.insert("User", user);
+        spec.schemas.insert("Unused", Schema::new_bool());
+        spec.parameters.insert("Limit", Parameter::query("limit", Schema::new_integer()));
+        let mut op = Operation::default();
+        op.parameters.push(RefOr::ref_("#/components/parameters/Limit"));
+        op.add_response_success_json(Some(RefOr::schema_ref("Pet")));
+        spec.paths.insert_operation("/pets".to_string(), PathMethod::GET, op);
+
+        let inlined = spec.inline_single_use_components(1);
+        assert_eq!(inlined, vec!["#/components/parameters/Limit", "#/components/schemas/Pet", "#/components/schemas/Tag"]);
+        assert_eq!(spec.schemas.keys().collect::<Vec<_>>(), vec!["Unused", "User"]);
+        assert!(spec.parameters.is_empty());
+        let op = spec.paths["/pets"].as_item().unwrap().get.as_ref().unwrap();
+        let pet = op.responses.responses[&StatusCode::Code(200)].as_item().unwrap().json_schema().unwrap().as_item().unwrap();
+        assert_eq!(pet.properties().get2("tag"), Some(&Schema::new_string()));
+        assert_eq!(pet.properties()["owner"].as_ref_str(), Some("#/components/schemas/User"));
+        assert!(spec.inline_single_use_components(1).is_empty());
+    }
+}
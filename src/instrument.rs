@@ -0,0 +1,35 @@
+use crate::*;
+
+/// Callbacks invoked by [SharedSpec] as it routes and validates requests, e.g. to export
+/// per-operation request counts and validation failure rates. Every method does nothing by default.
+///
+/// Hooks run synchronously on the request path, so they should be cheap, e.g. incrementing counters.
+pub trait Instrumentation: Send + Sync {
+    /// A request matched an operation. `path` is the path template, e.g. `/pets/{id}`.
+    fn operation_matched(&self, _method: &PathMethod, _path: &str, _operation: &Operation) {}
+
+    /// No operation handles the request.
+    fn operation_unmatched(&self, _method: &PathMethod, _request_path: &str) {}
+
+    /// A value was validated against a schema of an operation.
+    fn schema_validated(&self, _event: &ValidationEvent) {}
+}
+
+/// The outcome of one validation, passed to [Instrumentation::schema_validated].
+#[derive(Debug, Clone, Copy)]
+pub struct ValidationEvent<'a> {
+    pub method: &'a PathMethod,
+    /// The path template of the operation.
+    pub path: &'a str,
+    pub operation_id: Option<&'a str>,
+    /// The reference of the schema, e.g. `#/components/schemas/Pet`, or for inline schemas a
+    /// pointer to it, e.g. `#/paths/~1pets/post/requestBody/content/application~1json/schema`.
+    pub schema: &'a str,
+    pub errors: &'a [ValidationError],
+}
+
+impl ValidationEvent<'_> {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
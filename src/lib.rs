@@ -1,66 +1,181 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+mod arena;
+mod audience;
+mod auth;
+mod aws;
+mod bundle;
+mod callable;
 mod callback;
+mod compact;
+mod compat;
 mod components;
+mod constraint_policy;
 mod contact;
+mod cors;
+mod counterexample;
+mod diff;
+mod digest;
 mod discriminator;
+mod doc_coverage;
+mod docs;
+mod document_meta;
+mod edit_session;
 mod encoding;
+mod envelope;
 mod example;
+mod example_gen;
+mod example_lint;
+mod extension_keys;
 mod external_documentation;
+mod feature_flags;
+mod filter;
+mod graphql;
+mod grpc;
+mod head_options;
 mod header;
+mod header_check;
 mod info;
+mod inline;
+mod instrument;
 mod license;
 mod link;
+mod lint;
 mod media_type;
+mod ndjson;
+mod nullable;
+mod oidc;
 mod openapi;
 mod operation;
+mod pagination;
+mod param_codec;
 mod parameter;
+mod parse;
 mod paths;
+mod problem;
+mod provenance;
+mod rate_limit;
+mod recursion;
+mod redact;
 mod reference;
 mod request_body;
 mod responses;
+pub mod roundtrip;
 mod schema;
+mod schema_algebra;
+mod scopes;
 mod security_requirement;
 mod security_scheme;
 mod server;
+mod server_template;
 mod server_variable;
+mod shared;
+mod spec_fragment;
+mod sse;
 mod status_code;
+mod summary;
+mod sunset;
 mod tag;
+mod texts;
+mod trace;
+mod tracked;
+mod translate;
+mod usage;
 mod util;
+mod validate;
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+mod value_strategy;
+mod value_validation;
 mod variant_or;
 #[cfg(feature = "v2")]
 #[cfg_attr(docsrs, doc(cfg(feature = "v2")))]
 pub mod v2;
+pub mod client;
+pub mod export;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 mod versioned;
+mod view;
 mod map;
 
+pub use self::arena::*;
+pub use self::audience::*;
+pub use self::auth::*;
+pub use self::aws::*;
+pub use self::callable::*;
 pub use self::callback::*;
+pub use self::compact::*;
+pub use self::compat::*;
 pub use self::components::*;
+pub use self::constraint_policy::*;
 pub use self::contact::*;
+pub use self::cors::*;
+pub use self::counterexample::*;
+pub use self::diff::*;
+pub use self::digest::*;
 pub use self::discriminator::*;
+pub use self::doc_coverage::*;
+pub use self::docs::*;
+pub use self::document_meta::*;
+pub use self::edit_session::*;
 pub use self::encoding::*;
+pub use self::envelope::*;
 pub use self::example::*;
+pub use self::example_lint::*;
+pub use self::extension_keys::*;
 pub use self::external_documentation::*;
+pub use self::feature_flags::*;
+pub use self::filter::*;
+pub use self::grpc::*;
+pub use self::head_options::*;
 pub use self::header::*;
+pub use self::header_check::*;
 pub use self::info::*;
+pub use self::instrument::*;
 pub use self::license::*;
 pub use self::link::*;
+pub use self::lint::*;
 pub use self::media_type::*;
+pub use self::ndjson::*;
+pub use self::nullable::*;
+pub use self::oidc::*;
 pub use self::openapi::*;
 pub use self::operation::*;
+pub use self::pagination::*;
+pub use self::param_codec::*;
 pub use self::parameter::*;
+pub use self::parse::*;
 pub use self::paths::*;
+pub use self::problem::*;
+pub use self::provenance::*;
+pub use self::rate_limit::*;
+pub use self::recursion::*;
+pub use self::redact::*;
 pub use self::reference::*;
 pub use self::request_body::*;
 pub use self::responses::*;
 pub use self::schema::*;
+pub use self::scopes::*;
 pub use self::security_requirement::*;
 pub use self::security_scheme::*;
 pub use self::server::*;
 pub use self::server_variable::*;
+pub use self::shared::*;
+pub use self::spec_fragment::*;
+pub use self::sse::*;
 pub use self::status_code::*;
+pub use self::summary::*;
+pub use self::sunset::*;
 pub use self::tag::*;
+pub use self::texts::*;
+pub use self::tracked::*;
+pub use self::translate::*;
+pub use self::usage::*;
 pub use self::util::*;
+pub use self::validate::*;
 pub use self::variant_or::*;
+pub use self::view::*;
 pub use map::*;
 pub use http::method::Method as PathMethod;
 pub use versioned::*;
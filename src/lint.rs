@@ -0,0 +1,347 @@
+use std::fmt;
+
+use crate::util::escape_pointer_segment;
+use crate::*;
+
+/// A likely mistake or style problem found by [OpenAPI::lint].
+///
+/// Unlike [ValidationError]s, lints don't make the document invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    /// The name of the rule which produced this lint, e.g. `empty-content`.
+    pub rule: &'static str,
+    /// A JSON pointer to the offending object.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Lint {
+    pub fn new(rule: &'static str, pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        Lint {
+            rule,
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.pointer, self.message, self.rule)
+    }
+}
+
+impl OpenAPI {
+    /// Checks the document for likely mistakes which don't make it invalid.
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let mut schemas: Vec<(String, &RefOr<Schema>)> = Vec::new();
+        let mut last_item = None;
+        for op in self.callable_operations() {
+            lint_empty_content(&op.pointer, op.operation, &mut lints);
+            lint_missing_error_responses(&op.pointer, op.operation, &mut lints);
+            // Operations of the same path item come one after another.
+            let item_pointer = op.pointer[..op.pointer.rfind('/').unwrap_or_default()].to_string();
+            if last_item.as_ref() != Some(&item_pointer) {
+                parameter_schemas(&item_pointer, &op.path_item.parameters, &mut schemas);
+                last_item = Some(item_pointer);
+            }
+            parameter_schemas(&op.pointer, &op.operation.parameters, &mut schemas);
+            if let Some(RefOr::Item(body)) = &op.operation.request_body {
+                content_schemas(&format!("{}/requestBody", op.pointer), &body.content, &mut schemas);
+            }
+            let responses = op.operation.responses.responses.iter().map(|(code, r)| (code.to_string(), r))
+                .chain(op.operation.responses.default.as_ref().map(|r| ("default".to_string(), r)));
+            for (code, response) in responses {
+                if let RefOr::Item(response) = response {
+                    response_schemas(&format!("{}/responses/{}", op.pointer, code), response, &mut schemas);
+                }
+            }
+        }
+        for (name, schema) in self.schemas.iter() {
+            schemas.push((ComponentKind::Schema.reference(name), schema));
+        }
+        for (name, param) in self.parameters.iter() {
+            if let RefOr::Item(param) = param {
+                format_schemas(&ComponentKind::Parameter.reference(name), &param.format, &mut schemas);
+            }
+        }
+        for (name, header) in self.components.headers.iter() {
+            if let RefOr::Item(header) = header {
+                format_schemas(&ComponentKind::Header.reference(name), &header.format, &mut schemas);
+            }
+        }
+        for (name, response) in self.responses.iter() {
+            if let RefOr::Item(response) = response {
+                response_schemas(&ComponentKind::Response.reference(name), response, &mut schemas);
+            }
+        }
+        for (name, body) in self.components.request_bodies.iter() {
+            if let RefOr::Item(body) = body {
+                content_schemas(&ComponentKind::RequestBody.reference(name), &body.content, &mut schemas);
+            }
+        }
+        for (pointer, schema) in schemas {
+            if let RefOr::Item(schema) = schema {
+                lint_overlapping_one_of(&pointer, schema, self, &mut lints);
+            }
+        }
+        lints
+    }
+}
+
+fn parameter_schemas<'a>(pointer: &str, params: &'a [RefOr<Parameter>], out: &mut Vec<(String, &'a RefOr<Schema>)>) {
+    for (i, param) in params.iter().enumerate() {
+        if let RefOr::Item(param) = param {
+            format_schemas(&format!("{}/parameters/{}", pointer, i), &param.format, out);
+        }
+    }
+}
+
+fn format_schemas<'a>(pointer: &str, format: &'a ParameterSchemaOrContent, out: &mut Vec<(String, &'a RefOr<Schema>)>) {
+    match format {
+        ParameterSchemaOrContent::Schema(schema) => out.push((format!("{}/schema", pointer), schema)),
+        ParameterSchemaOrContent::Content(content) => content_schemas(pointer, content, out),
+    }
+}
+
+fn response_schemas<'a>(pointer: &str, response: &'a Response, out: &mut Vec<(String, &'a RefOr<Schema>)>) {
+    for (name, header) in &response.headers {
+        if let RefOr::Item(header) = header {
+            format_schemas(&format!("{}/headers/{}", pointer, escape_pointer_segment(name)), &header.format, out);
+        }
+    }
+    content_schemas(pointer, &response.content, out);
+}
+
+fn content_schemas<'a>(pointer: &str, content: &'a IndexMap<String, MediaType>, out: &mut Vec<(String, &'a RefOr<Schema>)>) {
+    for (mime, media) in content {
+        if let Some(schema) = &media.schema {
+            out.push((format!("{}/content/{}/schema", pointer, escape_pointer_segment(mime)), schema));
+        }
+    }
+}
+
+/// Flags request bodies, and success responses which should have a body, that have no content.
+fn lint_empty_content(pointer: &str, op: &Operation, lints: &mut Vec<Lint>) {
+    if let Some(RefOr::Item(body)) = &op.request_body {
+        if body.content.is_empty() {
+            lints.push(Lint::new("empty-content", format!("{}/requestBody", pointer), "Request body has no content."));
+        }
+    }
+    for (code, response) in &op.responses.responses {
+        let RefOr::Item(response) = response else { continue };
+        let expects_body = match code {
+            StatusCode::Code(n) => (200..300).contains(n) && ![204, 205].contains(n),
+            StatusCode::Range(n) => *n == 2,
+        };
+        if expects_body && response.content.is_empty() {
+            lints.push(Lint::new("empty-content", format!("{}/responses/{}", pointer, code), format!("Response {} has no content.", code)));
+        }
+    }
+}
+
+/// Flags operations which document no 4xx or 5xx responses, and no default response.
+fn lint_missing_error_responses(pointer: &str, op: &Operation, lints: &mut Vec<Lint>) {
+    let has_error = op.responses.default.is_some() || op.responses.responses.keys().any(|code| match code {
+        StatusCode::Code(n) => *n >= 400,
+        StatusCode::Range(n) => *n >= 4,
+    });
+    if !has_error {
+        lints.push(Lint::new("missing-error-responses", format!("{}/responses", pointer), "Operation documents no error responses."));
+    }
+}
+
+fn disjoint_enums<T: PartialEq>(a: &[T], b: &[T]) -> bool {
+    !a.is_empty() && !b.is_empty() && !a.iter().any(|v| b.contains(v))
+}
+
+/// Returns true if one object can't match the other: a closed object forbids a property the
+/// other requires, or a property both require has disjoint enums, as with a type tag.
+fn objects_distinguishable(a: &ObjectType, b: &ObjectType, spec: &OpenAPI) -> bool {
+    let closed = |o: &ObjectType| matches!(o.additional_properties, Some(AdditionalProperties::Any(false)));
+    let excludes = |x: &ObjectType, y: &ObjectType| closed(x) && y.required.iter().any(|r| !x.properties.contains_key(r));
+    if excludes(a, b) || excludes(b, a) {
+        return true;
+    }
+    a.required.iter().filter(|r| b.required.contains(r)).any(|name| {
+        let (Some(x), Some(y)) = (a.properties.get(name), b.properties.get(name)) else { return false };
+        let (Ok(x), Ok(y)) = (Resolve::resolve(x, spec), Resolve::resolve(y, spec)) else { return false };
+        disjoint_enums(&x.enum_values(), &y.enum_values())
+    })
+}
+
+/// Returns true if no value can match both schemas. Composed schemas are assumed distinguishable.
+fn distinguishable(a: &Schema, b: &Schema, spec: &OpenAPI) -> bool {
+    if a.nullable && b.nullable {
+        return false;
+    }
+    let (SchemaKind::Type(x), SchemaKind::Type(y)) = (&a.kind, &b.kind) else { return true };
+    match (x, y) {
+        (Type::String(_), Type::String(_)) | (Type::Integer(_), Type::Integer(_)) | (Type::Number(_), Type::Number(_)) => {
+            disjoint_enums(&a.enum_values(), &b.enum_values())
+        }
+        (Type::Object(o), Type::Object(p)) => objects_distinguishable(o, p, spec),
+        (Type::Integer(_), Type::Number(_)) | (Type::Number(_), Type::Integer(_)) => false,
+        (Type::Array(_), Type::Array(_)) | (Type::Boolean {}, Type::Boolean {}) => false,
+        _ => true,
+    }
+}
+
+/// Flags `oneOf` schemas without a discriminator where a value may match several branches,
+/// which makes it invalid. Recurses into inline subschemas.
+fn lint_overlapping_one_of(pointer: &str, schema: &Schema, spec: &OpenAPI, lints: &mut Vec<Lint>) {
+    let child = |segment: &str| format!("{}/{}", pointer, segment);
+    let mut children: Vec<(String, &RefOr<Schema>)> = Vec::new();
+    fn composed<'a>(pointer: &str, keyword: &str, schemas: &'a [RefOr<Schema>]) -> Vec<(String, &'a RefOr<Schema>)> {
+        schemas.iter().enumerate().map(|(i, s)| (format!("{}/{}/{}", pointer, keyword, i), s)).collect()
+    }
+    match &schema.kind {
+        SchemaKind::OneOf { one_of } => {
+            lint_one_of_branches(pointer, schema, one_of, spec, lints);
+            children.extend(composed(pointer, "oneOf", one_of));
+        }
+        SchemaKind::AllOf { all_of } => children.extend(composed(pointer, "allOf", all_of)),
+        SchemaKind::AnyOf { any_of } => children.extend(composed(pointer, "anyOf", any_of)),
+        SchemaKind::Not { not } => children.push((child("not"), not)),
+        SchemaKind::Type(Type::Array(a)) => children.extend(a.items.as_deref().map(|s| (child("items"), s))),
+        SchemaKind::Type(Type::Object(o)) => {
+            if let Some(AdditionalProperties::Schema(s)) = &o.additional_properties {
+                children.push((child("additionalProperties"), s));
+            }
+        }
+        SchemaKind::Any(a) => {
+            if !a.one_of.is_empty() {
+                lint_one_of_branches(pointer, schema, &a.one_of, spec, lints);
+            }
+            children.extend(composed(pointer, "oneOf", &a.one_of));
+            children.extend(composed(pointer, "allOf", &a.all_of));
+            children.extend(composed(pointer, "anyOf", &a.any_of));
+            children.extend(a.not.as_deref().map(|s| (child("not"), s)));
+            children.extend(a.items.as_deref().map(|s| (child("items"), s)));
+            if let Some(AdditionalProperties::Schema(s)) = &a.additional_properties {
+                children.push((child("additionalProperties"), s));
+            }
+        }
+        _ => {}
+    }
+    if let Some(properties) = schema.get_properties() {
+        children.extend(properties.iter().map(|(name, s)| (child(&format!("properties/{}", escape_pointer_segment(name))), s)));
+    }
+    for (pointer, schema) in children {
+        if let RefOr::Item(schema) = schema {
+            lint_overlapping_one_of(&pointer, schema, spec, lints);
+        }
+    }
+}
+
+fn lint_one_of_branches(pointer: &str, schema: &Schema, one_of: &[RefOr<Schema>], spec: &OpenAPI, lints: &mut Vec<Lint>) {
+    if schema.discriminator.is_some() {
+        return;
+    }
+    let branches: Vec<Option<&Schema>> = one_of.iter().map(|s| Resolve::resolve(s, spec).ok()).collect();
+    for (i, a) in branches.iter().enumerate() {
+        for (j, b) in branches.iter().enumerate().skip(i + 1) {
+            if let (Some(a), Some(b)) = (a, b) {
+                if !distinguishable(a, b, spec) {
+                    lints.push(Lint::new("overlapping-one-of", pointer, format!(
+                        "oneOf branches {} and {} can match the same value; use anyOf or add a discriminator.", i, j,
+                    )));
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_content() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let mut op = Operation::default();
+        let mut body = RequestBody::default();
+        body.extensions.insert("x-schema".to_string(), serde_json::json!({"$ref": "#/components/schemas/Pet"}));
+        op.request_body = Some(body.into());
+        let mut response = Response::new("OK");
+        response.extensions.insert("x-schema".to_string(), serde_json::json!({"type": "string"}));
+        op.responses.responses.insert(StatusCode::Code(200), response.into());
+        op.responses.responses.insert(StatusCode::Code(204), Response::new("No content").into());
+        api.add_operation(PathMethod::POST, "/pets", op).unwrap();
+
+        let pointers: Vec<String> = api.lint().into_iter().filter(|l| l.rule == "empty-content").map(|l| l.pointer).collect();
+        assert_eq!(pointers, vec!["#/paths/~1pets/post/requestBody", "#/paths/~1pets/post/responses/200"]);
+
+        api.default_content_types("application/json", "text/plain");
+        assert!(api.lint().iter().all(|l| l.rule != "empty-content"));
+        let (_, _, op, _) = api.operations().next().unwrap();
+        let body = op.request_body.as_ref().unwrap().as_item().unwrap();
+        assert_eq!(body.schema_for("application/json").unwrap().as_ref_str(), Some("#/components/schemas/Pet"));
+        assert!(body.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_missing_error_responses() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.add_operation(PathMethod::GET, "/pets", Operation::default()).unwrap();
+        assert_eq!(api.lint()[0].rule, "missing-error-responses");
+        api.responses.insert("Error", Response::new("Error"));
+        api.ensure_error_responses(&[500], "#/components/responses/Error").unwrap();
+        assert!(api.lint().is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_one_of() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let schema = |value: serde_json::Value| serde_json::from_value::<Schema>(value).unwrap();
+        api.schemas.insert("Cat", schema(serde_json::json!({"type": "object", "required": ["meow"], "properties": {"meow": {"type": "boolean"}}})));
+        api.schemas.insert("Dog", schema(serde_json::json!({"type": "object", "required": ["bark"], "properties": {"bark": {"type": "boolean"}}})));
+        api.schemas.insert("Pet", schema(serde_json::json!({"oneOf": [
+            {"$ref": "#/components/schemas/Cat"},
+            {"$ref": "#/components/schemas/Dog"},
+        ]})));
+        api.schemas.insert("Id", schema(serde_json::json!({"oneOf": [{"type": "string"}, {"type": "integer"}]})));
+        api.schemas.insert("Shape", schema(serde_json::json!({"type": "object", "properties": {"shape": {"oneOf": [
+            {"type": "object", "required": ["kind"], "properties": {"kind": {"type": "string", "enum": ["circle"]}}},
+            {"type": "object", "required": ["kind"], "properties": {"kind": {"type": "string", "enum": ["square"]}}},
+            {"type": "object", "required": ["kind"], "properties": {"kind": {"type": "string"}}},
+        ]}}})));
+
+        let lints: Vec<String> = api.lint().into_iter().filter(|l| l.rule == "overlapping-one-of").map(|l| l.to_string()).collect();
+        assert_eq!(lints, vec![
+            "#/components/schemas/Pet: oneOf branches 0 and 1 can match the same value; use anyOf or add a discriminator. [overlapping-one-of]",
+            "#/components/schemas/Shape/properties/shape: oneOf branches 0 and 2 can match the same value; use anyOf or add a discriminator. [overlapping-one-of]",
+        ]);
+
+        api.schemas.get_mut2("Pet").unwrap().discriminator = Some(Discriminator::default());
+        assert_eq!(api.lint().iter().filter(|l| l.rule == "overlapping-one-of").count(), 1);
+    }
+
+    #[test]
+    fn test_overlapping_one_of_outside_schemas() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let id = || serde_json::from_value::<Schema>(serde_json::json!({"oneOf": [{"type": "string"}, {"type": "string"}]})).unwrap();
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::query("id", id()).into());
+        let mut response = Response::new("Error");
+        response.headers.insert("X-Id".to_string(), Header::new(id()).into());
+        op.responses.default = Some(response.into());
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        api.parameters.insert("Id", Parameter::query("id", id()));
+        let mut map = Schema::new_object();
+        if let SchemaKind::Type(Type::Object(o)) = &mut map.kind {
+            o.additional_properties = Some(id().into());
+        }
+        api.schemas.insert("Ids", map);
+
+        let pointers: Vec<String> = api.lint().into_iter().filter(|l| l.rule == "overlapping-one-of").map(|l| l.pointer).collect();
+        assert_eq!(pointers, vec![
+            "#/paths/~1pets/get/parameters/0/schema",
+            "#/paths/~1pets/get/responses/default/headers/X-Id/schema",
+            "#/components/schemas/Ids/additionalProperties",
+            "#/components/parameters/Id/schema",
+        ]);
+    }
+}
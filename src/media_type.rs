@@ -1,4 +1,5 @@
 use crate::*;
+use anyhow::{anyhow, Result};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
@@ -16,3 +17,104 @@ pub struct MediaType {
     #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
     pub extensions: IndexMap<String, serde_json::Value>,
 }
+
+impl MediaType {
+    /// Sets the inline example, clearing any named examples.
+    /// `example` and `examples` are mutually exclusive.
+    pub fn set_example(&mut self, example: serde_json::Value) {
+        self.examples.clear();
+        self.example = Some(example);
+    }
+
+    /// Adds a named example, clearing the inline example.
+    /// `example` and `examples` are mutually exclusive.
+    pub fn add_named_example(&mut self, name: impl Into<String>, example: impl Into<RefOr<Example>>) {
+        self.example = None;
+        self.examples.insert(name.into(), example.into());
+    }
+
+    /// Checks that `example` and `examples` are not both set.
+    pub fn validate_examples(&self) -> Result<()> {
+        if self.example.is_some() && !self.examples.is_empty() {
+            return Err(anyhow!("The example and examples fields are mutually exclusive."));
+        }
+        Ok(())
+    }
+
+    /// Returns the example that best represents this media type, resolving references as needed.
+    ///
+    /// Precedence is: the inline `example`, then the first of `examples` with a value,
+    /// then the `example` of the schema.
+    pub fn primary_example<'a>(&'a self, spec: &'a OpenAPI) -> Option<&'a serde_json::Value> {
+        if let Some(example) = &self.example {
+            return Some(example);
+        }
+        let named = self.examples.values()
+            .filter_map(|e| e.resolve(spec).ok())
+            .find_map(|e| e.value.as_ref());
+        if named.is_some() {
+            return named;
+        }
+        self.schema.as_ref()
+            .and_then(|s| Resolve::resolve(s, spec).ok())
+            .and_then(|s| s.example.as_ref())
+    }
+}
+
+/// Finds the most specific entry in `content` matching the given mime type.
+///
+/// Exact matches take precedence over `type/*` ranges, which take precedence over `*/*`.
+/// A type with a `+json` structured syntax suffix, such as `application/merge-patch+json`, and
+/// `application/json` match each other, ranked between exact matches and ranges. Media type parameters (e.g. `; charset=utf-8`) are ignored.
+pub(crate) fn find_media_type<'a>(content: &'a Content, mime: &str) -> Option<(&'a String, &'a MediaType)> {
+    let essence = |s: &str| s.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    let mime = essence(mime);
+    let main_type = mime.split('/').next().unwrap_or_default();
+    let range = format!("{}/*", main_type);
+    content.iter()
+        .filter_map(|(k, v)| {
+            let key = essence(k);
+            let rank = if key == mime {
+                0
+            } else if (key == "application/json" && mime.ends_with("+json")) || (mime == "application/json" && key.ends_with("+json")) {
+                1
+            } else if key == range {
+                2
+            } else if key == "*/*" {
+                3
+            } else {
+                return None;
+            };
+            Some((rank, k, v))
+        })
+        .min_by_key(|(rank, _, _)| *rank)
+        .map(|(_, k, v)| (k, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{Example, MediaType, OpenAPI, RefOr};
+
+    #[test]
+    fn test_primary_example() {
+        let mut spec = OpenAPI::default();
+        spec.examples.insert("Cat", Example {
+            value: Some(json!({"name": "Tom"})),
+            ..Example::default()
+        });
+        let mut media = MediaType::default();
+        media.set_example(json!(1));
+        media.add_named_example("cat", RefOr::ref_("#/components/examples/Cat"));
+        assert!(media.validate_examples().is_ok());
+        assert_eq!(media.primary_example(&spec), Some(&json!({"name": "Tom"})));
+
+        media.example = Some(json!(1));
+        assert!(media.validate_examples().is_err());
+        assert_eq!(media.primary_example(&spec), Some(&json!(1)));
+
+        let dangling = MediaType { schema: Some(RefOr::schema_ref("Missing")), ..MediaType::default() };
+        assert_eq!(dangling.primary_example(&spec), None);
+    }
+}
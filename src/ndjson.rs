@@ -0,0 +1,92 @@
+use serde_json::Value;
+
+use crate::*;
+
+/// The media type of newline-delimited JSON.
+pub const NDJSON: &str = "application/x-ndjson";
+
+/// Returns true for newline-delimited JSON media types: `application/x-ndjson`,
+/// `application/jsonl` and `application/json-lines`. Media type parameters are ignored.
+pub fn is_ndjson_media_type(mime: &str) -> bool {
+    let essence = mime.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    matches!(essence.as_str(), NDJSON | "application/jsonl" | "application/json-lines" | "application/x-jsonlines")
+}
+
+impl MediaType {
+    /// Creates newline-delimited JSON content, where `item_schema` describes each line.
+    /// The body is described as an array of items, the stream of lines it consists of.
+    /// Use with the [NDJSON] media type.
+    pub fn ndjson(item_schema: impl Into<RefOr<Schema>>) -> Self {
+        MediaType {
+            schema: Some(Schema::new_array(item_schema).into()),
+            ..MediaType::default()
+        }
+    }
+
+    /// Returns the schema of each line of newline-delimited JSON content: the items of an inline
+    /// array schema, or else the schema itself.
+    pub fn ndjson_item_schema(&self) -> Option<&RefOr<Schema>> {
+        let schema = self.schema.as_ref()?;
+        match schema {
+            RefOr::Item(Schema { kind: SchemaKind::Type(Type::Array(array)), .. }) => array.items.as_deref(),
+            _ => Some(schema),
+        }
+    }
+
+    /// Parses a newline-delimited JSON body and validates each line against the item schema of
+    /// this media type, returning the lines as an array. Blank lines are skipped. Errors point at
+    /// the line's index in the returned array, e.g. `#/3/name`.
+    pub fn validate_ndjson(&self, body: &str, spec: &OpenAPI) -> (Value, Vec<ValidationError>) {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let pointer = format!("#/{}", values.len());
+            let value = match serde_json::from_str(line) {
+                Ok(value) => value,
+                Err(e) => {
+                    errors.push(ValidationError::new(pointer, format!("Invalid JSON: {}", e)));
+                    values.push(Value::Null);
+                    continue;
+                }
+            };
+            if let Some(schema) = self.ndjson_item_schema() {
+                errors.extend(schema.validate_value(&value, spec).into_iter().map(|mut e| {
+                    e.pointer = e.pointer.replacen('#', &pointer, 1);
+                    e
+                }));
+            }
+            values.push(value);
+        }
+        (Value::Array(values), errors)
+    }
+}
+
+impl Response {
+    /// Sets the `application/x-ndjson` content of this response, with `item_schema` describing each line.
+    pub fn ndjson(mut self, item_schema: impl Into<RefOr<Schema>>) -> Self {
+        self.content.insert(NDJSON.to_string(), MediaType::ndjson(item_schema));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_ndjson() {
+        let spec = OpenAPI::default();
+        let mut row = Schema::new_object();
+        row.properties_mut().insert("id", Schema::new_integer());
+        let media = MediaType::ndjson(row);
+        assert!(media.ndjson_item_schema().unwrap().as_item().unwrap().get_properties().is_some());
+        let (values, errors) = media.validate_ndjson("{\"id\": 1}\n\n{\"id\": \"x\"}\nnope\n", &spec);
+        assert_eq!(values, json!([{"id": 1}, {"id": "x"}, null]));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].pointer, "#/1/id");
+        assert_eq!(errors[1].pointer, "#/2");
+        assert!(is_ndjson_media_type("application/jsonl; charset=utf-8"));
+    }
+}
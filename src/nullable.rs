@@ -0,0 +1,120 @@
+use serde_json::{json, Value};
+
+use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key, to_json};
+use crate::*;
+
+fn is_nullable(schema: &Value) -> bool {
+    schema.get("nullable") == Some(&Value::Bool(true))
+}
+
+fn audit(value: &mut Value, pointer: &str, is_name_map: bool, fix: bool, lints: &mut Vec<Lint>) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                if let (Some(Value::String(reference)), true) = (map.get("$ref"), map.get("nullable") == Some(&Value::Bool(true))) {
+                    lints.push(Lint::new("nullable-ref", pointer, format!(
+                        "nullable is ignored next to $ref {} in OpenAPI 3.0; wrap the reference in allOf.", reference,
+                    )));
+                    if fix {
+                        let reference = reference.clone();
+                        map.remove("$ref");
+                        map.insert("allOf".to_string(), json!([{"$ref": reference}]));
+                    }
+                }
+                let null_enum = matches!(map.get("enum"), Some(Value::Array(values)) if values.contains(&Value::Null));
+                if null_enum && map.get("nullable") != Some(&Value::Bool(true)) {
+                    lints.push(Lint::new("null-enum-not-nullable", pointer, "enum contains null, but the schema isn't nullable."));
+                    if fix {
+                        map.insert("nullable".to_string(), Value::Bool(true));
+                    }
+                }
+                if let (Some(Value::Array(required)), Some(Value::Object(properties))) = (map.get("required"), map.get("properties")) {
+                    for name in required.iter().filter_map(|r| r.as_str()) {
+                        if properties.get(name).is_some_and(is_nullable) {
+                            lints.push(Lint::new("required-nullable", format!("{}/properties/{}", pointer, escape_pointer_segment(name)), format!(
+                                "{} is required but nullable, so clients must send it even when it is null.", name,
+                            )));
+                        }
+                    }
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                let names = !is_name_map && is_name_map_key(key);
+                audit(child, &format!("{}/{}", pointer, escape_pointer_segment(key)), names, fix, lints);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                audit(item, &format!("{}/{}", pointer, i), false, fix, lints);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Flags suspicious nullability in a raw document: `nullable` next to `$ref` (which OpenAPI 3.0
+/// ignores, and which [OpenAPI] drops when parsing), enums containing null in schemas which
+/// aren't nullable, and required properties which are nullable.
+pub fn audit_nullable(doc: &Value) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    audit(&mut doc.clone(), "#", false, false, &mut lints);
+    lints
+}
+
+/// Fixes what [audit_nullable] finds, where the intent is clear: nullable references are wrapped
+/// in `allOf`, and schemas with null enum values are made nullable. Returns the lints it
+/// found, including required nullable properties, which are left as they are.
+pub fn fix_nullable(doc: &mut Value) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    audit(doc, "#", false, true, &mut lints);
+    lints
+}
+
+impl OpenAPI {
+    /// Runs [audit_nullable] on this document. Nullable references can't be detected once parsed,
+    /// so run [audit_nullable] on the source document to catch those.
+    pub fn audit_nullable(&self) -> Vec<Lint> {
+        audit_nullable(&to_json(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nullable_audit() {
+        let mut doc = json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1"},
+            "paths": {},
+            "components": {"schemas": {"Pet": {
+                "type": "object",
+                "properties": {
+                    "owner": {"$ref": "#/components/schemas/Owner", "nullable": true},
+                    "size": {"type": "string", "enum": ["small", null]},
+                    "nickname": {"type": "string", "nullable": true},
+                },
+                "example": {"enum": [null]},
+            }}},
+        });
+        let rules = |lints: Vec<Lint>| lints.iter().map(|l| format!("{} {}", l.rule, l.pointer)).collect::<Vec<_>>();
+        let expected = vec![
+            "nullable-ref #/components/schemas/Pet/properties/owner",
+            "null-enum-not-nullable #/components/schemas/Pet/properties/size",
+        ];
+        assert_eq!(rules(audit_nullable(&doc)), expected);
+        assert_eq!(rules(fix_nullable(&mut doc)), expected);
+        assert!(audit_nullable(&doc).is_empty());
+        assert_eq!(doc["components"]["schemas"]["Pet"]["properties"]["owner"], json!({
+            "allOf": [{"$ref": "#/components/schemas/Owner"}], "nullable": true,
+        }));
+
+        doc["components"]["schemas"]["Pet"]["required"] = json!(["nickname"]);
+        let spec: OpenAPI = serde_json::from_value(doc).unwrap();
+        assert_eq!(rules(spec.audit_nullable()), vec!["required-nullable #/components/schemas/Pet/properties/nickname"]);
+    }
+}
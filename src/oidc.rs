@@ -0,0 +1,151 @@
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+const WELL_KNOWN_PATH: &str = "/.well-known/openid-configuration";
+
+/// The endpoints of an OpenID Connect provider, from its discovery document.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OidcDiscovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub userinfo_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwks_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes_supported: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub grant_types_supported: Vec<String>,
+    /// The remaining provider metadata.
+    #[serde(flatten)]
+    pub other: IndexMap<String, serde_json::Value>,
+}
+
+impl OidcDiscovery {
+    /// Parses a discovery document, as served at `openIdConnectUrl`.
+    pub fn parse(body: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+impl SecurityScheme {
+    /// Checks the `openIdConnectUrl` of an `openIdConnect` scheme without fetching it: it must be an
+    /// absolute `https` URL (`http` is allowed for localhost), and should point at the well-known
+    /// discovery document. A URL without that path gets it appended.
+    pub fn oidc_discovery_url(&self) -> Result<http::Uri> {
+        let SecurityScheme::OpenIDConnect { open_id_connect_url, .. } = self else {
+            bail!("Not an OpenID Connect security scheme");
+        };
+        let url: http::Uri = open_id_connect_url.parse()
+            .map_err(|e| anyhow!("Invalid openIdConnectUrl {}: {}", open_id_connect_url, e))?;
+        let Some(host) = url.host() else {
+            bail!("openIdConnectUrl {} is not absolute", open_id_connect_url);
+        };
+        match url.scheme_str() {
+            Some("https") => {}
+            Some("http") if host == "localhost" || host == "127.0.0.1" || host == "[::1]" => {}
+            _ => bail!("openIdConnectUrl {} must use https", open_id_connect_url),
+        }
+        if url.query().is_some() || open_id_connect_url.contains('#') {
+            bail!("openIdConnectUrl {} must not have a query or fragment", open_id_connect_url);
+        }
+        if url.path().ends_with(WELL_KNOWN_PATH) {
+            return Ok(url);
+        }
+        Ok(format!("{}{}", open_id_connect_url.trim_end_matches('/'), WELL_KNOWN_PATH).parse()?)
+    }
+
+    /// Fetches and parses the discovery document of an `openIdConnect` scheme. `fetch` performs
+    /// the GET request with the HTTP client of your choice, returning the response body.
+    ///
+    /// Fails if the document's `issuer` isn't the URL the discovery document was fetched from,
+    /// without the well-known path, as OpenID Connect Discovery requires. A trailing slash is ignored.
+    #[cfg(feature = "oidc")]
+    pub async fn discover_oidc<F, Fut>(&self, fetch: F) -> Result<OidcDiscovery>
+    where
+        F: FnOnce(http::Uri) -> Fut,
+        Fut: std::future::Future<Output=Result<Vec<u8>>>,
+    {
+        let url = self.oidc_discovery_url()?;
+        let body = fetch(url.clone()).await?;
+        let discovery = OidcDiscovery::parse(&body).map_err(|e| anyhow!("Invalid discovery document at {}: {}", url, e))?;
+        let url = url.to_string();
+        let issuer = url.strip_suffix(WELL_KNOWN_PATH).unwrap_or(&url);
+        if discovery.issuer.trim_end_matches('/') != issuer.trim_end_matches('/') {
+            bail!("Discovery document at {} has issuer {}, expected {}", url, discovery.issuer, issuer);
+        }
+        Ok(discovery)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oidc(url: &str) -> SecurityScheme {
+        SecurityScheme::OpenIDConnect { open_id_connect_url: url.to_string(), description: None, extensions: IndexMap::new() }
+    }
+
+    #[test]
+    fn test_oidc_discovery_url() {
+        let url = oidc("https://auth.example.com/.well-known/openid-configuration").oidc_discovery_url().unwrap();
+        assert_eq!(url, "https://auth.example.com/.well-known/openid-configuration");
+        let url = oidc("https://auth.example.com/realms/main/").oidc_discovery_url().unwrap();
+        assert_eq!(url, "https://auth.example.com/realms/main/.well-known/openid-configuration");
+        assert!(oidc("http://localhost:8080").oidc_discovery_url().is_ok());
+        assert_eq!(oidc("http://auth.example.com").oidc_discovery_url().unwrap_err().to_string(), "openIdConnectUrl http://auth.example.com must use https");
+        assert!(oidc("/.well-known/openid-configuration").oidc_discovery_url().is_err());
+
+        let discovery = OidcDiscovery::parse(br#"{
+            "issuer": "https://auth.example.com",
+            "authorization_endpoint": "https://auth.example.com/authorize",
+            "token_endpoint": "https://auth.example.com/token",
+            "response_types_supported": ["code"]
+        }"#).unwrap();
+        assert_eq!(discovery.token_endpoint.as_deref(), Some("https://auth.example.com/token"));
+        assert_eq!(discovery.other["response_types_supported"], serde_json::json!(["code"]));
+    }
+
+    #[cfg(feature = "oidc")]
+    #[test]
+    fn test_discover_oidc() {
+        use std::future::Future;
+        use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+        fn noop_raw_waker() -> RawWaker {
+            fn clone(_: *const ()) -> RawWaker {
+                noop_raw_waker()
+            }
+            fn noop(_: *const ()) {}
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+
+        let discover = |issuer: &'static str| {
+            let scheme = oidc("https://auth.example.com");
+            let mut future = Box::pin(async move {
+                scheme.discover_oidc(|url| async move {
+                    assert_eq!(url, "https://auth.example.com/.well-known/openid-configuration");
+                    let body = format!(r#"{{"issuer": "{}", "authorization_endpoint": "https://auth.example.com/authorize"}}"#, issuer);
+                    Ok(body.into_bytes())
+                }).await
+            });
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(discovery) => discovery,
+                Poll::Pending => panic!("Fetch is ready immediately"),
+            }
+        };
+        assert_eq!(discover("https://auth.example.com").unwrap().authorization_endpoint, "https://auth.example.com/authorize");
+        assert!(discover("https://auth.example.com/").is_ok());
+        assert_eq!(
+            discover("https://evil.example.com").unwrap_err().to_string(),
+            "Discovery document at https://auth.example.com/.well-known/openid-configuration has issuer https://evil.example.com, expected https://auth.example.com"
+        );
+    }
+}
@@ -13,13 +13,22 @@ pub struct OpenAPI {
     /// REQUIRED. Provides metadata about the API.
     /// The metadata MAY be used by tooling as required.
     pub info: Info,
+    /// OpenAPI 3.1: the default `$schema` dialect of the Schema Objects in the document.
+    #[serde(rename = "jsonSchemaDialect", skip_serializing_if = "Option::is_none")]
+    pub json_schema_dialect: Option<String>,
     /// An array of Server Objects, which provide connectivity information to a
     /// target server. If the servers property is not provided, or is an empty
     /// array, the default value would be a Server Object with a url value of /.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub servers: Vec<Server>,
     /// REQUIRED. The available paths and operations for the API.
+    /// In 3.1 a document may have only webhooks or components; [VersionedOpenAPI] reads such
+    /// documents with empty paths.
     pub paths: Paths,
+    /// OpenAPI 3.1: requests the API may send to its consumers, by name, as `callbacks` do
+    /// for a single operation.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub webhooks: IndexMap<String, RefOr<PathItem>>,
     /// An element to hold various schemas for the specification.
     #[serde(default, skip_serializing_if = "Components::is_empty")]
     pub components: Components,
@@ -62,6 +71,42 @@ impl std::ops::DerefMut for OpenAPI {
 }
 
 impl OpenAPI {
+    /// Creates a minimal valid 3.0.3 document with the given title and version.
+    ///
+    /// Prefer this over [OpenAPI::default], which leaves the required `info.title`
+    /// and `info.version` fields empty.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        OpenAPI {
+            info: Info {
+                title: title.into(),
+                version: version.into(),
+                ..Info::default()
+            },
+            ..OpenAPI::default()
+        }
+    }
+
+    /// Sets the OpenAPI 3.1 `jsonSchemaDialect`.
+    pub fn with_json_schema_dialect(mut self, dialect: impl Into<String>) -> Self {
+        self.json_schema_dialect = Some(dialect.into());
+        self
+    }
+
+    /// Adds an OpenAPI 3.1 webhook with the given name.
+    pub fn with_webhook(mut self, name: impl Into<String>, item: PathItem) -> Self {
+        self.webhooks.insert(name.into(), RefOr::Item(item));
+        self
+    }
+
+    /// Adds a server with the given url.
+    pub fn with_server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(Server {
+            url: url.into(),
+            ..Server::default()
+        });
+        self
+    }
+
     /// Iterates through all [Operation]s in this API.
     ///
     /// The iterated items are tuples of `(&str, &str, &Operation, &PathItem)` containing
@@ -100,6 +145,225 @@ impl OpenAPI {
             .map(|(_, _, op, item)| (op, item))
     }
 
+    /// Adds an operation at the given path, creating the [PathItem] if needed.
+    ///
+    /// Fails if the path already has an operation for the method, or if the path parameters
+    /// don't match the path template. Non-standard methods are added to [PathItem::other].
+    pub fn add_operation(&mut self, method: PathMethod, path: &str, operation: Operation) -> anyhow::Result<()> {
+        if !PathItem::supports_method(&method) {
+            return Err(anyhow::anyhow!("Unsupported method {}.", method));
+        }
+        let mut item = match self.paths.get(path) {
+            Some(RefOr::Item(item)) => item.clone(),
+            Some(RefOr::Reference { .. }) => return Err(anyhow::anyhow!("PathItem {} is a reference.", path)),
+            None => PathItem::default(),
+        };
+        if item.operation(&method).is_some() {
+            return Err(anyhow::anyhow!("{} {} is already defined.", method, path));
+        }
+        item.set(method, operation);
+        let mut errors = Vec::new();
+        crate::validate::validate_path_params(self, path, &item, &mut errors);
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error.into());
+        }
+        self.paths.insert(path.to_string(), item);
+        Ok(())
+    }
+
+    /// Removes the operation for the given method and path. If the path has no operations left,
+    /// the path is removed as well.
+    ///
+    /// If `prune_components` is true, components which were referenced before the removal but are
+    /// no longer referenced afterwards are removed too. Components which were already unreferenced are kept.
+    pub fn remove_operation(&mut self, method: PathMethod, path: &str, prune_components: bool) -> Option<Operation> {
+        let before = prune_components.then(|| self.reachable_components());
+        let item = self.paths.get_mut(path)?.as_mut()?;
+        let operation = item.remove(&method)?;
+        if item.iter().next().is_none() {
+            self.paths.shift_remove(path);
+        }
+        if let Some(before) = before {
+            self.prune_newly_unreferenced(before);
+        }
+        Some(operation)
+    }
+
+    /// Removes the given path and all of its operations. See [OpenAPI::remove_operation] for `prune_components`.
+    pub fn remove_path(&mut self, path: &str, prune_components: bool) -> Option<RefOr<PathItem>> {
+        let before = prune_components.then(|| self.reachable_components());
+        let item = self.paths.shift_remove(path)?;
+        if let Some(before) = before {
+            self.prune_newly_unreferenced(before);
+        }
+        Some(item)
+    }
+
+    /// Renames a component and rewrites every reference to it, including references into it,
+    /// discriminator mappings and, for security schemes, security requirements.
+    pub fn rename_component(&mut self, kind: ComponentKind, from: &str, to: &str) -> anyhow::Result<()> {
+        if self.components.contains(kind, to) {
+            return Err(anyhow::anyhow!("{} already exists.", kind.reference(to)));
+        }
+        if !self.components.contains(kind, from) {
+            return Err(anyhow::anyhow!("{} does not exist.", kind.reference(from)));
+        }
+        self.edit_json(|doc| rename_components_json(doc, &|k, name| (k == kind && name == from).then(|| to.to_string())));
+        Ok(())
+    }
+
+    /// Prepends `prefix` to the name of every component, rewriting references, security
+    /// requirements and discriminator mappings to match. Use before merging specs from different
+    /// sources, so their components cannot collide.
+    pub fn prefix_components(&mut self, prefix: &str) {
+        self.edit_json(|doc| rename_components_json(doc, &|_, name| Some(format!("{}{}", prefix, name))));
+    }
+
+    pub(crate) fn prune_newly_unreferenced(&mut self, before: std::collections::HashSet<(ComponentKind, String)>) {
+        let after = self.reachable_components();
+        for (kind, name) in before.difference(&after) {
+            self.components.remove(*kind, name);
+        }
+    }
+
+    /// Produces a standalone spec containing only the operation with the given operationId,
+    /// along with the components it references, directly or transitively.
+    pub fn extract_operation(&self, operation_id: &str) -> anyhow::Result<OpenAPI> {
+        let (path, method, operation, item) = self.operations()
+            .find(|(_, _, op, _)| op.operation_id.as_deref() == Some(operation_id))
+            .ok_or_else(|| anyhow::anyhow!("Operation {} not found.", operation_id))?;
+        let method = PathMethod::from_bytes(method.to_uppercase().as_bytes())?;
+        let mut extracted_item = PathItem {
+            summary: item.summary.clone(),
+            description: item.description.clone(),
+            servers: item.servers.clone(),
+            parameters: item.parameters.clone(),
+            extensions: item.extensions.clone(),
+            ..PathItem::default()
+        };
+        extracted_item.set(method, operation.clone());
+
+        let mut spec = OpenAPI {
+            openapi: self.openapi.clone(),
+            info: self.info.clone(),
+            json_schema_dialect: self.json_schema_dialect.clone(),
+            servers: self.servers.clone(),
+            paths: Paths::default(),
+            webhooks: self.webhooks.clone(),
+            components: self.components.clone(),
+            security: self.security.clone(),
+            tags: self.tags.iter().filter(|t| operation.tags.contains(&t.name)).cloned().collect(),
+            external_docs: self.external_docs.clone(),
+            extensions: self.extensions.clone(),
+        };
+        spec.paths.insert(path.to_string(), extracted_item);
+        spec.retain_components(&spec.reachable_components());
+        Ok(spec)
+    }
+
+    /// Removes all components not in the given set.
+    pub(crate) fn retain_components(&mut self, keep: &std::collections::HashSet<(ComponentKind, String)>) {
+        for kind in ComponentKind::ALL {
+            let names: Vec<String> = self.components.keys(kind)
+                .filter(|name| !keep.contains(&(kind, name.to_string())))
+                .cloned()
+                .collect();
+            for name in names {
+                self.components.remove(kind, &name);
+            }
+        }
+    }
+
+    /// Fills in `content` for request bodies and responses which carry their schema in an `x-schema`
+    /// extension rather than under a media type, as is common in hand-written partial specs.
+    ///
+    /// The schema is moved out of the extension into `content`, keyed by `request` for request
+    /// bodies and `response` for responses. Objects which already have content are left untouched.
+    pub fn default_content_types(&mut self, request: &str, response: &str) {
+        fn take_schema(content: &mut IndexMap<String, MediaType>, extensions: &mut IndexMap<String, serde_json::Value>, mime: &str) {
+            if !content.is_empty() {
+                return;
+            }
+            let Some(schema) = extensions.get("x-schema") else { return };
+            let Ok(schema) = serde_json::from_value::<RefOr<Schema>>(schema.clone()) else { return };
+            extensions.shift_remove("x-schema");
+            content.insert(mime.to_string(), MediaType {
+                schema: Some(schema),
+                ..MediaType::default()
+            });
+        }
+        for (_, _, op) in self.operations_mut() {
+            if let Some(RefOr::Item(body)) = &mut op.request_body {
+                take_schema(&mut body.content, &mut body.extensions, request);
+            }
+            let responses = op.responses.responses.values_mut().chain(op.responses.default.as_mut());
+            for r in responses.filter_map(|r| r.as_mut()) {
+                take_schema(&mut r.content, &mut r.extensions, response);
+            }
+        }
+    }
+
+    /// Attaches common response headers (e.g. request ids or rate limits) to every response of the
+    /// operations matching `filter`.
+    ///
+    /// Each header is declared once in `components.headers`, keyed by its name, and referenced from
+    /// the responses. Existing component headers and response headers with the same name are left untouched.
+    /// Responses which are references are skipped, since they may be shared with operations outside the filter.
+    pub fn add_standard_headers(&mut self, headers: &[(&str, Schema)], filter: OperationFilter) {
+        for (name, schema) in headers {
+            if !self.components.headers.contains_key(*name) {
+                self.components.headers.insert(*name, Header::new(schema.clone()));
+            }
+        }
+        for (path, method, op) in self.operations_mut() {
+            if !filter.matches(path, method, op) {
+                continue;
+            }
+            let responses = op.responses.responses.values_mut().chain(op.responses.default.as_mut());
+            for response in responses.filter_map(|r| r.as_mut()) {
+                for (name, _) in headers {
+                    if !response.headers.contains_key(*name) {
+                        response.headers.insert(name.to_string(), RefOr::ref_(&ComponentKind::Header.reference(name)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds an error response for each of the given status codes to every operation which doesn't
+    /// already document that code.
+    ///
+    /// `reference` is either a response reference (`#/components/responses/Error`), which is used as is,
+    /// or a schema reference (`#/components/schemas/Problem`), in which case a JSON response component
+    /// with the same name is created if needed (`#/components/responses/Problem`).
+    /// Fails, leaving the document unchanged, if the referenced component doesn't exist.
+    pub fn ensure_error_responses(&mut self, codes: &[u16], reference: &str) -> anyhow::Result<()> {
+        let response_ref = match ComponentKind::parse_reference(reference) {
+            Some((ComponentKind::Response, name)) if !self.components.responses.contains_key(name) => {
+                return Err(anyhow::anyhow!("Response {} not found", reference));
+            }
+            Some((ComponentKind::Schema, name)) if !self.components.schemas.contains_key(name) => {
+                return Err(anyhow::anyhow!("Schema {} not found", reference));
+            }
+            Some((ComponentKind::Response, _)) => reference.to_string(),
+            Some((ComponentKind::Schema, name)) => {
+                if !self.components.responses.contains_key(name) {
+                    let response = Response::new(name).json(RefOr::ref_(reference));
+                    self.components.responses.insert(name, response);
+                }
+                ComponentKind::Response.reference(name)
+            }
+            _ => return Err(anyhow::anyhow!("Expected a response or schema reference: {}", reference)),
+        };
+        for (_, _, op) in self.operations_mut() {
+            for code in codes {
+                op.responses.responses.entry(StatusCode::Code(*code))
+                    .or_insert_with(|| RefOr::ref_(&response_ref));
+            }
+        }
+        Ok(())
+    }
+
     /// Merge another OpenAPI document into this one, keeping original schemas on conflict.
     /// `a.merge(b)` will have all schemas from `a` and `b`, but keep `a` for any duplicates.
     pub fn merge(mut self, other: OpenAPI) -> Result<Self, MergeError> {
@@ -119,6 +383,7 @@ impl OpenAPI {
                 option_or(&mut self_item.head, item.head);
                 option_or(&mut self_item.patch, item.patch);
                 option_or(&mut self_item.trace, item.trace);
+                merge_map(&mut self_item.other, item.other);
 
                 merge_vec(&mut self_item.servers, item.servers, |a, b| a.url == b.url);
                 merge_map(&mut self_item.extensions, item.extensions);
@@ -183,8 +448,10 @@ impl Default for OpenAPI {
         OpenAPI {
             openapi: "3.0.3".to_string(),
             info: default(),
+            json_schema_dialect: None,
             servers: default(),
             paths: default(),
+            webhooks: default(),
             components: default(),
             security: default(),
             tags: default(),
@@ -194,6 +461,88 @@ impl Default for OpenAPI {
     }
 }
 
+/// Renames components in a serialized document: the keys under `components`, `$ref`s into them,
+/// discriminator mappings and security requirements. `rename` returns the new name of a
+/// component, or [None] to keep it. Data such as examples is left untouched.
+fn rename_components_json(doc: &mut serde_json::Value, rename: &dyn Fn(ComponentKind, &str) -> Option<String>) {
+    use serde_json::Value;
+    use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key, unescape_pointer_segment};
+
+    fn renamed_reference(reference: &str, rename: &dyn Fn(ComponentKind, &str) -> Option<String>) -> Option<String> {
+        let rest = reference.strip_prefix("#/components/")?;
+        let (kind, rest) = rest.split_once('/')?;
+        let kind = ComponentKind::ALL.iter().find(|k| k.as_str() == kind)?;
+        let (name, tail) = rest.split_once('/').map_or((rest, None), |(name, tail)| (name, Some(tail)));
+        let new = rename(*kind, &unescape_pointer_segment(name))?;
+        let mut reference = format!("#/components/{}/{}", kind.as_str(), escape_pointer_segment(&new));
+        if let Some(tail) = tail {
+            reference.push('/');
+            reference.push_str(tail);
+        }
+        Some(reference)
+    }
+
+    fn rename_keys(map: &mut serde_json::Map<String, Value>, kind: ComponentKind, rename: &dyn Fn(ComponentKind, &str) -> Option<String>) {
+        *map = std::mem::take(map).into_iter()
+            .map(|(k, v)| (rename(kind, &k).unwrap_or(k), v))
+            .collect();
+    }
+
+    fn walk(value: &mut Value, names: bool, rename: &dyn Fn(ComponentKind, &str) -> Option<String>) {
+        match value {
+            Value::Object(map) if names => map.values_mut().for_each(|v| walk(v, false, rename)),
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                    if let Some(new) = renamed_reference(reference, rename) {
+                        *reference = new;
+                    }
+                }
+                for (key, child) in map.iter_mut() {
+                    if is_data_field(key, child) {
+                        continue;
+                    }
+                    match (key.as_str(), child) {
+                        ("discriminator", Value::Object(discriminator)) => {
+                            let Some(Value::Object(mapping)) = discriminator.get_mut("mapping") else { continue };
+                            for target in mapping.values_mut() {
+                                let Value::String(target) = target else { continue };
+                                // Bare values name a schema, anything else is a reference.
+                                let new = if target.contains('/') {
+                                    renamed_reference(target, rename)
+                                } else {
+                                    rename(ComponentKind::Schema, target)
+                                };
+                                if let Some(new) = new {
+                                    *target = new;
+                                }
+                            }
+                        }
+                        ("security", Value::Array(requirements)) => {
+                            for requirement in requirements {
+                                if let Value::Object(requirement) = requirement {
+                                    rename_keys(requirement, ComponentKind::SecurityScheme, rename);
+                                }
+                            }
+                        }
+                        (key, child) => walk(child, is_name_map_key(key), rename),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| walk(v, names, rename)),
+            _ => {}
+        }
+    }
+
+    walk(doc, false, rename);
+    if let Some(Value::Object(components)) = doc.get_mut("components") {
+        for kind in ComponentKind::ALL {
+            if let Some(Value::Object(map)) = components.get_mut(kind.as_str()) {
+                rename_keys(map, kind, rename);
+            }
+        }
+    }
+}
+
 fn merge_vec<T>(original: &mut Vec<T>, mut other: Vec<T>, cmp: fn(&T, &T) -> bool) {
     other.retain(|o| !original.iter().any(|r| cmp(o, r)));
     original.extend(other);
@@ -247,4 +596,218 @@ mod tests {
         a = a.merge(b).unwrap();
         assert_eq!(a.servers.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_prefix_components() {
+        let mut spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+security:
+  - key: []
+paths:
+  /pets:
+    get:
+      security:
+        - key: []
+      responses:
+        "200":
+          $ref: "#/components/responses/Pets"
+components:
+  securitySchemes:
+    key:
+      type: apiKey
+      in: header
+      name: X-Key
+  responses:
+    Pets:
+      description: OK
+      content:
+        application/json:
+          schema:
+            $ref: "#/components/schemas/Pet"
+  schemas:
+    Pet:
+      oneOf:
+        - $ref: "#/components/schemas/Cat"
+      discriminator:
+        propertyName: kind
+        mapping:
+          cat: Cat
+          kitten: "#/components/schemas/Cat"
+    Cat:
+      type: object
+"##).unwrap();
+        spec.prefix_components("pets_");
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json["security"], serde_json::json!([{"pets_key": []}]));
+        assert_eq!(json["paths"]["/pets"]["get"]["security"], serde_json::json!([{"pets_key": []}]));
+        assert_eq!(json["paths"]["/pets"]["get"]["responses"]["200"]["$ref"], "#/components/responses/pets_Pets");
+        assert_eq!(json["components"]["responses"]["pets_Pets"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/pets_Pet");
+        let pet = &json["components"]["schemas"]["pets_Pet"];
+        assert_eq!(pet["oneOf"][0]["$ref"], "#/components/schemas/pets_Cat");
+        assert_eq!(pet["discriminator"]["mapping"], serde_json::json!({"cat": "pets_Cat", "kitten": "#/components/schemas/pets_Cat"}));
+        assert!(spec.components.contains(ComponentKind::SecurityScheme, "pets_key"));
+        assert!(spec.validate().is_empty(), "{:?}", spec.validate());
+    }
+
+    #[test]
+    fn test_prefix_components_keeps_data() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        pet.example = Some(serde_json::json!({"security": [{"admin": []}], "doc": {"$ref": "#/components/schemas/Other"}}));
+        spec.schemas.insert("Pet", pet);
+        let example = spec.schemas.get2("Pet").unwrap().example.clone();
+        spec.prefix_components("svc_");
+        assert_eq!(spec.schemas.get2("svc_Pet").unwrap().example, example);
+    }
+
+    #[test]
+    fn test_rename_component() {
+        let mut spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+security: [{key: []}]
+paths: {}
+components:
+  securitySchemes:
+    key: {type: apiKey, in: header, name: X-Key}
+  schemas:
+    Pet:
+      oneOf: [{$ref: "#/components/schemas/Cat"}]
+      discriminator: {propertyName: kind, mapping: {cat: Cat, kitten: "#/components/schemas/Cat"}}
+      example: {kind: cat, link: {$ref: "#/components/schemas/Cat"}}
+    Cat: {type: object, properties: {name: {type: string}}}
+    Dog: {$ref: "#/components/schemas/Cat/properties/name"}
+"##).unwrap();
+        spec.rename_component(ComponentKind::Schema, "Cat", "pets/Cat").unwrap();
+        let json = serde_json::to_value(&spec).unwrap();
+        let pet = &json["components"]["schemas"]["Pet"];
+        assert_eq!(pet["oneOf"][0]["$ref"], "#/components/schemas/pets~1Cat");
+        assert_eq!(pet["discriminator"]["mapping"], serde_json::json!({"cat": "pets/Cat", "kitten": "#/components/schemas/pets~1Cat"}));
+        assert_eq!(pet["example"]["link"]["$ref"], "#/components/schemas/Cat");
+        assert_eq!(json["components"]["schemas"]["Dog"]["$ref"], "#/components/schemas/pets~1Cat/properties/name");
+
+        spec.rename_component(ComponentKind::SecurityScheme, "key", "apiKey").unwrap();
+        assert_eq!(spec.security[0].keys().collect::<Vec<_>>(), vec!["apiKey"]);
+        assert!(spec.rename_component(ComponentKind::Schema, "Cat", "Kitty").is_err());
+        assert!(spec.rename_component(ComponentKind::Schema, "Pet", "Dog").is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        let api = OpenAPI::new("Petstore", "1.0.0").with_server("https://petstore.example.com");
+        let json = serde_json::to_value(&api).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Petstore", "version": "1.0.0"},
+            "servers": [{"url": "https://petstore.example.com"}],
+            "paths": {},
+        }));
+    }
+
+    #[test]
+    fn test_add_operation() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.add_operation(PathMethod::GET, "/pets", Operation::default()).unwrap();
+        assert!(api.add_operation(PathMethod::GET, "/pets", Operation::default()).is_err());
+        api.add_operation(PathMethod::POST, "/pets", Operation::default()).unwrap();
+
+        assert!(api.add_operation(PathMethod::GET, "/pets/{id}", Operation::default()).is_err());
+        let mut op = Operation::default();
+        op.add_path_param("id", Schema::new_string());
+        api.add_operation(PathMethod::GET, "/pets/{id}", op).unwrap();
+        api.add_operation(PathMethod::CONNECT, "/pets", Operation::default()).unwrap();
+        assert!(api.paths["/pets"].as_item().unwrap().other.contains_key("connect"));
+        assert_eq!(api.operations().count(), 4);
+    }
+
+    #[test]
+    fn test_remove_operation_prunes() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.schemas.insert("Pet", Schema::new_object());
+        api.schemas.insert("Pets", Schema::new_array(RefOr::schema_ref("Pet")));
+        api.schemas.insert("Orphan", Schema::new_object());
+        api.schemas.insert("Error", Schema::new_object());
+        let mut op = Operation::default();
+        op.add_response_success_json(Some(RefOr::schema_ref("Pets")));
+        op.responses.default = Some(Response::new("Error").json(RefOr::schema_ref("Error")).into());
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        let mut op = Operation::default();
+        op.responses.default = Some(Response::new("Error").json(RefOr::schema_ref("Error")).into());
+        api.add_operation(PathMethod::POST, "/pets", op).unwrap();
+
+        assert!(api.remove_operation(PathMethod::GET, "/pets", true).is_some());
+        assert_eq!(api.schemas.keys().collect::<Vec<_>>(), vec!["Orphan", "Error"]);
+        assert!(api.remove_operation(PathMethod::GET, "/pets", true).is_none());
+
+        assert!(api.remove_path("/pets", false).is_some());
+        assert!(api.paths.is_empty());
+        assert_eq!(api.schemas.len(), 2);
+    }
+
+    #[test]
+    fn test_extract_operation() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.schemas.insert("Pet", Schema::new_object());
+        api.schemas.insert("Pets", Schema::new_array(RefOr::schema_ref("Pet")));
+        api.schemas.insert("Other", Schema::new_object());
+        let mut op = Operation {
+            operation_id: Some("listPets".to_string()),
+            ..Operation::default()
+        };
+        op.add_response_success_json(Some(RefOr::schema_ref("Pets")));
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        let mut op = Operation {
+            operation_id: Some("createPet".to_string()),
+            ..Operation::default()
+        };
+        op.add_request_body_json(Some(RefOr::schema_ref("Other")));
+        api.add_operation(PathMethod::POST, "/pets", op).unwrap();
+
+        let extracted = api.extract_operation("listPets").unwrap();
+        assert_eq!(extracted.operations().count(), 1);
+        assert_eq!(extracted.schemas.keys().collect::<Vec<_>>(), vec!["Pet", "Pets"]);
+        assert!(api.extract_operation("missing").is_err());
+    }
+
+    #[test]
+    fn test_add_standard_headers() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let mut op = Operation {
+            tags: vec!["pets".to_string()],
+            ..Operation::default()
+        };
+        op.add_response_success_json(None);
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        let mut op = Operation::default();
+        op.add_response_success_json(None);
+        api.add_operation(PathMethod::GET, "/health", op).unwrap();
+
+        api.add_standard_headers(&[("X-Request-Id", Schema::new_string())], OperationFilter::all().tag("pets"));
+        assert!(api.headers.contains_key("X-Request-Id"));
+        let headers: Vec<usize> = api.operations()
+            .map(|(_, _, op, _)| op.responses.responses[&StatusCode::Code(200)].as_item().unwrap().headers.len())
+            .collect();
+        assert_eq!(headers, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_ensure_error_responses() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.schemas.insert("Problem", Schema::new_object());
+        let mut op = Operation::default();
+        op.responses.responses.insert(StatusCode::Code(404), Response::new("Not found").into());
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+
+        api.ensure_error_responses(&[404, 500], "#/components/schemas/Problem").unwrap();
+        assert!(api.responses.contains_key("Problem"));
+        let (_, _, op, _) = api.operations().next().unwrap();
+        assert!(op.responses.responses[&StatusCode::Code(404)].as_item().is_some());
+        assert_eq!(op.responses.responses[&StatusCode::Code(500)].as_ref_str(), Some("#/components/responses/Problem"));
+        assert!(api.ensure_error_responses(&[500], "Problem").is_err());
+        assert!(api.ensure_error_responses(&[500], "#/components/schemas/Missing").is_err());
+        assert!(api.ensure_error_responses(&[500], "#/components/responses/Missing").is_err());
+    }
+}
@@ -49,6 +49,12 @@ pub struct Operation {
     /// REQUIRED. The list of possible responses as they are returned
     /// from executing this operation.
     pub responses: Responses,
+    /// A map of possible out-of band callbacks related to the parent operation.
+    /// The key is a unique identifier for the Callback Object. Each value in the
+    /// map is a Callback Object that describes a request that may be initiated
+    /// by the API provider and the expected responses.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub callbacks: IndexMap<String, RefOr<Callback>>,
     /// Declares this operation to be deprecated.Default value is false.
     #[serde(default, skip_serializing_if = "is_false")]
     pub deprecated: bool,
@@ -98,11 +104,107 @@ impl Operation {
             ..RequestBody::default()
         }));
     }
+
+    /// Finds the parameter with the given name and location (`in` value), resolving references.
+    /// Header names are matched case-insensitively. Parameters which fail to resolve are skipped.
+    pub fn find_param<'a>(&'a self, name: &str, location: &str, spec: &'a OpenAPI) -> Option<&'a Parameter> {
+        self.parameters.iter()
+            .filter_map(|p| p.resolve(spec).ok())
+            .filter(|p| p.kind.location() == location)
+            .find(|p| if location == "header" { p.name.eq_ignore_ascii_case(name) } else { p.name == name })
+    }
+
+    pub fn query_param<'a>(&'a self, name: &str, spec: &'a OpenAPI) -> Option<&'a Parameter> {
+        self.find_param(name, "query", spec)
+    }
+
+    pub fn path_param<'a>(&'a self, name: &str, spec: &'a OpenAPI) -> Option<&'a Parameter> {
+        self.find_param(name, "path", spec)
+    }
+
+    /// Header names are matched case-insensitively. Accepts `&str` or [http::HeaderName].
+    pub fn header_param<'a>(&'a self, name: impl AsRef<str>, spec: &'a OpenAPI) -> Option<&'a Parameter> {
+        self.find_param(name.as_ref(), "header", spec)
+    }
+
+    /// Returns the parameters which apply to this operation, combining the parameters of the
+    /// [PathItem] it belongs to. Operation parameters override path item parameters with the same name and location.
+    /// Parameters which fail to resolve are skipped; see [Operation::try_effective_parameters].
+    pub fn effective_parameters<'a>(&'a self, path_item: &'a PathItem, spec: &'a OpenAPI) -> Vec<&'a Parameter> {
+        let resolved = |params: &'a [RefOr<Parameter>]| params.iter().filter_map(|p| p.resolve(spec).ok()).collect();
+        combine_parameters(resolved(&path_item.parameters), resolved(&self.parameters))
+    }
+
+    /// Like [Operation::effective_parameters], but fails if a parameter reference doesn't resolve.
+    pub fn try_effective_parameters<'a>(&'a self, path_item: &'a PathItem, spec: &'a OpenAPI) -> anyhow::Result<Vec<&'a Parameter>> {
+        let resolved = |params: &'a [RefOr<Parameter>]| params.iter().map(|p| p.resolve(spec)).collect::<anyhow::Result<Vec<_>>>();
+        Ok(combine_parameters(resolved(&path_item.parameters)?, resolved(&self.parameters)?))
+    }
+
+    pub fn add_query_param(&mut self, name: impl Into<String>, schema: impl Into<RefOr<Schema>>) {
+        self.parameters.push(Parameter::query(name, schema).into());
+    }
+
+    /// Adds a path parameter. Path parameters are always required.
+    pub fn add_path_param(&mut self, name: impl Into<String>, schema: impl Into<RefOr<Schema>>) {
+        self.parameters.push(Parameter::path_required(name, schema).into());
+    }
+}
+
+fn combine_parameters<'a>(inherited: Vec<&'a Parameter>, own: Vec<&'a Parameter>) -> Vec<&'a Parameter> {
+    let mut params: Vec<&Parameter> = inherited.into_iter()
+        .filter(|p| !own.iter().any(|o| o.name == p.name && o.kind.location() == p.kind.location()))
+        .collect();
+    params.extend(own);
+    params
+}
+
+/// A concise one-line signature, such as `getPet(path id: string) -> 200: Pet, 404`.
+/// Request bodies are shown as a `body` argument, by JSON schema or else by content type.
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.operation_id.as_deref().unwrap_or("<anonymous>"))?;
+        let mut args: Vec<String> = self.parameters.iter().map(|p| p.to_string()).collect();
+        match &self.request_body {
+            Some(RefOr::Item(body)) => {
+                let optional = if body.required { "" } else { "?" };
+                let schema = match body.schema_for("application/json") {
+                    Some(schema) => schema.to_string(),
+                    None => body.content.keys().next().cloned().unwrap_or_else(|| "any".to_string()),
+                };
+                args.push(format!("body{}: {}", optional, schema));
+            }
+            Some(reference) => args.push(format!("body: {}", reference.reference_name().unwrap_or("?"))),
+            None => {}
+        }
+        write!(f, "{}) -> ", args.join(", "))?;
+        let responses = self.responses.responses.iter()
+            .map(|(code, r)| (code.to_string(), r))
+            .chain(self.responses.default.iter().map(|r| ("default".to_string(), r)));
+        let mut first = true;
+        for (code, response) in responses {
+            if !first {
+                f.write_str(", ")?;
+            }
+            first = false;
+            match response {
+                RefOr::Item(r) => match r.json_schema() {
+                    Some(schema) => write!(f, "{}: {}", code, schema)?,
+                    None => f.write_str(&code)?,
+                },
+                RefOr::Reference { .. } => write!(f, "{}: {}", code, response.reference_name().unwrap_or("?"))?,
+            }
+        }
+        if first {
+            f.write_str("()")?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Operation, RefOr, Responses, Schema, StatusCode};
+    use crate::{OpenAPI, Operation, Parameter, RefOr, Responses, Schema, StatusCode};
     use indexmap::IndexMap;
     use serde_yaml::from_str;
 
@@ -163,4 +265,35 @@ mod tests {
         let mut op = Operation::default();
         op.add_request_body_json(Some(RefOr::Item(Schema::new_string())));
     }
+
+    #[test]
+    fn test_param_lookup() {
+        let mut spec = OpenAPI::default();
+        spec.parameters.insert("Limit", Parameter::query("limit", Schema::new_integer()));
+        let mut op = Operation::default();
+        op.add_path_param("id", Schema::new_string());
+        op.parameters.push(RefOr::ref_("#/components/parameters/Limit"));
+
+        assert!(op.path_param("id", &spec).unwrap().required);
+        assert!(op.query_param("id", &spec).is_none());
+        assert!(op.query_param("limit", &spec).is_some());
+        assert!(op.header_param("limit", &spec).is_none());
+        assert!(op.query_param("Limit", &spec).is_none());
+
+        op.parameters.push(Parameter::header("X-Request-Id", Schema::new_string()).into());
+        assert!(op.find_param("x-request-id", "header", &spec).is_some());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut op = Operation {
+            operation_id: Some("getPet".to_string()),
+            ..Operation::default()
+        };
+        op.add_path_param("id", Schema::new_string());
+        op.add_query_param("verbose", Schema::new_bool());
+        op.responses.responses.insert(StatusCode::Code(200), crate::Response::new("OK").json(RefOr::schema_ref("Pet")).into());
+        op.responses.responses.insert(StatusCode::Code(404), RefOr::ref_("#/components/responses/NotFound"));
+        assert_eq!(op.to_string(), "getPet(path id: string, query verbose?: boolean) -> 200: Pet, 404: NotFound");
+    }
 }
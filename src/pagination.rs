@@ -0,0 +1,150 @@
+use crate::*;
+
+/// The pagination scheme of an operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaginationStyle {
+    /// `page` and `per_page`/`limit` query parameters.
+    Page,
+    /// `offset` and `limit` query parameters.
+    Offset,
+    /// An opaque `cursor` query parameter and a `limit`.
+    Cursor,
+}
+
+/// Pagination metadata of an operation, see [Operation::pagination].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaginationInfo {
+    pub style: PaginationStyle,
+    /// The name of the query parameter holding the page, offset or cursor.
+    pub position_param: String,
+    /// The name of the query parameter holding the page size, if any.
+    pub limit_param: Option<String>,
+    /// The property of the response object holding the items, or [None] if the response is itself an array.
+    pub items_property: Option<String>,
+}
+
+const PAGE_PARAMS: [&str; 2] = ["page", "page_number"];
+const OFFSET_PARAMS: [&str; 2] = ["offset", "skip"];
+const CURSOR_PARAMS: [&str; 5] = ["cursor", "after", "starting_after", "page_token", "pageToken"];
+const LIMIT_PARAMS: [&str; 6] = ["limit", "per_page", "perPage", "page_size", "pageSize", "size"];
+
+impl Operation {
+    /// Detects common pagination patterns: a page, offset or cursor query parameter, optionally a
+    /// limit query parameter, and a 200 response which is an array or an object with an array property.
+    pub fn pagination(&self, spec: &OpenAPI) -> Option<PaginationInfo> {
+        let query: Vec<&str> = self.parameters.iter()
+            .filter_map(|p| p.resolve(spec).ok())
+            .filter(|p| matches!(p.kind, ParameterKind::Query { .. }))
+            .map(|p| p.name.as_str())
+            .collect();
+        let find = |names: &[&str]| names.iter().find(|n| query.contains(n)).map(|n| n.to_string());
+        let limit_param = find(&LIMIT_PARAMS);
+        let (style, position_param) = if let Some(p) = find(&CURSOR_PARAMS) {
+            (PaginationStyle::Cursor, p)
+        } else if let Some(p) = find(&OFFSET_PARAMS) {
+            (PaginationStyle::Offset, p)
+        } else if let Some(p) = find(&PAGE_PARAMS) {
+            (PaginationStyle::Page, p)
+        } else {
+            return None;
+        };
+
+        let response = self.responses.responses.get(&StatusCode::Code(200))?.resolve(spec).ok()?;
+        let schema = Resolve::resolve(response.json_schema()?, spec).ok()?;
+        let items_property = match &schema.kind {
+            SchemaKind::Type(Type::Array(_)) => None,
+            _ => {
+                let (name, _) = schema.properties_iter(spec)
+                    .find(|(_, s)| matches!(Resolve::resolve(*s, spec).map(|s| &s.kind), Ok(SchemaKind::Type(Type::Array(_)))))?;
+                Some(name.clone())
+            }
+        };
+        Some(PaginationInfo {
+            style,
+            position_param,
+            limit_param,
+            items_property,
+        })
+    }
+
+    /// Adds the standard query parameters for the given pagination style, and wraps the
+    /// JSON schema of the 200 response into a page envelope, e.g. `{data: [...], next_cursor: string}`.
+    ///
+    /// Parameters which already exist are not added again, and a response which is not an array is left as is.
+    pub fn paginate(mut self, style: PaginationStyle) -> Self {
+        let (position, position_schema, extra) = match style {
+            PaginationStyle::Page => ("page", Schema::new_integer(), ("total", Schema::new_integer())),
+            PaginationStyle::Offset => ("offset", Schema::new_integer(), ("total", Schema::new_integer())),
+            PaginationStyle::Cursor => ("cursor", Schema::new_string(), ("next_cursor", Schema::new_string())),
+        };
+        for (name, schema) in [(position, position_schema), ("limit", Schema::new_integer())].iter() {
+            let exists = self.parameters.iter()
+                .filter_map(|p| p.as_item())
+                .any(|p| p.name == *name && matches!(p.kind, ParameterKind::Query { .. }));
+            if !exists {
+                self.parameters.push(Parameter::query(*name, schema.clone()).into());
+            }
+        }
+        let Some(RefOr::Item(response)) = self.responses.responses.get_mut(&StatusCode::Code(200)) else { return self };
+        let Some(media) = response.content.get_mut("application/json") else { return self };
+        let Some(RefOr::Item(schema)) = &media.schema else { return self };
+        if !matches!(schema.kind, SchemaKind::Type(Type::Array(_))) {
+            return self;
+        }
+        let mut envelope = Schema::new_object();
+        envelope.properties_mut().insert("data", schema.clone());
+        envelope.properties_mut().insert(extra.0, extra.1);
+        envelope.add_required("data");
+        media.schema = Some(envelope.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_roundtrip() {
+        let spec = OpenAPI::default();
+        let mut op = Operation::default();
+        op.add_response_success_json(Some(Schema::new_array(Schema::new_string()).into()));
+        assert!(op.pagination(&spec).is_none());
+
+        let op = op.paginate(PaginationStyle::Cursor);
+        assert_eq!(op.pagination(&spec), Some(PaginationInfo {
+            style: PaginationStyle::Cursor,
+            position_param: "cursor".to_string(),
+            limit_param: Some("limit".to_string()),
+            items_property: Some("data".to_string()),
+        }));
+    }
+
+    #[test]
+    fn test_detects_bare_array() {
+        let spec = OpenAPI::default();
+        let mut op = Operation::default();
+        op.add_query_param("page", Schema::new_integer());
+        op.add_response_success_json(Some(Schema::new_array(Schema::new_string()).into()));
+        let info = op.pagination(&spec).unwrap();
+        assert_eq!(info.style, PaginationStyle::Page);
+        assert_eq!(info.limit_param, None);
+        assert_eq!(info.items_property, None);
+    }
+
+    #[test]
+    fn test_dangling_references() {
+        let spec = OpenAPI::default();
+        let mut op = Operation::default();
+        op.add_query_param("page", Schema::new_integer());
+        op.add_response_success_json(Some(RefOr::schema_ref("Missing")));
+        assert!(op.pagination(&spec).is_none());
+
+        let mut op = Operation::default();
+        op.add_query_param("page", Schema::new_integer());
+        let mut page = Schema::new_object();
+        page.properties_mut().insert("data", RefOr::schema_ref("Missing"));
+        op.add_response_success_json(Some(Schema::new_all_of(vec![RefOr::schema_ref("Missing"), page.into()]).into()));
+        assert!(op.pagination(&spec).is_none());
+    }
+}
@@ -0,0 +1,464 @@
+//! Serializes parameter values to strings and parses them back, following the parameter's
+//! `style` and `explode` settings.
+use serde_json::{Map, Value};
+
+use crate::*;
+use crate::util::{percent_decode, percent_encode};
+
+/// The shape of a parameter value, for serialization.
+pub(crate) enum Shape {
+    Primitive(String),
+    Array(Vec<String>),
+    Object(Vec<(String, String)>),
+}
+
+pub(crate) fn primitive(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+impl Shape {
+    pub(crate) fn of(value: &Value, encode: impl Fn(&str) -> String) -> Shape {
+        match value {
+            Value::Array(items) => Shape::Array(items.iter().map(|v| encode(&primitive(v))).collect()),
+            Value::Object(map) => Shape::Object(map.iter().map(|(k, v)| (encode(k), encode(&primitive(v)))).collect()),
+            other => Shape::Primitive(encode(&primitive(other))),
+        }
+    }
+
+    /// Joins the values, with `k=v` pairs for exploded objects and `k,v` otherwise.
+    pub(crate) fn join(&self, sep: &str, explode: bool) -> String {
+        match self {
+            Shape::Primitive(v) => v.clone(),
+            Shape::Array(items) => items.join(sep),
+            Shape::Object(pairs) if explode => pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(sep),
+            Shape::Object(pairs) => pairs.iter().flat_map(|(k, v)| vec![k.as_str(), v.as_str()]).collect::<Vec<_>>().join(sep),
+        }
+    }
+}
+
+pub(crate) fn serialize_path(param: &Parameter, style: &PathStyle, value: &Value) -> String {
+    let explode = param.effective_explode();
+    let shape = Shape::of(value, |s| percent_encode(s, false));
+    match style {
+        PathStyle::Simple => shape.join(",", explode),
+        PathStyle::Label => format!(".{}", shape.join(if explode { "." } else { "," }, explode)),
+        PathStyle::Matrix => {
+            let name = &param.name;
+            match &shape {
+                Shape::Primitive(v) => format!(";{}={}", name, v),
+                Shape::Array(items) if explode => items.iter().map(|v| format!(";{}={}", name, v)).collect(),
+                Shape::Object(pairs) if explode => pairs.iter().map(|(k, v)| format!(";{}={}", k, v)).collect(),
+                _ => format!(";{}={}", name, shape.join(",", false)),
+            }
+        }
+    }
+}
+
+/// Serializes a query parameter to encoded `(name, value)` pairs.
+pub(crate) fn serialize_query(param: &Parameter, style: &QueryStyle, allow_reserved: bool, value: &Value) -> Vec<(String, String)> {
+    let explode = param.effective_explode();
+    let name = percent_encode(&param.name, false);
+    let shape = Shape::of(value, |s| percent_encode(s, allow_reserved));
+    match (style, &shape) {
+        (QueryStyle::DeepObject, Shape::Object(pairs)) => {
+            pairs.iter().map(|(k, v)| (format!("{}%5B{}%5D", name, k), v.clone())).collect()
+        }
+        (QueryStyle::Form, Shape::Array(items)) if explode => items.iter().map(|v| (name.clone(), v.clone())).collect(),
+        (QueryStyle::Form, Shape::Object(pairs)) if explode => pairs.clone(),
+        (QueryStyle::SpaceDelimited, _) => vec![(name, shape.join("%20", false))],
+        (QueryStyle::PipeDelimited, _) => vec![(name, shape.join("|", false))],
+        _ => vec![(name, shape.join(",", false))],
+    }
+}
+
+/// Serializes a cookie parameter to encoded `name=value` pairs. Exploded arrays repeat the
+/// name, exploded objects use the property names, and otherwise values are comma separated.
+pub(crate) fn serialize_cookie(param: &Parameter, value: &Value) -> Vec<String> {
+    let name = &param.name;
+    let shape = Shape::of(value, |s| percent_encode(s, false));
+    match &shape {
+        Shape::Array(items) if param.effective_explode() => items.iter().map(|v| format!("{}={}", name, v)).collect(),
+        Shape::Object(pairs) if param.effective_explode() => pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect(),
+        _ => vec![format!("{}={}", name, shape.join(",", false))],
+    }
+}
+
+/// Splits a `Cookie` header into `(name, value)` pairs. Values are left encoded, to be
+/// decoded once split into their items.
+pub(crate) fn parse_cookie_header(header: &str) -> Vec<(String, String)> {
+    header.split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+/// The kind of value a parameter schema describes.
+pub(crate) enum ValueKind<'a> {
+    Primitive(Option<&'a Schema>),
+    Array(Option<&'a Schema>),
+    Object(&'a Schema),
+}
+
+impl<'a> ValueKind<'a> {
+    pub(crate) fn of(param: &'a Parameter, spec: &'a OpenAPI) -> Self {
+        Self::of_schema(param.schema(), spec)
+    }
+
+    pub(crate) fn of_schema(schema: Option<&'a RefOr<Schema>>, spec: &'a OpenAPI) -> Self {
+        let Some(schema) = schema.and_then(|s| Resolve::resolve(s, spec).ok()) else {
+            return ValueKind::Primitive(None);
+        };
+        match &schema.kind {
+            SchemaKind::Type(Type::Array(a)) => ValueKind::Array(a.item_schema(spec)),
+            SchemaKind::Type(Type::Object(_)) => ValueKind::Object(schema),
+            SchemaKind::Any(a) if a.typ.as_deref() == Some("array") => {
+                ValueKind::Array(a.items.as_ref().and_then(|s| Resolve::resolve(s.as_ref(), spec).ok()))
+            }
+            SchemaKind::Any(a) if a.typ.as_deref() == Some("object") || !a.properties.is_empty() => ValueKind::Object(schema),
+            _ => ValueKind::Primitive(Some(schema)),
+        }
+    }
+}
+
+/// Converts a raw string to the JSON type the schema asks for. Strings which don't parse as
+/// that type are kept as strings, so that validation reports them.
+pub(crate) fn coerce(raw: &str, schema: Option<&Schema>) -> Value {
+    let typ = match schema.map(|s| &s.kind) {
+        Some(SchemaKind::Type(Type::Integer(_))) => "integer",
+        Some(SchemaKind::Type(Type::Number(_))) => "number",
+        Some(SchemaKind::Type(Type::Boolean {})) => "boolean",
+        Some(SchemaKind::Any(a)) => a.typ.as_deref().unwrap_or("string"),
+        _ => "string",
+    };
+    let parsed = match typ {
+        "integer" => raw.parse::<i64>().ok().map(Value::from),
+        "number" => raw.parse::<f64>().ok().map(Value::from),
+        "boolean" => raw.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn property_schema<'a>(object: &'a Schema, name: &str, spec: &'a OpenAPI) -> Option<&'a Schema> {
+    object.get_properties()?.get(name).and_then(|s| Resolve::resolve(s, spec).ok())
+}
+
+/// Builds an object from alternating keys and values, e.g. `["a", "1", "b", "2"]`.
+fn object_from_list(items: &[String], object: &Schema, spec: &OpenAPI) -> Value {
+    let mut map = Map::new();
+    for pair in items.chunks(2) {
+        if let [k, v] = pair {
+            map.insert(k.clone(), coerce(v, property_schema(object, k, spec)));
+        }
+    }
+    Value::Object(map)
+}
+
+/// Splits a raw value on the delimiter, then decodes each piece, so that encoded delimiters
+/// are kept within a piece.
+fn split_decoded(raw: &str, delimiter: &str, decode: &impl Fn(&str) -> String) -> Vec<String> {
+    raw.split(delimiter).map(decode).collect()
+}
+
+/// Parses a form style parameter, as used by query and cookie parameters, from `(name, value)`
+/// pairs with decoded names and raw values, which `decode` decodes once they are split.
+/// Returns the value, if the parameter is present, and the indices of the pairs it was built from.
+///
+/// Exploded objects take their properties from pairs named after the schema's properties.
+pub(crate) fn parse_form(
+    param: &Parameter,
+    delimiter: &str,
+    pairs: &[(String, String)],
+    decode: impl Fn(&str) -> String,
+    spec: &OpenAPI,
+) -> (Option<Value>, Vec<usize>) {
+    let explode = param.effective_explode();
+    let kind = ValueKind::of(param, spec);
+    if let (ValueKind::Object(object), true) = (&kind, explode) {
+        let mut map = Map::new();
+        let mut used = Vec::new();
+        for (i, (k, v)) in pairs.iter().enumerate() {
+            if let Some(schema) = object.get_properties().filter(|p| p.contains_key(k)).map(|_| property_schema(object, k, spec)) {
+                map.insert(k.clone(), coerce(&decode(v), schema));
+                used.push(i);
+            }
+        }
+        let value = if used.is_empty() { None } else { Some(Value::Object(map)) };
+        return (value, used);
+    }
+    let used: Vec<usize> = pairs.iter().enumerate().filter(|(_, (k, _))| *k == param.name).map(|(i, _)| i).collect();
+    if used.is_empty() {
+        return (None, used);
+    }
+    let raw: Vec<&str> = used.iter().map(|i| pairs[*i].1.as_str()).collect();
+    let split = || -> Vec<String> {
+        if explode {
+            raw.iter().map(|s| decode(s)).collect()
+        } else {
+            split_decoded(raw[0], delimiter, &decode)
+        }
+    };
+    let value = match kind {
+        ValueKind::Primitive(schema) => coerce(&decode(raw[0]), schema),
+        ValueKind::Array(items) => Value::Array(split().iter().map(|v| coerce(v, items)).collect()),
+        ValueKind::Object(object) => object_from_list(&split_decoded(raw[0], delimiter, &decode), object, spec),
+    };
+    (Some(value), used)
+}
+
+impl Parameter {
+    /// Serializes a cookie parameter value to its `name=value` pairs, joined as in a `Cookie`
+    /// header. Fails for parameters in other locations.
+    pub fn serialize_cookie(&self, value: &Value) -> anyhow::Result<String> {
+        if !matches!(self.kind, ParameterKind::Cookie { .. }) {
+            anyhow::bail!("Parameter {} is in {}, not cookie", self.name, self.kind.location());
+        }
+        Ok(serialize_cookie(self, value).join("; "))
+    }
+
+    /// Parses this cookie parameter's value out of a `Cookie` header, converting values to the
+    /// types of the schema. Returns `None` if the cookie isn't present.
+    pub fn parse_cookie(&self, cookie_header: &str, spec: &OpenAPI) -> anyhow::Result<Option<Value>> {
+        if !matches!(self.kind, ParameterKind::Cookie { .. }) {
+            anyhow::bail!("Parameter {} is in {}, not cookie", self.name, self.kind.location());
+        }
+        Ok(parse_form(self, ",", &parse_cookie_header(cookie_header), |s| percent_decode(s, false), spec).0)
+    }
+}
+
+impl Operation {
+    /// Returns the cookie parameters of this operation and its path item, resolving references.
+    /// Parameters which fail to resolve are skipped.
+    pub fn cookie_params<'a>(&'a self, path_item: &'a PathItem, spec: &'a OpenAPI) -> Vec<&'a Parameter> {
+        self.effective_parameters(path_item, spec).into_iter()
+            .filter(|p| matches!(p.kind, ParameterKind::Cookie { .. }))
+            .collect()
+    }
+}
+
+/// What to do with query parameters an operation doesn't declare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownParams {
+    /// Leave them out.
+    #[default]
+    Ignore,
+    /// Fail.
+    Error,
+    /// Keep them as strings, or arrays of strings if repeated.
+    Collect,
+}
+
+fn parse_deep_object(param: &Parameter, pairs: &[(String, String)], spec: &OpenAPI) -> (Option<Value>, Vec<usize>) {
+    let prefix = format!("{}[", param.name);
+    let object = match ValueKind::of(param, spec) {
+        ValueKind::Object(object) => Some(object),
+        _ => None,
+    };
+    let mut map = Map::new();
+    let mut used = Vec::new();
+    for (i, (k, v)) in pairs.iter().enumerate() {
+        let Some(key) = k.strip_prefix(&prefix).and_then(|k| k.strip_suffix(']')) else { continue };
+        let schema = object.and_then(|o| property_schema(o, key, spec));
+        map.insert(key.to_string(), coerce(&decode_query(v), schema));
+        used.push(i);
+    }
+    let value = if used.is_empty() { None } else { Some(Value::Object(map)) };
+    (value, used)
+}
+
+fn decode_query(s: &str) -> String {
+    percent_decode(s, true)
+}
+
+/// Parses a spaceDelimited parameter. Spaces within values can't be told apart from
+/// delimiters, so every encoded or literal space is a delimiter.
+fn parse_form_spaces(param: &Parameter, pairs: &[(String, String)], spec: &OpenAPI) -> (Option<Value>, Vec<usize>) {
+    let pairs: Vec<(String, String)> = pairs.iter()
+        .map(|(k, v)| (k.clone(), v.replace(['+', ' '], "%20")))
+        .collect();
+    parse_form(param, "%20", &pairs, decode_query, spec)
+}
+
+impl Operation {
+    fn query_params<'a>(&'a self, path_item: &'a PathItem, spec: &'a OpenAPI) -> Vec<&'a Parameter> {
+        self.effective_parameters(path_item, spec).into_iter()
+            .filter(|p| matches!(p.kind, ParameterKind::Query { .. }))
+            .collect()
+    }
+
+    /// Parses a raw query string (without the leading `?`) into values for the query parameters
+    /// of the operation and its path item, keyed by name, following each parameter's style and
+    /// converting values to the types of its schema.
+    ///
+    /// Fails if a required parameter is missing, or per `unknown`, if the query contains
+    /// parameters which aren't declared.
+    pub fn parse_query(&self, path_item: &PathItem, raw_query: &str, spec: &OpenAPI, unknown: UnknownParams) -> anyhow::Result<IndexMap<String, Value>> {
+        // Values are decoded once split, so that encoded delimiters stay within a value.
+        let pairs: Vec<(String, String)> = raw_query.trim_start_matches('?').split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let (k, v) = p.split_once('=').unwrap_or((p, ""));
+                (decode_query(k), v.to_string())
+            })
+            .collect();
+        let mut values = IndexMap::new();
+        let mut used = vec![false; pairs.len()];
+        for param in self.query_params(path_item, spec) {
+            let (value, indices) = match param.effective_style() {
+                Style::DeepObject => parse_deep_object(param, &pairs, spec),
+                Style::SpaceDelimited => parse_form_spaces(param, &pairs, spec),
+                Style::PipeDelimited => parse_form(param, "|", &pairs, decode_query, spec),
+                _ => parse_form(param, ",", &pairs, decode_query, spec),
+            };
+            for i in indices {
+                used[i] = true;
+            }
+            match value {
+                Some(value) => {
+                    values.insert(param.name.clone(), value);
+                }
+                None if param.required => anyhow::bail!("Missing required query parameter {}", param.name),
+                None => {}
+            }
+        }
+        for ((k, v), used) in pairs.into_iter().zip(used) {
+            if used {
+                continue;
+            }
+            match unknown {
+                UnknownParams::Ignore => {}
+                UnknownParams::Error => anyhow::bail!("Unknown query parameter {}", k),
+                UnknownParams::Collect => {
+                    let v = decode_query(&v);
+                    match values.get_mut(&k) {
+                        Some(Value::Array(items)) => items.push(Value::String(v)),
+                        Some(existing) => *existing = Value::Array(vec![existing.take(), Value::String(v)]),
+                        None => {
+                            values.insert(k, Value::String(v));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Builds a query string (without the leading `?`) from values for the query parameters of
+    /// the operation and its path item, keyed by name, following each parameter's style.
+    /// Parameters are written in declaration order, path item parameters first.
+    ///
+    /// Fails if a required parameter is missing, or per `unknown`, if a value is given for a
+    /// parameter which isn't declared. Collected unknown values are written in form style.
+    pub fn build_query(&self, path_item: &PathItem, values: &IndexMap<String, Value>, spec: &OpenAPI, unknown: UnknownParams) -> anyhow::Result<String> {
+        let params = self.query_params(path_item, spec);
+        let mut pairs = Vec::new();
+        for param in &params {
+            let ParameterKind::Query { style, allow_reserved, .. } = &param.kind else { continue };
+            match values.get(&param.name) {
+                Some(value) => pairs.extend(serialize_query(param, style, *allow_reserved, value)),
+                None if param.required => anyhow::bail!("Missing required query parameter {}", param.name),
+                None => {}
+            }
+        }
+        for (name, value) in values {
+            if params.iter().any(|p| p.name == *name) {
+                continue;
+            }
+            match unknown {
+                UnknownParams::Ignore => {}
+                UnknownParams::Error => anyhow::bail!("Unknown query parameter {}", name),
+                UnknownParams::Collect => {
+                    let param = Parameter::query(name.clone(), Schema::new_any());
+                    pairs.extend(serialize_query(&param, &QueryStyle::Form, false, value));
+                }
+            }
+        }
+        Ok(pairs.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_cookie_roundtrip() {
+        let spec = OpenAPI::default();
+        let mut prefs = Schema::new_object();
+        prefs.properties_mut().insert("theme", Schema::new_string());
+        prefs.properties_mut().insert("size", Schema::new_integer());
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::cookie("ids", Schema::new_array(Schema::new_integer())).explode(false).into());
+        op.parameters.push(Parameter::cookie("prefs", prefs).into());
+        op.parameters.push(Parameter::cookie("session", Schema::new_string()).into());
+        op.add_query_param("q", Schema::new_string());
+
+        let item = PathItem {
+            parameters: vec![Parameter::cookie("theme", Schema::new_string()).into()],
+            ..PathItem::default()
+        };
+        let params = op.cookie_params(&item, &spec);
+        assert_eq!(params.len(), 4);
+        assert_eq!(params[0].name, "theme");
+        let params = &params[1..];
+        let values = [json!([1, 2]), json!({"theme": "dark mode", "size": 3}), json!("abc")];
+        let header: Vec<String> = params.iter().zip(&values).map(|(p, v)| p.serialize_cookie(v).unwrap()).collect();
+        let header = header.join("; ");
+        assert_eq!(header, "ids=1,2; size=3; theme=dark%20mode; session=abc");
+        for (param, value) in params.iter().zip(&values) {
+            assert_eq!(param.parse_cookie(&header, &spec).unwrap().as_ref(), Some(value));
+        }
+        assert_eq!(params[2].parse_cookie("other=1", &spec).unwrap(), None);
+        assert_eq!(params[0].parse_cookie("ids=1%2C2,3", &spec).unwrap(), Some(json!(["1,2", 3])));
+    }
+    #[test]
+    fn test_query_roundtrip() {
+        let spec = OpenAPI::default();
+        let mut filter = Schema::new_object();
+        filter.properties_mut().insert("min", Schema::new_integer());
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::query("tags", Schema::new_array(Schema::new_string())).into());
+        op.parameters.push(Parameter::query("ids", Schema::new_array(Schema::new_integer())).style(QueryStyle::PipeDelimited).unwrap().into());
+        op.parameters.push(Parameter::query("filter", filter).style(QueryStyle::DeepObject).unwrap().into());
+        op.parameters.push(Parameter::query("verbose", Schema::new_bool()).required().into());
+
+        let mut values = IndexMap::new();
+        values.insert("tags".to_string(), json!(["a b", "c"]));
+        values.insert("ids".to_string(), json!([1, 2]));
+        values.insert("filter".to_string(), json!({"min": 3}));
+        values.insert("verbose".to_string(), json!(true));
+        let item = PathItem::default();
+        let query = op.build_query(&item, &values, &spec, UnknownParams::Error).unwrap();
+        assert_eq!(query, "tags=a%20b&tags=c&ids=1|2&filter%5Bmin%5D=3&verbose=true");
+        assert_eq!(op.parse_query(&item, &query, &spec, UnknownParams::Error).unwrap(), values);
+
+        let parsed = op.parse_query(&item, "verbose=false&x=1&x=2", &spec, UnknownParams::Collect).unwrap();
+        assert_eq!(parsed["x"], json!(["1", "2"]));
+        assert!(op.parse_query(&item, "verbose=false&x=1", &spec, UnknownParams::Error).is_err());
+        assert!(op.parse_query(&item, "tags=a", &spec, UnknownParams::Ignore).is_err());
+    }
+
+    #[test]
+    fn test_query_encoded_delimiters_and_path_params() {
+        let spec = OpenAPI::default();
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::query("names", Schema::new_array(Schema::new_string())).explode(false).into());
+        op.parameters.push(Parameter::query("words", Schema::new_array(Schema::new_string())).style(QueryStyle::SpaceDelimited).unwrap().into());
+        let item = PathItem {
+            parameters: vec![Parameter::query("page", Schema::new_integer()).into()],
+            ..PathItem::default()
+        };
+        let parsed = op.parse_query(&item, "names=a%2Cb,c&words=x%20y+z&page=2", &spec, UnknownParams::Error).unwrap();
+        assert_eq!(parsed["names"], json!(["a,b", "c"]));
+        assert_eq!(parsed["words"], json!(["x", "y", "z"]));
+        assert_eq!(parsed["page"], json!(2));
+        let query = op.build_query(&item, &parsed, &spec, UnknownParams::Error).unwrap();
+        assert_eq!(query, "page=2&names=a%2Cb,c&words=x%20y%20z");
+    }
+}
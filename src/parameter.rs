@@ -1,6 +1,14 @@
+use anyhow::{anyhow, Result};
+
 use crate::*;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// The extension holding members of a parameter which are invalid for its location, such as
+/// `allowReserved` on a header parameter, a `style` the location doesn't support, or `content`
+/// next to `schema`. They are set aside when parsing and reported by [OpenAPI::validate].
+pub const INVALID_PARAMETER_MEMBERS: &str = "x-invalid-members";
 
 /// Describes a single operation parameter.
 ///
@@ -72,7 +80,7 @@ pub enum ParameterSchemaOrContent {
 
 pub type Content = IndexMap<String, MediaType>;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct Parameter {
     #[serde(flatten)]
     pub data: ParameterData,
@@ -80,6 +88,69 @@ pub struct Parameter {
     pub kind: ParameterKind,
 }
 
+impl<'de> Deserialize<'de> for Parameter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            #[serde(flatten)]
+            data: ParameterData,
+            #[serde(flatten)]
+            kind: ParameterKind,
+        }
+        let mut value = Value::deserialize(deserializer)?;
+        if let Value::Object(map) = &mut value {
+            set_aside_invalid_members(map);
+        }
+        let Fields { data, kind } = serde_json::from_value(value).map_err(de::Error::custom)?;
+        Ok(Parameter { data, kind })
+    }
+}
+
+/// Moves the members which [Parameter] can't represent into the [INVALID_PARAMETER_MEMBERS] extension.
+fn set_aside_invalid_members(map: &mut Map<String, Value>) {
+    let styles: &[&str] = match map.get("in").and_then(Value::as_str) {
+        Some("query") => &["form", "spaceDelimited", "pipeDelimited", "deepObject"],
+        Some("path") => &["matrix", "label", "simple"],
+        Some("header") => &["simple"],
+        Some("cookie") => &["form"],
+        _ => return,
+    };
+    let mut invalid = Map::new();
+    if map.get("in").and_then(Value::as_str) != Some("query") {
+        if let Some(allow_reserved) = map.remove("allowReserved") {
+            invalid.insert("allowReserved".to_string(), allow_reserved);
+        }
+    }
+    let style_valid = match map.get("style") {
+        None => true,
+        Some(Value::String(style)) => styles.contains(&style.as_str()),
+        Some(_) => false,
+    };
+    if !style_valid {
+        invalid.insert("style".to_string(), map.remove("style").expect("Key is present"));
+    }
+    if map.contains_key("schema") {
+        if let Some(content) = map.remove("content") {
+            invalid.insert("content".to_string(), content);
+        }
+    }
+    if !invalid.is_empty() {
+        map.insert(INVALID_PARAMETER_MEMBERS.to_string(), Value::Object(invalid));
+    }
+}
+
+/// Displays the parameter as `location name: schema`, with `?` marking optional parameters.
+impl std::fmt::Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let optional = if self.required { "" } else { "?" };
+        write!(f, "{} {}{}: ", self.kind.location(), self.name, optional)?;
+        match &self.format {
+            ParameterSchemaOrContent::Schema(schema) => write!(f, "{}", schema),
+            ParameterSchemaOrContent::Content(content) => f.write_str(content.keys().next().map(String::as_str).unwrap_or("any")),
+        }
+    }
+}
+
 impl std::ops::Deref for Parameter {
     type Target = ParameterData;
 
@@ -176,6 +247,147 @@ impl Parameter {
             style: PathStyle::Simple,
         })
     }
+
+    /// Create a path parameter with `required: true`, as the spec mandates for path parameters.
+    pub fn path_required(name: impl Into<String>, schema: impl Into<RefOr<Schema>>) -> Self {
+        Self::path(name, schema).required()
+    }
+
+    pub fn header(name: impl Into<String>, schema: impl Into<RefOr<Schema>>) -> Self {
+        Self::new_kind(name.into(), schema.into(), ParameterKind::Header {
+            style: HeaderStyle::Simple,
+        })
+    }
+
+    pub fn cookie(name: impl Into<String>, schema: impl Into<RefOr<Schema>>) -> Self {
+        Self::new_kind(name.into(), schema.into(), ParameterKind::Cookie {
+            style: CookieStyle::Form,
+        })
+    }
+
+    pub fn required(mut self) -> Self {
+        self.data.required = true;
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.data.description = Some(description.into());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.data.deprecated = Some(true);
+        self
+    }
+
+    pub fn explode(mut self, explode: bool) -> Self {
+        self.data.explode = Some(explode);
+        self
+    }
+
+    /// Sets the serialization style.
+    ///
+    /// Fails if the style does not belong to this parameter's location,
+    /// e.g. a [QueryStyle] on a path parameter.
+    pub fn style(mut self, style: impl ParameterStyle) -> Result<Self> {
+        let location = self.kind.location();
+        if !style.apply(&mut self.kind) {
+            return Err(anyhow!("Style is not valid for a parameter in {}", location));
+        }
+        Ok(self)
+    }
+
+    /// Returns the serialization style, which defaults to `form` for query and cookie
+    /// parameters and `simple` for path and header parameters.
+    pub fn effective_style(&self) -> Style {
+        match &self.kind {
+            ParameterKind::Query { style, .. } => match style {
+                QueryStyle::Form => Style::Form,
+                QueryStyle::SpaceDelimited => Style::SpaceDelimited,
+                QueryStyle::PipeDelimited => Style::PipeDelimited,
+                QueryStyle::DeepObject => Style::DeepObject,
+            },
+            ParameterKind::Path { style } => match style {
+                PathStyle::Matrix => Style::Matrix,
+                PathStyle::Label => Style::Label,
+                PathStyle::Simple => Style::Simple,
+            },
+            ParameterKind::Header { .. } => Style::Simple,
+            ParameterKind::Cookie { .. } => Style::Form,
+        }
+    }
+
+    /// Returns whether arrays and objects are exploded. Unless `explode` is set, this is
+    /// true for the `form` style and false otherwise.
+    pub fn effective_explode(&self) -> bool {
+        self.explode.unwrap_or(self.effective_style() == Style::Form)
+    }
+}
+
+/// A parameter serialization style, regardless of location. See [Parameter::effective_style].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Matrix,
+    Label,
+    Simple,
+    Form,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
+}
+
+impl Style {
+    /// Returns the value of the `style` field for this style.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Style::Matrix => "matrix",
+            Style::Label => "label",
+            Style::Simple => "simple",
+            Style::Form => "form",
+            Style::SpaceDelimited => "spaceDelimited",
+            Style::PipeDelimited => "pipeDelimited",
+            Style::DeepObject => "deepObject",
+        }
+    }
+}
+
+/// A style which can be set on a [Parameter] with [Parameter::style].
+pub trait ParameterStyle {
+    /// Sets the style on the parameter kind, returning false if the locations don't match.
+    fn apply(self, kind: &mut ParameterKind) -> bool;
+}
+
+macro_rules! impl_parameter_style {
+    ($ty:ty, $variant:ident) => {
+        impl ParameterStyle for $ty {
+            fn apply(self, kind: &mut ParameterKind) -> bool {
+                match kind {
+                    ParameterKind::$variant { style, .. } => {
+                        *style = self;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        }
+    };
+}
+
+impl_parameter_style!(QueryStyle, Query);
+impl_parameter_style!(PathStyle, Path);
+impl_parameter_style!(HeaderStyle, Header);
+impl_parameter_style!(CookieStyle, Cookie);
+
+impl ParameterKind {
+    /// Returns the value of the `in` field for this kind.
+    pub fn location(&self) -> &'static str {
+        match self {
+            ParameterKind::Query { .. } => "query",
+            ParameterKind::Header { .. } => "header",
+            ParameterKind::Path { .. } => "path",
+            ParameterKind::Cookie { .. } => "cookie",
+        }
+    }
 }
 
 struct SkipSerializeIfDefault;
@@ -221,4 +433,40 @@ pub enum CookieStyle {
 pub enum HeaderStyle {
     #[default]
     Simple,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_parameter_builders() {
+        let p = Parameter::header("X-Request-Id", Schema::new_string())
+            .required()
+            .description("Request id")
+            .deprecated();
+        assert!(matches!(p.kind, ParameterKind::Header { .. }));
+        assert!(p.required);
+        assert_eq!(p.deprecated, Some(true));
+
+        let p = Parameter::query("ids", Schema::new_array(Schema::new_string()))
+            .style(QueryStyle::PipeDelimited)
+            .unwrap()
+            .explode(false);
+        assert!(matches!(p.kind, ParameterKind::Query { style: QueryStyle::PipeDelimited, .. }));
+        assert_eq!(p.explode, Some(false));
+
+        assert_eq!(p.effective_style(), Style::PipeDelimited);
+        assert!(!p.effective_explode());
+        assert!(Parameter::cookie("session", Schema::new_string()).effective_explode());
+        assert!(!Parameter::header("X-Id", Schema::new_string()).effective_explode());
+
+        assert!(Parameter::path_required("id", Schema::new_string()).required);
+        assert_eq!(Parameter::cookie("session", Schema::new_string()).kind.location(), "cookie");
+    }
+
+    #[test]
+    fn test_parameter_style_mismatch() {
+        let error = Parameter::path("id", Schema::new_string()).style(QueryStyle::DeepObject).unwrap_err();
+        assert_eq!(error.to_string(), "Style is not valid for a parameter in path");
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::Result;
+
+use crate::*;
+
+/// Options for [OpenAPI::parse].
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// The URL the document was retrieved from, against which relative server URLs are resolved
+    /// by [ParseOptions::resolve_server_url]. It isn't part of the document, so the parsed
+    /// [OpenAPI] doesn't keep it.
+    pub document_base_url: Option<http::Uri>,
+    /// Fail on unknown keys without an `x-` prefix, which are otherwise dropped.
+    /// See [OpenAPI::parse_reporting_unknown_keys].
+    pub strict: bool,
+}
+
+impl OpenAPI {
+    /// Parses a YAML or JSON document, applying the given options. YAML needs the `yaml` feature.
+    pub fn parse(input: &str, options: &ParseOptions) -> Result<OpenAPI> {
+        if !options.strict {
+            return crate::util::from_yaml_or_json(input);
+        }
+        let (spec, unknown) = OpenAPI::parse_reporting_unknown_keys(input)?;
+        if !unknown.is_empty() {
+            anyhow::bail!("Unknown keys: {}", unknown.join(", "));
+        }
+        Ok(spec)
+    }
+
+    /// Parses like [OpenAPI::parse] in lenient mode, also returning JSON pointers to the keys which
+    /// were dropped because they are neither fields of their object nor `x-` extensions.
+    ///
+    /// Only `x-` keys are kept as extensions, and keys of known fields never are. Unknown keys with a
+    /// default-like value (`false`, `null`, or empty) can't be told apart from defaulted fields
+    /// and aren't reported.
+    pub fn parse_reporting_unknown_keys(input: &str) -> Result<(OpenAPI, Vec<String>)> {
+        let raw: serde_json::Value = crate::util::from_yaml_or_json(input)?;
+        let spec: OpenAPI = serde_json::from_value(raw.clone())?;
+        let parsed = serde_json::to_value(&spec)?;
+        let mut unknown = Vec::new();
+        find_unknown_keys(&raw, &parsed, &mut String::from("#"), &mut unknown);
+        Ok((spec, unknown))
+    }
+}
+
+impl ParseOptions {
+    /// Returns the absolute URL of a server, with variables at their defaults, resolving relative
+    /// URLs against [ParseOptions::document_base_url]. Fails for relative URLs if the base URL is unknown.
+    pub fn resolve_server_url(&self, server: &Server) -> Result<http::Uri> {
+        crate::server::absolute_url(&server.default_url(), self.document_base_url.as_ref())
+    }
+}
+
+/// Compares the input document with the serialized result of parsing it.
+pub(crate) fn find_unknown_keys(raw: &serde_json::Value, parsed: &serde_json::Value, pointer: &mut String, unknown: &mut Vec<String>) {
+    use serde_json::Value;
+    match (raw, parsed) {
+        (Value::Object(raw), Value::Object(parsed)) => {
+            for (key, value) in raw {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&crate::util::escape_pointer_segment(key));
+                // Status code ranges such as `2xx` are normalized to uppercase.
+                let kept = parsed.get(key).or_else(|| {
+                    is_status_code_range(key)
+                        .then(|| parsed.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v))
+                        .flatten()
+                });
+                match kept {
+                    Some(kept) => find_unknown_keys(value, kept, pointer, unknown),
+                    None => {
+                        let default_like = match value {
+                            Value::Null | Value::Bool(false) => true,
+                            Value::String(s) => s.is_empty(),
+                            Value::Array(items) => items.is_empty(),
+                            Value::Object(map) => map.is_empty(),
+                            _ => false,
+                        };
+                        if !default_like {
+                            unknown.push(pointer.clone());
+                        }
+                    }
+                }
+                pointer.truncate(len);
+            }
+        }
+        (Value::Array(raw), Value::Array(parsed)) => {
+            for (i, (value, kept)) in raw.iter().zip(parsed).enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                find_unknown_keys(value, kept, pointer, unknown);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_status_code_range(key: &str) -> bool {
+    matches!(key.as_bytes(), [b'1'..=b'5', b'x' | b'X', b'x' | b'X'])
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_base_url() {
+        let input = "openapi: 3.0.3\ninfo: {title: Pets, version: '1'}\nservers: [{url: /api/v1}]\npaths: {}\n";
+        let options = ParseOptions::default();
+        let spec = OpenAPI::parse(input, &options).unwrap();
+        assert!(options.resolve_server_url(&spec.servers[0]).is_err());
+        let options = ParseOptions {
+            document_base_url: Some("https://pets.example.com/openapi.yaml".parse().unwrap()),
+            ..ParseOptions::default()
+        };
+        let spec = OpenAPI::parse(input, &options).unwrap();
+        assert_eq!(options.resolve_server_url(&spec.servers[0]).unwrap().to_string(), "https://pets.example.com/api/v1");
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let input = r#"
+openapi: 3.0.3
+info: {title: Pets, version: '1', x-logo: logo.png, summry: Pets}
+tags: [{name: pets, descripton: Pets}]
+servers: [{url: /api, variables: {v: {default: '1', enumm: ['1']}}}]
+paths:
+  /pets:
+    x-owner: pets-team
+    get:
+      operationID: listPets
+      deprecated: false
+      parameters:
+        - {name: limit, in: query, requierd: true, schema: {type: integer, maximun: 100}}
+      responses:
+        2xx: {description: OK, content: {application/json: {schema: {$ref: '#/components/schemas/Pet'}, exmaple: {name: Tom}}}}
+components:
+  schemas:
+    Pet:
+      type: object
+      x-table: pets
+      properties: {name: {type: string, nulable: true}}
+  securitySchemes:
+    key: {type: apiKey, in: header, name: key, x-rotate: true, scope: admin}
+"#;
+        let (spec, unknown) = OpenAPI::parse_reporting_unknown_keys(input).unwrap();
+        assert_eq!(unknown, vec![
+            "#/components/schemas/Pet/properties/name/nulable",
+            "#/components/securitySchemes/key/scope",
+            "#/info/summry",
+            "#/paths/~1pets/get/operationID",
+            "#/paths/~1pets/get/parameters/0/requierd",
+            "#/paths/~1pets/get/parameters/0/schema/maximun",
+            "#/paths/~1pets/get/responses/2xx/content/application~1json/exmaple",
+            "#/servers/0/variables/v/enumm",
+            "#/tags/0/descripton",
+        ]);
+        assert_eq!(spec.info.extensions.keys().collect::<Vec<_>>(), vec!["x-logo"]);
+        assert_eq!(spec.paths["/pets"].as_item().unwrap().extensions.keys().collect::<Vec<_>>(), vec!["x-owner"]);
+        assert_eq!(spec.schemas.get2("Pet").unwrap().extensions.keys().collect::<Vec<_>>(), vec!["x-table"]);
+        assert_eq!(spec.operations().next().unwrap().2.operation_id, None);
+
+        let strict = ParseOptions { strict: true, ..ParseOptions::default() };
+        let error = OpenAPI::parse(input, &strict).unwrap_err();
+        assert!(error.to_string().starts_with("Unknown keys: #/components/schemas/Pet/properties/name/nulable, "));
+        assert!(OpenAPI::parse(input, &ParseOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_keys_per_object() {
+        let header = "openapi: 3.0.3\ninfo: {title: Pets, version: '1'}\n";
+        let cases = [
+            ("info: {title: Pets, version: '1', Description: Pets}\npaths: {}", "#/info/Description"),
+            ("info: {title: Pets, version: '1', contact: {name: Me, emial: me@example.com}}\npaths: {}", "#/info/contact/emial"),
+            ("info: {title: Pets, version: '1', license: {name: MIT, uri: x}}\npaths: {}", "#/info/license/uri"),
+            ("externalDocs: {url: x, Description: Docs}\npaths: {}", "#/externalDocs/Description"),
+            ("servers: [{url: /, Description: Main}]\npaths: {}", "#/servers/0/Description"),
+            ("paths: {/pets: {gett: {responses: {200: {description: OK}}}}}", "#/paths/~1pets/gett"),
+            ("paths: {/pets: {get: {responses: {200: {description: OK}}, Summary: List}}}", "#/paths/~1pets/get/Summary"),
+            ("paths: {/pets: {parameters: [{name: id, in: query, schema: {type: string}, Required: true}]}}", "#/paths/~1pets/parameters/0/Required"),
+            ("paths: {/pets: {post: {requestBody: {content: {}, Required: true}, responses: {}}}}", "#/paths/~1pets/post/requestBody/Required"),
+            ("paths: {/pets: {post: {requestBody: {content: {multipart/form-data: {encoding: {a: {contentTyp: x}}}}}, responses: {}}}}",
+             "#/paths/~1pets/post/requestBody/content/multipart~1form-data/encoding/a/contentTyp"),
+            ("paths: {/pets: {get: {responses: {200: {description: OK, headers: {X-Rate: {schema: {type: integer}, Description: Rate}}}}}}}",
+             "#/paths/~1pets/get/responses/200/headers/X-Rate/Description"),
+            ("paths: {/pets: {get: {responses: {200: {description: OK, links: {next: {operationId: x, Description: Next}}}}}}}",
+             "#/paths/~1pets/get/responses/200/links/next/Description"),
+            ("paths: {/pets: {get: {responses: {defualt: {description: Error}}}}}", "#/paths/~1pets/get/responses/defualt"),
+            ("paths: {}\ncomponents: {schemas: {Pet: {}}, responsess: {A: {description: A}}}", "#/components/responsess"),
+            ("paths: {}\ncomponents: {examples: {a: {Summary: A}}}", "#/components/examples/a/Summary"),
+            ("paths: {}\ncomponents: {schemas: {Pet: {oneOf: [], discriminator: {propertyName: kind, maping: {a: b}}}}}",
+             "#/components/schemas/Pet/discriminator/maping"),
+            ("paths: {}\ncomponents: {securitySchemes: {o: {type: oauth2, flows: {implicit: {authorizationUrl: x, scopes: {}, tokenUrl: y}}}}}",
+             "#/components/securitySchemes/o/flows/implicit/tokenUrl"),
+            ("paths: {}\ntags: [{name: pets, externalDocs: {url: x, Description: Docs}}]", "#/tags/0/externalDocs/Description"),
+        ];
+        for (body, pointer) in cases {
+            let (_, unknown) = OpenAPI::parse_reporting_unknown_keys(&format!("{}{}\n", header, body))
+                .unwrap_or_else(|e| panic!("{}: {}", body, e));
+            assert_eq!(unknown, vec![pointer], "{}", body);
+        }
+    }
+}
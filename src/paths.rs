@@ -36,6 +36,10 @@ pub struct PathItem {
     pub patch: Option<Operation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trace: Option<Operation>,
+    /// Operations for non-standard methods, such as `query`, keyed by lowercase method name.
+    /// Only the methods in [EXTENSION_METHODS] are read from documents.
+    #[serde(flatten, deserialize_with = "deserialize_other_methods")]
+    pub other: IndexMap<String, Operation>,
     /// An alternative server array to service all operations in this path.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub servers: Vec<Server>,
@@ -54,6 +58,17 @@ pub struct PathItem {
     pub extensions: IndexMap<String, serde_json::Value>,
 }
 
+/// Registered HTTP methods beyond the eight which have their own [PathItem] field, as they
+/// are keyed in [PathItem::other].
+pub const EXTENSION_METHODS: [&str; 11] = [
+    "connect", "query", "search", "copy", "lock", "mkcol", "move", "propfind", "proppatch", "unlock", "link",
+];
+
+/// Returns the [EXTENSION_METHODS] entry for a lowercase method name.
+fn extension_method(method: &str) -> Option<&'static str> {
+    EXTENSION_METHODS.iter().copied().find(|m| *m == method)
+}
+
 impl PathItem {
     /// Returns an iterator of references to the [Operation]s in the [PathItem].
     pub fn iter(&self) -> impl Iterator<Item=(&str, &'_ Operation)> {
@@ -69,6 +84,7 @@ impl PathItem {
         ]
             .into_iter()
             .filter_map(|(method, maybe_op)| maybe_op.as_ref().map(|op| (method, op)))
+            .chain(self.other.iter().filter_map(|(method, op)| Some((extension_method(method)?, op))))
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item=(&str, &'_ mut Operation)> {
@@ -84,6 +100,90 @@ impl PathItem {
         ]
             .into_iter()
             .filter_map(|(method, maybe_op)| maybe_op.as_mut().map(|op| (method, op)))
+            .chain(self.other.iter_mut().filter_map(|(method, op)| Some((extension_method(method)?, op))))
+    }
+
+    /// Returns the operation for the given method, including non-standard methods.
+    ///
+    /// This is not named `get`, which constructs a [PathItem] with a GET operation.
+    pub fn operation(&self, method: &Method) -> Option<&Operation> {
+        match self.slot(method) {
+            Some(slot) => slot.as_ref(),
+            None => self.other.get(&method.as_str().to_lowercase()),
+        }
+    }
+
+    pub fn operation_mut(&mut self, method: &Method) -> Option<&mut Operation> {
+        if self.slot(method).is_some() {
+            return self.slot_mut(method)?.as_mut();
+        }
+        self.other.get_mut(&method.as_str().to_lowercase())
+    }
+
+    /// Sets the operation for the given method, returning the previous one.
+    /// Non-standard methods are stored in [PathItem::other].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is neither a standard method nor one of [EXTENSION_METHODS].
+    pub fn set(&mut self, method: Method, operation: Operation) -> Option<Operation> {
+        match self.slot_mut(&method) {
+            Some(slot) => slot.replace(operation),
+            None => {
+                let name = method.as_str().to_lowercase();
+                assert!(extension_method(&name).is_some(), "Unsupported method {}", method);
+                self.other.insert(name, operation)
+            }
+        }
+    }
+
+    /// Whether [PathItem::set] accepts the method.
+    pub fn supports_method(method: &Method) -> bool {
+        matches!(*method, Method::GET | Method::PUT | Method::POST | Method::DELETE | Method::PATCH | Method::HEAD | Method::OPTIONS | Method::TRACE)
+            || extension_method(&method.as_str().to_lowercase()).is_some()
+    }
+
+    /// Removes the operation for the given method, returning it.
+    pub fn remove(&mut self, method: &Method) -> Option<Operation> {
+        match self.slot_mut(method) {
+            Some(slot) => slot.take(),
+            None => self.other.shift_remove(&method.as_str().to_lowercase()),
+        }
+    }
+
+    /// Returns the methods which have an operation, in document order.
+    pub fn methods(&self) -> impl Iterator<Item=Method> + '_ {
+        self.iter()
+            .map(|(method, _)| Method::from_bytes(method.to_uppercase().as_bytes()).expect("Method names are valid tokens"))
+    }
+
+    fn slot(&self, method: &Method) -> Option<&Option<Operation>> {
+        Some(match *method {
+            Method::GET => &self.get,
+            Method::PUT => &self.put,
+            Method::POST => &self.post,
+            Method::DELETE => &self.delete,
+            Method::PATCH => &self.patch,
+            Method::HEAD => &self.head,
+            Method::OPTIONS => &self.options,
+            Method::TRACE => &self.trace,
+            _ => return None,
+        })
+    }
+
+    /// Returns the field holding the operation for the given method, or [None] if the method is not supported.
+    pub(crate) fn slot_mut(&mut self, method: &Method) -> Option<&mut Option<Operation>> {
+        Some(match *method {
+            Method::GET => &mut self.get,
+            Method::PUT => &mut self.put,
+            Method::POST => &mut self.post,
+            Method::DELETE => &mut self.delete,
+            Method::PATCH => &mut self.patch,
+            Method::HEAD => &mut self.head,
+            Method::OPTIONS => &mut self.options,
+            Method::TRACE => &mut self.trace,
+            _ => return None,
+        })
     }
 
     pub fn get(operation: Operation) -> Self {
@@ -106,7 +206,7 @@ impl IntoIterator for PathItem {
 
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
-    /// Returns an iterator of the [Operation]s in the [PathItem].
+    /// Returns an iterator of the [Operation]s in the [PathItem], in the same order as [PathItem::iter].
     fn into_iter(self) -> Self::IntoIter {
         vec![
             ("get", self.get),
@@ -120,6 +220,7 @@ impl IntoIterator for PathItem {
         ]
             .into_iter()
             .filter_map(|(method, maybe_op)| maybe_op.map(|op| (method, op)))
+            .chain(self.other.into_iter().filter_map(|(method, op)| Some((extension_method(&method)?, op))))
             .collect::<Vec<_>>()
             .into_iter()
     }
@@ -161,20 +262,18 @@ impl Paths {
     pub fn insert_operation(&mut self, path: String, method: Method, operation: Operation) -> Option<Operation> {
         let item = self.paths.entry(path).or_default();
         let item = item.as_mut().expect("Currently don't support references for PathItem");
-        match method {
-            Method::GET => item.get.replace(operation),
-            Method::PUT => item.put.replace(operation),
-            Method::POST => item.post.replace(operation),
-            Method::DELETE => item.delete.replace(operation),
-            Method::PATCH => item.patch.replace(operation),
-            Method::HEAD => item.head.replace(operation),
-            Method::OPTIONS => item.options.replace(operation),
-            Method::TRACE => item.trace.replace(operation),
-            _ => panic!("Unsupported method: {:?}", method),
-        }
+        item.set(method, operation)
     }
 }
 
+/// Returns the names of the `{placeholder}` segments of a path template, in order.
+pub(crate) fn path_template_params(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|s| s.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
 impl IntoIterator for Paths {
     type Item = (String, RefOr<PathItem>);
 
@@ -185,6 +284,19 @@ impl IntoIterator for Paths {
     }
 }
 
+/// Collects the operations of the [EXTENSION_METHODS]. Other keys are ignored.
+fn deserialize_other_methods<'de, D>(
+    deserializer: D,
+) -> Result<IndexMap<String, Operation>, D::Error>
+    where
+        D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(PredicateVisitor(
+        |key: &String| extension_method(key).is_some(),
+        PhantomData,
+    ))
+}
+
 fn deserialize_paths<'de, D>(
     deserializer: D,
 ) -> Result<IndexMap<String, RefOr<PathItem>>, D::Error>
@@ -226,4 +338,33 @@ mod tests {
         ];
         assert_eq!(path_item.into_iter().collect::<Vec<_>>(), expected);
     }
+
+    #[test]
+    fn test_path_template_params() {
+        assert_eq!(path_template_params("/users/{user_id}/pets/{id}.json"), vec!["user_id", "id"]);
+        assert!(path_template_params("/users").is_empty());
+    }
+
+    #[test]
+    fn test_method_map() {
+        let query = Method::from_bytes(b"QUERY").unwrap();
+        let mut item = PathItem::default();
+        assert!(item.set(Method::GET, Operation::default()).is_none());
+        item.set(query.clone(), Operation { operation_id: Some("search".to_string()), ..Operation::default() });
+        assert_eq!(item.methods().collect::<Vec<_>>(), vec![Method::GET, query.clone()]);
+        assert!(item.operation(&Method::GET).is_some());
+        assert!(item.operation(&Method::POST).is_none());
+
+        let yaml = serde_yaml::to_string(&item).unwrap();
+        assert!(yaml.contains("query:"), "{}", yaml);
+        let parsed: PathItem = serde_yaml::from_str(&(yaml + "x-internal: true\ngett: {}\n")).unwrap();
+        assert_eq!(parsed.operation(&query).unwrap().operation_id.as_deref(), Some("search"));
+        assert_eq!(parsed.other.len(), 1);
+        assert_eq!(parsed.iter().map(|(m, _)| m).collect::<Vec<_>>(), vec!["get", "query"]);
+        assert_eq!(parsed.clone().into_iter().map(|(m, _)| m).collect::<Vec<_>>(), vec!["get", "query"]);
+        assert!(!PathItem::supports_method(&Method::from_bytes(b"GETT").unwrap()));
+
+        item.remove(&query);
+        assert!(item.other.is_empty());
+    }
 }
@@ -0,0 +1,92 @@
+use serde_json::json;
+
+use crate::*;
+
+/// The media type of RFC 9457 Problem Details responses.
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Creates the RFC 9457 Problem Details schema, with the standard `type`, `title`, `status`,
+/// `detail` and `instance` members. Further extension members are allowed.
+pub fn problem_details() -> Schema {
+    let mut schema = Schema::new_object();
+    schema.title = Some("Problem".to_string());
+    schema.description = Some("Problem Details for HTTP APIs (RFC 9457)".to_string());
+    let props = schema.properties_mut();
+    let mut typ = Schema::new_string().with_format("uri-reference");
+    typ.default = Some(json!("about:blank"));
+    typ.description = Some("A URI reference identifying the problem type.".to_string());
+    props.insert("type", typ);
+    let mut title = Schema::new_string();
+    title.description = Some("A short, human-readable summary of the problem type.".to_string());
+    props.insert("title", title);
+    let mut status = Schema::new_integer();
+    if let SchemaKind::Type(Type::Integer(i)) = &mut status.kind {
+        i.minimum = Some(100);
+        i.maximum = Some(599);
+    }
+    status.description = Some("The HTTP status code generated by the origin server.".to_string());
+    props.insert("status", status);
+    let mut detail = Schema::new_string();
+    detail.description = Some("A human-readable explanation specific to this occurrence of the problem.".to_string());
+    props.insert("detail", detail);
+    let mut instance = Schema::new_string().with_format("uri-reference");
+    instance.description = Some("A URI reference identifying this occurrence of the problem.".to_string());
+    props.insert("instance", instance);
+    schema
+}
+
+impl OpenAPI {
+    /// Adds the [problem_details] schema as the `Problem` component, unless a schema with that
+    /// name exists, and returns a reference to it.
+    pub fn ensure_problem_details(&mut self) -> RefOr<Schema> {
+        if !self.schemas.contains_key("Problem") {
+            self.schemas.insert("Problem", problem_details());
+        }
+        RefOr::schema_ref("Problem")
+    }
+}
+
+impl Response {
+    /// Creates an `application/problem+json` error response for the given status code, with
+    /// an example of the problem type `type_uri`. The description is the reason phrase.
+    ///
+    /// The content references the `Problem` component, so that the schema is declared once;
+    /// add it with [OpenAPI::ensure_problem_details].
+    pub fn problem(status: u16, type_uri: &str) -> Self {
+        let reason = http::StatusCode::from_u16(status).ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("Error");
+        let mut response = Response::new(reason);
+        response.content.insert(PROBLEM_JSON.to_string(), MediaType {
+            schema: Some(RefOr::schema_ref("Problem")),
+            example: Some(json!({"type": type_uri, "title": reason, "status": status})),
+            ..MediaType::default()
+        });
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_response() {
+        let mut spec = OpenAPI::default();
+        let reference = spec.ensure_problem_details();
+        let response = Response::problem(404, "https://example.com/probs/not-found");
+        assert_eq!(response.description, "Not Found");
+        let schema = response.json_schema().unwrap();
+        assert_eq!(schema, &reference);
+        let example = response.content[PROBLEM_JSON].example.as_ref().unwrap();
+        assert!(schema.validate_value(example, &spec).is_empty());
+        assert!(!schema.validate_value(&json!({"status": 42}), &spec).is_empty());
+    }
+
+    #[test]
+    fn test_problem_json_negotiation() {
+        let body = RequestBody::json(Schema::new_object());
+        assert!(body.schema_for(PROBLEM_JSON).is_some());
+        assert!(body.schema_for("application/problem+xml").is_none());
+    }
+}
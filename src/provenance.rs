@@ -0,0 +1,107 @@
+use anyhow::Result;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::*;
+
+/// Records on a flattened property the schemas which declared it, see [Schema::flatten_with_provenance].
+pub const PROVENANCE_EXTENSION: &str = "x-merged-from";
+
+impl Schema {
+    /// Like [Schema::flatten], also returning for each top level property the schemas which declared it,
+    /// in allOf order. Sources are component references such as `#/components/schemas/Base`, or for
+    /// inline parts pointers relative to this schema, such as `#/allOf/1`, with `#` for the schema itself.
+    ///
+    /// With `annotate`, each property also gets a [PROVENANCE_EXTENSION] holding its source,
+    /// or an array if there are several.
+    pub fn flatten_with_provenance(&self, spec: &OpenAPI, policy: &RecursionPolicy, annotate: bool) -> Result<(Schema, IndexMap<String, Vec<String>>)> {
+        let mut sources = IndexMap::new();
+        collect_sources(self, "#", false, spec, &mut Vec::new(), &mut sources)?;
+        let mut flat = self.flatten(spec, policy)?;
+        if annotate {
+            if let Some(properties) = flat.get_properties_mut() {
+                for (name, property) in properties.iter_mut() {
+                    let (Some(from), RefOr::Item(property)) = (sources.get(name), property) else { continue };
+                    let value = match from.as_slice() {
+                        [single] => Value::from(single.as_str()),
+                        many => Value::from(many.to_vec()),
+                    };
+                    property.data.extensions.insert(PROVENANCE_EXTENSION.to_string(), value);
+                }
+            }
+        }
+        Ok((flat, sources))
+    }
+}
+
+/// `origin` names `schema`: a component reference if `in_component`, otherwise a pointer relative
+/// to the root schema.
+fn collect_sources(schema: &Schema, origin: &str, in_component: bool, spec: &OpenAPI, stack: &mut Vec<String>, sources: &mut IndexMap<String, Vec<String>>) -> Result<()> {
+    if let Some(properties) = schema.get_properties() {
+        for name in properties.keys() {
+            let from = sources.entry(name.clone()).or_default();
+            if !from.iter().any(|f| f == origin) {
+                from.push(origin.to_string());
+            }
+        }
+    }
+    let SchemaKind::AllOf { all_of } = &schema.kind else { return Ok(()) };
+    for (i, part) in all_of.iter().enumerate() {
+        match part {
+            RefOr::Reference { reference } => {
+                if stack.contains(reference) {
+                    continue;
+                }
+                stack.push(reference.clone());
+                collect_sources(Resolve::resolve(part, spec)?, reference, true, spec, stack, sources)?;
+                stack.pop();
+            }
+            RefOr::Item(item) => {
+                // Inline parts of a component are attributed to the component.
+                let origin = if in_component {
+                    origin.to_string()
+                } else {
+                    format!("{}/allOf/{}", origin, i)
+                };
+                collect_sources(item, &origin, in_component, spec, stack, sources)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance() {
+        let mut spec = OpenAPI::default();
+        let mut entity = Schema::new_object();
+        entity.properties_mut().insert("id", Schema::new_string());
+        spec.schemas.insert("Entity", entity);
+        let mut named = Schema::new_object();
+        named.properties_mut().insert("name", Schema::new_string());
+        let own = vec![("created".to_string(), Schema::new_string().into())].into_iter().collect();
+        spec.schemas.insert("Base", Schema::new_all_of(vec![RefOr::schema_ref("Entity"), named.into(), Schema::new_extends(RefOr::schema_ref("Entity"), own).into()]));
+        let mut own = RefOrMap::new();
+        own.insert("name", Schema::new_string());
+        own.insert("bark", Schema::new_bool());
+        let dog = Schema::new_extends(RefOr::schema_ref("Base"), own);
+
+        let (flat, sources) = dog.flatten_with_provenance(&spec, &RecursionPolicy::default(), true).unwrap();
+        assert_eq!(sources["id"], vec!["#/components/schemas/Entity"]);
+        assert_eq!(sources["name"], vec!["#/components/schemas/Base", "#/allOf/1"]);
+        assert_eq!(sources["created"], vec!["#/components/schemas/Base"]);
+        assert_eq!(sources["bark"], vec!["#/allOf/1"]);
+        let (_, sources) = Schema::new_object().flatten_with_provenance(&spec, &RecursionPolicy::default(), false).unwrap();
+        assert!(sources.is_empty());
+        let mut own = Schema::new_object();
+        own.properties_mut().insert("id", Schema::new_string());
+        let (_, sources) = own.flatten_with_provenance(&spec, &RecursionPolicy::default(), false).unwrap();
+        assert_eq!(sources["id"], vec!["#"]);
+        let properties = flat.properties();
+        assert_eq!(properties.get2("id").unwrap().extensions[PROVENANCE_EXTENSION], "#/components/schemas/Entity");
+        assert_eq!(properties.get2("name").unwrap().extensions[PROVENANCE_EXTENSION], serde_json::json!(["#/components/schemas/Base", "#/allOf/1"]));
+    }
+}
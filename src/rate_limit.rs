@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+use crate::util::to_json;
+
+const RATE_LIMIT_EXTENSION: &str = "x-ratelimit";
+
+/// The conventional rate-limit response headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitHeader {
+    /// `X-RateLimit-Limit`: requests allowed per window.
+    Limit,
+    /// `X-RateLimit-Remaining`: requests left in the current window.
+    Remaining,
+    /// `X-RateLimit-Reset`: seconds until the window resets.
+    Reset,
+    /// `Retry-After`: seconds to wait before retrying a rejected request.
+    RetryAfter,
+}
+
+impl RateLimitHeader {
+    pub const ALL: [RateLimitHeader; 4] = [
+        RateLimitHeader::Limit,
+        RateLimitHeader::Remaining,
+        RateLimitHeader::Reset,
+        RateLimitHeader::RetryAfter,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RateLimitHeader::Limit => "X-RateLimit-Limit",
+            RateLimitHeader::Remaining => "X-RateLimit-Remaining",
+            RateLimitHeader::Reset => "X-RateLimit-Reset",
+            RateLimitHeader::RetryAfter => "Retry-After",
+        }
+    }
+
+    pub fn schema(&self) -> Schema {
+        let mut schema = Schema::new_integer();
+        schema.description = Some(match self {
+            RateLimitHeader::Limit => "The number of requests allowed in the current window.",
+            RateLimitHeader::Remaining => "The number of requests remaining in the current window.",
+            RateLimitHeader::Reset => "The number of seconds until the current window resets.",
+            RateLimitHeader::RetryAfter => "The number of seconds to wait before retrying.",
+        }.to_string());
+        schema
+    }
+}
+
+/// The rate limit applied to an operation, stored in its `x-ratelimit` extension.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    /// Requests allowed per window.
+    pub limit: u64,
+    /// Length of the window in seconds.
+    pub window_seconds: u64,
+    /// Requests allowed in excess of `limit` over short periods.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u64>,
+    /// What the limit is counted against, e.g. `user`, `ip` or `api-key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl Operation {
+    /// Returns the `x-ratelimit` of this operation, if it exists and is well-formed.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let value = self.extensions.get(RATE_LIMIT_EXTENSION)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    pub fn set_rate_limit(&mut self, rate_limit: &RateLimit) {
+        let value = to_json(rate_limit);
+        self.extensions.insert(RATE_LIMIT_EXTENSION.to_string(), value);
+    }
+}
+
+impl OpenAPI {
+    /// Adds the `X-RateLimit-*` headers to every response of the operations matching `filter`,
+    /// and `Retry-After` to their 429 and 503 responses.
+    ///
+    /// Headers are declared in `components.headers` and referenced, as in [OpenAPI::add_standard_headers].
+    pub fn add_rate_limit_headers(&mut self, filter: OperationFilter) {
+        let headers: Vec<(&str, Schema)> = [RateLimitHeader::Limit, RateLimitHeader::Remaining, RateLimitHeader::Reset]
+            .iter()
+            .map(|h| (h.name(), h.schema()))
+            .collect();
+        self.add_standard_headers(&headers, filter.clone());
+
+        let retry_after = RateLimitHeader::RetryAfter;
+        if !self.components.headers.contains_key(retry_after.name()) {
+            self.components.headers.insert(retry_after.name(), Header::new(retry_after.schema()));
+        }
+        let reference = ComponentKind::Header.reference(retry_after.name());
+        for (path, method, op) in self.operations_mut() {
+            if !filter.matches(path, method, op) {
+                continue;
+            }
+            for code in [429, 503].iter() {
+                let response = op.responses.responses.get_mut(&StatusCode::Code(*code)).and_then(RefOr::as_mut);
+                if let Some(response) = response {
+                    if !response.headers.contains_key(retry_after.name()) {
+                        response.headers.insert(retry_after.name().to_string(), RefOr::ref_(&reference));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit() {
+        let mut op = Operation::default();
+        assert!(op.rate_limit().is_none());
+        let limit = RateLimit {
+            limit: 100,
+            window_seconds: 60,
+            burst: None,
+            scope: Some("user".to_string()),
+        };
+        op.set_rate_limit(&limit);
+        assert_eq!(op.extensions["x-ratelimit"], serde_json::json!({"limit": 100, "windowSeconds": 60, "scope": "user"}));
+        assert_eq!(op.rate_limit(), Some(limit));
+
+        op.responses.responses.insert(StatusCode::Code(200), Response::new("OK").into());
+        op.responses.responses.insert(StatusCode::Code(429), Response::new("Too many requests").into());
+        let mut api = OpenAPI::default();
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        api.add_rate_limit_headers(OperationFilter::all());
+
+        let op = api.operations().next().unwrap().2;
+        let ok = op.responses.responses[&StatusCode::Code(200)].as_item().unwrap();
+        assert_eq!(ok.headers.keys().collect::<Vec<_>>(), vec!["X-RateLimit-Limit", "X-RateLimit-Remaining", "X-RateLimit-Reset"]);
+        let rejected = op.responses.responses[&StatusCode::Code(429)].as_item().unwrap();
+        assert_eq!(rejected.headers["Retry-After"], RefOr::ref_("#/components/headers/Retry-After"));
+        assert_eq!(api.components.headers.len(), 4);
+    }
+}
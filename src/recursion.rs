@@ -0,0 +1,261 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::*;
+use crate::trace::Phase;
+use crate::util::{is_data_field, is_name_map_key};
+
+/// What replaces a recursive reference once [RecursionPolicy::limit] is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecursionPlaceholder {
+    /// `null` in examples; an unconstrained nullable schema when dereferencing.
+    #[default]
+    Null,
+    /// `{}` in examples; an empty object schema when dereferencing.
+    EmptyObject,
+    /// `{"$recursive": "<reference>"}` in examples; the reference is kept when dereferencing.
+    Recursive,
+}
+
+/// How utilities which follow references, such as example generation and [Schema::dereference],
+/// handle self-referential schemas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecursionPolicy {
+    /// How many times a reference is expanded again within its own expansion. With `0`, the
+    /// first recursive reference is replaced by the placeholder. References which aren't
+    /// recursive are always expanded.
+    pub limit: usize,
+    pub placeholder: RecursionPlaceholder,
+}
+
+impl Default for RecursionPolicy {
+    fn default() -> Self {
+        RecursionPolicy {
+            limit: 0,
+            placeholder: RecursionPlaceholder::Null,
+        }
+    }
+}
+
+impl RecursionPolicy {
+    /// The placeholder for a value of the recursive schema.
+    pub fn placeholder_value(&self, reference: &str) -> Value {
+        match self.placeholder {
+            RecursionPlaceholder::Null => Value::Null,
+            RecursionPlaceholder::EmptyObject => json!({}),
+            RecursionPlaceholder::Recursive => json!({"$recursive": reference}),
+        }
+    }
+
+    /// The placeholder for the recursive schema itself.
+    pub fn placeholder_schema(&self, reference: &str) -> RefOr<Schema> {
+        match self.placeholder {
+            RecursionPlaceholder::Null => {
+                let mut any = Schema::new_any();
+                any.nullable = true;
+                any.into()
+            }
+            RecursionPlaceholder::EmptyObject => Schema::new_object().into(),
+            RecursionPlaceholder::Recursive => RefOr::ref_(reference),
+        }
+    }
+
+    /// Returns true if `reference` may be expanded again, given the references being expanded.
+    pub(crate) fn may_expand(&self, reference: &str, stack: &[String]) -> bool {
+        stack.iter().filter(|r| *r == reference).count() <= self.limit
+    }
+}
+
+/// The subschemas a schema directly contains.
+pub(crate) fn subschemas_mut(schema: &mut Schema) -> Vec<&mut RefOr<Schema>> {
+    let mut out: Vec<&mut RefOr<Schema>> = Vec::new();
+    match &mut schema.kind {
+        SchemaKind::Type(Type::Object(o)) => {
+            out.extend(o.properties.values_mut());
+            if let Some(AdditionalProperties::Schema(s)) = &mut o.additional_properties {
+                out.push(s);
+            }
+        }
+        SchemaKind::Type(Type::Array(a)) => out.extend(a.items.as_deref_mut()),
+        SchemaKind::Type(_) => {}
+        SchemaKind::OneOf { one_of: items } | SchemaKind::AllOf { all_of: items } | SchemaKind::AnyOf { any_of: items } => out.extend(items.iter_mut()),
+        SchemaKind::Not { not } => out.push(not),
+        SchemaKind::Any(a) => {
+            out.extend(a.properties.values_mut());
+            if let Some(AdditionalProperties::Schema(s)) = &mut a.additional_properties {
+                out.push(s);
+            }
+            out.extend(a.items.as_deref_mut());
+            out.extend(a.one_of.iter_mut());
+            out.extend(a.all_of.iter_mut());
+            out.extend(a.any_of.iter_mut());
+            out.extend(a.not.as_deref_mut());
+        }
+    }
+    out
+}
+
+/// Returns the number of references replaced, by their schemas or placeholders.
+fn dereference(schema: &mut Schema, spec: &OpenAPI, policy: &RecursionPolicy, stack: &mut Vec<String>) -> Result<usize> {
+    let mut count = 0;
+    for child in subschemas_mut(schema) {
+        if let RefOr::Reference { reference } = child {
+            let reference = reference.clone();
+            count += 1;
+            if !policy.may_expand(&reference, stack) {
+                *child = policy.placeholder_schema(&reference);
+                continue;
+            }
+            let mut resolved = Resolve::resolve(&*child, spec)?.clone();
+            stack.push(reference);
+            count += dereference(&mut resolved, spec, policy, stack)?;
+            stack.pop();
+            *child = RefOr::Item(resolved);
+        } else if let RefOr::Item(item) = child {
+            count += dereference(item, spec, policy, stack)?;
+        }
+    }
+    Ok(count)
+}
+
+/// Merges `allOf` schemas into one with [Schema::intersect], innermost first.
+/// Returns the number of `allOf` schemas merged.
+fn flatten(schema: &mut Schema, spec: &OpenAPI) -> Result<usize> {
+    let mut count = 0;
+    for child in subschemas_mut(schema) {
+        if let RefOr::Item(item) = child {
+            count += flatten(item, spec)?;
+        }
+    }
+    if let SchemaKind::AllOf { all_of } = &schema.kind {
+        let mut parts = all_of.iter().filter_map(|s| s.as_item());
+        if all_of.iter().all(|s| s.as_item().is_some()) {
+            if let Some(first) = parts.next() {
+                let mut merged = first.clone();
+                for part in parts {
+                    merged = merged.intersect(part, spec)?;
+                }
+                // Keep documentation of the allOf schema itself.
+                merged.data.title = schema.title.clone().or(merged.data.title);
+                merged.data.description = schema.description.clone().or(merged.data.description);
+                *schema = merged;
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Removes documentation fields, which don't affect a schema's [Schema::fingerprint].
+/// Property names and values such as defaults are kept, even if they look like documentation fields.
+fn strip_docs(value: &mut Value, is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                for key in ["title", "description", "example", "externalDocs"].iter() {
+                    map.remove(*key);
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                if !is_name_map && is_data_field(key, child) {
+                    continue;
+                }
+                strip_docs(child, !is_name_map && is_name_map_key(key));
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| strip_docs(v, false)),
+        _ => {}
+    }
+}
+
+impl Schema {
+    /// Returns a copy of the schema with references replaced by the schemas they point to.
+    /// References within their own expansion are expanded up to the policy's limit, then
+    /// replaced by its placeholder. Fails if a reference doesn't resolve.
+    pub fn dereference(&self, spec: &OpenAPI, policy: &RecursionPolicy) -> Result<Schema> {
+        let phase = Phase::start("dereference");
+        let mut schema = self.clone();
+        let count = dereference(&mut schema, spec, policy, &mut Vec::new())?;
+        phase.finish(count);
+        Ok(schema)
+    }
+
+    /// Dereferences the schema, then merges each `allOf` into a single schema with [Schema::intersect].
+    /// An `allOf` containing a reference left by [RecursionPlaceholder::Recursive] is kept as is.
+    pub fn flatten(&self, spec: &OpenAPI, policy: &RecursionPolicy) -> Result<Schema> {
+        let mut schema = self.dereference(spec, policy)?;
+        let phase = Phase::start("flatten");
+        let count = flatten(&mut schema, spec)?;
+        phase.finish(count);
+        Ok(schema)
+    }
+
+    /// Returns a hex SHA-256 hash of the dereferenced schema's canonical JSON, ignoring titles,
+    /// descriptions and examples, so structurally equal schemas have equal fingerprints.
+    /// Recursion is cut off at the policy's limit, always with a [RecursionPlaceholder::Recursive] marker.
+    pub fn fingerprint(&self, spec: &OpenAPI, policy: &RecursionPolicy) -> Result<String> {
+        let policy = RecursionPolicy { placeholder: RecursionPlaceholder::Recursive, ..*policy };
+        let mut value = serde_json::to_value(self.dereference(spec, &policy)?)?;
+        strip_docs(&mut value, false);
+        let hash = Sha256::digest(value.to_string().as_bytes());
+        Ok(hash.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_spec() -> OpenAPI {
+        let mut spec = OpenAPI::default();
+        let mut node = Schema::new_object();
+        node.properties_mut().insert("name", Schema::new_string());
+        node.properties_mut().insert("children", Schema::new_array(RefOr::schema_ref("Node")));
+        spec.schemas.insert("Node", node);
+        spec
+    }
+
+    #[test]
+    fn test_recursive_schemas() {
+        let spec = tree_spec();
+        let node = RefOr::schema_ref("Node");
+        let policy = RecursionPolicy { limit: 0, placeholder: RecursionPlaceholder::Recursive };
+        assert_eq!(node.generate_example_with(&spec, &policy), json!({
+            "name": "string",
+            "children": [{"$recursive": "#/components/schemas/Node"}],
+        }));
+        let policy = RecursionPolicy { limit: 1, ..policy };
+        assert_eq!(node.generate_example_with(&spec, &policy), json!({
+            "name": "string",
+            "children": [{"name": "string", "children": [{"$recursive": "#/components/schemas/Node"}]}],
+        }));
+        assert_eq!(node.generate_example(&spec), json!({"name": "string", "children": [null]}));
+
+        let root = spec.schemas.get2("Node").unwrap();
+        let deep = root.dereference(&spec, &RecursionPolicy { limit: 0, placeholder: RecursionPlaceholder::EmptyObject }).unwrap();
+        assert_eq!(serde_json::to_value(&deep).unwrap()["properties"]["children"]["items"]["properties"]["children"]["items"],
+            json!({"type": "object"}));
+
+        let mut renamed = root.clone();
+        renamed.title = Some("Tree".to_string());
+        let policy = RecursionPolicy::default();
+        assert_eq!(renamed.fingerprint(&spec, &policy).unwrap(), root.fingerprint(&spec, &policy).unwrap());
+        assert_ne!(Schema::new_string().fingerprint(&spec, &policy).unwrap(), root.fingerprint(&spec, &policy).unwrap());
+
+        // A property named like a documentation field is part of the structure.
+        let mut a = Schema::new_object();
+        a.properties_mut().insert("title", Schema::new_string());
+        let mut b = Schema::new_object();
+        b.properties_mut().insert("title", Schema::new_integer());
+        assert_ne!(a.fingerprint(&spec, &policy).unwrap(), b.fingerprint(&spec, &policy).unwrap());
+
+        let mut spec = spec;
+        spec.schemas.insert("Named", Schema::new_object());
+        let mut all_of = Schema::new_all_of(vec![RefOr::schema_ref("Node"), RefOr::schema_ref("Named")]);
+        all_of.description = Some("A named node".to_string());
+        let flat = all_of.flatten(&spec, &policy).unwrap();
+        assert!(matches!(flat.kind, SchemaKind::Type(Type::Object(_))));
+        assert_eq!(flat.description.as_deref(), Some("A named node"));
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+use crate::*;
+use crate::util::{is_data_field, is_name_map_key};
+
+/// What [OpenAPI::redact] removes or masks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedactionPolicy {
+    /// Remove `example` and `examples` from schemas, parameters, headers and media types,
+    /// as well as `components.examples`.
+    pub examples: bool,
+    /// Remove `default` values from schemas.
+    pub defaults: bool,
+    /// Remove servers whose url contains any of these substrings, e.g. `.internal` or `localhost`,
+    /// and server variable values which would make it do so.
+    pub internal_server_patterns: Vec<String>,
+    /// Replace contact emails with this value, or remove them if it is empty.
+    pub contact_email: Option<String>,
+    /// Remove extensions with these keys (e.g. `x-internal-owner`) anywhere in the document.
+    /// A key ending in `*` matches by prefix.
+    pub extensions: Vec<String>,
+}
+
+impl RedactionPolicy {
+    /// A policy removing examples, defaults and contact emails.
+    pub fn strict() -> Self {
+        RedactionPolicy {
+            examples: true,
+            defaults: true,
+            contact_email: Some(String::new()),
+            ..RedactionPolicy::default()
+        }
+    }
+}
+
+impl OpenAPI {
+    /// Removes or masks data which shouldn't be shared outside the organization, according to the policy.
+    /// Fails, leaving the document unchanged, if the redacted document would be invalid.
+    pub fn redact(&mut self, policy: &RedactionPolicy) -> Result<()> {
+        // Servers, examples, defaults and extensions occur throughout the document,
+        // so they are redacted on the JSON form rather than by visiting every type.
+        let mut doc = serde_json::to_value(&*self)?;
+        redact_value(&mut doc, policy, false);
+        let mut redacted: OpenAPI = serde_json::from_value(doc)?;
+        if let (Some(contact), Some(email)) = (redacted.info.contact.as_mut(), policy.contact_email.as_ref()) {
+            if contact.email.is_some() {
+                contact.email = Some(email.clone()).filter(|e| !e.is_empty());
+            }
+        }
+        *self = redacted;
+        Ok(())
+    }
+}
+
+/// Substitutes `value` for the variable `name` in a server url, and the defaults for the others.
+fn expand_url(url: &str, variables: Option<&Map<String, Value>>, name: &str, value: &str) -> String {
+    let mut url = url.to_string();
+    for (var, definition) in variables.into_iter().flatten() {
+        let substitute = if var == name { Some(value) } else { definition.get("default").and_then(Value::as_str) };
+        if let Some(substitute) = substitute {
+            url = url.replace(&format!("{{{}}}", var), substitute);
+        }
+    }
+    url
+}
+
+/// Removes the server variable values which make a server internal. Returns whether to keep
+/// the server, which isn't the case if its default url is internal.
+fn redact_server(server: &mut Value, policy: &RedactionPolicy) -> bool {
+    let is_internal = |url: &str| policy.internal_server_patterns.iter().any(|p| url.contains(p.as_str()));
+    let Some(url) = server.get("url").and_then(Value::as_str).map(str::to_string) else { return true };
+    let variables = server.get("variables").and_then(Value::as_object).cloned();
+    if is_internal(&expand_url(&url, variables.as_ref(), "", "")) {
+        return false;
+    }
+    if let Some(Value::Object(definitions)) = server.get_mut("variables") {
+        for (name, definition) in definitions.iter_mut() {
+            if let Some(Value::Array(values)) = definition.get_mut("enum") {
+                values.retain(|v| !v.as_str().is_some_and(|v| is_internal(&expand_url(&url, variables.as_ref(), name, v))));
+            }
+        }
+    }
+    true
+}
+
+fn redact_value(value: &mut Value, policy: &RedactionPolicy, is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                if policy.examples {
+                    map.remove("example");
+                    map.remove("examples");
+                }
+                if policy.defaults {
+                    map.remove("default");
+                }
+                if let Some(Value::Array(servers)) = map.get_mut("servers") {
+                    servers.retain_mut(|s| redact_server(s, policy));
+                    if servers.is_empty() {
+                        map.remove("servers");
+                    }
+                }
+                if let Some(server) = map.get_mut("server") {
+                    if !redact_server(server, policy) {
+                        map.remove("server");
+                    }
+                }
+                map.retain(|key, _| !policy.extensions.iter().any(|e| match e.strip_suffix('*') {
+                    Some(prefix) => key.starts_with(prefix),
+                    None => key == e,
+                }));
+            }
+            for (key, child) in map.iter_mut() {
+                // Server variables are redacted with their server, and require their default value.
+                if !is_name_map && (is_data_field(key, child) || key == "variables") {
+                    continue;
+                }
+                redact_value(child, policy, !is_name_map && is_name_map_key(key));
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_value(item, policy, false);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0")
+            .with_server("https://api.example.com")
+            .with_server("https://api.internal.example.com")
+            .with_server("https://{env}.example.com");
+        let variable: ServerVariable = serde_json::from_value(json!({"default": "api", "enum": ["api", "staging.internal"]})).unwrap();
+        api.servers[2].variables = Some(IndexMap::from([("env".to_string(), variable)]));
+        api.info.contact = Some(Contact {
+            email: Some("team@example.com".to_string()),
+            ..Contact::default()
+        });
+        let mut schema = Schema::new_object();
+        let mut name = Schema::new_string();
+        name.example = Some(json!("Tom"));
+        name.default = Some(json!("Tom"));
+        name.extensions.insert("x-owner".to_string(), json!("pets-team"));
+        schema.properties_mut().insert("name", name);
+        schema.properties_mut().insert("example", Schema::new_string());
+        api.schemas.insert("Pet", schema);
+
+        let policy = RedactionPolicy {
+            internal_server_patterns: vec![".internal.".to_string()],
+            extensions: vec!["x-own*".to_string()],
+            ..RedactionPolicy::strict()
+        };
+        api.redact(&policy).unwrap();
+        assert_eq!(api.servers.len(), 2);
+        let env = &api.servers[1].variables.as_ref().unwrap()["env"];
+        assert_eq!(env.enumeration, vec!["api".to_string()]);
+        assert_eq!(env.default, "api");
+        assert_eq!(api.info.contact.as_ref().unwrap().email, None);
+        let pet = api.schemas.get2("Pet").unwrap();
+        assert!(pet.properties().contains_key("example"));
+        let name = pet.properties().get2("name").unwrap();
+        assert_eq!(name.example, None);
+        assert_eq!(name.default, None);
+        assert!(name.extensions.is_empty());
+
+        // Extension payloads are data, kept as they are unless the extension itself is removed.
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.extensions.insert("x-sample".to_string(), json!({"example": "kept", "x-owner": "kept"}));
+        api.redact(&policy).unwrap();
+        assert_eq!(api.extensions["x-sample"], json!({"example": "kept", "x-owner": "kept"}));
+    }
+}
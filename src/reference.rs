@@ -1,7 +1,12 @@
 use std::collections::HashSet;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use crate::{OpenAPI, Parameter, RequestBody, Response, Schema};
+use crate::{Callback, Example, Header, Link, OpenAPI, Parameter, RefOrMap, RequestBody, Response, Schema, SecurityScheme};
+use crate::util::{escape_pointer_segment, unescape_pointer_segment};
+
+mod resolver;
+
+pub use resolver::*;
 
 /// A structured enum of an OpenAPI reference.
 /// e.g. #/components/schemas/Account or #/components/schemas/Account/properties/name
@@ -16,23 +21,30 @@ pub enum SchemaReference {
 }
 
 impl SchemaReference {
+    /// Parses a reference to a component schema, or to a property of one.
+    ///
+    /// Panics for other references. See [SchemaReference::try_from_str] for a fallible version.
     pub fn from_str(reference: &str) -> Self {
-        let mut ns = reference.rsplit('/');
-        let name = ns.next().unwrap();
-        match ns.next().unwrap() {
-            "schemas" => {
-                Self::Schema {
-                    schema: name.to_string(),
-                }
-            }
-            "properties" => {
-                let schema_name = ns.next().unwrap();
-                Self::Property {
-                    schema: schema_name.to_string(),
-                    property: name.to_string(),
-                }
-            }
-            _ => panic!("Unknown reference: {}", reference),
+        match Self::try_from_str(reference) {
+            Ok(reference) => reference,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Parses a reference to a component schema, or to a property of one. Fails for other
+    /// references, including ones into other documents.
+    pub fn try_from_str(reference: &str) -> Result<Self> {
+        let path = reference.strip_prefix("#/components/schemas/")
+            .ok_or_else(|| anyhow!("Unsupported schema reference: {}", reference))?;
+        match path.split('/').collect::<Vec<_>>()[..] {
+            [schema] if !schema.is_empty() => Ok(Self::Schema {
+                schema: unescape_pointer_segment(schema),
+            }),
+            [schema, "properties", property] if !schema.is_empty() && !property.is_empty() => Ok(Self::Property {
+                schema: unescape_pointer_segment(schema),
+                property: unescape_pointer_segment(property),
+            }),
+            _ => Err(anyhow!("Unsupported schema reference: {}", reference)),
         }
     }
 }
@@ -41,8 +53,10 @@ impl SchemaReference {
 impl std::fmt::Display for SchemaReference {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SchemaReference::Schema { schema } => write!(f, "#/components/schemas/{}", schema),
-            SchemaReference::Property { schema, property } => write!(f, "#/components/schemas/{}/properties/{}", schema, property),
+            SchemaReference::Schema { schema } => write!(f, "#/components/schemas/{}", escape_pointer_segment(schema)),
+            SchemaReference::Property { schema, property } => {
+                write!(f, "#/components/schemas/{}/properties/{}", escape_pointer_segment(schema), escape_pointer_segment(property))
+            }
         }
     }
 }
@@ -139,48 +153,121 @@ impl<T> Ref<T> {
     pub fn to_mut(&mut self) -> &mut T {
         self.as_mut().expect("Not an item")
     }
+
+    /// Maps the item inside this [RefOr], leaving references untouched.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Ref<U> {
+        match self {
+            Ref::Reference { reference } => Ref::Reference { reference },
+            Ref::Item(i) => Ref::Item(f(i)),
+        }
+    }
+
+    /// Converts from `&RefOr<T>` to `RefOr<&T::Target>`.
+    pub fn as_deref(&self) -> Ref<&T::Target>
+    where
+        T: std::ops::Deref,
+    {
+        match self {
+            Ref::Reference { reference } => Ref::Reference { reference: reference.clone() },
+            Ref::Item(i) => Ref::Item(i.deref()),
+        }
+    }
+
+    /// Returns the item inside this [RefOr].
+    ///
+    /// Panics with the reference string if this was a [RefOr::Reference].
+    pub fn unwrap_item(self) -> T {
+        match self {
+            Ref::Reference { reference } => panic!("Called unwrap_item on a reference: {}", reference),
+            Ref::Item(i) => i,
+        }
+    }
+
+    /// Returns the item directly if this is a [RefOr::Item], otherwise resolves the reference against the spec.
+    pub fn item_or_resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a T>
+    where
+        Self: Resolve<Output=T>,
+    {
+        match self {
+            Ref::Item(i) => Ok(i),
+            Ref::Reference { .. } => Resolve::resolve(self, spec),
+        }
+    }
+
+    /// Returns the component name of the reference, stripping the `#/components/<kind>/` prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use openapiv3::*;
+    /// let r: RefOr<Schema> = RefOr::schema_ref("Pet");
+    /// assert_eq!(r.reference_name(), Some("Pet"));
+    /// ```
+    pub fn reference_name(&self) -> Option<&str> {
+        let reference = self.as_ref_str()?;
+        let rest = reference.strip_prefix("#/components/")?;
+        let mut parts = rest.splitn(2, '/');
+        let _kind = parts.next()?;
+        parts.next().filter(|name| !name.is_empty() && !name.contains('/'))
+    }
 }
 
-fn resolve_helper<'a>(reference: &str, spec: &'a OpenAPI, seen: &mut HashSet<String>) -> &'a Schema {
+fn resolve_helper<'a>(reference: &str, spec: &'a OpenAPI, seen: &mut HashSet<String>) -> Result<&'a Schema> {
     if seen.contains(reference) {
-        panic!("Circular reference: {}", reference);
+        return Err(anyhow!("Circular reference: {}", reference));
     }
     seen.insert(reference.to_string());
-    let reference = SchemaReference::from_str(&reference);
+    let reference = SchemaReference::try_from_str(reference)?;
     match &reference {
         SchemaReference::Schema { ref schema } => {
             let schema_ref = spec.schemas.get(schema)
-                .expect(&format!("Schema {} not found in OpenAPI spec.", schema));
+                .ok_or_else(|| anyhow!("Schema {} not found in OpenAPI spec.", schema))?;
             // In theory both this as_item and the one below could have continue to be references
             // but assum
             match schema_ref {
                 RefOr::Reference { reference } => {
-                    resolve_helper(&reference, spec, seen)
+                    resolve_helper(reference, spec, seen)
                 }
-                RefOr::Item(s) => s
+                RefOr::Item(s) => Ok(s)
             }
         }
         SchemaReference::Property { schema: schema_name, property } => {
             let schema = spec.schemas.get(schema_name)
-                .expect(&format!("Schema {} not found in OpenAPI spec.", schema_name))
+                .ok_or_else(|| anyhow!("Schema {} not found in OpenAPI spec.", schema_name))?
                 .as_item()
-                .expect(&format!("The schema {} was used in a reference, but that schema is itself a reference to another schema.", schema_name));
+                .ok_or_else(|| anyhow!("The schema {} was used in a reference, but that schema is itself a reference to another schema.", schema_name))?;
             let prop_schema = schema
-                .properties()
-                .get(property)
-                .expect(&format!("Schema {} does not have property {}.", schema_name, property));
-            prop_schema.resolve(spec)
+                .get_properties()
+                .and_then(|p| p.get(property))
+                .ok_or_else(|| anyhow!("Schema {} does not have property {}.", schema_name, property))?;
+            match prop_schema {
+                RefOr::Reference { reference } => resolve_helper(reference, spec, seen),
+                RefOr::Item(s) => Ok(s),
+            }
         }
     }
 }
 
 impl RefOr<Schema> {
+    /// Resolves this schema against the spec, following nested references.
+    ///
+    /// Panics if the reference cannot be resolved, e.g. if it dangles. See [Resolve::resolve] for
+    /// a fallible version, which the Option-returning accessors of this crate use.
+    #[track_caller]
     pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> &'a Schema {
+        match Resolve::resolve(self, spec) {
+            Ok(schema) => schema,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+/// Displays references by their component name, and items with their own [Display](std::fmt::Display).
+impl<T: std::fmt::Display> std::fmt::Display for RefOr<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            RefOr::Reference { reference } => {
-                resolve_helper(reference, spec, &mut HashSet::new())
-            }
-            RefOr::Item(schema) => schema,
+            RefOr::Reference { reference } => f.write_str(self.reference_name().unwrap_or(reference)),
+            RefOr::Item(item) => item.fmt(f),
         }
     }
 }
@@ -191,49 +278,97 @@ impl<T> From<T> for RefOr<T> {
     }
 }
 
-impl RefOr<Parameter> {
-    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Parameter> {
-        match self {
-            RefOr::Reference { reference } => {
-                let name = get_parameter_name(&reference)?;
-                spec.parameters.get(name)
-                    .ok_or(anyhow!("{} not found in OpenAPI spec.", reference))?
-                    .as_item()
-                    .ok_or(anyhow!("{} is circular.", reference))
-            }
-            RefOr::Item(parameter) => Ok(parameter),
+/// Resolves a [RefOr] to the item it points to, using the `components` of the given spec.
+///
+/// Implemented for every kind of [RefOr] that can point into `#/components`, so generic code
+/// can resolve any component uniformly:
+///
+/// ```
+/// # use openapiv3::*;
+/// fn resolve_all<'a, T>(items: &'a [RefOr<T>], spec: &'a OpenAPI) -> anyhow::Result<Vec<&'a T>>
+///     where RefOr<T>: Resolve<Output=T>
+/// {
+///     items.iter().map(|i| Resolve::resolve(i, spec)).collect()
+/// }
+/// ```
+pub trait Resolve {
+    type Output;
+
+    fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Self::Output>;
+}
+
+/// Looks up `reference` in the given components map, following references within the map.
+fn resolve_component<'a, T>(reference: &str, map: &'a RefOrMap<T>, get_name: fn(&str) -> Result<&str>) -> Result<&'a T> {
+    let mut seen = HashSet::new();
+    let mut reference = reference;
+    loop {
+        if !seen.insert(reference) {
+            return Err(anyhow!("{} is circular.", reference));
+        }
+        let name = get_name(reference)?;
+        match map.get(name).ok_or_else(|| anyhow!("{} not found in OpenAPI spec.", reference))? {
+            RefOr::Reference { reference: next } => reference = next,
+            RefOr::Item(item) => return Ok(item),
         }
     }
 }
 
+impl Resolve for RefOr<Schema> {
+    type Output = Schema;
 
-impl RefOr<Response> {
-    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Response> {
+    fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Schema> {
         match self {
-            RefOr::Reference { reference } => {
-                let name = get_response_name(&reference)?;
-                spec.responses.get(name)
-                    .ok_or(anyhow!("{} not found in OpenAPI spec.", reference))?
-                    .as_item()
-                    .ok_or(anyhow!("{} is circular.", reference))
-            }
-            RefOr::Item(response) => Ok(response),
+            RefOr::Reference { reference } => resolve_helper(reference, spec, &mut HashSet::new()),
+            RefOr::Item(schema) => Ok(schema),
         }
     }
 }
 
-impl Ref<RequestBody> {
-    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a RequestBody> {
-        match self {
-            Ref::Reference { reference } => {
-                let name = get_request_body_name(&reference)?;
-                spec.request_bodies.get(name)
-                    .ok_or(anyhow!("{} not found in OpenAPI spec.", reference))?
-                    .as_item()
-                    .ok_or(anyhow!("{} is circular.", reference))
+macro_rules! impl_resolve {
+    ($ty:ty, $field:ident, $get_name:ident) => {
+        impl Resolve for RefOr<$ty> {
+            type Output = $ty;
+
+            fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a $ty> {
+                match self {
+                    RefOr::Reference { reference } => resolve_component(reference, &spec.components.$field, $get_name),
+                    RefOr::Item(item) => Ok(item),
+                }
             }
-            Ref::Item(request_body) => Ok(request_body),
         }
+    };
+}
+
+impl_resolve!(Parameter, parameters, get_parameter_name);
+impl_resolve!(Response, responses, get_response_name);
+impl_resolve!(RequestBody, request_bodies, get_request_body_name);
+impl_resolve!(Header, headers, get_header_name);
+impl_resolve!(Example, examples, get_example_name);
+impl_resolve!(Link, links, get_link_name);
+impl_resolve!(Callback, callbacks, get_callback_name);
+impl_resolve!(SecurityScheme, security_schemes, get_security_scheme_name);
+
+impl RefOr<Parameter> {
+    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Parameter> {
+        Resolve::resolve(self, spec)
+    }
+}
+
+impl RefOr<Response> {
+    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Response> {
+        Resolve::resolve(self, spec)
+    }
+}
+
+impl RefOr<RequestBody> {
+    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a RequestBody> {
+        Resolve::resolve(self, spec)
+    }
+}
+
+impl RefOr<Example> {
+    pub fn resolve<'a>(&'a self, spec: &'a OpenAPI) -> Result<&'a Example> {
+        Resolve::resolve(self, spec)
     }
 }
 
@@ -251,17 +386,36 @@ fn parse_reference<'a>(reference: &'a str, group: &str) -> Result<&'a str> {
 }
 
 
+fn get_request_body_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "requestBodies")
+}
+
+fn get_parameter_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "parameters")
+}
+
 fn get_response_name(reference: &str) -> Result<&str> {
     parse_reference(reference, "responses")
 }
 
+fn get_header_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "headers")
+}
 
-fn get_request_body_name(reference: &str) -> Result<&str> {
-    parse_reference(reference, "requestBodies")
+fn get_example_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "examples")
 }
 
-fn get_parameter_name(reference: &str) -> Result<&str> {
-    parse_reference(reference, "parameters")
+fn get_link_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "links")
+}
+
+fn get_callback_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "callbacks")
+}
+
+fn get_security_scheme_name(reference: &str) -> Result<&str> {
+    parse_reference(reference, "securitySchemes")
 }
 
 #[cfg(test)]
@@ -273,4 +427,46 @@ mod tests {
         assert!(matches!(get_request_body_name("#/components/requestBodies/Foo"), Ok("Foo")));
         assert!(get_request_body_name("#/components/schemas/Foo").is_err());
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_resolve_trait() {
+        let mut spec = OpenAPI::default();
+        spec.headers.insert("RateLimit", Header::new(Schema::new_integer()));
+        spec.headers.insert("Alias", RefOr::ref_("#/components/headers/RateLimit"));
+        spec.headers.insert("Loop", RefOr::ref_("#/components/headers/Loop"));
+
+        let header: RefOr<Header> = RefOr::ref_("#/components/headers/Alias");
+        assert!(Resolve::resolve(&header, &spec).is_ok());
+        let header: RefOr<Header> = RefOr::ref_("#/components/headers/Loop");
+        assert!(Resolve::resolve(&header, &spec).is_err());
+        let header: RefOr<Header> = RefOr::ref_("#/components/headers/Missing");
+        assert!(Resolve::resolve(&header, &spec).is_err());
+        let schema: RefOr<Schema> = RefOr::schema_ref("Missing");
+        assert!(Resolve::resolve(&schema, &spec).is_err());
+        let schema: RefOr<Schema> = RefOr::ref_("./pet.yaml#/Pet");
+        assert!(Resolve::resolve(&schema, &spec).is_err());
+        let schema: RefOr<Schema> = RefOr::ref_("#/components/headers/RateLimit");
+        assert!(Resolve::resolve(&schema, &spec).is_err());
+        assert!(SchemaReference::try_from_str("./pet.yaml#/Pet").is_err());
+        assert_eq!(SchemaReference::from_str("#/components/schemas/Pet/properties/name").to_string(), "#/components/schemas/Pet/properties/name");
+    }
+
+    #[test]
+    fn test_combinators() {
+        let r: RefOr<u8> = RefOr::Item(1);
+        assert_eq!(r.map(|i| i + 1), RefOr::Item(2));
+        let r: RefOr<u8> = RefOr::ref_("#/components/schemas/Foo");
+        assert_eq!(r.reference_name(), Some("Foo"));
+        assert_eq!(r.map(|i| i + 1).as_ref_str(), Some("#/components/schemas/Foo"));
+        let r: RefOr<u8> = RefOr::ref_("#/components/schemas/Foo/properties/bar");
+        assert_eq!(r.reference_name(), None);
+        let r: RefOr<String> = RefOr::Item("a".to_string());
+        assert_eq!(r.as_deref(), RefOr::Item("a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Called unwrap_item on a reference: #/components/schemas/Foo")]
+    fn test_unwrap_item_panics() {
+        let r: RefOr<u8> = RefOr::schema_ref("Foo");
+        r.unwrap_item();
+    }
+}
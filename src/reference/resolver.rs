@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{percent_decode, to_json};
+
+type Fetcher = Box<dyn Fn(&str) -> Result<String> + Send + Sync>;
+
+/// Resolves references into other documents, such as `./schemas/pet.yaml#/Pet` or
+/// `https://example.com/common.json#/Error`, which [Resolve] doesn't follow.
+///
+/// Documents are located relative to the document containing the reference, and each one is
+/// loaded once and cached. Files are read from disk. `http(s)` URLs are loaded with the fetcher
+/// passed to [ExternalResolver::with_fetcher], so the caller chooses the HTTP client. Documents
+/// loaded from a URL can only reference other URLs, so a remote document can't read local files.
+pub struct ExternalResolver {
+    base: String,
+    documents: HashMap<String, Value>,
+    fetch: Option<Fetcher>,
+}
+
+impl fmt::Debug for ExternalResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternalResolver")
+            .field("base", &self.base)
+            .field("documents", &self.documents.keys().collect::<Vec<_>>())
+            .field("fetch", &self.fetch.is_some())
+            .finish()
+    }
+}
+
+impl ExternalResolver {
+    /// Creates a resolver for the root document at `base`, a file path or a URL. A base ending
+    /// in `/` is a directory. Relative file paths are taken from the current directory.
+    pub fn new(base: impl Into<String>) -> Self {
+        let mut base = base.into();
+        if !is_url(&base) && !is_absolute_path(&base) {
+            if let Ok(dir) = std::env::current_dir() {
+                base = format!("{}/{}", dir.display(), base.trim_start_matches("./"));
+            }
+        }
+        ExternalResolver {
+            base,
+            documents: HashMap::new(),
+            fetch: None,
+        }
+    }
+
+    /// Creates a resolver with `spec` as its root document, located at `base`, the URL the
+    /// document was retrieved from, or in the current directory if that isn't known.
+    pub fn for_spec(spec: &OpenAPI, base: Option<&http::Uri>) -> Self {
+        let base = match base {
+            Some(url) => url.to_string(),
+            None => "./".to_string(),
+        };
+        let mut resolver = ExternalResolver::new(base);
+        let root = to_json(spec);
+        let base = resolver.base.clone();
+        resolver.insert_document(&base, root);
+        resolver
+    }
+
+    /// Caches `document` as the content of `location`, replacing what was loaded before.
+    pub(crate) fn insert_document(&mut self, location: &str, document: Value) {
+        self.documents.insert(location.to_string(), document);
+    }
+
+    /// Sets the function which loads `http` and `https` URLs, returning the body.
+    pub fn with_fetcher(mut self, fetch: impl Fn(&str) -> Result<String> + Send + Sync + 'static) -> Self {
+        self.fetch = Some(Box::new(fetch));
+        self
+    }
+
+    /// The location of the root document.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Returns the absolute form of `reference`, a document location followed by a `#` fragment,
+    /// relative to the document at `from`.
+    pub fn absolute(reference: &str, from: &str) -> String {
+        absolute(reference, from)
+    }
+
+    /// Returns the parsed YAML or JSON document at `location`, loading it if it isn't cached yet.
+    pub fn document(&mut self, location: &str) -> Result<&Value> {
+        if !self.documents.contains_key(location) {
+            let text = if location.starts_with("http://") || location.starts_with("https://") {
+                let fetch = self.fetch.as_ref()
+                    .ok_or_else(|| anyhow!("Cannot load {} without a fetcher.", location))?;
+                fetch(location)?
+            } else {
+                let path = location.strip_prefix("file://").unwrap_or(location);
+                std::fs::read_to_string(path).map_err(|e| anyhow!("Cannot read {}: {}", path, e))?
+            };
+            let document: Value = crate::util::from_yaml_or_json(&text)
+                .map_err(|e| anyhow!("Cannot parse {}: {}", location, e))?;
+            self.documents.insert(location.to_string(), document);
+        }
+        Ok(&self.documents[location])
+    }
+
+    /// Looks up `reference` relative to the document at `from`, following further references
+    /// until a value which isn't one. Returns the absolute reference of that value, which nested
+    /// references inside it are relative to, along with the value.
+    pub fn lookup(&mut self, reference: &str, from: &str) -> Result<(String, Value)> {
+        let mut seen = HashSet::new();
+        let mut current = absolute(reference, from);
+        check_access(from, &current)?;
+        loop {
+            if !seen.insert(current.clone()) {
+                return Err(anyhow!("{} is circular.", current));
+            }
+            let (location, fragment) = current.split_once('#').unwrap_or((&current, ""));
+            let pointer = percent_decode(fragment, false);
+            let document = self.document(location)?;
+            let value = if pointer.is_empty() {
+                document
+            } else {
+                document.pointer(&pointer).ok_or_else(|| anyhow!("{} not found.", current))?
+            };
+            match value.get("$ref").and_then(Value::as_str) {
+                Some(next) => {
+                    let next = absolute(next, location);
+                    check_access(location, &next)?;
+                    current = next;
+                }
+                None => return Ok((current, value.clone())),
+            }
+        }
+    }
+
+    /// Returns the item, or resolves the reference relative to the root document. References
+    /// nested in the result are left as written; see [ExternalResolver::lookup] for their base.
+    pub fn resolve<T: Clone + DeserializeOwned>(&mut self, item: &RefOr<T>) -> Result<T> {
+        match item {
+            RefOr::Item(item) => Ok(item.clone()),
+            RefOr::Reference { reference } => {
+                let base = self.base.clone();
+                let (target, value) = self.lookup(reference, &base)?;
+                serde_json::from_value(value).map_err(|e| anyhow!("{} is invalid: {}", target, e))
+            }
+        }
+    }
+}
+
+fn absolute(reference: &str, from: &str) -> String {
+    let (document, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+    let document = if document.is_empty() {
+        from.split('#').next().unwrap_or(from).to_string()
+    } else {
+        join(from, document)
+    };
+    format!("{}#{}", document, fragment)
+}
+
+fn is_url(location: &str) -> bool {
+    location.contains("://")
+}
+
+fn is_remote(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Whether a file path is absolute, on Unix or on Windows, e.g. `C:\specs` or `\\server\share`.
+fn is_absolute_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let has_drive = bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && matches!(bytes[2], b'/' | b'\\');
+    path.starts_with('/') || path.starts_with('\\') || has_drive
+}
+
+/// Refuses references from a remote document to anything but other remote documents.
+fn check_access(from: &str, to: &str) -> Result<()> {
+    let from = from.split('#').next().unwrap_or(from);
+    let to = to.split('#').next().unwrap_or(to);
+    if is_remote(from) && !is_remote(to) {
+        return Err(anyhow!("{} may not reference the local file {}.", from, to));
+    }
+    Ok(())
+}
+
+/// Resolves the relative location `relative` against `base`, normalizing `.` and `..` segments.
+fn join(base: &str, relative: &str) -> String {
+    if is_url(relative) {
+        return relative.to_string();
+    }
+    let directory = |path: &str| path[..path.rfind('/').map_or(0, |i| i + 1)].to_string();
+    if let Some(i) = base.find("://") {
+        // The part of the base which isn't a path, such as `https://example.com`.
+        let origin = &base[..base[i + 3..].find('/').map_or(base.len(), |j| i + 3 + j)];
+        let path = if relative.starts_with('/') {
+            relative.to_string()
+        } else {
+            directory(&base[origin.len()..]) + relative
+        };
+        return format!("{}{}", origin, normalize(&path));
+    }
+    // Windows separators are made uniform, since either can be used in a file reference.
+    let (base, relative) = (base.replace('\\', "/"), relative.replace('\\', "/"));
+    if is_absolute_path(&relative) {
+        normalize(&relative)
+    } else {
+        normalize(&(directory(&base) + &relative))
+    }
+}
+
+/// Removes `.` segments, and `..` segments along with the segment before them.
+fn normalize(joined: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "." => {}
+            ".." => {
+                if segments.len() > 1 {
+                    segments.pop();
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        assert_eq!(join("/specs/api.yaml", "./schemas/pet.yaml"), "/specs/schemas/pet.yaml");
+        assert_eq!(join("/specs/schemas/pet.yaml", "../common.yaml"), "/specs/common.yaml");
+        assert_eq!(join("/specs/", "pet.yaml"), "/specs/pet.yaml");
+        assert_eq!(join("https://example.com/v1/api.yaml", "common.yaml"), "https://example.com/v1/common.yaml");
+        assert_eq!(join("https://example.com/v1/api.yaml", "/shared/common.yaml"), "https://example.com/shared/common.yaml");
+        assert_eq!(join("/specs/api.yaml", "https://example.com/a.yaml"), "https://example.com/a.yaml");
+        assert_eq!(join("C:\\specs\\api.yaml", "schemas\\pet.yaml"), "C:/specs/schemas/pet.yaml");
+        assert_eq!(join("C:\\specs\\api.yaml", "D:\\common.yaml"), "D:/common.yaml");
+        assert_eq!(join("/specs/api.yaml", "/other/common.yaml"), "/other/common.yaml");
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_external_resolver() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let dir = std::env::temp_dir().join(format!("openapiv3-resolver-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("schemas")).unwrap();
+        std::fs::write(dir.join("schemas/pet.yaml"), r##"
+Pet:
+  $ref: "#/Animal"
+Animal:
+  type: object
+  properties:
+    owner:
+      $ref: "../common.yaml#/Owner"
+"##).unwrap();
+        std::fs::write(dir.join("common.yaml"), "Owner:\n  $ref: https://example.com/owner.json\n").unwrap();
+
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let mut resolver = ExternalResolver::new(format!("{}/openapi.yaml", dir.display()))
+            .with_fetcher(move |url| {
+                assert_eq!(url, "https://example.com/owner.json");
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(r#"{"type": "string"}"#.to_string())
+            });
+
+        let pet: RefOr<Schema> = RefOr::ref_("./schemas/pet.yaml#/Pet");
+        let schema = resolver.resolve(&pet).unwrap();
+        let owner = schema.properties().get("owner").unwrap();
+        assert_eq!(owner.as_ref_str(), Some("../common.yaml#/Owner"));
+
+        let from = format!("{}/schemas/pet.yaml", dir.display());
+        let (target, value) = resolver.lookup("../common.yaml#/Owner", &from).unwrap();
+        assert_eq!(target, "https://example.com/owner.json#");
+        assert_eq!(value, serde_json::json!({"type": "string"}));
+        resolver.lookup("../common.yaml#/Owner", &from).unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        assert!(resolver.lookup("./schemas/pet.yaml#/Missing", &from).is_err());
+        assert!(resolver.lookup("./missing.yaml#/Pet", &from).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolver_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ExternalResolver>();
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_remote_documents_cannot_read_files() {
+        let mut resolver = ExternalResolver::new("https://example.com/api.yaml").with_fetcher(|url| Ok(match url {
+            "https://example.com/api.yaml" => "Secret: {$ref: 'file:///etc/passwd#/x'}\nWindows: {$ref: 'C:/secrets.yaml#/x'}\nRelative: {$ref: 'pet.yaml#/Pet'}\n",
+            _ => "Pet: {type: object}\n",
+        }.to_string()));
+        let base = resolver.base().to_string();
+        let error = resolver.lookup("#/Secret", &base).unwrap_err().to_string();
+        assert!(error.contains("may not reference the local file file:///etc/passwd"), "{}", error);
+        assert!(resolver.lookup("#/Windows", &base).is_err());
+        assert!(resolver.lookup("/etc/passwd#/x", &base).unwrap_err().to_string().contains("https://example.com/etc/passwd"));
+        let (target, _) = resolver.lookup("#/Relative", &base).unwrap();
+        assert_eq!(target, "https://example.com/pet.yaml#/Pet");
+    }
+}
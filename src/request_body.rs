@@ -24,3 +24,109 @@ pub struct RequestBody {
     #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
     pub extensions: IndexMap<String, serde_json::Value>,
 }
+
+impl RequestBody {
+    fn new_content(mime: &str, media_type: MediaType) -> Self {
+        let mut content = IndexMap::new();
+        content.insert(mime.to_string(), media_type);
+        RequestBody {
+            content,
+            ..RequestBody::default()
+        }
+    }
+
+    /// Create a request body with `application/json` content.
+    pub fn json(schema: impl Into<RefOr<Schema>>) -> Self {
+        Self::new_content("application/json", MediaType {
+            schema: Some(schema.into()),
+            ..MediaType::default()
+        })
+    }
+
+    /// Create a request body with `application/x-www-form-urlencoded` content.
+    pub fn form(schema: impl Into<RefOr<Schema>>) -> Self {
+        Self::new_content("application/x-www-form-urlencoded", MediaType {
+            schema: Some(schema.into()),
+            ..MediaType::default()
+        })
+    }
+
+    /// Create a request body with `application/x-ndjson` content, with `item_schema` describing each line.
+    pub fn ndjson(item_schema: impl Into<RefOr<Schema>>) -> Self {
+        Self::new_content(NDJSON, MediaType::ndjson(item_schema))
+    }
+
+    /// Create a required request body whose content is a raw file of the given media type,
+    /// e.g. `image/png` or `application/octet-stream`.
+    pub fn file_upload(mime: &str) -> Self {
+        RequestBody {
+            required: true,
+            ..Self::new_content(mime, MediaType {
+                schema: Some(Schema::new_binary_upload(mime).into()),
+                ..MediaType::default()
+            })
+        }
+    }
+
+    /// Create a request body with `multipart/form-data` content, using the given per-property encodings.
+    pub fn multipart(schema: impl Into<RefOr<Schema>>, encoding: IndexMap<String, Encoding>) -> Self {
+        Self::new_content("multipart/form-data", MediaType {
+            schema: Some(schema.into()),
+            encoding,
+            ..MediaType::default()
+        })
+    }
+
+    /// Adds (or replaces) the content for the given mime type.
+    pub fn content(mut self, mime: impl Into<String>, media_type: MediaType) -> Self {
+        self.content.insert(mime.into(), media_type);
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Returns the schema for the given mime type.
+    ///
+    /// Wildcard content keys are matched, with the most specific key taking precedence,
+    /// e.g. `text/plain` overrides `text/*`, which overrides `*/*`.
+    pub fn schema_for(&self, mime: &str) -> Option<&RefOr<Schema>> {
+        find_media_type(&self.content, mime)
+            .and_then(|(_, media)| media.schema.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MediaType, RequestBody, Schema};
+
+    #[test]
+    fn test_schema_for_wildcards() {
+        let body = RequestBody::json(Schema::new_object())
+            .content("text/*", MediaType {
+                schema: Some(Schema::new_string().into()),
+                ..MediaType::default()
+            })
+            .required();
+        assert!(body.required);
+        assert!(body.schema_for("application/json; charset=utf-8").unwrap().as_item().unwrap().get_properties().is_some());
+        assert!(body.schema_for("text/plain").is_some());
+        assert!(body.schema_for("image/png").is_none());
+    }
+
+    #[test]
+    fn test_file_upload() {
+        let body = RequestBody::file_upload("image/png");
+        assert!(body.required);
+        let schema = body.schema_for("image/png").unwrap().as_item().unwrap();
+        assert!(schema.is_binary());
+        assert_eq!(schema.extensions["x-content-type"], "image/png");
+    }
+}
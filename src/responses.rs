@@ -32,6 +32,15 @@ pub struct Responses {
     pub extensions: IndexMap<String, serde_json::Value>,
 }
 
+impl Responses {
+    /// Returns the response for a status code: an exact match, else its range (e.g. `4XX`), else the default.
+    pub fn for_status(&self, status: u16) -> Option<&RefOr<Response>> {
+        self.responses.get(&StatusCode::Code(status))
+            .or_else(|| self.responses.get(&StatusCode::Range(status / 100)))
+            .or(self.default.as_ref())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Response {
     /// REQUIRED. A short description of the response.
@@ -64,6 +73,56 @@ pub struct Response {
     pub extensions: IndexMap<String, serde_json::Value>,
 }
 
+impl Response {
+    pub fn new(description: impl Into<String>) -> Self {
+        Response {
+            description: description.into(),
+            ..Response::default()
+        }
+    }
+
+    /// Sets the `application/json` content of this response to the given schema.
+    pub fn json(mut self, schema: impl Into<RefOr<Schema>>) -> Self {
+        self.content.insert("application/json".to_string(), MediaType {
+            schema: Some(schema.into()),
+            ..MediaType::default()
+        });
+        self
+    }
+
+    /// Adds a response header with the given schema.
+    pub fn header(mut self, name: impl Into<String>, schema: impl Into<RefOr<Schema>>) -> Self {
+        self.headers.insert(name.into(), RefOr::Item(Header::new(schema)));
+        self
+    }
+
+    /// Sets the example on every media type of this response.
+    /// Call this after the content has been set, e.g. after [Response::json].
+    pub fn example(mut self, example: serde_json::Value) -> Self {
+        for media in self.content.values_mut() {
+            media.example = Some(example.clone());
+        }
+        self
+    }
+
+    /// Returns the schema of the `application/json` content, if it exists, or else of the first
+    /// `+json` content such as `application/problem+json`.
+    pub fn json_schema(&self) -> Option<&RefOr<Schema>> {
+        self.content.get("application/json")
+            .or_else(|| self.content.iter().find(|(k, _)| k.ends_with("+json")).map(|(_, v)| v))
+            .and_then(|media| media.schema.as_ref())
+    }
+
+    /// Returns the declared header with the given name, which is matched case-insensitively.
+    /// Accepts `&str` or [http::HeaderName].
+    pub fn get_header(&self, name: impl AsRef<str>) -> Option<&RefOr<Header>> {
+        let name = name.as_ref();
+        self.headers.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, h)| h)
+    }
+}
+
 fn deserialize_responses<'de, D>(
     deserializer: D,
 ) -> Result<IndexMap<StatusCode, RefOr<Response>>, D::Error>
@@ -79,7 +138,7 @@ where
 mod tests {
     use serde_json::json;
 
-    use crate::{RefOr, Response, Responses, StatusCode};
+    use crate::{RefOr, Response, Responses, Schema, StatusCode};
 
     #[test]
     fn test_responses() {
@@ -103,4 +162,16 @@ mod tests {
         );
         assert_eq!(responses.extensions.get("x-foo"), Some(&json!("bar")));
     }
+
+    #[test]
+    fn test_response_builder() {
+        let response = Response::new("A list of pets")
+            .json(Schema::new_array(RefOr::schema_ref("Pet")))
+            .header("X-Rate-Limit", Schema::new_integer())
+            .example(json!([]));
+        assert_eq!(response.description, "A list of pets");
+        assert!(response.headers.contains_key("X-Rate-Limit"));
+        assert!(response.json_schema().is_some());
+        assert_eq!(response.content["application/json"].example, Some(json!([])));
+    }
 }
@@ -0,0 +1,186 @@
+//! Checks that documents survive parsing and re-serialization unchanged.
+//!
+//! ```
+//! use openapiv3::roundtrip::{self, Format};
+//! let report = roundtrip::check(r#"{"openapi": "3.0.0", "info": {"title": "t", "version": "1"}, "paths": {}}"#, Format::Json);
+//! assert!(report.is_clean(), "{}", report);
+//! ```
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::util::escape_pointer_segment;
+use crate::OpenAPI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    Yaml,
+}
+
+impl Format {
+    fn parse<T: serde::de::DeserializeOwned>(self, input: &str) -> Result<T, String> {
+        match self {
+            Format::Json => serde_json::from_str(input).map_err(|e| e.to_string()),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => serde_yaml::from_str(input).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn serialize(self, spec: &OpenAPI) -> Result<String, String> {
+        match self {
+            Format::Json => serde_json::to_string_pretty(spec).map_err(|e| e.to_string()),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => serde_yaml::to_string(spec).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// The outcome of [check].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundtripReport {
+    /// Set if the input, or the re-serialized output, could not be parsed.
+    pub error: Option<String>,
+    /// Values which were lost, added or changed by parsing and re-serializing the input.
+    pub structural_differences: Vec<String>,
+    /// Values which changed when the re-serialized output was parsed and serialized again.
+    /// Any entry here means serialization is not idempotent.
+    pub unstable: Vec<String>,
+    /// Lines of the re-serialized output which differ from the input, as `line: input => output`.
+    /// Formatting changes such as indentation and key order are expected here.
+    pub textual_differences: Vec<String>,
+}
+
+impl RoundtripReport {
+    /// Whether the document round-trips without error or structural change.
+    /// Textual differences are not considered.
+    pub fn is_clean(&self) -> bool {
+        self.error.is_none() && self.structural_differences.is_empty() && self.unstable.is_empty()
+    }
+}
+
+impl fmt::Display for RoundtripReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(error) = &self.error {
+            return write!(f, "error: {}", error);
+        }
+        if self.is_clean() {
+            return write!(f, "round-trip clean ({} lines differ textually)", self.textual_differences.len());
+        }
+        for d in &self.structural_differences {
+            writeln!(f, "changed: {}", d)?;
+        }
+        for d in &self.unstable {
+            writeln!(f, "unstable: {}", d)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the differences between two JSON values, one line per differing pointer.
+pub fn json_differences(expected: &Value, actual: &Value) -> Vec<String> {
+    fn walk(pointer: &mut String, expected: &Value, actual: &Value, out: &mut Vec<String>) {
+        match (expected, actual) {
+            (Value::Object(a), Value::Object(b)) => {
+                for (key, value) in a {
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&escape_pointer_segment(key));
+                    match b.get(key) {
+                        Some(other) => walk(pointer, value, other, out),
+                        None => out.push(format!("{}: missing, expected {}", pointer, value)),
+                    }
+                    pointer.truncate(len);
+                }
+                for (key, value) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                    out.push(format!("{}/{}: unexpected {}", pointer, escape_pointer_segment(key), value));
+                }
+            }
+            (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+                for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                    let len = pointer.len();
+                    pointer.push_str(&format!("/{}", i));
+                    walk(pointer, x, y, out);
+                    pointer.truncate(len);
+                }
+            }
+            _ if expected != actual => out.push(format!("{}: expected {}, found {}", pointer, expected, actual)),
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(&mut "#".to_string(), expected, actual, &mut out);
+    out
+}
+
+fn text_differences(input: &str, output: &str) -> Vec<String> {
+    let mut input = input.trim().lines();
+    let mut output = output.trim().lines();
+    let mut out = Vec::new();
+    let mut line = 1;
+    loop {
+        match (input.next(), output.next()) {
+            (None, None) => break,
+            (a, b) if a.map(str::trim_end) != b.map(str::trim_end) => {
+                out.push(format!("{}: {} => {}", line, a.unwrap_or("<end>"), b.unwrap_or("<end>")));
+            }
+            _ => {}
+        }
+        line += 1;
+    }
+    out
+}
+
+/// Parses `input`, re-serializes it in the same format, parses the output again, and reports
+/// every difference along the way.
+pub fn check(input: &str, format: Format) -> RoundtripReport {
+    let mut report = RoundtripReport::default();
+    let result = (|| {
+        let original: Value = format.parse(input)?;
+        let spec: OpenAPI = format.parse(input)?;
+        let first = serde_json::to_value(&spec).map_err(|e| e.to_string())?;
+        let text = format.serialize(&spec)?;
+        let reparsed: OpenAPI = format.parse(&text)
+            .map_err(|e| format!("re-serialized output does not parse: {}", e))?;
+        let second = serde_json::to_value(&reparsed).map_err(|e| e.to_string())?;
+        report.structural_differences = json_differences(&original, &first);
+        report.unstable = json_differences(&first, &second);
+        report.textual_differences = text_differences(input, &text);
+        Ok::<_, String>(())
+    })();
+    report.error = result.err();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_check() {
+        let yaml = "\
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths: {}
+x-unknown-root: true
+";
+        let report = check(yaml, Format::Yaml);
+        assert!(report.is_clean(), "{}", report);
+        assert!(report.textual_differences.is_empty(), "{:?}", report.textual_differences);
+
+        let report = check(&yaml.replace("paths: {}", "paths: {}\nunknownField: 1"), Format::Yaml);
+        assert_eq!(report.structural_differences, vec!["#/unknownField: missing, expected 1"]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_check_error() {
+        let report = check("{", Format::Json);
+        assert!(report.error.is_some());
+    }
+}
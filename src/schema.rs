@@ -108,6 +108,26 @@ impl Schema {
         Self::new_kind(SchemaKind::Type(Type::String(StringType::default())))
     }
 
+    /// Create a `format: binary` string schema for raw file contents. The media type of the
+    /// file is recorded in an `x-content-type` extension.
+    pub fn new_binary_upload(content_type: &str) -> Self {
+        let mut schema = Self::new_kind(SchemaKind::Type(Type::String(StringType {
+            format: VariantOrUnknownOrEmpty::Item(StringFormat::Binary),
+            ..StringType::default()
+        })));
+        schema.extensions.insert("x-content-type".to_string(), content_type.into());
+        schema
+    }
+
+    /// Returns true for strings holding file contents, i.e. with `format: binary` or `format: byte`.
+    pub fn is_binary(&self) -> bool {
+        match &self.kind {
+            SchemaKind::Type(Type::String(s)) => matches!(s.format, VariantOrUnknownOrEmpty::Item(StringFormat::Binary | StringFormat::Byte)),
+            SchemaKind::Any(a) => matches!(a.format.as_deref(), Some("binary" | "byte")),
+            _ => false,
+        }
+    }
+
     /// Create a schemaless object schema
     pub fn new_object() -> Self {
         Self::new_kind(SchemaKind::Type(Type::Object(ObjectType::default())))
@@ -115,9 +135,8 @@ impl Schema {
 
     /// Create a Map<String, inner> schema
     pub fn new_map(inner: impl Into<RefOr<Schema>>) -> Self {
-        let inner = inner.into().boxed();
         Self::new_kind(SchemaKind::Type(Type::Object(ObjectType {
-            additional_properties: Some(AdditionalProperties::Schema(inner)),
+            additional_properties: Some(inner.into().into()),
             ..ObjectType::default()
         })))
     }
@@ -125,7 +144,7 @@ impl Schema {
     /// Create a Map<String, Any> schema
     pub fn new_map_any() -> Self {
         Self::new_kind(SchemaKind::Type(Type::Object(ObjectType {
-            additional_properties: Some(AdditionalProperties::Any(true)),
+            additional_properties: Some(true.into()),
             ..ObjectType::default()
         })))
     }
@@ -137,11 +156,7 @@ impl Schema {
 
     /// Create a new array schema with items of the given type
     pub fn new_array(inner: impl Into<RefOr<Schema>>) -> Self {
-        let inner = inner.into().boxed();
-        Self::new_kind(SchemaKind::Type(Type::Array(ArrayType {
-            items: Some(inner),
-            ..ArrayType::default()
-        })))
+        Self::new_kind(SchemaKind::Type(Type::Array(ArrayType::new(inner))))
     }
 
     pub fn new_one_of(one_of: Vec<RefOr<Schema>>) -> Self {
@@ -156,6 +171,13 @@ impl Schema {
         Self::new_kind(SchemaKind::AnyOf { any_of })
     }
 
+    /// Create a schema inheriting from `base`: an allOf of the base and an object with the own properties.
+    pub fn new_extends(base: impl Into<RefOr<Schema>>, own_properties: RefOrMap<Schema>) -> Self {
+        let mut own = Schema::new_object();
+        *own.properties_mut() = own_properties;
+        Self::new_all_of(vec![base.into(), own.into()])
+    }
+
     /// Create an Any schema
     pub fn new_any() -> Self {
         Self {
@@ -192,6 +214,20 @@ pub enum Type {
     Boolean {},
 }
 
+impl Type {
+    /// Returns the value of the `type` field for this type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Type::String(_) => "string",
+            Type::Number(_) => "number",
+            Type::Integer(_) => "integer",
+            Type::Object(_) => "object",
+            Type::Array(_) => "array",
+            Type::Boolean {} => "boolean",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum AdditionalProperties {
@@ -199,6 +235,24 @@ pub enum AdditionalProperties {
     Schema(Box<RefOr<Schema>>),
 }
 
+impl From<bool> for AdditionalProperties {
+    fn from(allowed: bool) -> Self {
+        AdditionalProperties::Any(allowed)
+    }
+}
+
+impl From<Schema> for AdditionalProperties {
+    fn from(schema: Schema) -> Self {
+        AdditionalProperties::Schema(schema.into())
+    }
+}
+
+impl From<RefOr<Schema>> for AdditionalProperties {
+    fn from(schema: RefOr<Schema>) -> Self {
+        AdditionalProperties::Schema(schema.boxed())
+    }
+}
+
 /// Catch-all for any combination of properties that doesn't correspond to one
 /// of the predefined subsets.
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -335,6 +389,54 @@ pub struct ArrayType {
     pub unique_items: bool,
 }
 
+impl ObjectType {
+    /// Returns true if this object is a map, i.e. it has no fixed properties
+    /// and allows additional properties.
+    pub fn is_map(&self) -> bool {
+        self.properties.is_empty() && match &self.additional_properties {
+            Some(AdditionalProperties::Any(allowed)) => *allowed,
+            Some(AdditionalProperties::Schema(_)) => true,
+            None => false,
+        }
+    }
+
+    /// Returns the schema of the map values, resolving references as needed.
+    ///
+    /// Returns [None] unless `additionalProperties` is a schema which resolves.
+    pub fn map_value_schema<'a>(&'a self, spec: &'a OpenAPI) -> Option<&'a Schema> {
+        match &self.additional_properties {
+            Some(AdditionalProperties::Schema(schema)) => Resolve::resolve(&**schema, spec).ok(),
+            _ => None,
+        }
+    }
+
+    /// Sets `additionalProperties` to the given boolean.
+    pub fn allow_additional(&mut self, allowed: bool) {
+        self.additional_properties = Some(allowed.into());
+    }
+}
+
+impl ArrayType {
+    pub fn new(items: impl Into<RefOr<Schema>>) -> Self {
+        ArrayType {
+            items: Some(items.into().boxed()),
+            ..ArrayType::default()
+        }
+    }
+
+    /// Returns the item schema, resolving references as needed. Returns [None] if there is
+    /// none, or it doesn't resolve.
+    pub fn item_schema<'a>(&'a self, spec: &'a OpenAPI) -> Option<&'a Schema> {
+        self.items.as_ref().and_then(|items| Resolve::resolve(&**items, spec).ok())
+    }
+}
+
+impl From<Schema> for Box<RefOr<Schema>> {
+    fn from(schema: Schema) -> Self {
+        Box::new(RefOr::Item(schema))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum NumberFormat {
@@ -406,8 +508,8 @@ impl Schema {
             SchemaKind::Any(AnySchema { properties, .. }) => Box::new(properties.iter()),
             SchemaKind::AllOf { all_of } => Box::new(all_of
                 .iter()
-                .map(move |schema| schema.resolve(spec).properties_iter(spec))
-                .flatten()),
+                .filter_map(move |schema| Resolve::resolve(schema, spec).ok())
+                .flat_map(move |schema| schema.properties_iter(spec))),
             _ => Box::new(std::iter::empty())
         }
     }
@@ -465,14 +567,208 @@ impl Schema {
             _ => false,
         }
     }
+
+    /// Returns true if this schema is a map. See [ObjectType::is_map].
+    pub fn is_map(&self) -> bool {
+        match &self.kind {
+            SchemaKind::Type(Type::Object(o)) => o.is_map(),
+            _ => false,
+        }
+    }
+
+    /// Returns the schema of the map values. See [ObjectType::map_value_schema].
+    pub fn map_value_schema<'a>(&'a self, spec: &'a OpenAPI) -> Option<&'a Schema> {
+        match &self.kind {
+            SchemaKind::Type(Type::Object(o)) => o.map_value_schema(spec),
+            SchemaKind::Any(AnySchema { additional_properties: Some(AdditionalProperties::Schema(schema)), .. }) => Resolve::resolve(&**schema, spec).ok(),
+            _ => None,
+        }
+    }
+
+    /// Sets `additionalProperties` to the given boolean. Does nothing if the schema is not an object.
+    pub fn allow_additional(&mut self, allowed: bool) {
+        match &mut self.kind {
+            SchemaKind::Type(Type::Object(o)) => o.allow_additional(allowed),
+            SchemaKind::Any(a) => a.additional_properties = Some(allowed.into()),
+            _ => {}
+        }
+    }
+
+    /// Returns the enum values as JSON, whichever schema type holds them.
+    /// Numeric `null` entries are returned as [Value::Null].
+    pub fn enum_values(&self) -> Vec<Value> {
+        match &self.kind {
+            SchemaKind::Type(Type::String(s)) => s.enumeration.iter().map(|v| Value::from(v.as_str())).collect(),
+            SchemaKind::Type(Type::Integer(i)) => i.enumeration.iter().map(|v| Value::from(*v)).collect(),
+            SchemaKind::Type(Type::Number(n)) => n.enumeration.iter().map(|v| Value::from(*v)).collect(),
+            SchemaKind::Any(a) => a.enumeration.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replaces the enum values. Fails if a value doesn't fit the schema type, or the type has no enum.
+    pub fn set_enum_values(&mut self, values: Vec<Value>) -> anyhow::Result<()> {
+        match &mut self.kind {
+            SchemaKind::Type(Type::String(s)) => s.enumeration = serde_json::from_value(Value::Array(values))?,
+            SchemaKind::Type(Type::Integer(i)) => i.enumeration = serde_json::from_value(Value::Array(values))?,
+            SchemaKind::Type(Type::Number(n)) => n.enumeration = serde_json::from_value(Value::Array(values))?,
+            SchemaKind::Any(a) => a.enumeration = values,
+            _ => return Err(anyhow::anyhow!("Schema does not support enum values")),
+        }
+        Ok(())
+    }
+
+    /// Appends an enum value, unless it is already present.
+    pub fn add_enum_value(&mut self, value: Value) -> anyhow::Result<()> {
+        let mut values = self.enum_values();
+        if !values.contains(&value) {
+            values.push(value);
+            self.set_enum_values(values)?;
+        }
+        Ok(())
+    }
+
+    /// Removes an enum value, returning true if it was present.
+    pub fn remove_enum_value(&mut self, value: &Value) -> bool {
+        let mut values = self.enum_values();
+        let len = values.len();
+        values.retain(|v| v != value);
+        if values.len() == len {
+            return false;
+        }
+        self.set_enum_values(values).expect("Existing enum values fit the schema");
+        true
+    }
+
+    /// Replaces an enum value in place. Fails if `old` isn't present, `new` already is,
+    /// or `new` doesn't fit the schema type.
+    pub fn rename_enum_value(&mut self, old: &Value, new: Value) -> anyhow::Result<()> {
+        let mut values = self.enum_values();
+        if values.contains(&new) {
+            return Err(anyhow::anyhow!("Enum value {} already exists", new));
+        }
+        let slot = values.iter_mut().find(|v| *v == old)
+            .ok_or_else(|| anyhow::anyhow!("Enum value {} not found", old))?;
+        *slot = new;
+        self.set_enum_values(values)
+    }
+}
+
+/// The parts of a schema from [Schema::base_schemas].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaInheritance<'a> {
+    /// The referenced allOf parts, with their references.
+    pub bases: Vec<(&'a str, &'a Schema)>,
+    /// The inline allOf parts which extend the bases, or the schema itself if it isn't an allOf.
+    pub own: Vec<&'a Schema>,
+}
+
+impl Schema {
+    /// Splits an allOf into the referenced schemas it inherits from and its inline extensions.
+    /// Fails if a base can't be resolved.
+    pub fn base_schemas<'a>(&'a self, spec: &'a OpenAPI) -> anyhow::Result<SchemaInheritance<'a>> {
+        let SchemaKind::AllOf { all_of } = &self.kind else {
+            return Ok(SchemaInheritance { bases: Vec::new(), own: vec![self] });
+        };
+        let mut inheritance = SchemaInheritance { bases: Vec::new(), own: Vec::new() };
+        for part in all_of {
+            match part {
+                RefOr::Reference { reference } => inheritance.bases.push((reference, Resolve::resolve(part, spec)?)),
+                RefOr::Item(schema) => inheritance.own.push(schema),
+            }
+        }
+        Ok(inheritance)
+    }
+}
+
+fn write_joined(f: &mut std::fmt::Formatter<'_>, items: &[impl std::fmt::Display], sep: &str) -> std::fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn write_enum<T: Serialize>(f: &mut std::fmt::Formatter<'_>, values: &[T]) -> std::fmt::Result {
+    let values: Vec<_> = values.iter()
+        .map(|v| serde_json::to_string(v).unwrap_or_default())
+        .collect();
+    write_joined(f, &values, "|")
+}
+
+/// A concise one-line summary of the schema, such as `object{name: string, age?: int32}`.
+/// Formats are shown in place of their type, and references by their component name.
+impl std::fmt::Display for Schema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            SchemaKind::Type(Type::String(s)) if !s.enumeration.is_empty() => write_enum(f, &s.enumeration)?,
+            SchemaKind::Type(Type::String(s)) if !s.format.is_empty() => f.write_str(s.format.as_str())?,
+            SchemaKind::Type(Type::String(_)) => f.write_str("string")?,
+            SchemaKind::Type(Type::Number(n)) if !n.enumeration.is_empty() => write_enum(f, &n.enumeration)?,
+            SchemaKind::Type(Type::Number(n)) => f.write_str(match &n.format {
+                VariantOrUnknownOrEmpty::Item(NumberFormat::Float) => "float",
+                VariantOrUnknownOrEmpty::Item(NumberFormat::Double) => "double",
+                VariantOrUnknownOrEmpty::Unknown(s) => s,
+                VariantOrUnknownOrEmpty::Empty => "number",
+            })?,
+            SchemaKind::Type(Type::Integer(n)) if !n.enumeration.is_empty() => write_enum(f, &n.enumeration)?,
+            SchemaKind::Type(Type::Integer(n)) => f.write_str(match &n.format {
+                VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32) => "int32",
+                VariantOrUnknownOrEmpty::Item(IntegerFormat::Int64) => "int64",
+                VariantOrUnknownOrEmpty::Unknown(s) => s,
+                VariantOrUnknownOrEmpty::Empty => "integer",
+            })?,
+            SchemaKind::Type(Type::Boolean {}) => f.write_str("boolean")?,
+            SchemaKind::Type(Type::Array(a)) => match &a.items {
+                Some(items) => write!(f, "[{}]", items)?,
+                None => f.write_str("[any]")?,
+            },
+            SchemaKind::Type(Type::Object(o)) => match &o.additional_properties {
+                Some(AdditionalProperties::Schema(value)) if o.properties.is_empty() => write!(f, "map<{}>", value)?,
+                Some(AdditionalProperties::Any(true)) if o.properties.is_empty() => f.write_str("map<any>")?,
+                _ if o.properties.is_empty() => f.write_str("object")?,
+                _ => {
+                    f.write_str("object{")?;
+                    for (i, (name, schema)) in o.properties.iter().enumerate() {
+                        let optional = if o.required.contains(name) { "" } else { "?" };
+                        write!(f, "{}{}{}: {}", if i > 0 { ", " } else { "" }, name, optional, schema)?;
+                    }
+                    f.write_str("}")?;
+                }
+            },
+            SchemaKind::OneOf { one_of } => {
+                f.write_str("oneOf(")?;
+                write_joined(f, one_of, " | ")?;
+                f.write_str(")")?;
+            }
+            SchemaKind::AnyOf { any_of } => {
+                f.write_str("anyOf(")?;
+                write_joined(f, any_of, " | ")?;
+                f.write_str(")")?;
+            }
+            SchemaKind::AllOf { all_of } => {
+                f.write_str("allOf(")?;
+                write_joined(f, all_of, " & ")?;
+                f.write_str(")")?;
+            }
+            SchemaKind::Not { not } => write!(f, "not({})", not)?,
+            SchemaKind::Any(a) => f.write_str(a.typ.as_deref().unwrap_or("any"))?,
+        }
+        if self.nullable {
+            f.write_str("|null")?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
-    use serde_json::json;
+    use serde_json::{json, Value};
 
-    use crate::{AnySchema, Schema, SchemaData, SchemaKind};
+    use crate::{AnySchema, IntegerType, OpenAPI, RefOr, RefOrMap, Schema, SchemaData, SchemaKind, Type};
 
     #[test]
     fn test_schema_with_extensions() {
@@ -569,6 +865,22 @@ allOf:
         }
     }
 
+    #[test]
+    fn test_extends() {
+        let mut spec = OpenAPI::default();
+        spec.schemas.insert("Pet", Schema::new_object());
+        let own = vec![("bark".to_string(), Schema::new_bool().into())].into_iter().collect();
+        let dog = Schema::new_extends(RefOr::schema_ref("Pet"), own);
+        let inheritance = dog.base_schemas(&spec).unwrap();
+        assert_eq!(inheritance.bases, vec![("#/components/schemas/Pet", spec.schemas.get2("Pet").unwrap())]);
+        assert_eq!(inheritance.own.len(), 1);
+        assert!(inheritance.own[0].properties().contains_key("bark"));
+
+        let cat = Schema::new_object();
+        assert_eq!(cat.base_schemas(&spec).unwrap().own, vec![&cat]);
+        assert!(Schema::new_extends(RefOr::schema_ref("Animal"), RefOrMap::new()).base_schemas(&spec).is_err());
+    }
+
     #[test]
     fn test_with_format() {
         use crate::variant_or::VariantOrUnknownOrEmpty;
@@ -580,5 +892,90 @@ allOf:
         let SchemaKind::Type(crate::Type::String(s)) = s.kind else { panic!() };
         assert_matches!(s.format, VariantOrUnknownOrEmpty::Unknown(s) if s == "uuid");
     }
-}
 
+    #[test]
+    fn test_array_item_schema() {
+        use crate::{ArrayType, OpenAPI, RefOr, Type};
+        let mut spec = OpenAPI::default();
+        spec.schemas.insert("Pet", Schema::new_object());
+        let array = ArrayType::new(RefOr::schema_ref("Pet"));
+        assert!(array.item_schema(&spec).unwrap().get_properties().is_some());
+
+        let boxed: Box<RefOr<Schema>> = Schema::new_string().into();
+        let array = ArrayType { items: Some(boxed), ..ArrayType::default() };
+        assert!(matches!(array.item_schema(&spec).unwrap().kind, SchemaKind::Type(Type::String(_))));
+        assert!(ArrayType::default().item_schema(&spec).is_none());
+        assert!(ArrayType::new(RefOr::schema_ref("Missing")).item_schema(&spec).is_none());
+    }
+
+    #[test]
+    fn test_map_helpers() {
+        use crate::OpenAPI;
+        let spec = OpenAPI::default();
+        let map = Schema::new_map(Schema::new_integer());
+        assert!(map.is_map());
+        assert!(map.map_value_schema(&spec).is_some());
+        assert!(Schema::new_map(RefOr::schema_ref("Missing")).map_value_schema(&spec).is_none());
+        assert!(Schema::new_map_any().is_map());
+
+        let mut object = Schema::new_object();
+        assert!(!object.is_map());
+        object.allow_additional(true);
+        assert!(object.is_map());
+        assert!(object.map_value_schema(&spec).is_none());
+        object.allow_additional(false);
+        assert!(!object.is_map());
+    }
+
+    #[test]
+    fn test_display() {
+        use crate::RefOr;
+        let schema: Schema = serde_yaml::from_str(r##"
+type: object
+required: [name]
+properties:
+  name:
+    type: string
+  age:
+    type: integer
+    format: int32
+  kind:
+    type: string
+    enum: [cat, dog]
+    nullable: true
+  owner:
+    $ref: "#/components/schemas/Owner"
+  tags:
+    type: array
+    items:
+      type: string
+  extra:
+    type: object
+    additionalProperties:
+      type: number
+"##).unwrap();
+        assert_eq!(schema.to_string(), r#"object{name: string, age?: int32, kind?: "cat"|"dog"|null, owner?: Owner, tags?: [string], extra?: map<number>}"#);
+        let one_of = Schema::new_one_of(vec![RefOr::schema_ref("Cat"), Schema::new_bool().into()]);
+        assert_eq!(one_of.to_string(), "oneOf(Cat | boolean)");
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let mut schema = Schema::new_str_enum(vec!["cat".to_string()]);
+        schema.add_enum_value(json!("dog")).unwrap();
+        schema.add_enum_value(json!("dog")).unwrap();
+        assert!(schema.add_enum_value(json!(1)).is_err());
+        schema.rename_enum_value(&json!("cat"), json!("kitten")).unwrap();
+        assert!(schema.rename_enum_value(&json!("cat"), json!("lion")).is_err());
+        assert_eq!(schema.enum_values(), vec![json!("kitten"), json!("dog")]);
+        assert!(schema.remove_enum_value(&json!("kitten")));
+        assert!(!schema.remove_enum_value(&json!("kitten")));
+
+        let mut int = Schema::new_kind(SchemaKind::Type(Type::Integer(IntegerType::default())));
+        int.add_enum_value(json!(3)).unwrap();
+        int.add_enum_value(Value::Null).unwrap();
+        assert!(int.add_enum_value(json!("3")).is_err());
+        assert_eq!(int.enum_values(), vec![json!(3), Value::Null]);
+        assert!(Schema::new_bool().add_enum_value(json!(true)).is_err());
+    }
+}
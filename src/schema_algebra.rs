@@ -0,0 +1,350 @@
+use anyhow::{anyhow, Result};
+
+use crate::*;
+
+fn tighter<T: PartialOrd + Copy>(a: Option<T>, b: Option<T>, keep_larger: bool) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if (a > b) == keep_larger { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The tighter of two bounds with their exclusivity. For equal bounds, exclusive wins.
+fn tighter_bound<T: PartialOrd + Copy>(a: (Option<T>, bool), b: (Option<T>, bool), lower: bool) -> (Option<T>, bool) {
+    match (a, b) {
+        ((Some(x), ex_a), (Some(y), ex_b)) if x == y => (Some(x), ex_a || ex_b),
+        ((Some(x), _), (Some(y), _)) if (x > y) == lower => a,
+        ((Some(_), _), (Some(_), _)) => b,
+        ((Some(_), _), _) => a,
+        _ => b,
+    }
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(what: &str, min: Option<T>, max: Option<T>) -> Result<()> {
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err(anyhow!("Intersection requires {} between {} and {}", what, min, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if no value lies between the bounds, including equal bounds where either is exclusive.
+fn check_bounds<T: PartialOrd + std::fmt::Display>(min: (Option<T>, bool), max: (Option<T>, bool)) -> Result<()> {
+    match (min, max) {
+        ((Some(min), ex_min), (Some(max), ex_max)) if min > max || (min == max && (ex_min || ex_max)) => {
+            Err(anyhow!("Intersection requires values between {} and {}", min, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Restricts a number schema to integers. Returns None if its `multipleOf` is fractional, which
+/// an integer schema can't express, and fails if none of its enum values are integers.
+fn number_as_integer(n: &NumberType) -> Result<Option<IntegerType>> {
+    let multiple_of = match n.multiple_of {
+        Some(m) if m.fract() != 0.0 => return Ok(None),
+        m => m.map(|m| m as i64),
+    };
+    // A fractional bound becomes the nearest inclusive integer bound inside it.
+    let bound = |b: Option<f64>, exclusive: bool, round: fn(f64) -> f64| match b {
+        Some(b) if b.fract() != 0.0 => (Some(round(b) as i64), false),
+        b => (b.map(|b| b as i64), exclusive),
+    };
+    let (minimum, exclusive_minimum) = bound(n.minimum, n.exclusive_minimum, f64::ceil);
+    let (maximum, exclusive_maximum) = bound(n.maximum, n.exclusive_maximum, f64::floor);
+    let enumeration: Vec<Option<i64>> = n.enumeration.iter()
+        .filter(|v| !matches!(v, Some(v) if v.fract() != 0.0))
+        .map(|v| v.map(|v| v as i64))
+        .collect();
+    if !n.enumeration.is_empty() && enumeration.is_empty() {
+        return Err(anyhow!("Enum {:?} has no integer values", n.enumeration));
+    }
+    Ok(Some(IntegerType {
+        format: VariantOrUnknownOrEmpty::Empty,
+        multiple_of,
+        exclusive_minimum,
+        exclusive_maximum,
+        minimum,
+        maximum,
+        enumeration,
+    }))
+}
+
+fn intersect_enum<T: PartialEq + Clone + std::fmt::Debug>(a: &[T], b: &[T]) -> Result<Vec<T>> {
+    if a.is_empty() || b.is_empty() {
+        return Ok(if a.is_empty() { b.to_vec() } else { a.to_vec() });
+    }
+    let common: Vec<T> = a.iter().filter(|v| b.contains(v)).cloned().collect();
+    if common.is_empty() {
+        return Err(anyhow!("Enums {:?} and {:?} have no values in common", a, b));
+    }
+    Ok(common)
+}
+
+fn all_of(schemas: Vec<RefOr<Schema>>) -> Schema {
+    Schema { data: SchemaData::default(), kind: SchemaKind::AllOf { all_of: schemas } }
+}
+
+/// Intersects two schemas which may be references.
+fn intersect_refs(a: &RefOr<Schema>, b: &RefOr<Schema>, spec: &OpenAPI) -> Result<RefOr<Schema>> {
+    if a == b {
+        return Ok(a.clone());
+    }
+    let (a, b) = (Resolve::resolve(a, spec)?, Resolve::resolve(b, spec)?);
+    Ok(a.intersect(b, spec)?.into())
+}
+
+fn intersect_types(a: &Type, b: &Type, spec: &OpenAPI) -> Result<Option<Type>> {
+    let typ = match (a, b) {
+        (Type::String(a), Type::String(b)) => {
+            if a.pattern.is_some() && b.pattern.is_some() && a.pattern != b.pattern {
+                return Ok(None);
+            }
+            if !a.format.is_empty() && !b.format.is_empty() && a.format != b.format {
+                return Err(anyhow!("Formats {} and {} conflict", a.format.as_str(), b.format.as_str()));
+            }
+            let s = StringType {
+                format: if a.format.is_empty() { b.format.clone() } else { a.format.clone() },
+                pattern: a.pattern.clone().or_else(|| b.pattern.clone()),
+                enumeration: intersect_enum(&a.enumeration, &b.enumeration)?,
+                min_length: tighter(a.min_length, b.min_length, true),
+                max_length: tighter(a.max_length, b.max_length, false),
+            };
+            check_range("length", s.min_length, s.max_length)?;
+            Type::String(s)
+        }
+        (Type::Integer(a), Type::Integer(b)) => {
+            if a.multiple_of.is_some() && b.multiple_of.is_some() && a.multiple_of != b.multiple_of {
+                return Ok(None);
+            }
+            let (minimum, exclusive_minimum) = tighter_bound((a.minimum, a.exclusive_minimum), (b.minimum, b.exclusive_minimum), true);
+            let (maximum, exclusive_maximum) = tighter_bound((a.maximum, a.exclusive_maximum), (b.maximum, b.exclusive_maximum), false);
+            // Exclusive integer bounds are the next integer inside, inclusive.
+            let inclusive_min = minimum.map(|m| m as i128 + exclusive_minimum as i128);
+            let inclusive_max = maximum.map(|m| m as i128 - exclusive_maximum as i128);
+            check_range("values", inclusive_min, inclusive_max)?;
+            Type::Integer(IntegerType {
+                format: if a.format.is_empty() { b.format.clone() } else { a.format.clone() },
+                multiple_of: a.multiple_of.or(b.multiple_of),
+                exclusive_minimum,
+                exclusive_maximum,
+                minimum,
+                maximum,
+                enumeration: intersect_enum(&a.enumeration, &b.enumeration)?,
+            })
+        }
+        (Type::Number(a), Type::Number(b)) => {
+            if a.multiple_of.is_some() && b.multiple_of.is_some() && a.multiple_of != b.multiple_of {
+                return Ok(None);
+            }
+            let (minimum, exclusive_minimum) = tighter_bound((a.minimum, a.exclusive_minimum), (b.minimum, b.exclusive_minimum), true);
+            let (maximum, exclusive_maximum) = tighter_bound((a.maximum, a.exclusive_maximum), (b.maximum, b.exclusive_maximum), false);
+            check_bounds((minimum, exclusive_minimum), (maximum, exclusive_maximum))?;
+            Type::Number(NumberType {
+                format: if a.format.is_empty() { b.format.clone() } else { a.format.clone() },
+                multiple_of: a.multiple_of.or(b.multiple_of),
+                exclusive_minimum,
+                exclusive_maximum,
+                minimum,
+                maximum,
+                enumeration: intersect_enum(&a.enumeration, &b.enumeration)?,
+            })
+        }
+        (Type::Integer(a), Type::Number(b)) | (Type::Number(b), Type::Integer(a)) => {
+            return match number_as_integer(b)? {
+                Some(b) => intersect_types(&Type::Integer(a.clone()), &Type::Integer(b), spec),
+                None => Ok(None),
+            };
+        }
+        (Type::Boolean {}, Type::Boolean {}) => Type::Boolean {},
+        (Type::Array(a), Type::Array(b)) => {
+            let items = match (&a.items, &b.items) {
+                (Some(x), Some(y)) => Some(Box::new(intersect_refs(x, y, spec)?)),
+                (x, y) => x.clone().or_else(|| y.clone()),
+            };
+            let array = ArrayType {
+                items,
+                min_items: tighter(a.min_items, b.min_items, true),
+                max_items: tighter(a.max_items, b.max_items, false),
+                unique_items: a.unique_items || b.unique_items,
+            };
+            check_range("item count", array.min_items, array.max_items)?;
+            Type::Array(array)
+        }
+        (Type::Object(a), Type::Object(b)) => {
+            let mut properties = a.properties.clone();
+            for (name, schema) in b.properties.iter() {
+                let merged = match a.properties.get(name) {
+                    Some(existing) => intersect_refs(existing, schema, spec)?,
+                    None => schema.clone(),
+                };
+                properties.insert(name.clone(), merged);
+            }
+            let mut required = a.required.clone();
+            required.extend(b.required.iter().filter(|r| !a.required.contains(r)).cloned());
+            let closed = |o: &ObjectType| matches!(o.additional_properties, Some(AdditionalProperties::Any(false)));
+            for (closed_side, other) in [(a, b), (b, a)].iter() {
+                if closed(closed_side) {
+                    if let Some(name) = other.required.iter().find(|r| !closed_side.properties.contains_key(*r)) {
+                        return Err(anyhow!("Property {} is required but not allowed", name));
+                    }
+                }
+            }
+            // A closed object allows only its own properties, so the other side's optional ones go.
+            for closed_side in [a, b].iter() {
+                if closed(closed_side) {
+                    properties.retain(|name, _| closed_side.properties.contains_key(name));
+                }
+            }
+            let additional_properties = match (&a.additional_properties, &b.additional_properties) {
+                (Some(AdditionalProperties::Any(false)), _) | (_, Some(AdditionalProperties::Any(false))) => Some(AdditionalProperties::Any(false)),
+                (Some(AdditionalProperties::Schema(x)), Some(AdditionalProperties::Schema(y))) => {
+                    Some(AdditionalProperties::Schema(Box::new(intersect_refs(x, y, spec)?)))
+                }
+                (Some(AdditionalProperties::Schema(x)), _) | (_, Some(AdditionalProperties::Schema(x))) => Some(AdditionalProperties::Schema(x.clone())),
+                (x, y) => x.clone().or_else(|| y.clone()),
+            };
+            let object = ObjectType {
+                properties,
+                required,
+                additional_properties,
+                min_properties: tighter(a.min_properties, b.min_properties, true),
+                max_properties: tighter(a.max_properties, b.max_properties, false),
+            };
+            check_range("property count", object.min_properties, object.max_properties)?;
+            Type::Object(object)
+        }
+        (a, b) => return Err(anyhow!("Types {} and {} have no values in common", a.as_str(), b.as_str())),
+    };
+    Ok(Some(typ))
+}
+
+impl Schema {
+    /// Returns a schema accepting exactly the values both schemas accept.
+    ///
+    /// Schemas of the same type are merged into one, taking the tighter of each constraint.
+    /// Constraints which can't be combined in one schema (different patterns, composed schemas)
+    /// fall back to `allOf`. Fails if no value can satisfy both, e.g. for different types,
+    /// disjoint enums or crossing bounds. Documentation fields come from `self`.
+    pub fn intersect(&self, other: &Schema, spec: &OpenAPI) -> Result<Schema> {
+        if self == other {
+            return Ok(self.clone());
+        }
+        let fallback = || all_of(vec![self.clone().into(), other.clone().into()]);
+        let mut merged = match (&self.kind, &other.kind) {
+            (SchemaKind::Type(a), SchemaKind::Type(b)) => match intersect_types(a, b, spec)? {
+                Some(typ) => Schema { data: self.data.clone(), kind: SchemaKind::Type(typ) },
+                None => fallback(),
+            },
+            (SchemaKind::AllOf { all_of: items }, _) => {
+                let mut items = items.clone();
+                items.push(other.clone().into());
+                all_of(items)
+            }
+            _ => fallback(),
+        };
+        merged.nullable = self.nullable && other.nullable;
+        Ok(merged)
+    }
+
+    /// Returns a schema accepting the values either schema accepts.
+    ///
+    /// Enums of the same type with otherwise equal schemas are merged into one enum, and existing
+    /// `oneOf` alternatives are extended; anything else becomes a `oneOf` of both. As `oneOf`
+    /// rejects values matching several alternatives, the inputs should not overlap.
+    pub fn union(&self, other: &Schema) -> Schema {
+        if self == other {
+            return self.clone();
+        }
+        let (mut a, mut b) = (self.clone(), other.clone());
+        if !a.enum_values().is_empty() && !b.enum_values().is_empty() {
+            let mut values = a.enum_values();
+            values.extend(b.enum_values().into_iter().filter(|v| !self.enum_values().contains(v)));
+            let _ = a.set_enum_values(Vec::new());
+            let _ = b.set_enum_values(Vec::new());
+            if a == b && a.set_enum_values(values).is_ok() {
+                return a;
+            }
+        }
+        let mut alternatives = match &self.kind {
+            SchemaKind::OneOf { one_of } => one_of.clone(),
+            _ => vec![self.clone().into()],
+        };
+        match &other.kind {
+            SchemaKind::OneOf { one_of } => alternatives.extend(one_of.iter().cloned()),
+            _ => alternatives.push(other.clone().into()),
+        }
+        Schema { data: SchemaData::default(), kind: SchemaKind::OneOf { one_of: alternatives } }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_intersect_and_union() {
+        let spec = OpenAPI::default();
+        let base = schema(json!({"type": "object", "required": ["name"], "properties": {
+            "name": {"type": "string", "maxLength": 100},
+            "age": {"type": "integer", "minimum": 0},
+        }}));
+        let overlay = schema(json!({"type": "object", "required": ["age"], "properties": {
+            "name": {"type": "string", "minLength": 1, "maxLength": 40},
+            "age": {"type": "integer", "minimum": 18, "exclusiveMinimum": true},
+        }}));
+        assert_eq!(serde_json::to_value(base.intersect(&overlay, &spec).unwrap()).unwrap(), json!({
+            "type": "object", "required": ["name", "age"], "properties": {
+                "name": {"type": "string", "minLength": 1, "maxLength": 40},
+                "age": {"type": "integer", "minimum": 18, "exclusiveMinimum": true},
+            }
+        }));
+
+        let err = schema(json!({"type": "integer", "minimum": 10}))
+            .intersect(&schema(json!({"type": "integer", "maximum": 5})), &spec)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Intersection requires values between 10 and 5");
+        assert!(Schema::new_string().intersect(&Schema::new_bool(), &spec).is_err());
+        let patterns = schema(json!({"type": "string", "pattern": "^a"}))
+            .intersect(&schema(json!({"type": "string", "pattern": "b$"})), &spec)
+            .unwrap();
+        assert!(matches!(patterns.kind, SchemaKind::AllOf { ref all_of } if all_of.len() == 2));
+
+        let int = schema(json!({"type": "integer", "minimum": 0}))
+            .intersect(&schema(json!({"type": "number", "maximum": 9.5})), &spec)
+            .unwrap();
+        assert_eq!(serde_json::to_value(int).unwrap(), json!({"type": "integer", "minimum": 0, "maximum": 9}));
+        let number = schema(json!({"type": "number", "enum": [1, 1.5]}))
+            .intersect(&Schema::new_integer(), &spec)
+            .unwrap();
+        assert_eq!(serde_json::to_value(number).unwrap(), json!({"type": "integer", "enum": [1]}));
+
+        let closed = schema(json!({"type": "object", "additionalProperties": false, "properties": {"name": {"type": "string"}}}))
+            .intersect(&schema(json!({"type": "object", "properties": {"age": {"type": "integer"}}})), &spec)
+            .unwrap();
+        assert_eq!(serde_json::to_value(closed).unwrap(), json!({
+            "type": "object", "additionalProperties": false, "properties": {"name": {"type": "string"}}
+        }));
+
+        assert!(schema(json!({"type": "number", "minimum": 5, "exclusiveMinimum": true}))
+            .intersect(&schema(json!({"type": "number", "maximum": 5})), &spec)
+            .is_err());
+        assert!(schema(json!({"type": "integer", "minimum": 5, "exclusiveMinimum": true}))
+            .intersect(&schema(json!({"type": "integer", "maximum": 6, "exclusiveMaximum": true})), &spec)
+            .is_err());
+        assert!(schema(json!({"type": "number", "minimum": 5}))
+            .intersect(&schema(json!({"type": "number", "maximum": 5})), &spec)
+            .is_ok());
+
+        let cats = Schema::new_str_enum(vec!["cat".to_string()]);
+        let pets = cats.union(&Schema::new_str_enum(vec!["dog".to_string(), "cat".to_string()]));
+        assert_eq!(pets.enum_values(), vec![json!("cat"), json!("dog")]);
+        let either = Schema::new_string().union(&Schema::new_integer()).union(&Schema::new_bool());
+        assert!(matches!(either.kind, SchemaKind::OneOf { ref one_of } if one_of.len() == 3));
+    }
+}
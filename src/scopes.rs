@@ -0,0 +1,125 @@
+use indexmap::{IndexMap, IndexSet};
+
+use crate::*;
+use crate::client::OperationRef;
+
+/// OAuth2 scopes which are declared but not required, or required but not declared.
+/// Each entry is a security scheme name and a scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeMismatches {
+    /// Declared in a flow of the scheme, but no operation requires it.
+    pub unused: Vec<(String, String)>,
+    /// Required by an operation, but declared in none of the scheme's flows.
+    pub undeclared: Vec<(String, String)>,
+}
+
+impl ScopeMismatches {
+    pub fn is_empty(&self) -> bool {
+        self.unused.is_empty() && self.undeclared.is_empty()
+    }
+}
+
+impl OAuth2Flows {
+    /// The scopes of every flow, without duplicates.
+    pub fn scopes(&self) -> IndexSet<&str> {
+        let implicit = self.implicit.iter().flat_map(|f| f.scopes.keys());
+        let password = self.password.iter().flat_map(|f| f.scopes.keys());
+        let client_credentials = self.client_credentials.iter().flat_map(|f| f.scopes.keys());
+        let authorization_code = self.authorization_code.iter().flat_map(|f| f.scopes.keys());
+        implicit.chain(password).chain(client_credentials).chain(authorization_code)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl OpenAPI {
+    /// Maps each OAuth2 scope to the operations requiring it, taking the document's security for
+    /// operations without their own. Operations are listed by method and path.
+    /// Scopes of security schemes which aren't OAuth2 are skipped.
+    pub fn scopes_in_use(&self) -> IndexMap<String, Vec<OperationRef<'_>>> {
+        let mut scopes: IndexMap<String, Vec<OperationRef>> = IndexMap::new();
+        for (path, method, _, scheme, scope) in self.required_scopes() {
+            let Some(method) = PathMethod::from_bytes(method.to_uppercase().as_bytes()).ok() else { continue };
+            if !self.is_oauth2_scheme(scheme) {
+                continue;
+            }
+            let operations = scopes.entry(scope.to_string()).or_default();
+            let operation = OperationRef::Path { method, path };
+            if !operations.contains(&operation) {
+                operations.push(operation);
+            }
+        }
+        scopes
+    }
+
+    /// Compares the scopes declared in the flows of OAuth2 security schemes with those that
+    /// operations require.
+    pub fn scope_mismatches(&self) -> ScopeMismatches {
+        let mut required: IndexSet<(&str, &str)> = IndexSet::new();
+        let mut mismatches = ScopeMismatches::default();
+        for (_, _, _, scheme, scope) in self.required_scopes() {
+            required.insert((scheme, scope));
+        }
+        for (name, scheme) in self.security_schemes.iter() {
+            let Some(SecurityScheme::OAuth2 { flows, .. }) = scheme.as_item() else { continue };
+            let declared = flows.scopes();
+            for scope in &declared {
+                if !required.contains(&(name.as_str(), *scope)) {
+                    mismatches.unused.push((name.clone(), scope.to_string()));
+                }
+            }
+            for (_, scope) in required.iter().filter(|(scheme, _)| scheme == name) {
+                if !declared.contains(scope) {
+                    mismatches.undeclared.push((name.clone(), scope.to_string()));
+                }
+            }
+        }
+        mismatches
+    }
+
+    /// Every (path, method, operation, scheme, scope) that operations require.
+    fn required_scopes(&self) -> impl Iterator<Item=(&str, &str, &Operation, &str, &str)> {
+        self.operations().flat_map(move |(path, method, op, _)| {
+            op.security.as_ref().unwrap_or(&self.security).iter()
+                .flat_map(|requirement| requirement.iter())
+                .flat_map(move |(scheme, scopes)| scopes.iter().map(move |scope| (path, method, op, scheme.as_str(), scope.as_str())))
+        })
+    }
+
+    fn is_oauth2_scheme(&self, name: &str) -> bool {
+        matches!(self.security_schemes.get(name).and_then(|s| s.as_item()), Some(SecurityScheme::OAuth2 { .. }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_in_use() {
+        let mut spec = OpenAPI::default();
+        let flow = OAuth2Flow {
+            refresh_url: None,
+            token_url: "https://example.com/token".to_string(),
+            scopes: vec![("pets:read", ""), ("pets:write", ""), ("admin", "")].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        };
+        let flows = OAuth2Flows { client_credentials: Some(flow), ..OAuth2Flows::default() };
+        spec.security_schemes.insert("oauth", SecurityScheme::OAuth2 { flows, description: None, extensions: IndexMap::new() });
+        spec.security = vec![vec![("oauth".to_string(), vec!["pets:read".to_string()])].into_iter().collect()];
+        spec.paths.insert_operation("/pets".to_string(), PathMethod::GET, Operation::default());
+        let post = Operation {
+            security: Some(vec![vec![("oauth".to_string(), vec!["pets:write".to_string(), "pets:delete".to_string()])].into_iter().collect()]),
+            ..Operation::default()
+        };
+        spec.paths.insert_operation("/pets".to_string(), PathMethod::POST, post);
+
+        let scopes = spec.scopes_in_use();
+        assert_eq!(scopes.keys().collect::<Vec<_>>(), vec!["pets:read", "pets:write", "pets:delete"]);
+        assert_eq!(scopes["pets:read"], vec![OperationRef::Path { method: PathMethod::GET, path: "/pets" }]);
+        assert_eq!(scopes["pets:write"], vec![OperationRef::Path { method: PathMethod::POST, path: "/pets" }]);
+
+        let mismatches = spec.scope_mismatches();
+        assert_eq!(mismatches.unused, vec![("oauth".to_string(), "admin".to_string())]);
+        assert_eq!(mismatches.undeclared, vec![("oauth".to_string(), "pets:delete".to_string())]);
+    }
+}
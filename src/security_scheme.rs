@@ -15,6 +15,9 @@ pub enum SecurityScheme {
         name: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        /// Inline extensions to this object.
+        #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+        extensions: IndexMap<String, serde_json::Value>,
     },
     #[serde(rename = "http")]
     HTTP {
@@ -25,12 +28,18 @@ pub enum SecurityScheme {
         bearer_format: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        /// Inline extensions to this object.
+        #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+        extensions: IndexMap<String, serde_json::Value>,
     },
     #[serde(rename = "oauth2")]
     OAuth2 {
         flows: OAuth2Flows,
         #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        /// Inline extensions to this object.
+        #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+        extensions: IndexMap<String, serde_json::Value>,
     },
     #[serde(rename = "openIdConnect")]
     OpenIDConnect {
@@ -38,9 +47,32 @@ pub enum SecurityScheme {
         open_id_connect_url: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        /// Inline extensions to this object.
+        #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+        extensions: IndexMap<String, serde_json::Value>,
     },
 }
 
+impl SecurityScheme {
+    pub fn extensions(&self) -> &IndexMap<String, serde_json::Value> {
+        match self {
+            SecurityScheme::APIKey { extensions, .. }
+            | SecurityScheme::HTTP { extensions, .. }
+            | SecurityScheme::OAuth2 { extensions, .. }
+            | SecurityScheme::OpenIDConnect { extensions, .. } => extensions,
+        }
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut IndexMap<String, serde_json::Value> {
+        match self {
+            SecurityScheme::APIKey { extensions, .. }
+            | SecurityScheme::HTTP { extensions, .. }
+            | SecurityScheme::OAuth2 { extensions, .. }
+            | SecurityScheme::OpenIDConnect { extensions, .. } => extensions,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum APIKeyLocation {
@@ -25,3 +25,130 @@ pub struct Server {
     #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
     pub extensions: IndexMap<String, serde_json::Value>,
 }
+
+impl Server {
+    /// Returns the URL with every `{variable}` replaced by its default value.
+    /// Variables without a definition are left as is.
+    pub fn default_url(&self) -> String {
+        let mut url = self.url.clone();
+        for (name, variable) in self.variables.iter().flatten() {
+            url = url.replace(&format!("{{{}}}", name), &variable.default);
+        }
+        url
+    }
+
+    /// Returns the URL with the given variable values, and the other variables at their defaults.
+    /// Fails if a value is given for a variable the server doesn't define, or isn't one of the
+    /// variable's `enum` values.
+    pub fn url_with<K: AsRef<str>, V: Into<String>>(&self, values: impl IntoIterator<Item=(K, V)>) -> anyhow::Result<String> {
+        let mut values: IndexMap<String, String> = values.into_iter().map(|(k, v)| (k.as_ref().to_string(), v.into())).collect();
+        let mut url = self.url.clone();
+        for (name, variable) in self.variables.iter().flatten() {
+            let value = values.shift_remove(name).unwrap_or_else(|| variable.default.clone());
+            if !variable.enumeration.is_empty() && !variable.enumeration.contains(&value) {
+                anyhow::bail!("{} is not a value of server variable {}", value, name);
+            }
+            url = url.replace(&format!("{{{}}}", name), &value);
+        }
+        if let Some(name) = values.keys().next() {
+            anyhow::bail!("Unknown server variable {}", name);
+        }
+        Ok(url)
+    }
+
+    /// Resolves the server URL, with variables at their defaults, against the URL of the document
+    /// (RFC 3986 reference resolution). Absolute server URLs are returned as is; relative ones
+    /// such as `/api/v1` or `../v2` take the scheme and host of `base`.
+    pub fn resolve_against(&self, base: &http::Uri) -> anyhow::Result<http::Uri> {
+        resolve_url(&self.default_url(), base)
+    }
+}
+
+/// Resolves a server URL against the URL of the document; see [Server::resolve_against].
+pub(crate) fn resolve_url(url: &str, base: &http::Uri) -> anyhow::Result<http::Uri> {
+    if url.contains("://") {
+        return Ok(url.parse()?);
+    }
+    let (Some(scheme), Some(authority)) = (base.scheme_str(), base.authority()) else {
+        anyhow::bail!("Base URL {} is not absolute", base);
+    };
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    let resolved = if let Some(rest) = url.strip_prefix("//") {
+        format!("{}://{}", scheme, rest)
+    } else {
+        let path = if url.starts_with('/') {
+            url.to_string()
+        } else {
+            let base_path = base.path();
+            format!("{}{}", &base_path[..base_path.rfind('/').map(|i| i + 1).unwrap_or(0)], url)
+        };
+        format!("{}://{}{}", scheme, authority, remove_dot_segments(&path))
+    };
+    Ok(resolved.parse()?)
+}
+
+/// Resolves a server URL against the URL of the document if there is one, failing for relative
+/// URLs otherwise.
+pub(crate) fn absolute_url(url: &str, base: Option<&http::Uri>) -> anyhow::Result<http::Uri> {
+    match base {
+        Some(base) => resolve_url(url, base),
+        None if url.contains("://") => Ok(url.parse()?),
+        None => anyhow::bail!("Cannot resolve relative server URL {} without a document base URL", url),
+    }
+}
+
+/// Removes `.` and `..` segments from an absolute path.
+fn remove_dot_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path.split('/').skip(1).peekable();
+    while let Some(part) = parts.next() {
+        let last = parts.peek().is_none();
+        match part {
+            "." | ".." => {
+                if part == ".." {
+                    segments.pop();
+                }
+                if last {
+                    segments.push("");
+                }
+            }
+            _ => segments.push(part),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_against() {
+        let base: http::Uri = "https://example.com/docs/openapi.yaml".parse().unwrap();
+        let resolve = |url: &str| Server { url: url.to_string(), ..Server::default() }.resolve_against(&base).unwrap().to_string();
+        assert_eq!(resolve("/api/v1"), "https://example.com/api/v1");
+        assert_eq!(resolve("v1"), "https://example.com/docs/v1");
+        assert_eq!(resolve("../v2/"), "https://example.com/v2/");
+        assert_eq!(resolve("//cdn.example.com/api"), "https://cdn.example.com/api");
+        assert_eq!(resolve("http://other.com/x"), "http://other.com/x");
+        let relative: http::Uri = "/docs".parse().unwrap();
+        assert!(Server { url: "/api".to_string(), ..Server::default() }.resolve_against(&relative).is_err());
+    }
+
+    #[test]
+    fn test_url_with() {
+        let mut server = Server { url: "https://{region}.example.com/{version}".to_string(), ..Server::default() };
+        let variable = |default: &str, values: &[&str]| ServerVariable {
+            default: default.to_string(),
+            enumeration: values.iter().map(|v| v.to_string()).collect(),
+            ..ServerVariable::default()
+        };
+        server.variables = Some(vec![
+            ("region".to_string(), variable("eu", &["eu", "us"])),
+            ("version".to_string(), variable("v1", &[])),
+        ].into_iter().collect());
+        assert_eq!(server.url_with([("version", "v2")]).unwrap(), "https://eu.example.com/v2");
+        assert_eq!(server.url_with(Vec::<(&str, &str)>::new()).unwrap(), server.default_url());
+        assert!(server.url_with([("region", "ap")]).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+
+use crate::*;
+
+/// Splits an absolute URL into scheme, host labels and path segments.
+fn split_server_url(url: &str) -> Result<(&str, Vec<&str>, Vec<&str>)> {
+    let Some((scheme, rest)) = url.split_once("://") else { bail!("Server URL is not absolute: {}", url) };
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let path = path.trim_end_matches('/');
+    let segments = if path.is_empty() { Vec::new() } else { path.split('/').collect() };
+    Ok((scheme, host.split('.').collect(), segments))
+}
+
+/// Builds a template for one part of the URL. Positions where the values differ become
+/// variables, named by `name` from their index among the variables of this part.
+/// If the values have different numbers of components, the whole part becomes one variable.
+fn template_part(
+    values: &[Vec<&str>],
+    separator: &str,
+    name: impl Fn(usize) -> String,
+    variables: &mut IndexMap<String, ServerVariable>,
+) -> String {
+    let mut add_variable = |name: String, column: Vec<String>| {
+        let mut enumeration: Vec<String> = Vec::new();
+        for value in column {
+            if !enumeration.contains(&value) {
+                enumeration.push(value);
+            }
+        }
+        let placeholder = format!("{{{}}}", name);
+        variables.insert(name, ServerVariable {
+            default: enumeration[0].clone(),
+            enumeration,
+            ..ServerVariable::default()
+        });
+        placeholder
+    };
+    let len = values[0].len();
+    if values.iter().any(|v| v.len() != len) {
+        return add_variable(name(0), values.iter().map(|v| v.join(separator)).collect());
+    }
+    let mut count = 0;
+    (0..len)
+        .map(|i| {
+            let column: Vec<String> = values.iter().map(|v| v[i].to_string()).collect();
+            if column.iter().all(|c| *c == column[0]) {
+                return column[0].clone();
+            }
+            count += 1;
+            add_variable(name(count - 1), column)
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn numbered(base: &str) -> impl Fn(usize) -> String + '_ {
+    move |i| if i == 0 { base.to_string() } else { format!("{}{}", base, i + 1) }
+}
+
+impl OpenAPI {
+    /// Replaces the document's absolute servers with a single templated server, e.g.
+    /// `https://acme.api.example.com/v1` and `https://globex.api.example.com/v2` become
+    /// `https://{tenant}.api.example.com/{basePath}`.
+    ///
+    /// Host labels and path segments which differ between servers become variables, whose
+    /// enumeration lists the values seen and whose default is the first server's value. Host
+    /// variables are named `tenant`, `tenant2`, and so on, and path variables `basePath`,
+    /// `basePath2`. Servers with variables are expanded with their defaults first. The new server
+    /// keeps the first server's description, and the extensions of every server, the earlier
+    /// server's value winning when they disagree.
+    ///
+    /// Fails if there are fewer than two servers, a URL is relative, or the schemes differ.
+    pub fn consolidate_servers(&mut self) -> Result<&Server> {
+        if self.servers.len() < 2 {
+            bail!("Need at least two servers to consolidate");
+        }
+        let urls: Vec<String> = self.servers.iter().map(|s| s.default_url()).collect();
+        let parts = urls.iter().map(|u| split_server_url(u)).collect::<Result<Vec<_>>>()?;
+        let scheme = parts[0].0;
+        if let Some((other, _, _)) = parts.iter().find(|(s, _, _)| *s != scheme) {
+            bail!("Servers use different schemes: {} and {}", scheme, other);
+        }
+        let hosts: Vec<Vec<&str>> = parts.iter().map(|(_, h, _)| h.clone()).collect();
+        let paths: Vec<Vec<&str>> = parts.iter().map(|(_, _, p)| p.clone()).collect();
+
+        let mut variables = IndexMap::new();
+        let host = template_part(&hosts, ".", numbered("tenant"), &mut variables);
+        let path = template_part(&paths, "/", numbered("basePath"), &mut variables);
+        let mut url = format!("{}://{}", scheme, host);
+        if !path.is_empty() {
+            url.push('/');
+            url.push_str(&path);
+        }
+        let mut extensions = IndexMap::new();
+        for server in &self.servers {
+            for (key, value) in &server.extensions {
+                extensions.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        self.servers = vec![Server {
+            url,
+            description: self.servers[0].description.clone(),
+            variables: if variables.is_empty() { None } else { Some(variables) },
+            extensions,
+        }];
+        Ok(&self.servers[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consolidate_servers() {
+        let mut spec = OpenAPI::default()
+            .with_server("https://acme.api.example.com/v1")
+            .with_server("https://globex.api.example.com/v2")
+            .with_server("https://acme.api.example.com/v2");
+        let server = spec.consolidate_servers().unwrap().clone();
+        assert_eq!(server.url, "https://{tenant}.api.example.com/{basePath}");
+        let variables = server.variables.as_ref().unwrap();
+        assert_eq!(variables["tenant"].enumeration, vec!["acme", "globex"]);
+        assert_eq!(variables["basePath"].enumeration, vec!["v1", "v2"]);
+        assert_eq!(server.default_url(), "https://acme.api.example.com/v1");
+
+        let mut spec = OpenAPI::default()
+            .with_server("https://acme.api.example.com/v1")
+            .with_server("https://globex.api.example.com/v1");
+        spec.servers[0].description = Some("Production".to_string());
+        spec.servers[0].extensions.insert("x-region".to_string(), "eu".into());
+        spec.servers[1].extensions.insert("x-region".to_string(), "us".into());
+        spec.servers[1].extensions.insert("x-internal".to_string(), true.into());
+        let server = spec.consolidate_servers().unwrap();
+        assert_eq!(server.description.as_deref(), Some("Production"));
+        assert_eq!(server.extensions["x-region"], "eu");
+        assert_eq!(server.extensions["x-internal"], true);
+
+        let mut spec = OpenAPI::default()
+            .with_server("https://a.example.com/api")
+            .with_server("https://a.example.com/api/beta");
+        assert_eq!(spec.consolidate_servers().unwrap().url, "https://a.example.com/{basePath}");
+        assert!(OpenAPI::default().with_server("/api").with_server("/v2").consolidate_servers().is_err());
+    }
+}
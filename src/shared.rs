@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::*;
+use crate::media_type::find_media_type;
+use crate::util::escape_pointer_segment;
+
+/// An immutable [OpenAPI] document with lookup indexes, shared behind an [Arc].
+///
+/// Cloning is cheap. `SharedSpec` is `Send + Sync`, so one parsed spec can be handed to every
+/// worker of a server. [SharedSpec::make_mut] modifies the document copy-on-write: other clones
+/// keep seeing the old version, and the indexes are rebuilt once the change is done.
+#[derive(Clone)]
+pub struct SharedSpec {
+    indexed: Arc<Indexed>,
+    instrumentation: Option<Arc<dyn Instrumentation>>,
+}
+
+#[derive(Debug, Clone)]
+struct Indexed {
+    spec: OpenAPI,
+    operation_ids: HashMap<String, (String, PathMethod)>,
+    /// Path templates split into segments, with those having more literal segments first.
+    templates: Vec<(Vec<String>, String)>,
+    /// The paths of the server URLs, longest first, e.g. `/v1` for `https://api.example.com/v1`.
+    base_paths: Vec<String>,
+}
+
+impl Indexed {
+    fn new(spec: OpenAPI) -> Self {
+        let mut indexed = Indexed {
+            spec,
+            operation_ids: HashMap::new(),
+            templates: Vec::new(),
+            base_paths: Vec::new(),
+        };
+        indexed.reindex();
+        indexed
+    }
+
+    fn reindex(&mut self) {
+        self.operation_ids = self.spec.operations()
+            .filter_map(|(path, method, op, _)| {
+                let method = PathMethod::from_bytes(method.to_uppercase().as_bytes()).ok()?;
+                Some((op.operation_id.clone()?, (path.to_string(), method)))
+            })
+            .collect();
+        self.templates = self.spec.paths.keys()
+            .map(|path| (path.split('/').map(String::from).collect(), path.clone()))
+            .collect();
+        // More literal segments first, then more literal characters, so `/pets/mine` is tried
+        // before `/pets/{id}`, and `/files/{name}.json` before `/files/{name}`.
+        self.templates.sort_by_key(|(segments, _)| {
+            let literal_segments = segments.iter().filter(|s| !s.contains('{')).count();
+            let literal_chars: usize = segments.iter()
+                .flat_map(|s| template_parts(s))
+                .filter(|(_, is_placeholder)| !is_placeholder)
+                .map(|(literal, _)| literal.len())
+                .sum();
+            std::cmp::Reverse((literal_segments, literal_chars))
+        });
+        self.base_paths = self.spec.servers.iter()
+            .map(|server| server_base_path(&server.default_url()))
+            .filter(|base| !base.is_empty())
+            .collect();
+        self.base_paths.sort_by_key(|base| std::cmp::Reverse(base.len()));
+    }
+}
+
+/// The path of a server URL without a trailing slash, e.g. `/v1` for `https://api.example.com/v1/`.
+fn server_base_path(url: &str) -> String {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    let path = match url.split_once("//") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or_default(),
+        None => url,
+    };
+    path.trim_end_matches('/').to_string()
+}
+
+/// Splits a template segment into literal parts and `{placeholder}` parts, e.g. `{name}.json`
+/// into `name` (a placeholder) and `.json`.
+fn template_parts(segment: &str) -> Vec<(&str, bool)> {
+    let mut parts = Vec::new();
+    let mut rest = segment;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else { break };
+        if start > 0 {
+            parts.push((&rest[..start], false));
+        }
+        parts.push((&rest[start + 1..end], true));
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push((rest, false));
+    }
+    parts
+}
+
+/// Matches a request path segment against a template segment. Placeholders match at least one character.
+fn segment_matches(parts: &[(&str, bool)], segment: &str) -> bool {
+    match parts.split_first() {
+        None => segment.is_empty(),
+        Some(((literal, false), rest)) => segment.strip_prefix(literal).is_some_and(|segment| segment_matches(rest, segment)),
+        Some(((_, true), rest)) => (1..=segment.len())
+            .filter(|&i| segment.is_char_boundary(i))
+            .any(|i| segment_matches(rest, &segment[i..])),
+    }
+}
+
+impl SharedSpec {
+    pub fn new(spec: OpenAPI) -> Self {
+        SharedSpec {
+            indexed: Arc::new(Indexed::new(spec)),
+            instrumentation: None,
+        }
+    }
+
+    /// Reports routing and validation to `hook`, see [Instrumentation].
+    pub fn with_instrumentation(mut self, hook: Arc<dyn Instrumentation>) -> Self {
+        self.instrumentation = Some(hook);
+        self
+    }
+
+    pub fn spec(&self) -> &OpenAPI {
+        &self.indexed.spec
+    }
+
+    /// Finds an operation by operationId, returning its path and method.
+    pub fn operation_by_id(&self, operation_id: &str) -> Option<(&str, &PathMethod, &Operation)> {
+        let (path, method) = self.indexed.operation_ids.get(operation_id)?;
+        let operation = self.indexed.spec.paths.get(path)?.as_item()?.operation(method)?;
+        Some((path, method, operation))
+    }
+
+    /// Finds the path template matching a concrete request path, e.g. `/pets/{id}` for `/pets/1`
+    /// or `/files/{name}.json` for `/files/report.json`. Templates with more literal segments
+    /// take precedence.
+    ///
+    /// The query string is ignored, and the path of the server URL, such as `/v1` of
+    /// `https://api.example.com/v1`, is stripped if the request path starts with it.
+    pub fn match_path(&self, path: &str) -> Option<&str> {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let path = self.indexed.base_paths.iter()
+            .find_map(|base| path.strip_prefix(base.as_str()).filter(|rest| rest.starts_with('/')))
+            .unwrap_or(path);
+        let segments: Vec<&str> = path.split('/').collect();
+        self.indexed.templates.iter()
+            .find(|(template, _)| {
+                template.len() == segments.len() && template.iter().zip(&segments)
+                    .all(|(t, s)| segment_matches(&template_parts(t), s))
+            })
+            .map(|(_, template)| template.as_str())
+    }
+
+    /// Finds the operation handling a request, returning its path template.
+    pub fn match_operation(&self, method: &PathMethod, path: &str) -> Option<(&str, &Operation)> {
+        let found = self.match_path(path)
+            .and_then(|template| Some((template, self.indexed.spec.paths.get(template)?.as_item()?.operation(method)?)));
+        if let Some(hook) = &self.instrumentation {
+            match found {
+                Some((template, operation)) => hook.operation_matched(method, template, operation),
+                None => hook.operation_unmatched(method, path),
+            }
+        }
+        found
+    }
+
+    /// Validates a request body against the schema the operation handling the request declares
+    /// for `content_type`. Fails if no operation handles the request, or it has no schema for
+    /// the content type.
+    pub fn validate_request_body(&self, method: &PathMethod, path: &str, content_type: &str, body: &Value) -> Result<Vec<ValidationError>> {
+        let (template, operation) = self.match_operation(method, path)
+            .ok_or_else(|| anyhow!("No operation handles {} {}", method, path))?;
+        let request_body = operation.request_body.as_ref()
+            .ok_or_else(|| anyhow!("{} {} has no request body", method, template))?
+            .resolve(&self.indexed.spec)?;
+        let (mime, media) = find_media_type(&request_body.content, content_type)
+            .ok_or_else(|| anyhow!("{} {} doesn't accept {}", method, template, content_type))?;
+        let schema = media.schema.as_ref()
+            .ok_or_else(|| anyhow!("{} {} has no schema for {}", method, template, mime))?;
+        let errors = schema.validate_value(body, &self.indexed.spec);
+        if let Some(hook) = &self.instrumentation {
+            let label = match (schema.as_ref_str(), operation.request_body.as_ref().and_then(|b| b.as_ref_str())) {
+                (Some(reference), _) => reference.to_string(),
+                (None, Some(body_ref)) => format!("{}/content/{}/schema", body_ref, escape_pointer_segment(mime)),
+                (None, None) => format!(
+                    "#/paths/{}/{}/requestBody/content/{}/schema",
+                    escape_pointer_segment(template), method.as_str().to_lowercase(), escape_pointer_segment(mime),
+                ),
+            };
+            hook.schema_validated(&ValidationEvent {
+                method,
+                path: template,
+                operation_id: operation.operation_id.as_deref(),
+                schema: &label,
+                errors: &errors,
+            });
+        }
+        Ok(errors)
+    }
+
+    /// Modifies the document, cloning it first if other clones of this `SharedSpec` exist.
+    /// Indexes are rebuilt when the returned guard is dropped.
+    pub fn make_mut(&mut self) -> SharedSpecMut<'_> {
+        SharedSpecMut(Arc::make_mut(&mut self.indexed))
+    }
+
+    /// Returns the document, cloning it if other clones of this `SharedSpec` exist.
+    pub fn into_inner(self) -> OpenAPI {
+        match Arc::try_unwrap(self.indexed) {
+            Ok(indexed) => indexed.spec,
+            Err(shared) => shared.spec.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for SharedSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedSpec")
+            .field("spec", &self.indexed.spec)
+            .field("instrumented", &self.instrumentation.is_some())
+            .finish()
+    }
+}
+
+impl std::ops::Deref for SharedSpec {
+    type Target = OpenAPI;
+
+    fn deref(&self) -> &Self::Target {
+        &self.indexed.spec
+    }
+}
+
+impl From<OpenAPI> for SharedSpec {
+    fn from(spec: OpenAPI) -> Self {
+        SharedSpec::new(spec)
+    }
+}
+
+/// Mutable access to the document of a [SharedSpec], from [SharedSpec::make_mut].
+pub struct SharedSpecMut<'a>(&'a mut Indexed);
+
+impl std::ops::Deref for SharedSpecMut<'_> {
+    type Target = OpenAPI;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.spec
+    }
+}
+
+impl std::ops::DerefMut for SharedSpecMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0.spec
+    }
+}
+
+impl Drop for SharedSpecMut<'_> {
+    fn drop(&mut self) {
+        self.0.reindex();
+    }
+}
+
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedSpec>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared() {
+        let mut spec = OpenAPI::default();
+        let op = |id: &str| Operation {
+            operation_id: Some(id.to_string()),
+            ..Operation::default()
+        };
+        spec.paths.insert_operation("/pets/{id}".to_string(), PathMethod::GET, op("getPet"));
+        spec.paths.insert_operation("/pets/mine".to_string(), PathMethod::GET, op("getMine"));
+        let shared = SharedSpec::new(spec);
+
+        let (path, method, _) = shared.operation_by_id("getPet").unwrap();
+        assert_eq!((path, method), ("/pets/{id}", &PathMethod::GET));
+        assert_eq!(shared.match_path("/pets/1"), Some("/pets/{id}"));
+        assert_eq!(shared.match_path("/pets/mine"), Some("/pets/mine"));
+        assert_eq!(shared.match_path("/pets/"), None);
+        assert_eq!(shared.match_path("/pets/1?fields=name"), Some("/pets/{id}"));
+        assert!(shared.match_operation(&PathMethod::POST, "/pets/1").is_none());
+
+        let mut edited = shared.clone();
+        edited.make_mut().paths.insert_operation("/users".to_string(), PathMethod::POST, op("addUser"));
+        assert!(edited.operation_by_id("addUser").is_some());
+        assert!(shared.operation_by_id("addUser").is_none());
+        assert_eq!(shared.paths.len(), 2);
+    }
+
+    #[test]
+    fn test_match_path() {
+        let mut spec = OpenAPI::default();
+        spec.servers.push(Server { url: "https://api.example.com/v1/".to_string(), ..Server::default() });
+        for path in ["/files/{name}", "/files/{name}.json", "/files/{name}.{ext}", "/v1"] {
+            spec.paths.insert_operation(path.to_string(), PathMethod::GET, Operation::default());
+        }
+        let shared = SharedSpec::new(spec);
+        assert_eq!(shared.match_path("/v1/files/report.json"), Some("/files/{name}.json"));
+        assert_eq!(shared.match_path("/files/report.json?download=true"), Some("/files/{name}.json"));
+        assert_eq!(shared.match_path("/files/report.csv"), Some("/files/{name}.{ext}"));
+        assert_eq!(shared.match_path("/files/report"), Some("/files/{name}"));
+        assert_eq!(shared.match_path("/files/.json"), Some("/files/{name}"));
+        assert_eq!(shared.match_path("/v1"), Some("/v1"));
+    }
+
+    #[test]
+    fn test_instrumentation() {
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl Instrumentation for Recorder {
+            fn operation_matched(&self, method: &PathMethod, path: &str, _operation: &Operation) {
+                self.0.lock().unwrap().push(format!("{} {}", method, path));
+            }
+
+            fn operation_unmatched(&self, method: &PathMethod, request_path: &str) {
+                self.0.lock().unwrap().push(format!("unmatched {} {}", method, request_path));
+            }
+
+            fn schema_validated(&self, event: &ValidationEvent) {
+                self.0.lock().unwrap().push(format!("{} {} errors", event.schema, event.errors.len()));
+            }
+        }
+
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        pet.properties_mut().insert("name", Schema::new_string());
+        spec.schemas.insert("Pet", pet);
+        let op = Operation {
+            request_body: Some(RequestBody::json(RefOr::schema_ref("Pet")).into()),
+            ..Operation::default()
+        };
+        spec.paths.insert_operation("/pets/{id}".to_string(), PathMethod::PUT, op);
+        let recorder = Arc::new(Recorder::default());
+        let shared = SharedSpec::new(spec).with_instrumentation(recorder.clone());
+
+        let errors = shared.validate_request_body(&PathMethod::PUT, "/pets/1", "application/json", &serde_json::json!({"name": 1})).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(shared.validate_request_body(&PathMethod::PUT, "/pets/1", "text/plain", &Value::Null).is_err());
+        assert!(shared.match_operation(&PathMethod::GET, "/users").is_none());
+        assert_eq!(*recorder.0.lock().unwrap(), vec![
+            "PUT /pets/{id}",
+            "#/components/schemas/Pet 1 errors",
+            "PUT /pets/{id}",
+            "unmatched GET /users",
+        ]);
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Result};
+
+use crate::*;
+
+/// A piece of an API, such as one feature crate's operations and schemas, which can be assembled
+/// with others into a single spec by [OpenAPI::collect].
+pub trait SpecFragment {
+    /// Adds this fragment's paths, components, tags and so on to `spec`.
+    fn register(&self, spec: &mut OpenAPI);
+
+    /// Identifies the fragment in collision errors.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl<F: Fn(&mut OpenAPI)> SpecFragment for F {
+    fn register(&self, spec: &mut OpenAPI) {
+        self(spec)
+    }
+}
+
+fn merge_components<T: PartialEq>(kind: ComponentKind, into: &mut RefOrMap<T>, from: RefOrMap<T>, fragment: &str) -> Result<()> {
+    for (name, component) in from {
+        match into.get(&name) {
+            Some(existing) if *existing != component => {
+                return Err(anyhow!("{} defines {} differently from an earlier fragment", fragment, kind.reference(&name)));
+            }
+            Some(_) => {}
+            None => {
+                into.insert(name, component);
+            }
+        }
+    }
+    Ok(())
+}
+
+impl OpenAPI {
+    /// Builds a spec from fragments. Each fragment registers into an empty spec, which is then
+    /// merged into the result.
+    ///
+    /// Fails if two fragments define the same operation (path and method), the same `operationId`,
+    /// or a component or webhook of the same name with different content. Identical components, webhooks,
+    /// tags and servers are shared. The first fragment to set `info` provides it.
+    pub fn collect<'a>(fragments: impl IntoIterator<Item=&'a dyn SpecFragment>) -> Result<OpenAPI> {
+        let mut spec = OpenAPI::default();
+        for fragment in fragments {
+            let mut part = OpenAPI::default();
+            fragment.register(&mut part);
+            spec.absorb(part, fragment.name())?;
+        }
+        Ok(spec)
+    }
+
+    fn absorb(&mut self, part: OpenAPI, fragment: &str) -> Result<()> {
+        if self.info == Info::default() {
+            self.info = part.info;
+        }
+        for (path, item) in part.paths {
+            let item = item.into_item().ok_or_else(|| anyhow!("{} registers {} as a reference", fragment, path))?;
+            // PathItem::iter skips other keys, so they would be dropped silently.
+            if let Some(method) = item.other.keys().find(|m| !EXTENSION_METHODS.contains(&m.as_str())) {
+                return Err(anyhow!("{} registers unsupported method {} on {}", fragment, method, path));
+            }
+            for (method, op) in item.iter() {
+                if let Some(id) = &op.operation_id {
+                    if self.operations().any(|(_, _, o, _)| o.operation_id.as_ref() == Some(id)) {
+                        return Err(anyhow!("{} reuses operationId {}", fragment, id));
+                    }
+                }
+                if self.paths.get(&path).and_then(|i| i.as_item()).is_some_and(|i| i.iter().any(|(m, _)| m == method)) {
+                    return Err(anyhow!("{} redefines {} {}", fragment, method.to_uppercase(), path));
+                }
+            }
+            match self.paths.get_mut(&path) {
+                None => {
+                    self.paths.insert(path, item);
+                }
+                Some(existing) => {
+                    let existing = existing.as_mut().ok_or_else(|| anyhow!("PathItem {} is a reference", path))?;
+                    if !existing.parameters.is_empty() && !item.parameters.is_empty() && existing.parameters != item.parameters {
+                        return Err(anyhow!("{} declares different parameters for {}", fragment, path));
+                    }
+                    for (method, op) in item.iter() {
+                        let method = PathMethod::from_bytes(method.to_uppercase().as_bytes())?;
+                        if !PathItem::supports_method(&method) {
+                            return Err(anyhow!("{} registers unsupported method {} on {}", fragment, method, path));
+                        }
+                        existing.set(method, op.clone());
+                    }
+                    if existing.parameters.is_empty() {
+                        existing.parameters = item.parameters;
+                    }
+                    existing.summary = existing.summary.take().or(item.summary);
+                    existing.description = existing.description.take().or(item.description);
+                    for server in item.servers {
+                        if !existing.servers.contains(&server) {
+                            existing.servers.push(server);
+                        }
+                    }
+                    for (key, value) in item.extensions {
+                        existing.extensions.entry(key).or_insert(value);
+                    }
+                }
+            }
+        }
+        for (name, webhook) in part.webhooks {
+            match self.webhooks.get(&name) {
+                Some(existing) if *existing != webhook => return Err(anyhow!("{} defines webhook {} differently from an earlier fragment", fragment, name)),
+                Some(_) => {}
+                None => {
+                    self.webhooks.insert(name, webhook);
+                }
+            }
+        }
+        let components = part.components;
+        merge_components(ComponentKind::SecurityScheme, &mut self.components.security_schemes, components.security_schemes, fragment)?;
+        merge_components(ComponentKind::Response, &mut self.components.responses, components.responses, fragment)?;
+        merge_components(ComponentKind::Parameter, &mut self.components.parameters, components.parameters, fragment)?;
+        merge_components(ComponentKind::Example, &mut self.components.examples, components.examples, fragment)?;
+        merge_components(ComponentKind::RequestBody, &mut self.components.request_bodies, components.request_bodies, fragment)?;
+        merge_components(ComponentKind::Header, &mut self.components.headers, components.headers, fragment)?;
+        merge_components(ComponentKind::Schema, &mut self.components.schemas, components.schemas, fragment)?;
+        merge_components(ComponentKind::Link, &mut self.components.links, components.links, fragment)?;
+        merge_components(ComponentKind::Callback, &mut self.components.callbacks, components.callbacks, fragment)?;
+        for tag in part.tags {
+            match self.tags.iter().find(|t| t.name == tag.name) {
+                Some(existing) if *existing != tag => return Err(anyhow!("{} defines tag {} differently from an earlier fragment", fragment, tag.name)),
+                Some(_) => {}
+                None => self.tags.push(tag),
+            }
+        }
+        for server in part.servers {
+            if !self.servers.contains(&server) {
+                self.servers.push(server);
+            }
+        }
+        for requirement in part.security {
+            if !self.security.contains(&requirement) {
+                self.security.push(requirement);
+            }
+        }
+        for (key, value) in part.extensions {
+            self.extensions.entry(key).or_insert(value);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pets;
+
+    impl SpecFragment for Pets {
+        fn register(&self, spec: &mut OpenAPI) {
+            spec.schemas.insert("Pet", Schema::new_object());
+            let mut op = Operation {
+                operation_id: Some("listPets".to_string()),
+                ..Operation::default()
+            };
+            op.add_response_success_json(Some(RefOr::schema_ref("Pet")));
+            spec.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_collect() {
+        let create = |spec: &mut OpenAPI| {
+            spec.schemas.insert("Pet", Schema::new_object());
+            let op = Operation { operation_id: Some("createPet".to_string()), ..Operation::default() };
+            spec.add_operation(PathMethod::POST, "/pets", op).unwrap();
+        };
+        let spec = OpenAPI::collect(vec![&Pets as &dyn SpecFragment, &create]).unwrap();
+        let item = spec.paths.get("/pets").unwrap().as_item().unwrap();
+        assert!(item.get.is_some() && item.post.is_some());
+        assert_eq!(spec.schemas.len(), 1);
+
+        let err = OpenAPI::collect(vec![&Pets as &dyn SpecFragment, &Pets]).unwrap_err();
+        assert_eq!(err.to_string(), format!("{} reuses operationId listPets", std::any::type_name::<Pets>()));
+
+        let conflicting = |spec: &mut OpenAPI| {
+            spec.schemas.insert("Pet", Schema::new_string());
+        };
+        let err = OpenAPI::collect(vec![&Pets as &dyn SpecFragment, &conflicting]).unwrap_err();
+        assert!(err.to_string().ends_with("defines #/components/schemas/Pet differently from an earlier fragment"));
+
+        let hooks = |spec: &mut OpenAPI| {
+            spec.webhooks.insert("newPet".to_string(), RefOr::Item(PathItem::default()));
+            let mut item = PathItem::default();
+            item.extensions.insert("x-rate-limit".to_string(), 10.into());
+            spec.paths.insert("/pets".to_string(), item);
+        };
+        let spec = OpenAPI::collect(vec![&Pets as &dyn SpecFragment, &hooks, &hooks]).unwrap();
+        assert!(spec.webhooks.contains_key("newPet"));
+        assert_eq!(spec.paths.get("/pets").unwrap().as_item().unwrap().extensions["x-rate-limit"], 10);
+
+        let custom = |spec: &mut OpenAPI| {
+            let mut item = PathItem::default();
+            item.other.insert("frobnicate".to_string(), Operation::default());
+            spec.paths.insert("/pets".to_string(), item);
+        };
+        for fragments in [vec![&custom as &dyn SpecFragment], vec![&Pets as &dyn SpecFragment, &custom]] {
+            let err = OpenAPI::collect(fragments).unwrap_err();
+            assert!(err.to_string().ends_with("registers unsupported method frobnicate on /pets"), "{}", err);
+        }
+    }
+}
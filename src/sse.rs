@@ -0,0 +1,196 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{escape_pointer_segment, to_json};
+use crate::validate::path_pointer;
+
+/// The media type of server-sent event streams.
+pub const EVENT_STREAM: &str = "text/event-stream";
+
+const SSE_EXTENSION: &str = "x-sse-events";
+
+/// Returns true for media types whose bodies are a stream of messages rather than a single
+/// document, such as `text/event-stream`. Media type parameters are ignored.
+pub fn is_streaming_media_type(mime: &str) -> bool {
+    let essence = mime.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    essence == EVENT_STREAM
+}
+
+/// One kind of event sent on a `text/event-stream` response, stored in the `x-sse-events`
+/// extension of its [MediaType].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SseEvent {
+    /// The `event:` field. `None` covers events without one, which clients receive as `message`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    /// The schema of the `data:` payload, which is parsed as JSON unless the schema is a string.
+    pub schema: RefOr<Schema>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A single message parsed from a `text/event-stream` body.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerSentEvent {
+    pub event: Option<String>,
+    pub id: Option<String>,
+    /// The `data:` lines, joined with newlines.
+    pub data: String,
+}
+
+/// Parses a `text/event-stream` body into its messages. Comments, `retry:` fields and
+/// messages without data are skipped.
+pub fn parse_event_stream(body: &str) -> Vec<ServerSentEvent> {
+    let mut events = Vec::new();
+    let mut current = ServerSentEvent::default();
+    let mut data: Vec<&str> = Vec::new();
+    for line in body.lines().chain(std::iter::once("")) {
+        if line.is_empty() {
+            if !data.is_empty() {
+                current.data = data.join("\n");
+                events.push(std::mem::take(&mut current));
+            }
+            current = ServerSentEvent::default();
+            data.clear();
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "data" => data.push(value),
+            "event" => current.event = Some(value.to_string()),
+            "id" => current.id = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    events
+}
+
+impl MediaType {
+    /// Returns the `x-sse-events` of this media type, if it exists and is well-formed.
+    pub fn sse_events(&self) -> Option<Vec<SseEvent>> {
+        serde_json::from_value(self.extensions.get(SSE_EXTENSION)?.clone()).ok()
+    }
+
+    pub fn set_sse_events(&mut self, events: &[SseEvent]) {
+        self.extensions.insert(SSE_EXTENSION.to_string(), to_json(events));
+    }
+
+    /// Returns the schema for the given event type. Events without a matching entry fall back
+    /// to the entry without an `event` name.
+    pub fn sse_event_schema(&self, event: Option<&str>) -> Option<RefOr<Schema>> {
+        let events = self.sse_events()?;
+        events.iter()
+            .find(|e| e.event.as_deref() == event)
+            .or_else(|| events.iter().find(|e| e.event.is_none()))
+            .map(|e| e.schema.clone())
+    }
+}
+
+impl Response {
+    /// Adds `text/event-stream` content whose unnamed events carry `event_schema` as JSON data.
+    /// Add named events with [Response::sse_event].
+    pub fn sse(self, event_schema: impl Into<RefOr<Schema>>) -> Self {
+        self.add_sse_event(None, event_schema.into())
+    }
+
+    /// Declares an event type with the given `event:` name on the `text/event-stream` content.
+    pub fn sse_event(self, event: impl Into<String>, schema: impl Into<RefOr<Schema>>) -> Self {
+        self.add_sse_event(Some(event.into()), schema.into())
+    }
+
+    fn add_sse_event(mut self, event: Option<String>, schema: RefOr<Schema>) -> Self {
+        let media = self.content.entry(EVENT_STREAM.to_string()).or_default();
+        let mut events = media.sse_events().unwrap_or_default();
+        events.retain(|e| e.event != event);
+        events.push(SseEvent { event, schema, description: None });
+        media.set_sse_events(&events);
+        self
+    }
+}
+
+/// Validates each event of a `text/event-stream` body against its declared schema, returning
+/// the parsed data values. Data is parsed as JSON, unless its schema is a string, and data which
+/// isn't JSON is kept as a string. Events without a declared schema aren't checked, since streams
+/// need not have one.
+pub(crate) fn validate_event_stream(media: &MediaType, body: &str, spec: &OpenAPI) -> (Value, Vec<ValidationError>) {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for (i, event) in parse_event_stream(body).into_iter().enumerate() {
+        let schema = media.sse_event_schema(event.event.as_deref());
+        let is_string = schema.as_ref()
+            .and_then(|s| Resolve::resolve(s, spec).ok())
+            .is_some_and(|s| matches!(s.kind, SchemaKind::Type(Type::String(_))));
+        let value = if is_string {
+            Value::String(event.data)
+        } else {
+            serde_json::from_str(&event.data).unwrap_or(Value::String(event.data))
+        };
+        if let Some(schema) = schema {
+            errors.extend(schema.validate_value(&value, spec).into_iter().map(|mut e| {
+                e.pointer = e.pointer.replacen('#', &format!("#/{}", i), 1);
+                e
+            }));
+        }
+        values.push(value);
+    }
+    (Value::Array(values), errors)
+}
+
+/// Reports malformed `x-sse-events` extensions on response content.
+pub(crate) fn validate_sse_path(path: &str, item: &PathItem, errors: &mut Vec<ValidationError>) {
+    for (method, op) in item.iter() {
+        for (code, response) in &op.responses.responses {
+            let Some(response) = response.as_item() else { continue };
+            validate_sse_content(format!("{}/{}/responses/{}", path_pointer(path), method, code), &response.content, errors);
+        }
+        if let Some(RefOr::Item(response)) = &op.responses.default {
+            validate_sse_content(format!("{}/{}/responses/default", path_pointer(path), method), &response.content, errors);
+        }
+    }
+}
+
+pub(crate) fn validate_sse_content(pointer: String, content: &IndexMap<String, MediaType>, errors: &mut Vec<ValidationError>) {
+    for (mime, media) in content {
+        let Some(value) = media.extensions.get(SSE_EXTENSION) else { continue };
+        let pointer = format!("{}/content/{}/{}", pointer, escape_pointer_segment(mime), SSE_EXTENSION);
+        if let Err(e) = serde_json::from_value::<Vec<SseEvent>>(value.clone()) {
+            errors.push(ValidationError::new(pointer, format!("Invalid server-sent events: {}", e)));
+        } else if !is_streaming_media_type(mime) {
+            errors.push(ValidationError::new(pointer, format!("Server-sent events declared on {}", mime)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sse() {
+        let mut spec = OpenAPI::default();
+        let mut tick = Schema::new_object();
+        tick.properties_mut().insert("n", Schema::new_integer());
+        tick.add_required("n");
+        let response = Response::new("Ticks").sse(tick).sse_event("bye", Schema::new_string());
+        let media = &response.content[EVENT_STREAM];
+        assert!(media.schema.is_none());
+        assert_eq!(media.sse_events().unwrap().len(), 2);
+
+        let body = ": comment\ndata: {\"n\": 1}\n\nevent: bye\ndata: done\n\nevent: bye\ndata: 42\n\ndata: {}\n";
+        let (values, errors) = validate_event_stream(media, body, &spec);
+        assert_eq!(values, json!([{"n": 1}, "done", "42", {}]));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].pointer.starts_with("#/3"));
+
+        let mut op = Operation::default();
+        op.responses.responses.insert(StatusCode::Code(200), response.into());
+        spec.add_operation(PathMethod::GET, "/ticks", op).unwrap();
+        assert!(spec.validate().is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 use std::fmt;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -16,6 +17,24 @@ impl fmt::Display for StatusCode {
     }
 }
 
+impl From<http::StatusCode> for StatusCode {
+    fn from(status: http::StatusCode) -> Self {
+        StatusCode::Code(status.as_u16())
+    }
+}
+
+/// Fails for ranges such as `4XX` and for codes outside 100-999.
+impl TryFrom<&StatusCode> for http::StatusCode {
+    type Error = anyhow::Error;
+
+    fn try_from(status: &StatusCode) -> Result<Self, Self::Error> {
+        match status {
+            StatusCode::Code(n) => Ok(http::StatusCode::from_u16(*n)?),
+            StatusCode::Range(_) => Err(anyhow::anyhow!("{} is a range, not a status code", status)),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for StatusCode {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -125,4 +144,12 @@ mod tests {
     fn deserialize_invalid_range() {
         let _: StatusCode = from_str("2XY").unwrap();
     }
+
+    #[test]
+    fn http_status_code_interop() {
+        use std::convert::TryFrom;
+        assert_eq!(http::StatusCode::try_from(&StatusCode::Code(404)).unwrap(), http::StatusCode::NOT_FOUND);
+        assert!(http::StatusCode::try_from(&StatusCode::Range(4)).is_err());
+        assert_eq!(StatusCode::from(http::StatusCode::CREATED), StatusCode::Code(201));
+    }
 }
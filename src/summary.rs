@@ -0,0 +1,291 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::*;
+
+/// One row of a [SpecSummary].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationSummary {
+    pub method: String,
+    pub path: String,
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    /// Alternative security requirements, each rendered as scheme names joined with `+`.
+    /// Empty if the operation needs no authentication.
+    pub auth: Vec<String>,
+    pub deprecated: bool,
+}
+
+/// A compact overview of a document, for CLI output and quick inspection.
+/// Rendered as a text table by its [Display](fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecSummary {
+    pub title: String,
+    pub version: String,
+    pub paths: usize,
+    pub schemas: usize,
+    pub operations: Vec<OperationSummary>,
+}
+
+impl SpecSummary {
+    pub fn deprecated_count(&self) -> usize {
+        self.operations.iter().filter(|op| op.deprecated).count()
+    }
+}
+
+fn render_auth(security: &[SecurityRequirement]) -> Vec<String> {
+    security.iter()
+        .map(|req| if req.is_empty() {
+            "none".to_string()
+        } else {
+            req.keys().cloned().collect::<Vec<_>>().join("+")
+        })
+        .collect()
+}
+
+/// One row of [OpenAPI::to_operation_table]. Lists are joined into single cells, so rows
+/// serialize to flat records, e.g. for CSV.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationRow {
+    pub method: String,
+    pub path: String,
+    pub operation_id: String,
+    pub summary: String,
+    /// Joined with `, `.
+    pub tags: String,
+    /// Alternative security requirements as in [OperationSummary::auth], joined with ` | `.
+    pub auth: String,
+    /// Parameters as `name (location)`, required ones marked with `*`, joined with `, `.
+    pub parameters: String,
+    /// Joined with `, `.
+    pub request_content_types: String,
+    /// Content types of all responses, without duplicates, joined with `, `.
+    pub response_content_types: String,
+    pub deprecated: bool,
+}
+
+impl OperationRow {
+    const HEADER: [&'static str; 10] = [
+        "method", "path", "operation_id", "summary", "tags", "auth", "parameters",
+        "request_content_types", "response_content_types", "deprecated",
+    ];
+
+    fn cells(&self) -> [&str; 10] {
+        [
+            &self.method, &self.path, &self.operation_id, &self.summary, &self.tags, &self.auth,
+            &self.parameters, &self.request_content_types, &self.response_content_types,
+            if self.deprecated { "true" } else { "false" },
+        ]
+    }
+}
+
+/// Quotes a cell where needed. Cells which a spreadsheet would read as a formula get a leading `'`.
+fn csv_cell(cell: &str) -> String {
+    let cell = if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", cell)
+    } else {
+        cell.to_string()
+    };
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell
+    }
+}
+
+impl OpenAPI {
+    /// Flattens the operations into one row each, in document order, for spreadsheets.
+    /// References which don't resolve are skipped.
+    pub fn to_operation_table(&self) -> Vec<OperationRow> {
+        self.operations()
+            .map(|(path, method, op, item)| {
+                let parameters: Vec<String> = op.effective_parameters(item, self).iter()
+                    .map(|p| format!("{} ({}){}", p.name, p.kind.location(), if p.required { "*" } else { "" }))
+                    .collect();
+                let request_content_types: Vec<&str> = op.request_body.as_ref()
+                    .and_then(|b| b.resolve(self).ok())
+                    .map(|b| b.content.keys().map(String::as_str).collect())
+                    .unwrap_or_default();
+                let mut response_content_types: Vec<&str> = Vec::new();
+                for response in op.responses.responses.values().chain(&op.responses.default).filter_map(|r| r.resolve(self).ok()) {
+                    for mime in response.content.keys() {
+                        if !response_content_types.contains(&mime.as_str()) {
+                            response_content_types.push(mime);
+                        }
+                    }
+                }
+                OperationRow {
+                    method: method.to_uppercase(),
+                    path: path.to_string(),
+                    operation_id: op.operation_id.clone().unwrap_or_default(),
+                    summary: op.summary.clone().unwrap_or_default(),
+                    tags: op.tags.join(", "),
+                    auth: render_auth(op.security.as_ref().unwrap_or(&self.security)).join(" | "),
+                    parameters: parameters.join(", "),
+                    request_content_types: request_content_types.join(", "),
+                    response_content_types: response_content_types.join(", "),
+                    deprecated: op.deprecated,
+                }
+            })
+            .collect()
+    }
+
+    /// Renders [OpenAPI::to_operation_table] as CSV (RFC 4180), with a header row. Cells starting
+    /// with `=`, `+`, `-` or `@` are prefixed with `'`, so spreadsheets don't evaluate them.
+    pub fn to_operation_csv(&self) -> String {
+        let mut csv = format!("{}\r\n", OperationRow::HEADER.join(","));
+        for row in self.to_operation_table() {
+            let cells: Vec<String> = row.cells().iter().map(|c| csv_cell(c)).collect();
+            csv.push_str(&cells.join(","));
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+
+    /// Builds a [SpecSummary] of the document. Operations are listed in document order.
+    pub fn spec_summary(&self) -> SpecSummary {
+        let operations = self.operations()
+            .map(|(path, method, op, _)| OperationSummary {
+                method: method.to_uppercase(),
+                path: path.to_string(),
+                operation_id: op.operation_id.clone(),
+                summary: op.summary.clone(),
+                auth: render_auth(op.security.as_ref().unwrap_or(&self.security)),
+                deprecated: op.deprecated,
+            })
+            .collect();
+        SpecSummary {
+            title: self.info.title.clone(),
+            version: self.info.version.clone(),
+            paths: self.paths.len(),
+            schemas: self.schemas.len(),
+            operations,
+        }
+    }
+
+    /// Renders a compact text table of the operations and some key stats.
+    pub fn summary(&self) -> String {
+        self.spec_summary().to_string()
+    }
+}
+
+impl fmt::Display for SpecSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.title, self.version)?;
+        writeln!(f, "{} paths, {} operations ({} deprecated), {} schemas",
+            self.paths, self.operations.len(), self.deprecated_count(), self.schemas)?;
+        let rows: Vec<[String; 5]> = self.operations.iter()
+            .map(|op| {
+                let mut summary = op.summary.clone().unwrap_or_default();
+                if op.deprecated {
+                    summary = format!("[deprecated] {}", summary).trim_end().to_string();
+                }
+                [
+                    op.method.clone(),
+                    op.path.clone(),
+                    op.operation_id.clone().unwrap_or_else(|| "-".to_string()),
+                    summary,
+                    if op.auth.is_empty() { "none".to_string() } else { op.auth.join(" | ") },
+                ]
+            })
+            .collect();
+        let header = ["METHOD", "PATH", "OPERATION ID", "SUMMARY", "AUTH"];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+        let write_row = |f: &mut fmt::Formatter<'_>, cells: [&str; 5]| {
+            let line = cells.iter().zip(widths)
+                .map(|(cell, w)| format!("{:<w$}", cell, w = w))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())
+        };
+        write_row(f, header)?;
+        for row in &rows {
+            write_row(f, [&row[0], &row[1], &row[2], &row[3], &row[4]])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.security = vec![vec![("apiKey".to_string(), vec![])].into_iter().collect()];
+        api.add_operation(PathMethod::GET, "/pets", Operation {
+            operation_id: Some("listPets".to_string()),
+            summary: Some("List pets".to_string()),
+            ..Operation::default()
+        }).unwrap();
+        api.add_operation(PathMethod::DELETE, "/pets", Operation {
+            deprecated: true,
+            security: Some(vec![SecurityRequirement::new()]),
+            ..Operation::default()
+        }).unwrap();
+        assert_eq!(api.summary(), "\
+Petstore 1.0.0
+1 paths, 2 operations (1 deprecated), 0 schemas
+METHOD  PATH   OPERATION ID  SUMMARY       AUTH
+GET     /pets  listPets      List pets     apiKey
+DELETE  /pets  -             [deprecated]  none
+");
+    }
+
+    #[test]
+    fn test_operation_table() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        api.security = vec![vec![("apiKey".to_string(), vec![])].into_iter().collect()];
+        let mut op = Operation {
+            operation_id: Some("addPet".to_string()),
+            summary: Some("Add a pet, or two".to_string()),
+            tags: vec!["pets".to_string(), "admin".to_string()],
+            request_body: Some(RequestBody::json(Schema::new_object()).into()),
+            ..Operation::default()
+        };
+        op.add_path_param("store", Schema::new_string());
+        op.add_query_param("dryRun", Schema::new_bool());
+        op.add_response_success_json(Some(Schema::new_object().into()));
+        api.add_operation(PathMethod::POST, "/stores/{store}/pets", op).unwrap();
+
+        let table = api.to_operation_table();
+        assert_eq!(table[0], OperationRow {
+            method: "POST".to_string(),
+            path: "/stores/{store}/pets".to_string(),
+            operation_id: "addPet".to_string(),
+            summary: "Add a pet, or two".to_string(),
+            tags: "pets, admin".to_string(),
+            auth: "apiKey".to_string(),
+            parameters: "store (path)*, dryRun (query)".to_string(),
+            request_content_types: "application/json".to_string(),
+            response_content_types: "application/json".to_string(),
+            deprecated: false,
+        });
+        assert_eq!(serde_json::to_value(&table[0]).unwrap()["operation_id"], "addPet");
+        assert_eq!(api.to_operation_csv(), "\
+method,path,operation_id,summary,tags,auth,parameters,request_content_types,response_content_types,deprecated\r
+POST,/stores/{store}/pets,addPet,\"Add a pet, or two\",\"pets, admin\",apiKey,\"store (path)*, dryRun (query)\",application/json,application/json,false\r
+");
+    }
+
+    #[test]
+    fn test_csv_formula_cells() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let op = Operation {
+            operation_id: Some("@import".to_string()),
+            summary: Some("=HYPERLINK(\"http://example.com\")".to_string()),
+            tags: vec!["-1".to_string()],
+            ..Operation::default()
+        };
+        api.add_operation(PathMethod::GET, "/pets", op).unwrap();
+        let csv = api.to_operation_csv();
+        assert_eq!(csv.lines().nth(1).unwrap(), "GET,/pets,'@import,\"'=HYPERLINK(\"\"http://example.com\"\")\",'-1,,,,,false");
+    }
+}
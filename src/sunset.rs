@@ -0,0 +1,175 @@
+use anyhow::{bail, Result};
+use serde_json::json;
+
+use crate::*;
+
+const SUNSET_EXTENSION: &str = "x-sunset";
+
+/// Parses a `YYYY-MM-DD` date, checking the day exists in its month.
+fn parse_date(date: &str) -> Result<(i64, u32, u32)> {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [y, m, d] = parts.as_slice() else { bail!("Expected a YYYY-MM-DD date: {}", date) };
+    let (Ok(y), Ok(m), Ok(d)) = (y.parse::<i64>(), m.parse::<u32>(), d.parse::<u32>()) else {
+        bail!("Expected a YYYY-MM-DD date: {}", date)
+    };
+    if y < 1970 || !(1..=12).contains(&m) || !(1..=31).contains(&d) || date.len() != 10 {
+        bail!("Expected a YYYY-MM-DD date: {}", date);
+    }
+    // Days past the end of the month roll over into the next one.
+    if civil_from_days(days_from_civil(y, m, d)) != (y, m, d) {
+        bail!("No such date: {}", date);
+    }
+    Ok((y, m, d))
+}
+
+/// Days since 1970-01-01 of a civil date.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The civil date of a number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if m <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, m, d)
+}
+
+/// Formats a `YYYY-MM-DD` date as an HTTP-date at midnight, e.g. `Mon, 30 Jun 2025 00:00:00 GMT`.
+fn http_date(date: &str) -> Result<String> {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let (y, m, d) = parse_date(date)?;
+    let weekday = DAYS[days_from_civil(y, m, d).rem_euclid(7) as usize];
+    Ok(format!("{}, {:02} {} {} 00:00:00 GMT", weekday, d, MONTHS[m as usize - 1], y))
+}
+
+/// Formats a `YYYY-MM-DD` date as a structured field date at midnight, `@` followed by the
+/// seconds since the epoch, e.g. `@1751241600`.
+fn structured_date(date: &str) -> Result<String> {
+    let (y, m, d) = parse_date(date)?;
+    Ok(format!("@{}", days_from_civil(y, m, d) * 86400))
+}
+
+fn today() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (y, m, d) = civil_from_days((secs / 86400) as i64);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+impl Operation {
+    /// Returns the `x-sunset` date of this operation, if set.
+    pub fn sunset(&self) -> Option<&str> {
+        self.extensions.get(SUNSET_EXTENSION)?.as_str()
+    }
+
+    /// Marks the operation deprecated with a sunset date (`YYYY-MM-DD`), recorded in `x-sunset`.
+    /// The `Deprecation` and `Sunset` response headers are documented on each inline response.
+    /// The `Deprecation` header takes an RFC 9745 date such as `@1751241600`; its example is the
+    /// sunset date, the latest the operation may have been deprecated.
+    pub fn set_sunset(&mut self, date: &str) -> Result<()> {
+        let sunset = http_date(date)?;
+        let deprecated = structured_date(date)?;
+        self.deprecated = true;
+        self.extensions.insert(SUNSET_EXTENSION.to_string(), date.into());
+        let responses = self.responses.responses.values_mut().chain(self.responses.default.as_mut());
+        for response in responses.filter_map(|r| r.as_mut()) {
+            let mut schema = Schema::new_string();
+            if let SchemaKind::Type(Type::String(s)) = &mut schema.kind {
+                s.pattern = Some("^@-?[0-9]+$".to_string());
+            }
+            let mut deprecation = Header::new(schema);
+            deprecation.description = Some("When the operation was or will be deprecated, as `@` and seconds since the epoch (RFC 9745).".to_string());
+            deprecation.example = Some(json!(deprecated));
+            response.headers.insert("Deprecation".to_string(), deprecation.into());
+            let mut header = Header::new(Schema::new_string());
+            header.description = Some("When the operation will stop responding (RFC 8594).".to_string());
+            header.example = Some(json!(sunset));
+            response.headers.insert("Sunset".to_string(), header.into());
+        }
+        Ok(())
+    }
+}
+
+/// An operation with a sunset date, listed by [OpenAPI::sunset_report].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SunsetEntry {
+    pub method: String,
+    pub path: String,
+    pub operation_id: Option<String>,
+    pub sunset: String,
+}
+
+impl OpenAPI {
+    /// Lists operations whose `x-sunset` date has passed, in document order.
+    pub fn sunset_report(&self) -> Vec<SunsetEntry> {
+        self.sunset_report_at(&today())
+    }
+
+    /// Lists operations whose `x-sunset` date is before `date` (`YYYY-MM-DD`).
+    /// Malformed sunset dates are skipped.
+    pub fn sunset_report_at(&self, date: &str) -> Vec<SunsetEntry> {
+        self.operations()
+            .filter_map(|(path, method, op, _)| {
+                let sunset = op.sunset()?;
+                if parse_date(sunset).is_err() || sunset >= date {
+                    return None;
+                }
+                Some(SunsetEntry {
+                    method: method.to_uppercase(),
+                    path: path.to_string(),
+                    operation_id: op.operation_id.clone(),
+                    sunset: sunset.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sunset() {
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+        assert_eq!(http_date("2025-06-30").unwrap(), "Mon, 30 Jun 2025 00:00:00 GMT");
+        assert!(http_date("2025-13-01").is_err());
+        assert!(parse_date("2025-02-29").is_err());
+        assert!(parse_date("2024-02-29").is_ok());
+        assert!(parse_date("2025-04-31").is_err());
+        assert_eq!(structured_date("2025-06-30").unwrap(), "@1751241600");
+
+        let mut spec = OpenAPI::default();
+        let mut old = Operation { operation_id: Some("old".to_string()), ..Operation::default() };
+        old.responses.responses.insert(StatusCode::Code(200), Response::new("OK").into());
+        old.set_sunset("2020-01-01").unwrap();
+        assert!(old.deprecated);
+        let headers = &old.responses.responses[0].as_item().unwrap().headers;
+        assert!(headers.contains_key("Sunset"));
+        assert_eq!(headers["Deprecation"].as_item().unwrap().example, Some(json!("@1577836800")));
+        let mut later = Operation { operation_id: Some("later".to_string()), ..Operation::default() };
+        later.set_sunset("2999-01-01").unwrap();
+        spec.add_operation(PathMethod::GET, "/old", old).unwrap();
+        spec.add_operation(PathMethod::GET, "/later", later).unwrap();
+
+        let report = spec.sunset_report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].operation_id.as_deref(), Some("old"));
+        assert_eq!(spec.sunset_report_at("3000-01-01").len(), 2);
+    }
+}
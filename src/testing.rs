@@ -0,0 +1,165 @@
+//! Assertions for tests of code which produces or consumes OpenAPI documents.
+//!
+//! The macros panic with a list of the JSON pointers that differ, rather than a dump of
+//! both documents.
+use serde_json::Value;
+
+use crate::*;
+use crate::util::to_json;
+
+/// Fields ignored by [assert_schemas_equivalent!].
+const DOC_FIELDS: [&str; 5] = ["description", "title", "example", "examples", "externalDocs"];
+
+pub use crate::roundtrip::json_differences;
+
+fn strip_doc_fields(value: &mut Value, is_name_map: bool) {
+    match value {
+        Value::Object(map) => {
+            if !is_name_map {
+                map.retain(|k, _| !DOC_FIELDS.contains(&k.as_str()));
+            }
+            for (key, child) in map.iter_mut() {
+                strip_doc_fields(child, !is_name_map && key == "properties");
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| strip_doc_fields(v, false)),
+        _ => {}
+    }
+}
+
+#[track_caller]
+fn fail(what: &str, differences: &[String]) -> ! {
+    panic!("{}:\n  {}", what, differences.join("\n  "))
+}
+
+/// See [assert_valid_spec!].
+#[track_caller]
+pub fn assert_valid_spec(spec: &OpenAPI) {
+    let errors: Vec<String> = spec.validate().iter().map(|e| e.to_string()).collect();
+    if !errors.is_empty() {
+        fail("spec is invalid", &errors);
+    }
+}
+
+/// See [assert_roundtrip!].
+#[track_caller]
+pub fn assert_roundtrip(yaml: &str) -> OpenAPI {
+    let expected: Value = serde_yaml::from_str(yaml).expect("input is not valid YAML");
+    let spec: OpenAPI = serde_yaml::from_str(yaml).expect("input is not a valid OpenAPI document");
+    let actual = to_json(&spec);
+    let differences = json_differences(&expected, &actual);
+    if !differences.is_empty() {
+        fail("document changed after round-trip", &differences);
+    }
+    spec
+}
+
+/// See [assert_schemas_equivalent!].
+#[track_caller]
+pub fn assert_schemas_equivalent(expected: &Schema, actual: &Schema) {
+    assert_schemas_equivalent_ignoring(expected, actual, &[])
+}
+
+/// See [assert_schemas_equivalent!]. Values matching one of the `ignore` pointer selectors,
+/// as accepted by [OpenAPI::diff_ignoring], are also excluded.
+#[track_caller]
+pub fn assert_schemas_equivalent_ignoring(expected: &Schema, actual: &Schema, ignore: &[&str]) {
+    let mut expected = to_json(expected);
+    let mut actual = to_json(actual);
+    strip_doc_fields(&mut expected, false);
+    strip_doc_fields(&mut actual, false);
+    crate::util::remove_matching(&mut expected, ignore);
+    crate::util::remove_matching(&mut actual, ignore);
+    let differences = json_differences(&expected, &actual);
+    if !differences.is_empty() {
+        fail("schemas are not equivalent", &differences);
+    }
+}
+
+/// Asserts that [OpenAPI::validate] reports no errors.
+#[macro_export]
+macro_rules! assert_valid_spec {
+    ($spec:expr) => {
+        $crate::testing::assert_valid_spec(&$spec)
+    };
+}
+
+/// Asserts that a YAML document parses, and serializes back to the same structure.
+/// Evaluates to the parsed [OpenAPI](crate::OpenAPI).
+#[macro_export]
+macro_rules! assert_roundtrip {
+    ($yaml:expr) => {
+        $crate::testing::assert_roundtrip($yaml)
+    };
+}
+
+/// Asserts that two schemas are equal, ignoring descriptions, titles, examples and external docs.
+/// An optional `ignore = [...]` list of pointer selectors excludes further values.
+#[macro_export]
+macro_rules! assert_schemas_equivalent {
+    ($expected:expr, $actual:expr) => {
+        $crate::testing::assert_schemas_equivalent(&$expected, &$actual)
+    };
+    ($expected:expr, $actual:expr, ignore = $ignore:expr) => {
+        $crate::testing::assert_schemas_equivalent_ignoring(&$expected, &$actual, &$ignore)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets/{id}:
+    get:
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        "200":
+          description: OK
+"#;
+
+    #[test]
+    fn test_assertions() {
+        let spec = assert_roundtrip!(SPEC);
+        assert_valid_spec!(spec);
+
+        let mut a = Schema::new_object();
+        a.properties_mut().insert("description", Schema::new_string());
+        let mut b = a.clone();
+        b.description = Some("A pet".to_string());
+        assert_schemas_equivalent!(a, b);
+        b.properties_mut().insert("age", Schema::new_integer());
+        assert_schemas_equivalent!(a, b, ignore = ["/properties/age"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "#/properties/name/type: expected \"string\", found \"integer\"")]
+    fn test_schemas_not_equivalent() {
+        let mut a = Schema::new_object();
+        a.properties_mut().insert("name", Schema::new_string());
+        let mut b = Schema::new_object();
+        b.properties_mut().insert("name", Schema::new_integer());
+        assert_schemas_equivalent!(a, b);
+    }
+
+    #[test]
+    fn test_json_differences() {
+        let a = serde_json::json!({"a": 1, "b": [1, 2]});
+        let b = serde_json::json!({"b": [1, 3], "c": true});
+        assert_eq!(json_differences(&a, &b), vec![
+            "#/a: missing, expected 1",
+            "#/b/1: expected 2, found 3",
+            "#/c: unexpected true",
+        ]);
+    }
+}
@@ -0,0 +1,390 @@
+use indexmap::IndexMap;
+
+use crate::*;
+use crate::util::escape_pointer_segment;
+
+/// Which field of its object a [TextField] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextKind {
+    Title,
+    Summary,
+    Description,
+}
+
+/// A human-readable string of a document, from [OpenAPI::texts_mut].
+#[derive(Debug)]
+pub struct TextField<'a> {
+    /// JSON pointer to the field, e.g. `#/paths/~1pets/get/summary`.
+    pub pointer: String,
+    pub kind: TextKind,
+    pub text: &'a mut String,
+}
+
+impl OpenAPI {
+    /// Returns every title, summary and description in the document, for spellcheckers and terminology
+    /// linters which check or rewrite them. Covers the info, tags, servers, paths, webhooks, operations,
+    /// parameters, request bodies, responses, headers, examples, links, callbacks, schemas,
+    /// security schemes with their OAuth2 scopes, and external docs. Fields which aren't set are not included.
+    pub fn texts_mut(&mut self) -> impl Iterator<Item=TextField<'_>> {
+        let mut texts = Texts(Vec::new());
+        let OpenAPI { info, servers, paths, webhooks, components, tags, external_docs, .. } = self;
+        texts.add("#/info/title", TextKind::Title, Some(&mut info.title));
+        texts.add("#/info/summary", TextKind::Summary, info.summary.as_mut());
+        texts.add("#/info/description", TextKind::Description, info.description.as_mut());
+        texts.servers("#/servers", servers);
+        for (path, item) in paths.paths.iter_mut() {
+            if let RefOr::Item(item) = item {
+                texts.path_item(&format!("#/paths/{}", escape_pointer_segment(path)), item);
+            }
+        }
+        for (name, item) in webhooks.iter_mut() {
+            if let RefOr::Item(item) = item {
+                texts.path_item(&format!("#/webhooks/{}", escape_pointer_segment(name)), item);
+            }
+        }
+        texts.components(components);
+        for (i, tag) in tags.iter_mut().enumerate() {
+            let pointer = format!("#/tags/{}", i);
+            texts.add(&format!("{}/description", pointer), TextKind::Description, tag.description.as_mut());
+            texts.external_docs(&pointer, tag.external_docs.as_mut());
+        }
+        texts.external_docs("#", external_docs.as_mut());
+        texts.0.into_iter()
+    }
+}
+
+struct Texts<'a>(Vec<TextField<'a>>);
+
+impl<'a> Texts<'a> {
+    fn add(&mut self, pointer: &str, kind: TextKind, text: Option<&'a mut String>) {
+        if let Some(text) = text {
+            self.0.push(TextField { pointer: pointer.to_string(), kind, text });
+        }
+    }
+
+    fn description(&mut self, pointer: &str, text: Option<&'a mut String>) {
+        self.add(&format!("{}/description", pointer), TextKind::Description, text);
+    }
+
+    fn external_docs(&mut self, pointer: &str, docs: Option<&'a mut ExternalDocumentation>) {
+        if let Some(docs) = docs {
+            self.description(&format!("{}/externalDocs", pointer), docs.description.as_mut());
+        }
+    }
+
+    fn servers(&mut self, pointer: &str, servers: &'a mut [Server]) {
+        for (i, server) in servers.iter_mut().enumerate() {
+            self.server(&format!("{}/{}", pointer, i), server);
+        }
+    }
+
+    fn server(&mut self, pointer: &str, server: &'a mut Server) {
+        self.description(pointer, server.description.as_mut());
+        for (name, variable) in server.variables.iter_mut().flatten() {
+            self.description(&format!("{}/variables/{}", pointer, escape_pointer_segment(name)), variable.description.as_mut());
+        }
+    }
+
+    fn path_item(&mut self, pointer: &str, item: &'a mut PathItem) {
+        let PathItem { summary, description, get, put, post, delete, options, head, patch, trace, other, servers, parameters, .. } = item;
+        self.add(&format!("{}/summary", pointer), TextKind::Summary, summary.as_mut());
+        self.description(pointer, description.as_mut());
+        let methods = vec![("get", get), ("put", put), ("post", post), ("delete", delete), ("options", options), ("head", head), ("patch", patch), ("trace", trace)];
+        let operations = methods.into_iter()
+            .filter_map(|(method, op)| op.as_mut().map(|op| (method, op)))
+            .chain(other.iter_mut().map(|(method, op)| (method.as_str(), op)));
+        for (method, op) in operations {
+            self.operation(&format!("{}/{}", pointer, method), op);
+        }
+        self.servers(&format!("{}/servers", pointer), servers);
+        self.parameters(&format!("{}/parameters", pointer), parameters);
+    }
+
+    fn operation(&mut self, pointer: &str, op: &'a mut Operation) {
+        self.add(&format!("{}/summary", pointer), TextKind::Summary, op.summary.as_mut());
+        self.description(pointer, op.description.as_mut());
+        self.external_docs(pointer, op.external_docs.as_mut());
+        self.parameters(&format!("{}/parameters", pointer), &mut op.parameters);
+        if let Some(RefOr::Item(body)) = &mut op.request_body {
+            self.request_body(&format!("{}/requestBody", pointer), body);
+        }
+        let responses = op.responses.responses.iter_mut().map(|(code, r)| (code.to_string(), r))
+            .chain(op.responses.default.iter_mut().map(|r| ("default".to_string(), r)));
+        for (code, response) in responses {
+            if let RefOr::Item(response) = response {
+                self.response(&format!("{}/responses/{}", pointer, code), response);
+            }
+        }
+        for (name, callback) in op.callbacks.iter_mut() {
+            if let RefOr::Item(callback) = callback {
+                self.callback(&format!("{}/callbacks/{}", pointer, escape_pointer_segment(name)), callback);
+            }
+        }
+        self.servers(&format!("{}/servers", pointer), &mut op.servers);
+    }
+
+    fn callback(&mut self, pointer: &str, callback: &'a mut Callback) {
+        for (expression, item) in callback.iter_mut() {
+            self.path_item(&format!("{}/{}", pointer, escape_pointer_segment(expression)), item);
+        }
+    }
+
+    fn parameters(&mut self, pointer: &str, params: &'a mut [RefOr<Parameter>]) {
+        for (i, param) in params.iter_mut().enumerate() {
+            if let RefOr::Item(param) = param {
+                self.parameter(&format!("{}/{}", pointer, i), param);
+            }
+        }
+    }
+
+    fn parameter(&mut self, pointer: &str, param: &'a mut Parameter) {
+        let data = &mut param.data;
+        self.description(pointer, data.description.as_mut());
+        self.schema_or_content(pointer, &mut data.format);
+        self.examples(pointer, &mut data.examples);
+    }
+
+    fn header(&mut self, pointer: &str, header: &'a mut Header) {
+        self.description(pointer, header.description.as_mut());
+        self.schema_or_content(pointer, &mut header.format);
+        self.examples(pointer, &mut header.examples);
+    }
+
+    fn schema_or_content(&mut self, pointer: &str, format: &'a mut ParameterSchemaOrContent) {
+        match format {
+            ParameterSchemaOrContent::Schema(RefOr::Item(schema)) => self.schema(&format!("{}/schema", pointer), schema),
+            ParameterSchemaOrContent::Schema(_) => {}
+            ParameterSchemaOrContent::Content(content) => self.content(&format!("{}/content", pointer), content),
+        }
+    }
+
+    fn request_body(&mut self, pointer: &str, body: &'a mut RequestBody) {
+        self.description(pointer, body.description.as_mut());
+        self.content(&format!("{}/content", pointer), &mut body.content);
+    }
+
+    fn response(&mut self, pointer: &str, response: &'a mut Response) {
+        self.description(pointer, Some(&mut response.description));
+        for (name, header) in response.headers.iter_mut() {
+            if let RefOr::Item(header) = header {
+                self.header(&format!("{}/headers/{}", pointer, escape_pointer_segment(name)), header);
+            }
+        }
+        self.content(&format!("{}/content", pointer), &mut response.content);
+        for (name, link) in response.links.iter_mut() {
+            if let RefOr::Item(link) = link {
+                self.link(&format!("{}/links/{}", pointer, escape_pointer_segment(name)), link);
+            }
+        }
+    }
+
+    fn link(&mut self, pointer: &str, link: &'a mut Link) {
+        self.description(pointer, link.description.as_mut());
+        if let Some(server) = &mut link.server {
+            self.server(&format!("{}/server", pointer), server);
+        }
+    }
+
+    fn content(&mut self, pointer: &str, content: &'a mut IndexMap<String, MediaType>) {
+        for (mime, media) in content.iter_mut() {
+            let pointer = format!("{}/{}", pointer, escape_pointer_segment(mime));
+            if let Some(RefOr::Item(schema)) = &mut media.schema {
+                self.schema(&format!("{}/schema", pointer), schema);
+            }
+            self.examples(&pointer, &mut media.examples);
+            for (name, encoding) in media.encoding.iter_mut() {
+                for (header_name, header) in encoding.headers.iter_mut() {
+                    if let RefOr::Item(header) = header {
+                        let pointer = format!("{}/encoding/{}/headers/{}", pointer, escape_pointer_segment(name), escape_pointer_segment(header_name));
+                        self.header(&pointer, header);
+                    }
+                }
+            }
+        }
+    }
+
+    fn examples(&mut self, pointer: &str, examples: &'a mut IndexMap<String, RefOr<Example>>) {
+        for (name, example) in examples.iter_mut() {
+            if let RefOr::Item(example) = example {
+                self.example(&format!("{}/examples/{}", pointer, escape_pointer_segment(name)), example);
+            }
+        }
+    }
+
+    fn example(&mut self, pointer: &str, example: &'a mut Example) {
+        self.add(&format!("{}/summary", pointer), TextKind::Summary, example.summary.as_mut());
+        self.description(pointer, example.description.as_mut());
+    }
+
+    fn schema(&mut self, pointer: &str, schema: &'a mut Schema) {
+        let Schema { data, kind } = schema;
+        self.add(&format!("{}/title", pointer), TextKind::Title, data.title.as_mut());
+        self.description(pointer, data.description.as_mut());
+        self.external_docs(pointer, data.external_docs.as_mut());
+        let mut children: Vec<(String, &'a mut RefOr<Schema>)> = Vec::new();
+        let named = |key: &str, properties: &'a mut RefOrMap<Schema>, children: &mut Vec<(String, &'a mut RefOr<Schema>)>| {
+            children.extend(properties.iter_mut().map(|(name, s)| (format!("{}/{}", key, escape_pointer_segment(name)), s)));
+        };
+        let listed = |key: &str, items: &'a mut Vec<RefOr<Schema>>, children: &mut Vec<(String, &'a mut RefOr<Schema>)>| {
+            children.extend(items.iter_mut().enumerate().map(|(i, s)| (format!("{}/{}", key, i), s)));
+        };
+        match kind {
+            SchemaKind::Type(Type::Object(o)) => {
+                named("properties", &mut o.properties, &mut children);
+                if let Some(AdditionalProperties::Schema(s)) = &mut o.additional_properties {
+                    children.push(("additionalProperties".to_string(), s));
+                }
+            }
+            SchemaKind::Type(Type::Array(a)) => children.extend(a.items.as_deref_mut().map(|s| ("items".to_string(), s))),
+            SchemaKind::Type(_) => {}
+            SchemaKind::OneOf { one_of } => listed("oneOf", one_of, &mut children),
+            SchemaKind::AllOf { all_of } => listed("allOf", all_of, &mut children),
+            SchemaKind::AnyOf { any_of } => listed("anyOf", any_of, &mut children),
+            SchemaKind::Not { not } => children.push(("not".to_string(), not)),
+            SchemaKind::Any(a) => {
+                named("properties", &mut a.properties, &mut children);
+                if let Some(AdditionalProperties::Schema(s)) = &mut a.additional_properties {
+                    children.push(("additionalProperties".to_string(), s));
+                }
+                children.extend(a.items.as_deref_mut().map(|s| ("items".to_string(), s)));
+                listed("oneOf", &mut a.one_of, &mut children);
+                listed("allOf", &mut a.all_of, &mut children);
+                listed("anyOf", &mut a.any_of, &mut children);
+                children.extend(a.not.as_deref_mut().map(|s| ("not".to_string(), s)));
+            }
+        }
+        for (key, child) in children {
+            if let RefOr::Item(child) = child {
+                self.schema(&format!("{}/{}", pointer, key), child);
+            }
+        }
+    }
+
+    fn components(&mut self, components: &'a mut Components) {
+        let pointer = |kind: ComponentKind, name: &str| format!("#/components/{}/{}", kind.as_str(), escape_pointer_segment(name));
+        for (name, schema) in components.schemas.iter_mut() {
+            if let RefOr::Item(schema) = schema {
+                self.schema(&pointer(ComponentKind::Schema, name), schema);
+            }
+        }
+        for (name, response) in components.responses.iter_mut() {
+            if let RefOr::Item(response) = response {
+                self.response(&pointer(ComponentKind::Response, name), response);
+            }
+        }
+        for (name, param) in components.parameters.iter_mut() {
+            if let RefOr::Item(param) = param {
+                self.parameter(&pointer(ComponentKind::Parameter, name), param);
+            }
+        }
+        for (name, example) in components.examples.iter_mut() {
+            if let RefOr::Item(example) = example {
+                self.example(&pointer(ComponentKind::Example, name), example);
+            }
+        }
+        for (name, body) in components.request_bodies.iter_mut() {
+            if let RefOr::Item(body) = body {
+                self.request_body(&pointer(ComponentKind::RequestBody, name), body);
+            }
+        }
+        for (name, header) in components.headers.iter_mut() {
+            if let RefOr::Item(header) = header {
+                self.header(&pointer(ComponentKind::Header, name), header);
+            }
+        }
+        for (name, scheme) in components.security_schemes.iter_mut() {
+            if let RefOr::Item(scheme) = scheme {
+                let (description, flows) = match scheme {
+                    SecurityScheme::APIKey { description, .. }
+                    | SecurityScheme::HTTP { description, .. }
+                    | SecurityScheme::OpenIDConnect { description, .. } => (description, None),
+                    SecurityScheme::OAuth2 { description, flows, .. } => (description, Some(flows)),
+                };
+                self.description(&pointer(ComponentKind::SecurityScheme, name), description.as_mut());
+                if let Some(flows) = flows {
+                    let OAuth2Flows { implicit, password, client_credentials, authorization_code } = flows;
+                    let flows = vec![
+                        ("implicit", implicit.as_mut().map(|f| &mut f.scopes)),
+                        ("password", password.as_mut().map(|f| &mut f.scopes)),
+                        ("clientCredentials", client_credentials.as_mut().map(|f| &mut f.scopes)),
+                        ("authorizationCode", authorization_code.as_mut().map(|f| &mut f.scopes)),
+                    ];
+                    for (flow, scopes) in flows {
+                        for (scope, description) in scopes.into_iter().flatten() {
+                            let pointer = format!("{}/flows/{}/scopes/{}", pointer(ComponentKind::SecurityScheme, name), flow, escape_pointer_segment(scope));
+                            self.add(&pointer, TextKind::Description, Some(description));
+                        }
+                    }
+                }
+            }
+        }
+        for (name, link) in components.links.iter_mut() {
+            if let RefOr::Item(link) = link {
+                self.link(&pointer(ComponentKind::Link, name), link);
+            }
+        }
+        for (name, callback) in components.callbacks.iter_mut() {
+            if let RefOr::Item(callback) = callback {
+                self.callback(&pointer(ComponentKind::Callback, name), callback);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texts_mut() {
+        let mut spec = OpenAPI::new("Petstore", "1.0.0");
+        spec.tags.push(Tag {
+            name: "pets".to_string(),
+            description: Some("Everything about teh pets".to_string()),
+            ..Tag::default()
+        });
+        let mut pet = Schema::new_object();
+        let mut name = Schema::new_string();
+        name.description = Some("The pet's name".to_string());
+        pet.properties_mut().insert("name", name);
+        spec.schemas.insert("Pet", pet);
+        let mut op = Operation {
+            summary: Some("Get teh pet".to_string()),
+            ..Operation::default()
+        };
+        op.responses.responses.insert(StatusCode::Code(200), Response::new("The pet").into());
+        spec.paths.insert_operation("/pet".to_string(), PathMethod::GET, op);
+        spec.info.summary = Some("Pets for sale".to_string());
+        let hook = Operation { summary: Some("New pet".to_string()), ..Operation::default() };
+        spec.webhooks.insert("newPet".to_string(), RefOr::Item(PathItem { post: Some(hook), ..PathItem::default() }));
+        let mut scopes = IndexMap::new();
+        scopes.insert("read:pets".to_string(), "Read teh pets".to_string());
+        spec.security_schemes.insert("oauth", SecurityScheme::OAuth2 {
+            flows: OAuth2Flows {
+                client_credentials: Some(OAuth2Flow { refresh_url: None, token_url: "https://example.com/token".to_string(), scopes }),
+                ..OAuth2Flows::default()
+            },
+            description: None,
+            extensions: IndexMap::new(),
+        });
+
+        let pointers: Vec<(String, TextKind)> = spec.texts_mut().map(|t| (t.pointer, t.kind)).collect();
+        assert_eq!(pointers, vec![
+            ("#/info/title".to_string(), TextKind::Title),
+            ("#/info/summary".to_string(), TextKind::Summary),
+            ("#/paths/~1pet/get/summary".to_string(), TextKind::Summary),
+            ("#/paths/~1pet/get/responses/200/description".to_string(), TextKind::Description),
+            ("#/webhooks/newPet/post/summary".to_string(), TextKind::Summary),
+            ("#/components/schemas/Pet/properties/name/description".to_string(), TextKind::Description),
+            ("#/components/securitySchemes/oauth/flows/clientCredentials/scopes/read:pets".to_string(), TextKind::Description),
+            ("#/tags/0/description".to_string(), TextKind::Description),
+        ]);
+        for field in spec.texts_mut() {
+            *field.text = field.text.replace("teh", "the");
+        }
+        assert_eq!(spec.tags[0].description.as_deref(), Some("Everything about the pets"));
+        let Some(RefOr::Item(SecurityScheme::OAuth2 { flows, .. })) = spec.security_schemes.get("oauth") else { panic!() };
+        assert_eq!(flows.client_credentials.as_ref().unwrap().scopes["read:pets"], "Read the pets");
+        assert_eq!(spec.paths["/pet"].as_item().unwrap().get.as_ref().unwrap().summary.as_deref(), Some("Get the pet"));
+    }
+}
@@ -0,0 +1,34 @@
+//! Timing of the phases of heavy operations, reported as `tracing` spans and events when the
+//! `tracing` feature is enabled, and compiled out otherwise.
+
+/// A phase of an operation. Enters a span named after the phase until finished or dropped.
+pub(crate) struct Phase {
+    #[cfg(feature = "tracing")]
+    name: &'static str,
+    #[cfg(feature = "tracing")]
+    start: std::time::Instant,
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+impl Phase {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn start(name: &'static str) -> Self {
+        Phase {
+            #[cfg(feature = "tracing")]
+            name,
+            #[cfg(feature = "tracing")]
+            start: std::time::Instant::now(),
+            #[cfg(feature = "tracing")]
+            _span: tracing::debug_span!("openapiv3", phase = name).entered(),
+        }
+    }
+
+    /// Ends the phase with an event giving its duration and how many items it produced,
+    /// e.g. errors found or changes detected.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn finish(self, count: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(phase = self.name, elapsed_ms = self.start.elapsed().as_secs_f64() * 1000.0, count, "phase finished");
+    }
+}
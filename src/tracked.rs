@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+use crate::util::to_json;
+
+/// A change made through [TrackedOpenAPI]. Serializes with an `op` tag, e.g. `{"op": "removeSchema", "name": "Pet"}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Mutation {
+    AddOperation { path: String, method: String, operation: Box<Operation> },
+    RemoveOperation { path: String, method: String },
+    /// Changes the operationId of an operation.
+    RenameOperation { from: String, to: String },
+    /// Inserts or replaces a component schema.
+    SetSchema { name: String, schema: Box<Schema> },
+    RemoveSchema { name: String },
+    /// Renames a component schema, rewriting references to it.
+    RenameSchema { from: String, to: String },
+}
+
+impl Mutation {
+    /// Performs the mutation on `spec`. Fails without changing `spec` if the mutation doesn't apply.
+    pub fn apply(&self, spec: &mut OpenAPI) -> Result<()> {
+        match self {
+            Mutation::AddOperation { path, method, operation } => {
+                spec.add_operation(parse_method(method)?, path, (**operation).clone())
+            }
+            Mutation::RemoveOperation { path, method } => {
+                spec.remove_operation(parse_method(method)?, path, false)
+                    .map(|_| ())
+                    .ok_or_else(|| anyhow!("{} {} is not defined.", method, path))
+            }
+            Mutation::RenameOperation { from, to } => {
+                if spec.operations().any(|(_, _, op, _)| op.operation_id.as_ref() == Some(to)) {
+                    return Err(anyhow!("Operation {} already exists.", to));
+                }
+                let (_, _, operation) = spec.operations_mut()
+                    .find(|(_, _, op)| op.operation_id.as_ref() == Some(from))
+                    .ok_or_else(|| anyhow!("Operation {} does not exist.", from))?;
+                operation.operation_id = Some(to.clone());
+                Ok(())
+            }
+            Mutation::SetSchema { name, schema } => {
+                spec.schemas.insert(name, (**schema).clone());
+                Ok(())
+            }
+            Mutation::RemoveSchema { name } => {
+                spec.schemas.shift_remove(name)
+                    .map(|_| ())
+                    .ok_or_else(|| anyhow!("Schema {} does not exist.", name))
+            }
+            Mutation::RenameSchema { from, to } => spec.rename_component(ComponentKind::Schema, from, to),
+        }
+    }
+}
+
+fn parse_method(method: &str) -> Result<PathMethod> {
+    PathMethod::from_bytes(method.to_uppercase().as_bytes()).map_err(|_| anyhow!("Invalid method {}.", method))
+}
+
+/// Applies each mutation of `log` in order.
+pub fn replay<'a>(spec: &mut OpenAPI, log: impl IntoIterator<Item=&'a Mutation>) -> Result<()> {
+    for mutation in log {
+        mutation.apply(spec)?;
+    }
+    Ok(())
+}
+
+/// Wraps an [OpenAPI] document and records every mutation performed through it.
+///
+/// Read access goes through `Deref`. Changes made to the document by other means aren't recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackedOpenAPI {
+    spec: OpenAPI,
+    log: Vec<Mutation>,
+}
+
+impl TrackedOpenAPI {
+    pub fn new(spec: OpenAPI) -> Self {
+        TrackedOpenAPI { spec, log: Vec::new() }
+    }
+
+    /// Applies and records the mutation. Failed mutations are not recorded.
+    pub fn apply(&mut self, mutation: Mutation) -> Result<()> {
+        mutation.apply(&mut self.spec)?;
+        self.log.push(mutation);
+        Ok(())
+    }
+
+    pub fn add_operation(&mut self, method: PathMethod, path: impl Into<String>, operation: Operation) -> Result<()> {
+        self.apply(Mutation::AddOperation { path: path.into(), method: method.to_string(), operation: Box::new(operation) })
+    }
+
+    pub fn remove_operation(&mut self, method: PathMethod, path: impl Into<String>) -> Result<()> {
+        self.apply(Mutation::RemoveOperation { path: path.into(), method: method.to_string() })
+    }
+
+    pub fn rename_operation(&mut self, from: impl Into<String>, to: impl Into<String>) -> Result<()> {
+        self.apply(Mutation::RenameOperation { from: from.into(), to: to.into() })
+    }
+
+    pub fn set_schema(&mut self, name: impl Into<String>, schema: Schema) -> Result<()> {
+        self.apply(Mutation::SetSchema { name: name.into(), schema: Box::new(schema) })
+    }
+
+    pub fn remove_schema(&mut self, name: impl Into<String>) -> Result<()> {
+        self.apply(Mutation::RemoveSchema { name: name.into() })
+    }
+
+    pub fn rename_schema(&mut self, from: impl Into<String>, to: impl Into<String>) -> Result<()> {
+        self.apply(Mutation::RenameSchema { from: from.into(), to: to.into() })
+    }
+
+    /// The mutations performed so far, oldest first.
+    pub fn log(&self) -> &[Mutation] {
+        &self.log
+    }
+
+    /// Clears the log, e.g. after publishing a version of the document.
+    pub fn take_log(&mut self) -> Vec<Mutation> {
+        std::mem::take(&mut self.log)
+    }
+
+    /// The log as a JSON array.
+    pub fn export_log(&self) -> serde_json::Value {
+        to_json(&self.log)
+    }
+
+    pub fn into_inner(self) -> OpenAPI {
+        self.spec
+    }
+}
+
+impl std::ops::Deref for TrackedOpenAPI {
+    type Target = OpenAPI;
+
+    fn deref(&self) -> &Self::Target {
+        &self.spec
+    }
+}
+
+impl From<OpenAPI> for TrackedOpenAPI {
+    fn from(spec: OpenAPI) -> Self {
+        TrackedOpenAPI::new(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let original = OpenAPI::default();
+        let mut tracked = TrackedOpenAPI::new(original.clone());
+        tracked.set_schema("Pet", Schema::new_object()).unwrap();
+        let mut operation = Operation {
+            operation_id: Some("getPet".to_string()),
+            ..Operation::default()
+        };
+        operation.add_response_success_json(Some(RefOr::schema_ref("Pet")));
+        tracked.add_operation(PathMethod::GET, "/pet", operation).unwrap();
+        tracked.rename_schema("Pet", "Animal").unwrap();
+        tracked.rename_operation("getPet", "getAnimal").unwrap();
+        assert!(tracked.remove_schema("Pet").is_err());
+        assert_eq!(tracked.log().len(), 4);
+
+        let op = tracked.get_operation("getAnimal").unwrap().0;
+        assert_eq!(op.responses.responses[&StatusCode::Code(200)].as_item().unwrap().json_schema().unwrap().as_ref_str(), Some("#/components/schemas/Animal"));
+
+        let exported = tracked.export_log();
+        assert_eq!(exported[0]["op"], "setSchema");
+        assert_eq!(exported[1]["method"], "GET");
+        let log: Vec<Mutation> = serde_json::from_value(exported).unwrap();
+        let mut replayed = original;
+        replay(&mut replayed, &log).unwrap();
+        assert_eq!(&replayed, &*tracked);
+    }
+}
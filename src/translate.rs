@@ -0,0 +1,152 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{escape_pointer_segment, is_data_field, is_name_map_key, to_json};
+
+/// A translatable string of the document, and its translations keyed by language.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranslationEntry {
+    /// The text in the document's own language.
+    pub source: String,
+    /// Translations keyed by language, e.g. `de` or `pt-BR`.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub translations: IndexMap<String, String>,
+}
+
+/// Translated descriptions and summaries, keyed by the JSON pointer of the string they translate,
+/// e.g. `#/paths/~1pets/get/summary`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranslationCatalog(pub IndexMap<String, TranslationEntry>);
+
+impl std::ops::Deref for TranslationCatalog {
+    type Target = IndexMap<String, TranslationEntry>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for TranslationCatalog {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Fields holding human-readable text.
+const TRANSLATABLE_FIELDS: [&str; 3] = ["title", "summary", "description"];
+
+impl OpenAPI {
+    /// Produces a catalog of every title, summary and description in the document, without translations.
+    /// Fill in the translations and pass it to [OpenAPI::apply_translations].
+    pub fn extract_translatable_strings(&self) -> TranslationCatalog {
+        /// `names` is set for maps keyed by names, such as `properties`, whose keys aren't fields.
+        fn walk(value: &Value, pointer: &mut String, names: bool, out: &mut TranslationCatalog) {
+            match value {
+                Value::Object(map) => {
+                    for (key, child) in map {
+                        if !names && is_data_field(key, child) {
+                            continue;
+                        }
+                        let len = pointer.len();
+                        pointer.push('/');
+                        pointer.push_str(&escape_pointer_segment(key));
+                        match child {
+                            Value::String(s) if !names && TRANSLATABLE_FIELDS.contains(&key.as_str()) && !s.is_empty() => {
+                                out.insert(pointer.clone(), TranslationEntry {
+                                    source: s.clone(),
+                                    translations: IndexMap::new(),
+                                });
+                            }
+                            _ => walk(child, pointer, !names && is_name_map_key(key) && child.is_object(), out),
+                        }
+                        pointer.truncate(len);
+                    }
+                }
+                Value::Array(items) => {
+                    for (i, item) in items.iter().enumerate() {
+                        let len = pointer.len();
+                        pointer.push('/');
+                        pointer.push_str(&i.to_string());
+                        walk(item, pointer, false, out);
+                        pointer.truncate(len);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let doc = to_json(self);
+        let mut catalog = TranslationCatalog::default();
+        walk(&doc, &mut "#".to_string(), false, &mut catalog);
+        catalog
+    }
+
+    /// Replaces strings in the document with their translation into `lang`.
+    ///
+    /// Entries without a translation into `lang` are left untouched. Returns the pointers of entries
+    /// which could not be applied, because the pointer doesn't exist or doesn't hold a string.
+    pub fn apply_translations(&mut self, lang: &str, catalog: &TranslationCatalog) -> Vec<String> {
+        let mut failed = Vec::new();
+        self.edit_json(|doc| {
+            for (pointer, entry) in catalog.iter() {
+                let translation = match entry.translations.get(lang) {
+                    Some(t) => t,
+                    None => continue,
+                };
+                match doc.pointer_mut(pointer.trim_start_matches('#')) {
+                    Some(Value::String(s)) => *s = translation.clone(),
+                    _ => failed.push(pointer.clone()),
+                }
+            }
+        });
+        failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translation_roundtrip() {
+        let mut api = OpenAPI::new("Petstore", "1.0.0");
+        let mut schema = Schema::new_string();
+        schema.description = Some("The name".to_string());
+        schema.example = Some(serde_json::json!({"description": "not translatable"}));
+        api.schemas.insert("Name", schema);
+        // Names in name maps aren't fields, even when they look like one.
+        let mut book = Schema::new_object();
+        book.properties_mut().insert("title", Schema::new_string());
+        let mut kind = Schema::new_one_of(vec![RefOr::schema_ref("Name")]);
+        let mut discriminator = Discriminator { property_name: "kind".to_string(), ..Discriminator::default() };
+        discriminator.mapping.insert("summary".to_string(), "Name".to_string());
+        kind.discriminator = Some(discriminator);
+        book.properties_mut().insert("kind", kind);
+        api.schemas.insert("Book", book);
+        api.add_operation(PathMethod::GET, "/pets", Operation {
+            summary: Some("List pets".to_string()),
+            ..Operation::default()
+        }).unwrap();
+
+        let mut catalog = api.extract_translatable_strings();
+        let mut keys = catalog.keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec![
+            "#/components/schemas/Name/description",
+            "#/info/title",
+            "#/paths/~1pets/get/summary",
+        ]);
+        catalog["#/paths/~1pets/get/summary"].translations.insert("de".to_string(), "Haustiere auflisten".to_string());
+        catalog.insert("#/missing/description".to_string(), TranslationEntry {
+            source: String::new(),
+            translations: vec![("de".to_string(), "x".to_string())].into_iter().collect(),
+        });
+
+        let failed = api.apply_translations("de", &catalog);
+        assert_eq!(failed, vec!["#/missing/description"]);
+        let (_, _, op, _) = api.operations().next().unwrap();
+        assert_eq!(op.summary.as_deref(), Some("Haustiere auflisten"));
+        assert_eq!(api.info.title, "Petstore");
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::*;
+use crate::util::{escape_pointer_segment, to_json, unescape_pointer_segment};
+
+/// How often, and from where, a component is referenced. See [OpenAPI::component_usage].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageInfo {
+    /// The number of `$ref`s pointing at the component.
+    pub count: usize,
+    /// The operations which reference the component directly, identified by their
+    /// operationId, or by `METHOD path` if they have none.
+    pub operations: Vec<String>,
+    /// JSON pointers to each `$ref` pointing at the component.
+    pub pointers: Vec<String>,
+}
+
+impl OpenAPI {
+    /// Reports, for each component, how many times it is referenced and from where.
+    ///
+    /// The map is keyed by reference, e.g. `#/components/schemas/Pet`. Every declared component
+    /// is present, including unreferenced ones, as are references to components which don't exist.
+    pub fn component_usage(&self) -> IndexMap<String, UsageInfo> {
+        let mut usage = IndexMap::new();
+        for kind in ComponentKind::ALL {
+            for name in self.components.keys(kind) {
+                usage.insert(kind.reference(name), UsageInfo::default());
+            }
+        }
+        let operation_names: IndexMap<(String, String), String> = self.operations()
+            .map(|(path, method, op, _)| {
+                let name = op.operation_id.clone()
+                    .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+                ((escape_pointer_segment(path), method.to_string()), name)
+            })
+            .collect();
+
+        let doc = to_json(self);
+        for (pointer, reference) in collect_refs(&doc) {
+            let info: &mut UsageInfo = usage.entry(reference.to_string()).or_default();
+            info.count += 1;
+            let mut segments = pointer.split('/').skip(1);
+            if let (Some("paths"), Some(path), Some(method)) = (segments.next(), segments.next(), segments.next()) {
+                if let Some(name) = operation_names.get(&(path.to_string(), method.to_string())) {
+                    if !info.operations.contains(name) {
+                        info.operations.push(name.clone());
+                    }
+                }
+            }
+            info.pointers.push(pointer);
+        }
+        usage
+    }
+}
+
+impl OpenAPI {
+    /// Returns the components which are reachable from outside `components`, directly or through other components.
+    /// Security schemes named in security requirements are considered reachable.
+    pub(crate) fn reachable_components(&self) -> HashSet<(ComponentKind, String)> {
+        let mut roots = Vec::new();
+        let mut visit = |kind, name: &str| roots.push((kind, name.to_string()));
+        for item in self.paths.values().chain(self.webhooks.values()) {
+            item.references(&mut visit);
+        }
+        visit_security_schemes(&self.security, &mut visit);
+        self.reachable_from(roots)
+    }
+
+    /// Returns the given components and those they reference, directly or through other components.
+    pub(crate) fn reachable_from(&self, roots: Vec<(ComponentKind, String)>) -> HashSet<(ComponentKind, String)> {
+        let mut stack = roots;
+        let mut reachable = HashSet::new();
+        while let Some(component) = stack.pop() {
+            if reachable.contains(&component) {
+                continue;
+            }
+            if let Some(c) = self.components.get(component.0, &component.1) {
+                c.references(&mut |kind, name| stack.push((kind, name.to_string())));
+            }
+            reachable.insert(component);
+        }
+        reachable
+    }
+}
+
+/// Parts of the document which can reference components.
+pub(crate) trait References {
+    /// Calls `visit` with the kind and name of each component the item references, without
+    /// following the references. Security requirements reference security schemes.
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str));
+}
+
+fn visit_reference(reference: &str, visit: &mut dyn FnMut(ComponentKind, &str)) {
+    if let Some((kind, name)) = ComponentKind::parse_reference(reference) {
+        visit(kind, &unescape_pointer_segment(name));
+    }
+}
+
+impl<T: References> References for RefOr<T> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        match self {
+            RefOr::Reference { reference } => visit_reference(reference, visit),
+            RefOr::Item(item) => item.references(visit),
+        }
+    }
+}
+
+impl<T: References> References for Box<T> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        (**self).references(visit)
+    }
+}
+
+impl<T: References> References for Vec<T> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        for item in self {
+            item.references(visit);
+        }
+    }
+}
+
+impl<T: References> References for Option<T> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        if let Some(item) = self {
+            item.references(visit);
+        }
+    }
+}
+
+impl<T: References> References for IndexMap<String, T> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        for item in self.values() {
+            item.references(visit);
+        }
+    }
+}
+
+impl<T: References> References for RefOrMap<T> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        for item in self.values() {
+            item.references(visit);
+        }
+    }
+}
+
+/// Calls `visit` with the security schemes named in the requirements.
+pub(crate) fn visit_security_schemes(requirements: &[SecurityRequirement], visit: &mut dyn FnMut(ComponentKind, &str)) {
+    for name in requirements.iter().flat_map(|r| r.keys()) {
+        visit(ComponentKind::SecurityScheme, name);
+    }
+}
+
+impl References for Schema {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        if let Some(discriminator) = &self.discriminator {
+            for target in discriminator.mapping.values() {
+                visit_reference(target, visit);
+            }
+        }
+        let additional_properties = |a: &Option<AdditionalProperties>, visit: &mut dyn FnMut(ComponentKind, &str)| {
+            if let Some(AdditionalProperties::Schema(schema)) = a {
+                schema.references(visit);
+            }
+        };
+        match &self.kind {
+            SchemaKind::Type(Type::Object(o)) => {
+                o.properties.references(visit);
+                additional_properties(&o.additional_properties, visit);
+            }
+            SchemaKind::Type(Type::Array(a)) => a.items.references(visit),
+            SchemaKind::Type(_) => {}
+            SchemaKind::OneOf { one_of: schemas } | SchemaKind::AllOf { all_of: schemas } | SchemaKind::AnyOf { any_of: schemas } => schemas.references(visit),
+            SchemaKind::Not { not } => not.references(visit),
+            SchemaKind::Any(a) => {
+                a.properties.references(visit);
+                additional_properties(&a.additional_properties, visit);
+                a.items.references(visit);
+                a.one_of.references(visit);
+                a.all_of.references(visit);
+                a.any_of.references(visit);
+                a.not.references(visit);
+            }
+        }
+    }
+}
+
+impl References for ParameterSchemaOrContent {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        match self {
+            ParameterSchemaOrContent::Schema(schema) => schema.references(visit),
+            ParameterSchemaOrContent::Content(content) => content.references(visit),
+        }
+    }
+}
+
+impl References for Parameter {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.format.references(visit);
+        self.examples.references(visit);
+    }
+}
+
+impl References for Header {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.format.references(visit);
+        self.examples.references(visit);
+    }
+}
+
+impl References for MediaType {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.schema.references(visit);
+        self.examples.references(visit);
+        for encoding in self.encoding.values() {
+            encoding.headers.references(visit);
+        }
+    }
+}
+
+impl References for RequestBody {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.content.references(visit);
+    }
+}
+
+impl References for Response {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.headers.references(visit);
+        self.content.references(visit);
+        self.links.references(visit);
+    }
+}
+
+impl References for Operation {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.parameters.references(visit);
+        self.request_body.references(visit);
+        self.responses.default.references(visit);
+        for response in self.responses.responses.values() {
+            response.references(visit);
+        }
+        self.callbacks.references(visit);
+        visit_security_schemes(self.security.as_deref().unwrap_or_default(), visit);
+    }
+}
+
+impl References for PathItem {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        self.parameters.references(visit);
+        for (_, op) in self.iter() {
+            op.references(visit);
+        }
+    }
+}
+
+/// Examples, links and security schemes hold no references to other components.
+macro_rules! impl_no_references {
+    ($($ty:ty),*) => {
+        $(impl References for $ty {
+            fn references(&self, _visit: &mut dyn FnMut(ComponentKind, &str)) {}
+        })*
+    };
+}
+
+impl_no_references!(Example, Link, SecurityScheme);
+
+impl References for ComponentRef<'_> {
+    fn references(&self, visit: &mut dyn FnMut(ComponentKind, &str)) {
+        match *self {
+            ComponentRef::SecurityScheme(c) => c.references(visit),
+            ComponentRef::Response(c) => c.references(visit),
+            ComponentRef::Parameter(c) => c.references(visit),
+            ComponentRef::Example(c) => c.references(visit),
+            ComponentRef::RequestBody(c) => c.references(visit),
+            ComponentRef::Header(c) => c.references(visit),
+            ComponentRef::Schema(c) => c.references(visit),
+            ComponentRef::Link(c) => c.references(visit),
+            ComponentRef::Callback(c) => c.references(visit),
+        }
+    }
+}
+
+/// Collects every `$ref` in the document, as (JSON pointer of the referencing object, reference) pairs.
+pub(crate) fn collect_refs(value: &Value) -> Vec<(String, &str)> {
+    fn walk<'a>(value: &'a Value, pointer: &mut String, out: &mut Vec<(String, &'a str)>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    out.push((pointer.clone(), reference));
+                }
+                for (key, child) in map {
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&escape_pointer_segment(key));
+                    walk(child, pointer, out);
+                    pointer.truncate(len);
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&i.to_string());
+                    walk(child, pointer, out);
+                    pointer.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(value, &mut "#".to_string(), &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_usage() {
+        let mut spec = OpenAPI::default();
+        spec.schemas.insert("Pet", Schema::new_object());
+        spec.schemas.insert("Pets", Schema::new_array(RefOr::schema_ref("Pet")));
+        spec.schemas.insert("Unused", Schema::new_object());
+        let mut op = Operation {
+            operation_id: Some("listPets".to_string()),
+            ..Operation::default()
+        };
+        op.add_response_success_json(Some(RefOr::schema_ref("Pets")));
+        spec.paths.insert_operation("/pets".to_string(), PathMethod::GET, op);
+
+        let usage = spec.component_usage();
+        assert_eq!(usage["#/components/schemas/Unused"].count, 0);
+        assert_eq!(usage["#/components/schemas/Pet"].pointers, vec!["#/components/schemas/Pets/items"]);
+        let pets = &usage["#/components/schemas/Pets"];
+        assert_eq!(pets.count, 1);
+        assert_eq!(pets.operations, vec!["listPets"]);
+        assert_eq!(pets.pointers, vec!["#/paths/~1pets/get/responses/200/content/application~1json/schema"]);
+    }
+
+    #[test]
+    fn test_reachable_components() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        pet.example = Some(serde_json::json!({"$ref": "#/components/schemas/Example"}));
+        pet.discriminator = Some(Discriminator {
+            property_name: "kind".to_string(),
+            mapping: vec![("dog".to_string(), "#/components/schemas/Dog".to_string())].into_iter().collect(),
+            extensions: Default::default(),
+        });
+        spec.schemas.insert("Pet", pet);
+        spec.schemas.insert("Dog", Schema::new_object());
+        spec.schemas.insert("Example", Schema::new_object());
+        let mut op = Operation::default();
+        op.add_response_success_json(Some(RefOr::schema_ref("Pet")));
+        spec.paths.insert_operation("/pets".to_string(), PathMethod::GET, op);
+
+        let reachable = spec.reachable_components();
+        assert!(reachable.contains(&(ComponentKind::Schema, "Pet".to_string())));
+        assert!(reachable.contains(&(ComponentKind::Schema, "Dog".to_string())));
+        assert!(!reachable.contains(&(ComponentKind::Schema, "Example".to_string())));
+    }
+}
@@ -18,6 +18,197 @@ pub const fn is_false(v: &bool) -> bool {
     !(*v)
 }
 
+/// Parses a YAML or JSON document. Without the `yaml` feature, only JSON is accepted.
+pub(crate) fn from_yaml_or_json<T: serde::de::DeserializeOwned>(input: &str) -> anyhow::Result<T> {
+    #[cfg(feature = "yaml")]
+    return Ok(serde_yaml::from_str(input)?);
+    #[cfg(not(feature = "yaml"))]
+    return Ok(serde_json::from_str(input)?);
+}
+
+/// Serializes a value of the model to JSON, which can't fail: its maps all have string keys.
+pub(crate) fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).expect("model types serialize to JSON")
+}
+
+/// Reads the extension `key` as a `T`. [None] if it's missing, an error if it's malformed.
+pub(crate) fn read_extension<T: serde::de::DeserializeOwned>(extensions: &IndexMap<String, serde_json::Value>, key: &str) -> Option<serde_json::Result<T>> {
+    extensions.get(key).map(|v| serde_json::from_value(v.clone()))
+}
+
+pub(crate) fn write_extension<T: serde::Serialize + ?Sized>(extensions: &mut IndexMap<String, serde_json::Value>, key: &str, value: &T) {
+    extensions.insert(key.to_string(), to_json(value));
+}
+
+/// Escapes a single segment of a JSON pointer, per RFC 6901.
+pub(crate) fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Percent-encodes everything but the RFC 3986 unreserved characters, and also the reserved
+/// characters if `allow_reserved` is set.
+pub(crate) fn percent_encode(s: &str, allow_reserved: bool) -> String {
+    const RESERVED: &str = ":/?#[]@!$&'()*+,;=";
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) || (allow_reserved && RESERVED.contains(c)) {
+            out.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("%{:02X}", b));
+            }
+        }
+    }
+    out
+}
+
+/// Decodes `%XX` escapes, and `+` as a space if `plus_as_space` is set (as in form data).
+/// Invalid escapes are kept as is, and invalid UTF-8 is replaced.
+pub(crate) fn percent_decode(s: &str, plus_as_space: bool) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+            (b'+', _) if plus_as_space => out.push(b' '),
+            (b, _) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Reverses [escape_pointer_segment].
+pub(crate) fn unescape_pointer_segment(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Fields holding maps keyed by user-chosen names, such as property names or paths, rather
+/// than by field names.
+pub(crate) const NAME_MAPS: [&str; 18] = [
+    "paths", "webhooks", "properties", "schemas", "responses", "parameters", "headers", "content",
+    "callbacks", "securitySchemes", "requestBodies", "links", "encoding", "examples", "variables",
+    "pathItems", "scopes", "mapping",
+];
+
+/// Whether the field `key` of an object, which isn't itself in a name map, holds a name map.
+pub(crate) fn is_name_map_key(key: &str) -> bool {
+    NAME_MAPS.contains(&key)
+}
+
+/// Whether the field `key` holds data, such as an example, a default, enum values or an
+/// extension, rather than document structure. Walks of the document shouldn't descend into it.
+pub(crate) fn is_data_field(key: &str, value: &serde_json::Value) -> bool {
+    matches!(key, "example" | "default" | "enum" | "const" | "value")
+        || key.starts_with("x-")
+        // OpenAPI 3.1 schemas have a list of example values, rather than a map of Example Objects.
+        || (key == "examples" && value.is_array())
+}
+
+/// Splits a pointer selector such as `#/info/version` or `**/description` into unescaped
+/// segments. `*` matches a single segment and `**` any number of them.
+fn selector_segments(selector: &str) -> Vec<String> {
+    let selector = selector.trim_start_matches('#').trim_start_matches('/');
+    selector.split('/').map(unescape_pointer_segment).collect()
+}
+
+/// Matches a path against a selector. `names` tells which path segments are keys of name maps,
+/// such as property names. Those aren't matched by a final segment which directly follows a
+/// `**`, so that `**/description` doesn't select a property named `description`.
+fn selector_matches(selector: &[String], path: &[String], names: &[bool], after_globstar: bool) -> bool {
+    match selector.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|i| selector_matches(rest, &path[i..], &names[i..], true))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => {
+                if rest.is_empty() && path.is_empty() && names[0] && after_globstar {
+                    return false;
+                }
+                (first == "*" || first == segment) && selector_matches(rest, path, &names[1..], false)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Removes every value whose location matches one of the pointer selectors.
+/// Returns the number of values removed.
+pub(crate) fn remove_matching(value: &mut serde_json::Value, selectors: &[&str]) -> usize {
+    struct Walk<'a> {
+        selectors: &'a [Vec<String>],
+        path: Vec<String>,
+        names: Vec<bool>,
+        removed: usize,
+    }
+
+    impl Walk<'_> {
+        /// Whether the current path, with `segment` appended, matches a selector.
+        fn matches(&mut self, segment: String, is_name: bool) -> bool {
+            self.path.push(segment);
+            self.names.push(is_name);
+            let matches = self.selectors.iter().any(|s| selector_matches(s, &self.path, &self.names, false));
+            self.path.pop();
+            self.names.pop();
+            matches
+        }
+
+        fn walk(&mut self, value: &mut serde_json::Value, keys_are_names: bool) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    map.retain(|key, _| {
+                        let matches = self.matches(key.clone(), keys_are_names);
+                        self.removed += matches as usize;
+                        !matches
+                    });
+                    for (key, child) in map.iter_mut() {
+                        let holds_names = !keys_are_names && is_name_map_key(key) && !is_data_field(key, child);
+                        self.path.push(key.clone());
+                        self.names.push(keys_are_names);
+                        self.walk(child, holds_names);
+                        self.path.pop();
+                        self.names.pop();
+                    }
+                }
+                serde_json::Value::Array(items) => {
+                    // Walk before removing anything, so indices refer to the original array.
+                    for (i, child) in items.iter_mut().enumerate() {
+                        self.path.push(i.to_string());
+                        self.names.push(false);
+                        self.walk(child, false);
+                        self.path.pop();
+                        self.names.pop();
+                    }
+                    let mut i = 0;
+                    items.retain(|_| {
+                        i += 1;
+                        let matches = self.matches((i - 1).to_string(), false);
+                        self.removed += matches as usize;
+                        !matches
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if selectors.is_empty() {
+        return 0;
+    }
+    let selectors: Vec<Vec<String>> = selectors.iter().map(|s| selector_segments(s)).collect();
+    let mut walk = Walk { selectors: &selectors, path: Vec::new(), names: Vec::new(), removed: 0 };
+    walk.walk(value, false);
+    walk.removed
+}
+
 pub(crate) fn deserialize_extensions<'de, D>(
     deserializer: D,
 ) -> Result<IndexMap<String, serde_json::Value>, D::Error>
@@ -30,6 +221,15 @@ where
     ))
 }
 
+/// Deserializes a present field as `Some`, so that an explicit `null` is kept as
+/// `Some(Value::Null)`. Use with `#[serde(default)]` for fields that may be missing.
+pub(crate) fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    serde_json::Value::deserialize(deserializer).map(Some)
+}
+
 /// Used to deserialize IndexMap<K, V> that are flattened within other structs.
 /// This only adds keys that satisfy the given predicate.
 pub(crate) struct PredicateVisitor<F, K, V>(pub F, pub PhantomData<(K, V)>);
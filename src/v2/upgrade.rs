@@ -67,6 +67,7 @@ impl Into<v3::OpenAPI> for v2::OpenAPI {
         v3::OpenAPI {
             openapi: "3.0.3".to_string(),
             info: info.into(),
+            json_schema_dialect: None,
             servers: host
                 .map(|h| {
                     let scheme = schemes
@@ -87,6 +88,7 @@ impl Into<v3::OpenAPI> for v2::OpenAPI {
                 })
                 .unwrap_or_default(),
             paths: paths.into(),
+            webhooks: Default::default(),
             components,
             security: security.unwrap_or_default(),
             tags: tags
@@ -135,6 +137,7 @@ impl Into<v3::RefOr<v3::PathItem>> for v2::PathItem {
             head: head.map(|op| op.into()),
             patch: patch.map(|op| op.into()),
             trace: None,
+            other: IndexMap::new(),
             servers: vec![],
             parameters: parameters
                 .unwrap_or_default()
@@ -384,6 +387,7 @@ impl Into<v3::Operation> for v2::Operation {
                 .collect(),
             request_body: Some(v3::RefOr::Item(body)),
             responses,
+            callbacks: IndexMap::new(),
             deprecated: false,
             security,
             servers: vec![],
@@ -492,6 +496,7 @@ impl Into<v3::Info> for v2::Info {
         } = self;
         v3::Info {
             title: title.unwrap_or_default(),
+            summary: None,
             description,
             terms_of_service,
             contact: contact.map(|c| c.into()),
@@ -541,12 +546,14 @@ impl Into<v3::RefOr<v3::SecurityScheme>> for v2::Security {
                     location,
                     name,
                     description,
+                    extensions: IndexMap::new(),
                 })
             }
             v2::Security::Basic { description } => v3::RefOr::Item(v3::SecurityScheme::HTTP {
                 scheme: "basic".to_string(),
                 bearer_format: None,
                 description,
+                extensions: IndexMap::new(),
             }),
             v2::Security::Oauth2 {
                 flow,
@@ -596,7 +603,7 @@ impl Into<v3::RefOr<v3::SecurityScheme>> for v2::Security {
                     client_credentials,
                     authorization_code,
                 };
-                v3::RefOr::Item(v3::SecurityScheme::OAuth2 { flows, description })
+                v3::RefOr::Item(v3::SecurityScheme::OAuth2 { flows, description, extensions: IndexMap::new() })
             }
         }
     }
@@ -0,0 +1,610 @@
+use std::fmt;
+
+use indexmap::{IndexMap, IndexSet};
+
+use crate::*;
+use crate::paths::path_template_params;
+use crate::trace::Phase;
+use crate::util::{escape_pointer_segment, to_json};
+
+/// A violation of the OpenAPI specification found by [OpenAPI::validate].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// A JSON pointer to the offending object, e.g. `#/paths/~1pets~1{id}/get`.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationError {
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl OpenAPI {
+    /// Validates the document against the rules of the specification which the types alone can't enforce.
+    ///
+    /// Returns an empty vec if no problems were found.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let _validate = Phase::start("validate");
+        let mut errors = Vec::new();
+        let phase = Phase::start("validate paths");
+        for path in self.paths.paths.keys() {
+            errors.extend(self.validate_path(path));
+        }
+        phase.finish(errors.len());
+        let phase = Phase::start("validate components");
+        let before = errors.len();
+        for kind in ComponentKind::ALL {
+            for name in self.components.keys(kind) {
+                errors.extend(self.validate_component(kind, name));
+            }
+        }
+        phase.finish(errors.len() - before);
+        let phase = Phase::start("validate document");
+        let before = errors.len();
+        errors.extend(self.validate_document());
+        phase.finish(errors.len() - before);
+        errors
+    }
+
+    /// Runs the checks of [OpenAPI::validate] which concern a single path item.
+    /// Returns an empty vec if the path doesn't exist or is a reference.
+    pub fn validate_path(&self, path: &str) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let Some(item) = self.paths.get(path).and_then(|item| item.as_item()) else { return errors };
+        validate_path_params(self, path, item, &mut errors);
+        validate_duplicate_params(self, path, item, &mut errors);
+        validate_param_styles(self, path, item, &mut errors);
+        crate::cors::validate_cors_path(path, item, &mut errors);
+        crate::sse::validate_sse_path(path, item, &mut errors);
+        crate::grpc::validate_grpc_path(path, item, &mut errors);
+        errors
+    }
+
+    /// Runs the checks of [OpenAPI::validate] which concern a single component.
+    /// Returns an empty vec if the component doesn't exist.
+    pub fn validate_component(&self, kind: ComponentKind, name: &str) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let pointer = kind.reference(name);
+        match self.components.get(kind, name) {
+            Some(ComponentRef::Parameter(RefOr::Item(param))) => validate_param_style(self, param, &pointer, &mut errors),
+            Some(ComponentRef::Response(RefOr::Item(response))) => crate::sse::validate_sse_content(pointer, &response.content, &mut errors),
+            _ => {}
+        }
+        errors
+    }
+
+    /// The checks which span the whole document, such as servers and top level extensions.
+    fn validate_document(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        crate::cors::validate_cors(self, &mut errors);
+        crate::grpc::validate_grpc(self, &mut errors);
+        errors
+    }
+
+    /// Removes duplicate parameters (same name and location) from every path item and operation,
+    /// keeping the first declaration in each list.
+    ///
+    /// Operation parameters which override path item parameters are not duplicates and are kept.
+    pub fn dedupe_parameters(&mut self) {
+        let mut removals: Vec<(String, Option<String>, Vec<usize>)> = Vec::new();
+        for (path, item) in self.paths.iter() {
+            let Some(item) = item.as_item() else { continue };
+            removals.push((path.clone(), None, duplicate_param_indices(self, &item.parameters)));
+            for (method, op) in item.iter() {
+                removals.push((path.clone(), Some(method.to_string()), duplicate_param_indices(self, &op.parameters)));
+            }
+        }
+        for (path, method, indices) in removals {
+            if indices.is_empty() {
+                continue;
+            }
+            let Some(item) = self.paths.get_mut(&path).and_then(|i| i.as_mut()) else { continue };
+            let params = match method {
+                None => &mut item.parameters,
+                Some(method) => match item.iter_mut().find(|(m, _)| *m == method) {
+                    Some((_, op)) => &mut op.parameters,
+                    None => continue,
+                },
+            };
+            let mut i = 0;
+            params.retain(|_| {
+                i += 1;
+                !indices.contains(&(i - 1))
+            });
+        }
+    }
+
+    /// Sets `required: true` on every path parameter, including path parameters in `components`
+    /// which are referenced from a path.
+    pub fn fix_path_param_required(&mut self) {
+        let mut referenced = Vec::new();
+        for (_, item) in self.paths.iter_mut() {
+            let Some(item) = item.as_mut() else { continue };
+            fix_params_required(&mut item.parameters, &mut referenced);
+            for (_, op) in item.iter_mut() {
+                fix_params_required(&mut op.parameters, &mut referenced);
+            }
+        }
+        for name in referenced {
+            if let Some(p) = self.components.parameters.get_mut2(&name) {
+                if let ParameterKind::Path { .. } = p.kind {
+                    p.required = true;
+                }
+            }
+        }
+    }
+}
+
+fn fix_params_required(params: &mut [RefOr<Parameter>], referenced: &mut Vec<String>) {
+    for param in params {
+        match param {
+            RefOr::Item(p) => {
+                if let ParameterKind::Path { .. } = p.kind {
+                    p.required = true;
+                }
+            }
+            RefOr::Reference { .. } => referenced.extend(param.reference_name().map(|s| s.to_string())),
+        }
+    }
+}
+
+pub(crate) fn path_pointer(path: &str) -> String {
+    format!("#/paths/{}", escape_pointer_segment(path))
+}
+
+/// Checks that path parameters are required, and that they match the placeholders of the path template.
+pub(crate) fn validate_path_params(spec: &OpenAPI, path: &str, item: &PathItem, errors: &mut Vec<ValidationError>) {
+    let pointer = path_pointer(path);
+    let check_required = |params: &[RefOr<Parameter>], pointer: &str, errors: &mut Vec<ValidationError>| {
+        for (i, param) in params.iter().enumerate() {
+            let Ok(param) = param.resolve(spec) else { continue };
+            if matches!(param.kind, ParameterKind::Path { .. }) && !param.required {
+                errors.push(ValidationError::new(
+                    format!("{}/parameters/{}", pointer, i),
+                    format!("Path parameter {} must be required.", param.name),
+                ));
+            }
+        }
+    };
+    check_required(&item.parameters, &pointer, errors);
+
+    let placeholders = path_template_params(path);
+    let check_template = |params: Vec<&Parameter>, pointer: &str, errors: &mut Vec<ValidationError>| {
+        let path_params: Vec<&str> = params.iter()
+            .filter(|p| matches!(p.kind, ParameterKind::Path { .. }))
+            .map(|p| p.name.as_str())
+            .collect();
+        for placeholder in &placeholders {
+            if !path_params.contains(placeholder) {
+                errors.push(ValidationError::new(pointer, format!("Path template parameter {} has no matching path parameter.", placeholder)));
+            }
+        }
+        for name in path_params {
+            if !placeholders.contains(&name) {
+                errors.push(ValidationError::new(pointer, format!("Path parameter {} does not appear in the path template.", name)));
+            }
+        }
+    };
+
+    if item.iter().next().is_none() {
+        let params = item.parameters.iter().filter_map(|p| p.resolve(spec).ok()).collect();
+        check_template(params, &pointer, errors);
+    }
+    for (method, op) in item.iter() {
+        let pointer = format!("{}/{}", pointer, method);
+        check_required(&op.parameters, &pointer, errors);
+        check_template(op.effective_parameters(item, spec), &pointer, errors);
+    }
+}
+
+/// Returns the indices of parameters which duplicate (by name and location) an earlier parameter in the list.
+fn duplicate_param_indices(spec: &OpenAPI, params: &[RefOr<Parameter>]) -> Vec<usize> {
+    let mut seen: Vec<(&str, &str)> = Vec::new();
+    let mut duplicates = Vec::new();
+    for (i, param) in params.iter().enumerate() {
+        let Ok(param) = param.resolve(spec) else { continue };
+        let key = (param.name.as_str(), param.kind.location());
+        if seen.contains(&key) {
+            duplicates.push(i);
+        } else {
+            seen.push(key);
+        }
+    }
+    duplicates
+}
+
+/// Checks that neither the path item nor its operations declare the same parameter twice.
+fn validate_duplicate_params(spec: &OpenAPI, path: &str, item: &PathItem, errors: &mut Vec<ValidationError>) {
+    let pointer = path_pointer(path);
+    let mut check = |params: &[RefOr<Parameter>], pointer: &str| {
+        for i in duplicate_param_indices(spec, params) {
+            let param = params[i].resolve(spec).unwrap();
+            errors.push(ValidationError::new(
+                format!("{}/parameters/{}", pointer, i),
+                format!("Duplicate parameter {} in {}.", param.name, param.kind.location()),
+            ));
+        }
+    };
+    check(&item.parameters, &pointer);
+    for (method, op) in item.iter() {
+        check(&op.parameters, &format!("{}/{}", pointer, method));
+    }
+}
+
+/// Checks that a parameter's content has exactly one entry, and that its style suits its schema:
+/// `spaceDelimited` and `pipeDelimited` need an array or object, and `deepObject` an exploded object.
+///
+/// Also reports the members set aside when parsing because [Parameter] can't represent them:
+/// styles which don't belong to the parameter's location, `allowReserved` outside of query
+/// parameters, and `content` next to `schema`. See [INVALID_PARAMETER_MEMBERS].
+fn validate_param_style(spec: &OpenAPI, param: &Parameter, pointer: &str, errors: &mut Vec<ValidationError>) {
+    if let Some(serde_json::Value::Object(invalid)) = param.extensions.get(INVALID_PARAMETER_MEMBERS) {
+        let location = param.kind.location();
+        for (key, value) in invalid {
+            let message = match key.as_str() {
+                "allowReserved" => format!("allowReserved of parameter {} is only allowed in query, not {}.", param.name, location),
+                "style" => {
+                    let style = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                    format!("Style {} of parameter {} is not allowed in {}.", style, param.name, location)
+                }
+                "content" => format!("Parameter {} must have either schema or content, not both.", param.name),
+                _ => format!("Invalid member {} of parameter {}.", key, param.name),
+            };
+            errors.push(ValidationError::new(pointer, message));
+        }
+    }
+    let schema = match &param.format {
+        ParameterSchemaOrContent::Content(content) => {
+            if content.len() != 1 {
+                errors.push(ValidationError::new(pointer, format!("Parameter {} content must have exactly one entry, found {}.", param.name, content.len())));
+            }
+            return;
+        }
+        ParameterSchemaOrContent::Schema(schema) => schema,
+    };
+    let Ok(schema) = Resolve::resolve(schema, spec) else { return };
+    let typ = match &schema.kind {
+        SchemaKind::Type(Type::Array(_)) => Some("array"),
+        SchemaKind::Type(Type::Object(_)) => Some("object"),
+        SchemaKind::Type(_) => Some("primitive"),
+        SchemaKind::Any(a) => a.typ.as_deref().map(|t| if t == "array" || t == "object" { t } else { "primitive" }),
+        _ => None,
+    };
+    let style = param.effective_style();
+    match style {
+        Style::SpaceDelimited | Style::PipeDelimited if typ == Some("primitive") => {
+            errors.push(ValidationError::new(pointer, format!("Style {} of parameter {} requires an array or object schema.", style.as_str(), param.name)));
+        }
+        Style::DeepObject => {
+            if typ.is_some() && typ != Some("object") {
+                errors.push(ValidationError::new(pointer, format!("Style deepObject of parameter {} requires an object schema.", param.name)));
+            }
+            if !param.effective_explode() {
+                errors.push(ValidationError::new(pointer, format!("Style deepObject of parameter {} requires explode.", param.name)));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_param_styles(spec: &OpenAPI, path: &str, item: &PathItem, errors: &mut Vec<ValidationError>) {
+    let pointer = path_pointer(path);
+    let check = |params: &[RefOr<Parameter>], pointer: &str, errors: &mut Vec<ValidationError>| {
+        for (i, param) in params.iter().enumerate() {
+            let Some(param) = param.as_item() else { continue };
+            validate_param_style(spec, param, &format!("{}/parameters/{}", pointer, i), errors);
+        }
+    };
+    check(&item.parameters, &pointer, errors);
+    for (method, op) in item.iter() {
+        check(&op.parameters, &format!("{}/{}", pointer, method), errors);
+    }
+}
+
+/// Wraps an [OpenAPI] document and caches its validation errors per path and component, so
+/// that only the parts changed since the last [IncrementalValidator::revalidate] are validated again.
+///
+/// Changes go through [IncrementalValidator::apply], [IncrementalValidator::path_mut],
+/// [IncrementalValidator::insert_component] and [IncrementalValidator::remove_component], which
+/// mark what they touch. A changed component also marks the components and paths which reference
+/// it, directly or through other components. [IncrementalValidator::spec_mut] allows any change
+/// and marks the whole document. Document level checks are cheap and always re-run.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalValidator {
+    spec: OpenAPI,
+    /// The errors and outgoing references of each path.
+    paths: IndexMap<String, (Vec<ValidationError>, Vec<String>)>,
+    /// The errors and outgoing references of each component.
+    components: IndexMap<(ComponentKind, String), (Vec<ValidationError>, Vec<String>)>,
+    dirty_paths: IndexSet<String>,
+    dirty_components: IndexSet<(ComponentKind, String)>,
+    all_dirty: bool,
+    document: Vec<ValidationError>,
+}
+
+impl IncrementalValidator {
+    /// Validates the whole document.
+    pub fn new(spec: OpenAPI) -> Self {
+        let mut validator = IncrementalValidator { spec, all_dirty: true, ..IncrementalValidator::default() };
+        validator.revalidate();
+        validator
+    }
+
+    /// Applies the mutation, marking the paths and components it changes.
+    /// A failed mutation leaves the document unchanged.
+    pub fn apply(&mut self, mutation: Mutation) -> anyhow::Result<()> {
+        let renamed_in = match &mutation {
+            Mutation::RenameOperation { from, .. } => self.spec.operations()
+                .find(|(_, _, op, _)| op.operation_id.as_ref() == Some(from))
+                .map(|(path, ..)| path.to_string()),
+            _ => None,
+        };
+        mutation.apply(&mut self.spec)?;
+        match mutation {
+            Mutation::AddOperation { path, .. } | Mutation::RemoveOperation { path, .. } => self.mark_path(path),
+            Mutation::RenameOperation { .. } => self.dirty_paths.extend(renamed_in),
+            Mutation::SetSchema { name, .. } | Mutation::RemoveSchema { name } => self.mark_component(ComponentKind::Schema, name),
+            Mutation::RenameSchema { from, to } => {
+                self.mark_component(ComponentKind::Schema, from);
+                self.mark_component(ComponentKind::Schema, to);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the path item for editing, marking the path.
+    pub fn path_mut(&mut self, path: &str) -> Option<&mut PathItem> {
+        let item = self.spec.paths.get_mut(path)?.as_mut()?;
+        self.dirty_paths.insert(path.to_string());
+        Some(item)
+    }
+
+    /// Inserts or replaces a component, marking it and its dependents.
+    pub fn insert_component(&mut self, name: impl Into<String>, value: Component) -> Option<Component> {
+        let name = name.into();
+        let kind = value.kind();
+        let previous = self.spec.components.insert(name.clone(), value);
+        self.mark_component(kind, name);
+        previous
+    }
+
+    /// Removes a component, marking it and its dependents.
+    pub fn remove_component(&mut self, kind: ComponentKind, name: &str) -> Option<Component> {
+        let removed = self.spec.components.remove(kind, name)?;
+        self.mark_component(kind, name);
+        Some(removed)
+    }
+
+    /// Returns the document for arbitrary changes, marking all of it.
+    pub fn spec_mut(&mut self) -> &mut OpenAPI {
+        self.all_dirty = true;
+        &mut self.spec
+    }
+
+    fn mark_path(&mut self, path: String) {
+        self.dirty_paths.insert(path);
+    }
+
+    /// Marks the component, and every component and path which references it, transitively.
+    fn mark_component(&mut self, kind: ComponentKind, name: impl Into<String>) {
+        let mut pending = vec![(kind, name.into())];
+        while let Some((kind, name)) = pending.pop() {
+            if !self.dirty_components.insert((kind, name.clone())) {
+                continue;
+            }
+            let reference = kind.reference(&name);
+            for (path, (_, refs)) in &self.paths {
+                if refs.contains(&reference) {
+                    self.dirty_paths.insert(path.clone());
+                }
+            }
+            pending.extend(self.components.iter()
+                .filter(|(_, (_, refs))| refs.contains(&reference))
+                .map(|(key, _)| key.clone()));
+        }
+    }
+
+    /// Marks every path and component of the document, as well as those cached from earlier versions.
+    fn mark_all(&mut self) {
+        let paths = self.paths.keys().cloned().chain(self.spec.paths.paths.keys().cloned());
+        self.dirty_paths.extend(paths.collect::<Vec<_>>());
+        let components: Vec<(ComponentKind, String)> = ComponentKind::ALL.iter()
+            .flat_map(|&kind| self.spec.components.keys(kind).map(move |name| (kind, name.to_string())))
+            .collect();
+        self.dirty_components.extend(self.components.keys().cloned().collect::<Vec<_>>());
+        self.dirty_components.extend(components);
+    }
+
+    /// Validates the marked parts of the document and returns all current errors.
+    pub fn revalidate(&mut self) -> Vec<ValidationError> {
+        if std::mem::take(&mut self.all_dirty) {
+            self.mark_all();
+        }
+        let spec = &self.spec;
+        for path in std::mem::take(&mut self.dirty_paths) {
+            let Some(item) = spec.paths.get(&path) else {
+                self.paths.shift_remove(&path);
+                continue;
+            };
+            let refs = outgoing_refs(&to_json(item));
+            let errors = spec.validate_path(&path);
+            self.paths.insert(path, (errors, refs));
+        }
+        for (kind, name) in std::mem::take(&mut self.dirty_components) {
+            if let Some(component) = spec.components.get(kind, &name) {
+                let refs = outgoing_refs(&component.to_json());
+                let errors = spec.validate_component(kind, &name);
+                self.components.insert((kind, name), (errors, refs));
+            } else {
+                self.components.shift_remove(&(kind, name));
+            }
+        }
+        self.document = spec.validate_document();
+        self.errors()
+    }
+
+    /// The errors found by the last call to [IncrementalValidator::revalidate].
+    pub fn errors(&self) -> Vec<ValidationError> {
+        self.paths.values().flat_map(|(errors, _)| errors)
+            .chain(self.components.values().flat_map(|(errors, _)| errors))
+            .chain(&self.document)
+            .cloned()
+            .collect()
+    }
+
+    pub fn into_inner(self) -> OpenAPI {
+        self.spec
+    }
+}
+
+impl std::ops::Deref for IncrementalValidator {
+    type Target = OpenAPI;
+
+    fn deref(&self) -> &Self::Target {
+        &self.spec
+    }
+}
+
+fn outgoing_refs(value: &serde_json::Value) -> Vec<String> {
+    crate::usage::collect_refs(value).into_iter().map(|(_, r)| r.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_params() {
+        let mut spec = OpenAPI::default();
+        spec.parameters.insert("Id", Parameter::path("id", Schema::new_string()));
+        let mut op = Operation::default();
+        op.parameters.push(RefOr::ref_("#/components/parameters/Id"));
+        op.parameters.push(Parameter::path("extra", Schema::new_string()).into());
+        spec.paths.insert_operation("/pets/{id}/{name}".to_string(), PathMethod::GET, op);
+
+        let errors = spec.validate();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "Path parameter id must be required.",
+            "Path parameter extra must be required.",
+            "Path template parameter name has no matching path parameter.",
+            "Path parameter extra does not appear in the path template.",
+        ]);
+        assert_eq!(errors[0].pointer, "#/paths/~1pets~1{id}~1{name}/get/parameters/0");
+
+        spec.fix_path_param_required();
+        assert_eq!(spec.validate().len(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_params() {
+        let mut spec = OpenAPI::default();
+        spec.parameters.insert("Limit", Parameter::query("limit", Schema::new_integer()));
+        let mut op = Operation::default();
+        op.parameters.push(RefOr::ref_("#/components/parameters/Limit"));
+        op.parameters.push(Parameter::query("limit", Schema::new_string()).into());
+        op.parameters.push(Parameter::header("limit", Schema::new_string()).into());
+        let mut item = PathItem::get(op);
+        item.parameters.push(Parameter::query("limit", Schema::new_integer()).into());
+        spec.paths.insert("/pets".to_string(), item);
+
+        let errors = spec.validate();
+        assert_eq!(errors, vec![ValidationError::new("#/paths/~1pets/get/parameters/1", "Duplicate parameter limit in query.")]);
+
+        spec.dedupe_parameters();
+        assert!(spec.validate().is_empty());
+        let op = spec.paths["/pets"].as_item().unwrap().get.as_ref().unwrap();
+        assert_eq!(op.parameters.len(), 2);
+        assert!(op.parameters[0].as_ref_str().is_some());
+    }
+    #[test]
+    fn test_param_styles() {
+        let mut spec = OpenAPI::default();
+        let mut op = Operation::default();
+        op.parameters.push(Parameter::query("ids", Schema::new_integer()).style(QueryStyle::PipeDelimited).unwrap().into());
+        op.parameters.push(Parameter::query("filter", Schema::new_object()).style(QueryStyle::DeepObject).unwrap().explode(false).into());
+        op.parameters.push(Parameter::query("tags", Schema::new_array(Schema::new_string())).style(QueryStyle::SpaceDelimited).unwrap().into());
+        let mut param = Parameter::query("q", Schema::new_string());
+        param.format = ParameterSchemaOrContent::Content(IndexMap::new());
+        op.parameters.push(param.into());
+        spec.paths.insert("/pets".to_string(), PathItem::get(op));
+
+        let messages: Vec<String> = spec.validate().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec![
+            "Style pipeDelimited of parameter ids requires an array or object schema.",
+            "Style deepObject of parameter filter requires explode.",
+            "Parameter q content must have exactly one entry, found 0.",
+        ]);
+    }
+
+    #[test]
+    fn test_param_invalid_members() {
+        let spec: OpenAPI = serde_yaml::from_str(r#"
+openapi: 3.0.3
+info: {title: t, version: "1"}
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: X-Filter, in: header, style: deepObject, allowReserved: true, schema: {type: object}}
+        - {name: q, in: query, schema: {type: string}, content: {text/plain: {}}}
+      responses: {}
+"#).unwrap();
+        let messages: Vec<String> = spec.validate().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec![
+            "allowReserved of parameter X-Filter is only allowed in query, not header.",
+            "Style deepObject of parameter X-Filter is not allowed in header.",
+            "Parameter q must have either schema or content, not both.",
+        ]);
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut spec = OpenAPI::default();
+        spec.parameters.insert("Id", RefOr::ref_("#/components/parameters/PathId"));
+        spec.parameters.insert("PathId", Parameter::path("id", Schema::new_string()));
+        let mut op = Operation::default();
+        op.parameters.push(RefOr::ref_("#/components/parameters/Id"));
+        spec.paths.insert_operation("/pets/{id}".to_string(), PathMethod::GET, op);
+        spec.paths.insert_operation("/users".to_string(), PathMethod::GET, Operation::default());
+        assert_eq!(spec.validate_path("/pets/{id}").len(), 1);
+        assert!(spec.validate_path("/users").is_empty());
+
+        let mut validator = IncrementalValidator::new(spec);
+        assert_eq!(validator.errors(), validator.validate());
+
+        // Reaches /pets/{id} through the Id parameter.
+        validator.insert_component("PathId", Component::Parameter(Parameter::path_required("id", Schema::new_string()).into()));
+        assert!(validator.revalidate().is_empty());
+
+        let mut op = Operation::default();
+        op.parameters.push(RefOr::ref_("#/components/parameters/Id"));
+        validator.apply(Mutation::AddOperation { path: "/users/{id}".to_string(), method: "GET".to_string(), operation: Box::new(op) }).unwrap();
+        assert!(validator.revalidate().is_empty());
+
+        let params = &mut validator.path_mut("/users/{id}").unwrap().get.as_mut().unwrap().parameters;
+        params.push(Parameter::query("limit", Schema::new_integer()).into());
+        params.push(Parameter::query("limit", Schema::new_integer()).into());
+        assert_eq!(validator.revalidate(), validator.validate());
+        assert_eq!(validator.errors().len(), 1);
+
+        validator.spec_mut().paths.paths.shift_remove("/users/{id}");
+        validator.spec_mut().paths.insert_operation("/orders/{id}".to_string(), PathMethod::GET, Operation::default());
+        assert_eq!(validator.revalidate(), validator.validate());
+        assert_eq!(validator.errors().len(), 1);
+    }
+
+}
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use proptest::prelude::*;
+use proptest::strategy::BoxedStrategy;
+use serde_json::{Map, Value};
+
+use crate::*;
+
+/// Deeper nesting is cut off: arrays become empty and optional properties are left out.
+const MAX_DEPTH: usize = 6;
+/// Bounds for numbers without a minimum or maximum, within which integers are exact as `f64`.
+const DEFAULT_BOUND: i64 = 1 << 53;
+/// The largest number of items, properties or characters generated where no maximum is given.
+const DEFAULT_SIZE: usize = 8;
+
+fn string_format_regex(format: &str) -> Option<&'static str> {
+    Some(match format {
+        "date" => "20[0-9]{2}-(0[1-9]|1[0-2])-(0[1-9]|1[0-9]|2[0-8])",
+        "date-time" => "20[0-9]{2}-(0[1-9]|1[0-2])-(0[1-9]|1[0-9]|2[0-8])T([01][0-9]|2[0-3]):[0-5][0-9]:[0-5][0-9]Z",
+        "uuid" => "[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}",
+        "email" => "[a-z0-9]{1,10}@[a-z0-9]{1,10}\\.(com|org|net)",
+        "uri" | "url" => "https://[a-z0-9]{1,10}\\.(com|org)(/[a-z0-9]{1,8}){0,3}",
+        "byte" => "([A-Za-z0-9+/]{4}){0,4}",
+        "ipv4" => "(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])(\\.(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])){3}",
+        _ => return None,
+    })
+}
+
+fn string_strategy(format: &str, pattern: Option<&str>, min_length: Option<usize>, max_length: Option<usize>) -> BoxedStrategy<Value> {
+    let min = min_length.unwrap_or(0);
+    let regex = pattern.or_else(|| string_format_regex(format));
+    let strategy = match regex.and_then(|r| proptest::string::string_regex(r).ok()) {
+        Some(strategy) => {
+            let max = max_length.unwrap_or(usize::MAX);
+            strategy
+                .prop_filter("string length out of range", move |s| (min..=max).contains(&s.chars().count()))
+                .boxed()
+        }
+        None => proptest::collection::vec(any::<char>(), min..=max_length.unwrap_or(min + DEFAULT_SIZE).max(min))
+            .prop_map(|chars| chars.into_iter().collect())
+            .boxed(),
+    };
+    strategy.prop_map(Value::String).boxed()
+}
+
+fn integer_strategy(n: &IntegerType) -> BoxedStrategy<Value> {
+    let (min, max) = match n.format {
+        VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32) => (i32::MIN as i64, i32::MAX as i64),
+        _ => (-DEFAULT_BOUND, DEFAULT_BOUND),
+    };
+    let min = n.minimum.map(|m| m + n.exclusive_minimum as i64).unwrap_or(min);
+    let max = n.maximum.map(|m| m - n.exclusive_maximum as i64).unwrap_or(max);
+    let step = n.multiple_of.filter(|m| *m > 0).unwrap_or(1);
+    let (low, high) = (min.div_euclid(step) + (min.rem_euclid(step) != 0) as i64, max.div_euclid(step));
+    if low > high {
+        return Just(Value::Null).prop_filter("no integer satisfies the constraints", |_| false).boxed();
+    }
+    (low..=high).prop_map(move |k| Value::from(k * step)).boxed()
+}
+
+fn number_strategy(n: &NumberType) -> BoxedStrategy<Value> {
+    let min = n.minimum.unwrap_or(-DEFAULT_BOUND as f64);
+    let max = n.maximum.unwrap_or(DEFAULT_BOUND as f64).max(min);
+    let (exclusive_min, exclusive_max) = (n.exclusive_minimum, n.exclusive_maximum);
+    let strategy = match n.multiple_of.filter(|m| *m > 0.0) {
+        Some(step) => ((min / step).ceil() as i64..=(max / step).floor() as i64)
+            .prop_map(move |k| k as f64 * step)
+            .boxed(),
+        None => (min..=max).boxed(),
+    };
+    strategy
+        .prop_filter("number on an exclusive bound", move |x| !(exclusive_min && *x == min || exclusive_max && *x == max))
+        .prop_filter_map("number isn't finite", |x| serde_json::Number::from_f64(x).map(Value::Number))
+        .boxed()
+}
+
+/// Filters out arrays with duplicate items when `unique` is set.
+fn array_strategy(items: BoxedStrategy<Value>, min: usize, max: usize, unique: bool) -> BoxedStrategy<Value> {
+    proptest::collection::vec(items, min..=max)
+        .prop_filter("array items are not unique", move |items| !unique || items.iter().enumerate().all(|(i, a)| !items[..i].contains(a)))
+        .prop_map(Value::Array)
+        .boxed()
+}
+
+struct Sampler {
+    spec: Arc<OpenAPI>,
+    /// References being expanded.
+    stack: Vec<String>,
+}
+
+impl Sampler {
+    fn strategy(&mut self, schema: &RefOr<Schema>, depth: usize) -> Option<BoxedStrategy<Value>> {
+        match schema {
+            RefOr::Reference { reference } => {
+                if depth > MAX_DEPTH || self.stack.iter().filter(|r| *r == reference).count() > 1 {
+                    return None;
+                }
+                let resolved = Resolve::resolve(schema, &self.spec).ok()?.clone();
+                self.stack.push(reference.clone());
+                let strategy = self.item_strategy(&resolved, depth);
+                self.stack.pop();
+                strategy
+            }
+            RefOr::Item(schema) => self.item_strategy(schema, depth),
+        }
+    }
+
+    /// Returns `None` if values can only be generated by nesting too deep.
+    fn item_strategy(&mut self, schema: &Schema, depth: usize) -> Option<BoxedStrategy<Value>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let strategy = self.kind_strategy(schema, depth)?;
+        if schema.nullable {
+            return Some(prop_oneof![1 => Just(Value::Null), 4 => strategy].boxed());
+        }
+        Some(strategy)
+    }
+
+    fn kind_strategy(&mut self, schema: &Schema, depth: usize) -> Option<BoxedStrategy<Value>> {
+        let select = |values: Vec<Value>| Some(proptest::sample::select(values).boxed());
+        Some(match &schema.kind {
+            SchemaKind::Type(Type::String(s)) if !s.enumeration.is_empty() => return select(s.enumeration.iter().map(|e| Value::from(e.as_str())).collect()),
+            SchemaKind::Type(Type::String(s)) => string_strategy(s.format.as_str(), s.pattern.as_deref(), s.min_length, s.max_length),
+            SchemaKind::Type(Type::Integer(n)) if !n.enumeration.is_empty() => return select(n.enumeration.iter().map(|e| e.map(Value::from).unwrap_or_default()).collect()),
+            SchemaKind::Type(Type::Integer(n)) => integer_strategy(n),
+            SchemaKind::Type(Type::Number(n)) if !n.enumeration.is_empty() => return select(n.enumeration.iter().map(|e| e.map(Value::from).unwrap_or_default()).collect()),
+            SchemaKind::Type(Type::Number(n)) => number_strategy(n),
+            SchemaKind::Type(Type::Boolean {}) => any::<bool>().prop_map(Value::Bool).boxed(),
+            SchemaKind::Type(Type::Array(a)) => {
+                let min = a.min_items.unwrap_or(0);
+                let max = a.max_items.unwrap_or(min + DEFAULT_SIZE / 2).max(min);
+                match a.items.as_deref().map(|items| self.strategy(items, depth + 1)) {
+                    Some(Some(items)) => array_strategy(items, min, max, a.unique_items),
+                    Some(None) if min == 0 => Just(Value::Array(Vec::new())).boxed(),
+                    Some(None) => return None,
+                    None => array_strategy(any_json(), min, max, a.unique_items),
+                }
+            }
+            SchemaKind::Type(Type::Object(o)) => self.object_strategy(&o.properties, &o.required, o.additional_properties.as_ref(), o.min_properties, o.max_properties, depth)?,
+            SchemaKind::OneOf { one_of: options } | SchemaKind::AnyOf { any_of: options } => {
+                let branches: Vec<BoxedStrategy<Value>> = options.iter().filter_map(|o| self.strategy(o, depth + 1)).collect();
+                if branches.is_empty() {
+                    return None;
+                }
+                let strategy = proptest::strategy::Union::new(branches).boxed();
+                if matches!(schema.kind, SchemaKind::OneOf { .. }) {
+                    self.conforming(strategy, schema)
+                } else {
+                    strategy
+                }
+            }
+            SchemaKind::AllOf { all_of } => {
+                // Merge the parts, resolved one level, so nested references stay references.
+                let spec = self.spec.clone();
+                let mut parts = all_of.iter().map(|part| Resolve::resolve(part, &spec));
+                let first = parts.next()?.ok()?.clone();
+                let merged = parts.try_fold(first, |merged, part| merged.intersect(part?, &spec)).ok()?;
+                match &merged.kind {
+                    SchemaKind::AllOf { all_of } => {
+                        let first = self.strategy(all_of.first()?, depth + 1)?;
+                        self.conforming(first, schema)
+                    }
+                    _ => self.kind_strategy(&merged, depth)?,
+                }
+            }
+            SchemaKind::Not { .. } => self.conforming(any_json(), schema),
+            SchemaKind::Any(a) if !a.enumeration.is_empty() => return select(a.enumeration.clone()),
+            SchemaKind::Any(a) => {
+                let strategy = match a.typ.as_deref() {
+                    Some("string") => string_strategy(a.format.as_deref().unwrap_or_default(), a.pattern.as_deref(), a.min_length, a.max_length),
+                    Some("boolean") => any::<bool>().prop_map(Value::Bool).boxed(),
+                    Some("object") => self.object_strategy(&a.properties, &a.required, a.additional_properties.as_ref(), a.min_properties, a.max_properties, depth)?,
+                    _ => any_json(),
+                };
+                self.conforming(strategy, schema)
+            }
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn object_strategy(
+        &mut self,
+        properties: &RefOrMap<Schema>,
+        required: &[String],
+        additional: Option<&AdditionalProperties>,
+        min_properties: Option<usize>,
+        max_properties: Option<usize>,
+        depth: usize,
+    ) -> Option<BoxedStrategy<Value>> {
+        let mut fields: Vec<BoxedStrategy<Option<(String, Value)>>> = Vec::new();
+        for (name, property) in properties.iter() {
+            let strategy = self.strategy(property, depth + 1);
+            let name = name.clone();
+            fields.push(match strategy {
+                Some(strategy) if required.contains(&name) => strategy.prop_map(move |v| Some((name.clone(), v))).boxed(),
+                Some(strategy) => proptest::option::of(strategy.prop_map(move |v| (name.clone(), v))).boxed(),
+                None if required.contains(&name) => return None,
+                None => Just(None).boxed(),
+            });
+        }
+        let extra = match additional {
+            Some(AdditionalProperties::Schema(schema)) => self.strategy(schema, depth + 1),
+            _ => None,
+        };
+        let known: Vec<String> = properties.keys().cloned().collect();
+        let room = max_properties.map_or(DEFAULT_SIZE / 2, |max| max.saturating_sub(required.len()).min(DEFAULT_SIZE / 2));
+        let extra = match extra {
+            Some(values) => proptest::collection::btree_map("[a-z]{1,8}", values, 0..=room)
+                .prop_map(move |map| map.into_iter().filter(|(k, _)| !known.contains(k)).collect::<Vec<_>>())
+                .boxed(),
+            None => Just(Vec::new()).boxed(),
+        };
+        let (min, max) = (min_properties.unwrap_or(0), max_properties.unwrap_or(usize::MAX));
+        Some((fields, extra)
+            .prop_map(|(fields, extra)| Value::Object(fields.into_iter().flatten().chain(extra).collect::<Map<String, Value>>()))
+            .prop_filter("property count out of range", move |v| (min..=max).contains(&v.as_object().map(|m| m.len()).unwrap_or_default()))
+            .boxed())
+    }
+
+    /// Keeps only values which the schema accepts.
+    fn conforming(&self, strategy: BoxedStrategy<Value>, schema: &Schema) -> BoxedStrategy<Value> {
+        let (spec, schema) = (self.spec.clone(), schema.clone());
+        strategy
+            .prop_filter("value doesn't conform to the schema", move |v| schema.validate_value(v, &spec).is_empty())
+            .boxed()
+    }
+}
+
+/// Arbitrary JSON, nested a few levels.
+fn any_json() -> BoxedStrategy<Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        (-1000i64..1000).prop_map(Value::from),
+        "[a-z]{0,8}".prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 16, 4, |inner| prop_oneof![
+        proptest::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+        proptest::collection::btree_map("[a-z]{1,4}", inner, 0..4).prop_map(|m| Value::Object(m.into_iter().collect())),
+    ]).boxed()
+}
+
+impl Schema {
+    /// A proptest strategy generating values which conform to the schema, for property-based
+    /// testing of handlers. Shrinks towards smaller values, as proptest strategies do.
+    ///
+    /// Respects types, enums, `nullable`, numeric bounds and `multipleOf`, lengths and counts,
+    /// `pattern` and common string formats, required and additional properties, and the
+    /// combinators; `oneOf`, `not` and untyped schemas filter candidates with [Schema::validate_value].
+    /// Recursive references are expanded a few levels, after which optional properties are
+    /// left out and arrays are empty.
+    #[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+    pub fn value_strategy(&self, spec: &OpenAPI) -> BoxedStrategy<Value> {
+        let mut sampler = Sampler { spec: Arc::new(spec.clone()), stack: Vec::new() };
+        sampler.item_strategy(self, 0)
+            .unwrap_or_else(|| Just(Value::Null).prop_filter("schema nests too deep to sample", |_| false).boxed())
+    }
+}
+
+impl RefOr<Schema> {
+    /// Resolves the reference and returns a strategy for its values. See [Schema::value_strategy].
+    #[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+    pub fn value_strategy(&self, spec: &OpenAPI) -> BoxedStrategy<Value> {
+        let mut sampler = Sampler { spec: Arc::new(spec.clone()), stack: Vec::new() };
+        sampler.strategy(self, 0)
+            .unwrap_or_else(|| Just(Value::Null).prop_filter("schema nests too deep to sample", |_| false).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::test_runner::TestRunner;
+
+    use super::*;
+
+    #[test]
+    fn test_value_strategy() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name, kind]
+      properties:
+        id: {type: integer, minimum: 1, maximum: 100, multipleOf: 3}
+        name: {type: string, minLength: 2, maxLength: 5}
+        kind: {type: string, enum: [cat, dog]}
+        weight: {type: number, minimum: 0, exclusiveMinimum: true, maximum: 10, nullable: true}
+        tags: {type: array, items: {type: string, format: uuid}, maxItems: 3, uniqueItems: true}
+        owner: {$ref: "#/components/schemas/Owner"}
+        extra: {type: object, additionalProperties: {type: boolean}, maxProperties: 2}
+        shape:
+          oneOf:
+            - {type: string}
+            - {type: integer}
+    Owner:
+      allOf:
+        - type: object
+          required: [pets]
+          properties:
+            pets: {type: array, items: {$ref: "#/components/schemas/Pet"}}
+        - type: object
+          properties:
+            email: {type: string, format: email}
+"##).unwrap();
+        let schema = RefOr::schema_ref("Pet");
+        let mut runner = TestRunner::deterministic();
+        runner.run(&schema.value_strategy(&spec), |value| {
+            let errors = schema.validate_value(&value, &spec);
+            prop_assert!(errors.is_empty(), "{} doesn't conform: {:?}", value, errors);
+            Ok(())
+        }).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+use serde_json::Value;
+
+use crate::*;
+use crate::util::escape_pointer_segment;
+
+/// Deeper nesting is reported as an error rather than followed, to guard against `allOf` cycles.
+const MAX_DEPTH: usize = 64;
+
+struct ValueValidator<'a> {
+    spec: &'a OpenAPI,
+    errors: Vec<ValidationError>,
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_integer(value: &Value) -> bool {
+    match value {
+        Value::Number(n) => n.is_i64() || n.is_u64() || n.as_f64().map(|f| f.fract() == 0.0).unwrap_or(false),
+        _ => false,
+    }
+}
+
+// `usize::is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+fn is_base64(s: &str) -> bool {
+    let data = s.trim_end_matches('=');
+    s.len() % 4 == 0
+        && s.len() - data.len() <= 2
+        && data.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+}
+
+impl<'a> ValueValidator<'a> {
+    fn error(&mut self, pointer: &str, message: String) {
+        self.errors.push(ValidationError::new(pointer, message));
+    }
+
+    /// Validates without recording errors, for `oneOf`, `anyOf` and `not`.
+    fn matches(&self, schema: &RefOr<Schema>, value: &Value, depth: usize) -> bool {
+        let mut inner = ValueValidator { spec: self.spec, errors: Vec::new() };
+        inner.validate_ref(schema, value, "#", depth);
+        inner.errors.is_empty()
+    }
+
+    fn validate_ref(&mut self, schema: &RefOr<Schema>, value: &Value, pointer: &str, depth: usize) {
+        match Resolve::resolve(schema, self.spec) {
+            Ok(schema) => self.validate(schema, value, pointer, depth),
+            Err(e) => self.error(pointer, e.to_string()),
+        }
+    }
+
+    fn validate(&mut self, schema: &Schema, value: &Value, pointer: &str, depth: usize) {
+        if depth > MAX_DEPTH {
+            return self.error(pointer, "Schema nesting is too deep".to_string());
+        }
+        if value.is_null() && schema.nullable {
+            return;
+        }
+        match &schema.kind {
+            SchemaKind::Type(Type::String(s)) => {
+                let Some(string) = value.as_str() else { return self.type_error(pointer, "string", value) };
+                let len = string.chars().count();
+                if !s.enumeration.is_empty() && !s.enumeration.iter().any(|e| e == string) {
+                    self.error(pointer, format!("{:?} is not one of the allowed values", string));
+                }
+                self.check_length(pointer, len, s.min_length, s.max_length);
+                if s.format == VariantOrUnknownOrEmpty::Item(StringFormat::Byte) && !is_base64(string) {
+                    self.error(pointer, "String is not valid base64".to_string());
+                }
+            }
+            SchemaKind::Type(Type::Number(n)) => {
+                let Some(number) = value.as_f64() else { return self.type_error(pointer, "number", value) };
+                if !n.enumeration.is_empty() && !n.enumeration.contains(&Some(number)) {
+                    self.error(pointer, format!("{} is not one of the allowed values", number));
+                }
+                self.check_range(pointer, number, n.minimum, n.exclusive_minimum, n.maximum, n.exclusive_maximum);
+                if let Some(m) = n.multiple_of {
+                    if (number / m).fract() != 0.0 {
+                        self.error(pointer, format!("{} is not a multiple of {}", number, m));
+                    }
+                }
+            }
+            SchemaKind::Type(Type::Integer(n)) => {
+                if !is_integer(value) {
+                    return self.type_error(pointer, "integer", value);
+                }
+                let number = value.as_f64().unwrap_or_default();
+                if !n.enumeration.is_empty() && !n.enumeration.iter().any(|e| e.map(|e| e as f64) == Some(number)) {
+                    self.error(pointer, format!("{} is not one of the allowed values", value));
+                }
+                self.check_range(pointer, number, n.minimum.map(|m| m as f64), n.exclusive_minimum, n.maximum.map(|m| m as f64), n.exclusive_maximum);
+                if let Some(m) = n.multiple_of {
+                    if m != 0 && number % m as f64 != 0.0 {
+                        self.error(pointer, format!("{} is not a multiple of {}", value, m));
+                    }
+                }
+            }
+            SchemaKind::Type(Type::Boolean {}) => {
+                if !value.is_boolean() {
+                    self.type_error(pointer, "boolean", value);
+                }
+            }
+            SchemaKind::Type(Type::Object(o)) => {
+                if !value.is_object() {
+                    return self.type_error(pointer, "object", value);
+                }
+                self.validate_object(&o.properties, &o.required, o.additional_properties.as_ref(), value, pointer, depth);
+                let len = value.as_object().map(|m| m.len()).unwrap_or_default();
+                self.check_count(pointer, "properties", len, o.min_properties, o.max_properties);
+            }
+            SchemaKind::Type(Type::Array(a)) => {
+                let Some(items) = value.as_array() else { return self.type_error(pointer, "array", value) };
+                self.validate_array(a.items.as_deref(), a.unique_items, items, pointer, depth);
+                self.check_count(pointer, "items", items.len(), a.min_items, a.max_items);
+            }
+            SchemaKind::OneOf { one_of } => {
+                let matching = one_of.iter().filter(|s| self.matches(s, value, depth + 1)).count();
+                if matching != 1 {
+                    self.error(pointer, format!("Value matches {} of the oneOf schemas, expected exactly 1", matching));
+                }
+            }
+            SchemaKind::AnyOf { any_of } => {
+                if !any_of.iter().any(|s| self.matches(s, value, depth + 1)) {
+                    self.error(pointer, "Value matches none of the anyOf schemas".to_string());
+                }
+            }
+            SchemaKind::AllOf { all_of } => {
+                for schema in all_of {
+                    self.validate_ref(schema, value, pointer, depth + 1);
+                }
+            }
+            SchemaKind::Not { not } => {
+                if self.matches(not, value, depth + 1) {
+                    self.error(pointer, "Value matches the schema in not".to_string());
+                }
+            }
+            SchemaKind::Any(a) => self.validate_any(a, value, pointer, depth),
+        }
+    }
+
+    fn validate_any(&mut self, a: &AnySchema, value: &Value, pointer: &str, depth: usize) {
+        if let Some(typ) = &a.typ {
+            let actual = type_name(value);
+            let ok = typ == actual || (typ == "number" && actual == "integer") || (typ == "integer" && is_integer(value));
+            if !ok {
+                return self.type_error(pointer, typ, value);
+            }
+        }
+        if !a.enumeration.is_empty() && !a.enumeration.contains(value) {
+            self.error(pointer, format!("{} is not one of the allowed values", value));
+        }
+        match value {
+            Value::Object(_) => self.validate_object(&a.properties, &a.required, a.additional_properties.as_ref(), value, pointer, depth),
+            Value::Array(items) => self.validate_array(a.items.as_deref(), a.unique_items.unwrap_or(false), items, pointer, depth),
+            Value::String(s) => self.check_length(pointer, s.chars().count(), a.min_length, a.max_length),
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                self.check_range(pointer, n, a.minimum, a.exclusive_minimum.unwrap_or(false), a.maximum, a.exclusive_maximum.unwrap_or(false));
+            }
+            _ => {}
+        }
+        for schema in &a.all_of {
+            self.validate_ref(schema, value, pointer, depth + 1);
+        }
+        if !a.any_of.is_empty() && !a.any_of.iter().any(|s| self.matches(s, value, depth + 1)) {
+            self.error(pointer, "Value matches none of the anyOf schemas".to_string());
+        }
+        if !a.one_of.is_empty() {
+            let matching = a.one_of.iter().filter(|s| self.matches(s, value, depth + 1)).count();
+            if matching != 1 {
+                self.error(pointer, format!("Value matches {} of the oneOf schemas, expected exactly 1", matching));
+            }
+        }
+        if let Some(not) = &a.not {
+            if self.matches(not, value, depth + 1) {
+                self.error(pointer, "Value matches the schema in not".to_string());
+            }
+        }
+    }
+
+    fn validate_object(&mut self, properties: &RefOrMap<Schema>, required: &[String], additional: Option<&AdditionalProperties>, value: &Value, pointer: &str, depth: usize) {
+        let Some(map) = value.as_object() else { return };
+        for name in required {
+            if !map.contains_key(name) {
+                self.error(pointer, format!("Missing required property {}", name));
+            }
+        }
+        for (key, child) in map {
+            let child_pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+            match (properties.get(key), additional) {
+                (Some(schema), _) => self.validate_ref(schema, child, &child_pointer, depth + 1),
+                (None, Some(AdditionalProperties::Any(false))) => self.error(&child_pointer, format!("Unexpected property {}", key)),
+                (None, Some(AdditionalProperties::Schema(schema))) => self.validate_ref(schema, child, &child_pointer, depth + 1),
+                _ => {}
+            }
+        }
+    }
+
+    fn validate_array(&mut self, item_schema: Option<&RefOr<Schema>>, unique: bool, items: &[Value], pointer: &str, depth: usize) {
+        if let Some(schema) = item_schema {
+            for (i, item) in items.iter().enumerate() {
+                self.validate_ref(schema, item, &format!("{}/{}", pointer, i), depth + 1);
+            }
+        }
+        if unique && items.iter().enumerate().any(|(i, a)| items[..i].contains(a)) {
+            self.error(pointer, "Array items are not unique".to_string());
+        }
+    }
+
+    fn type_error(&mut self, pointer: &str, expected: &str, value: &Value) {
+        self.error(pointer, format!("Expected {}, found {}", expected, type_name(value)));
+    }
+
+    fn check_length(&mut self, pointer: &str, len: usize, min: Option<usize>, max: Option<usize>) {
+        if min.map(|m| len < m).unwrap_or(false) {
+            self.error(pointer, format!("String is shorter than {} characters", min.unwrap_or_default()));
+        }
+        if max.map(|m| len > m).unwrap_or(false) {
+            self.error(pointer, format!("String is longer than {} characters", max.unwrap_or_default()));
+        }
+    }
+
+    fn check_count(&mut self, pointer: &str, what: &str, len: usize, min: Option<usize>, max: Option<usize>) {
+        if min.map(|m| len < m).unwrap_or(false) {
+            self.error(pointer, format!("Fewer than {} {}", min.unwrap_or_default(), what));
+        }
+        if max.map(|m| len > m).unwrap_or(false) {
+            self.error(pointer, format!("More than {} {}", max.unwrap_or_default(), what));
+        }
+    }
+
+    fn check_range(&mut self, pointer: &str, n: f64, min: Option<f64>, exclusive_min: bool, max: Option<f64>, exclusive_max: bool) {
+        if let Some(min) = min {
+            if n < min || (exclusive_min && n == min) {
+                self.error(pointer, format!("{} is less than the minimum of {}", n, min));
+            }
+        }
+        if let Some(max) = max {
+            if n > max || (exclusive_max && n == max) {
+                self.error(pointer, format!("{} is greater than the maximum of {}", n, max));
+            }
+        }
+    }
+}
+
+impl Schema {
+    /// Validates a JSON value against this schema, resolving references against `spec`.
+    /// Error pointers point into the value, e.g. `#/pets/0/name`.
+    ///
+    /// Covers types, `nullable`, `enum`, numeric ranges, lengths and counts, object properties,
+    /// array items, and the `oneOf`/`anyOf`/`allOf`/`not` combinators. `pattern` is not checked,
+    /// and of the formats only `byte`, which must be base64.
+    pub fn validate_value(&self, value: &Value, spec: &OpenAPI) -> Vec<ValidationError> {
+        let mut validator = ValueValidator { spec, errors: Vec::new() };
+        validator.validate(self, value, "#", 0);
+        validator.errors
+    }
+}
+
+impl RefOr<Schema> {
+    /// Resolves the schema and validates a value against it. See [Schema::validate_value].
+    pub fn validate_value(&self, value: &Value, spec: &OpenAPI) -> Vec<ValidationError> {
+        let mut validator = ValueValidator { spec, errors: Vec::new() };
+        validator.validate_ref(self, value, "#", 0);
+        validator.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_value() {
+        let spec: OpenAPI = serde_yaml::from_str(r##"
+openapi: 3.0.0
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      additionalProperties: false
+      properties:
+        name: {type: string, minLength: 1}
+        age: {type: integer, minimum: 0}
+        kind: {type: string, enum: [cat, dog]}
+        owner: {type: string, nullable: true}
+    Pets:
+      type: array
+      items: {$ref: "#/components/schemas/Pet"}
+      maxItems: 2
+"##).unwrap();
+        let pets = RefOr::schema_ref("Pets");
+        assert!(pets.validate_value(&json!([{"name": "Tom", "age": 3, "owner": null}]), &spec).is_empty());
+
+        let errors = pets.validate_value(&json!([{"age": -1.5, "kind": "cow", "color": "red"}, {"name": ""}, {"name": "c"}]), &spec);
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, vec![
+            "#/0: Missing required property name",
+            "#/0/age: Expected integer, found number",
+            "#/0/color: Unexpected property color",
+            "#/0/kind: \"cow\" is not one of the allowed values",
+            "#/1/name: String is shorter than 1 characters",
+            "#: More than 2 items",
+        ]);
+
+        let one_of = Schema::new_one_of(vec![Schema::new_string().into(), Schema::new_integer().into()]);
+        assert!(one_of.validate_value(&json!(1), &spec).is_empty());
+        assert_eq!(one_of.validate_value(&json!(true), &spec)[0].message, "Value matches 0 of the oneOf schemas, expected exactly 1");
+
+        let mut bytes = Schema::new_string();
+        if let SchemaKind::Type(Type::String(s)) = &mut bytes.kind {
+            s.format = VariantOrUnknownOrEmpty::Item(StringFormat::Byte);
+        }
+        assert!(bytes.validate_value(&json!("aGVsbG8="), &spec).is_empty());
+        assert!(!bytes.validate_value(&json!("hello!"), &spec).is_empty());
+    }
+}
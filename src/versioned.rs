@@ -1,13 +1,38 @@
 use crate as v3;
-use serde::{Deserialize, Serialize};
+use crate::util::{is_data_field, is_name_map_key};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
-#[serde(untagged)]
+/// The extension holding the members of a 3.1 object which the 3.0 model has no field for.
+pub const OAS31_PASS_THROUGH: &str = "x-oas31";
+
+/// Members of 3.1 objects without a 3.0 equivalent: JSON Schema 2020-12 keywords,
+/// `license.identifier` and `components.pathItems`.
+const OAS31_ONLY: [&str; 29] = [
+    "$id", "$schema", "$anchor", "$dynamicAnchor", "$dynamicRef", "$defs", "$comment", "$vocabulary",
+    "const", "contains", "minContains", "maxContains", "prefixItems", "if", "then", "else",
+    "dependentSchemas", "dependentRequired", "patternProperties", "propertyNames",
+    "unevaluatedItems", "unevaluatedProperties", "contentEncoding", "contentMediaType", "contentSchema",
+    "identifier", "pathItems", "examples", "type",
+];
+
+/// A document of any supported OpenAPI version, picked by its `swagger` or `openapi` field.
+///
+/// OpenAPI 3.1 documents use the same model as 3.0 ones, which holds the 3.1 additions
+/// (`webhooks`, `jsonSchemaDialect`, `info.summary`). When reading them, Schema Objects are
+/// mapped to 3.0 where there is an equivalent: `type: [T, 'null']` becomes `nullable`, and a
+/// numeric `exclusiveMinimum`/`exclusiveMaximum` becomes a `minimum`/`maximum` with the boolean
+/// flag. Members without an equivalent, such as `const`, `$defs` or `components.pathItems`,
+/// are kept in an [OAS31_PASS_THROUGH] extension of their object. Serializing a
+/// [VersionedOpenAPI::V31] maps all of these back.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub enum VersionedOpenAPI {
     #[cfg(feature = "v2")]
     #[cfg_attr(docsrs, doc(cfg(feature = "v2")))]
     V2(crate::v2::OpenAPI),
     V3(v3::OpenAPI),
+    V31(v3::OpenAPI),
 }
 
 impl VersionedOpenAPI {
@@ -16,7 +41,201 @@ impl VersionedOpenAPI {
         match self {
             #[cfg(feature = "v2")]
             V2(v2) => v2.into(),
-            V3(v3) => v3,
+            V3(v3) | V31(v3) => v3,
+        }
+    }
+
+    /// The `swagger` or `openapi` version of the document.
+    pub fn version(&self) -> &str {
+        match self {
+            #[cfg(feature = "v2")]
+            VersionedOpenAPI::V2(v2) => &v2.swagger,
+            VersionedOpenAPI::V3(v3) | VersionedOpenAPI::V31(v3) => &v3.openapi,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedOpenAPI {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value.get("swagger").is_some() {
+            #[cfg(feature = "v2")]
+            return serde_json::from_value(value).map(VersionedOpenAPI::V2).map_err(de::Error::custom);
+            #[cfg(not(feature = "v2"))]
+            return Err(de::Error::custom("Swagger 2.0 documents need the v2 feature"));
+        }
+        let Some(version) = value.get("openapi").and_then(|v| v.as_str()) else {
+            return Err(de::Error::missing_field("openapi"));
+        };
+        match version.split('.').take(2).collect::<Vec<_>>()[..] {
+            ["3", "0"] => serde_json::from_value(value).map(VersionedOpenAPI::V3).map_err(de::Error::custom),
+            ["3", "1"] => {
+                let mut value = value;
+                if let Value::Object(root) = &mut value {
+                    // Only 3.0 requires `paths`.
+                    root.entry("paths").or_insert_with(|| Value::Object(Map::new()));
+                }
+                walk_structure(&mut value, false, &to_oas30);
+                serde_json::from_value(value).map(VersionedOpenAPI::V31).map_err(de::Error::custom)
+            }
+            _ => Err(de::Error::custom(format!("Unsupported OpenAPI version {}", version))),
+        }
+    }
+}
+
+impl Serialize for VersionedOpenAPI {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            #[cfg(feature = "v2")]
+            VersionedOpenAPI::V2(v2) => v2.serialize(serializer),
+            VersionedOpenAPI::V3(v3) => v3.serialize(serializer),
+            VersionedOpenAPI::V31(v3) => {
+                let mut value = serde_json::to_value(v3).map_err(serde::ser::Error::custom)?;
+                walk_structure(&mut value, false, &to_oas31);
+                value.serialize(serializer)
+            }
+        }
+    }
+}
+
+/// Calls `f` on every object of the document which holds fields rather than names, skipping
+/// data such as examples. Children are visited after their parent.
+pub(crate) fn walk_structure(value: &mut Value, names: bool, f: &impl Fn(&mut Map<String, Value>)) {
+    match value {
+        Value::Object(map) if names => {
+            for child in map.values_mut() {
+                walk_structure(child, false, f);
+            }
+        }
+        Value::Object(map) => {
+            f(map);
+            for (key, child) in map.iter_mut() {
+                if key == OAS31_PASS_THROUGH || is_data_field(key, child) {
+                    continue;
+                }
+                // Security requirements are keyed by scheme name. Operation parameters are a list.
+                let names = (is_name_map_key(key) && !child.is_array()) || key == "security";
+                walk_structure(child, names, f);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                walk_structure(item, names, f);
+            }
         }
+        _ => {}
     }
-}
\ No newline at end of file
+}
+
+/// Maps the members of a 3.1 object to the 3.0 model, moving those without an equivalent into
+/// the [OAS31_PASS_THROUGH] extension.
+fn to_oas30(map: &mut Map<String, Value>) {
+    if let Some(Value::Array(types)) = map.get("type") {
+        let non_null: Vec<&Value> = types.iter().filter(|t| t.as_str() != Some("null")).collect();
+        if let [typ] = non_null[..] {
+            if types.len() == 2 {
+                let typ = typ.clone();
+                map.insert("type".to_string(), typ);
+                map.insert("nullable".to_string(), Value::Bool(true));
+            }
+        }
+    }
+    for (exclusive, bound) in [("exclusiveMinimum", "minimum"), ("exclusiveMaximum", "maximum")] {
+        if let (Some(Value::Number(n)), None) = (map.get(exclusive), map.get(bound)) {
+            let n = Value::Number(n.clone());
+            map.insert(bound.to_string(), n);
+            map.insert(exclusive.to_string(), Value::Bool(true));
+        }
+    }
+    let mut pass_through = Map::new();
+    for key in OAS31_ONLY {
+        let keep = match (key, map.get(key)) {
+            (_, None) => true,
+            // Single types are 3.0 types, and `examples` maps are 3.0 examples.
+            ("type", Some(t)) => t.is_string() && t.as_str() != Some("null"),
+            ("examples", Some(e)) => !e.is_array(),
+            _ => false,
+        };
+        if !keep {
+            pass_through.insert(key.to_string(), map.remove(key).expect("Key is present"));
+        }
+    }
+    for key in ["exclusiveMinimum", "exclusiveMaximum"] {
+        if matches!(map.get(key), Some(Value::Number(_))) {
+            pass_through.insert(key.to_string(), map.remove(key).expect("Key is present"));
+        }
+    }
+    if !pass_through.is_empty() {
+        map.insert(OAS31_PASS_THROUGH.to_string(), Value::Object(pass_through));
+    }
+}
+
+/// Reverses [to_oas30].
+fn to_oas31(map: &mut Map<String, Value>) {
+    if map.get("nullable") == Some(&Value::Bool(true)) {
+        if let Some(Value::String(typ)) = map.get("type") {
+            let types = Value::Array(vec![Value::String(typ.clone()), Value::String("null".to_string())]);
+            map.insert("type".to_string(), types);
+            map.remove("nullable");
+        }
+    }
+    for (exclusive, bound) in [("exclusiveMinimum", "minimum"), ("exclusiveMaximum", "maximum")] {
+        if map.get(exclusive) == Some(&Value::Bool(true)) {
+            if let Some(n) = map.remove(bound) {
+                map.insert(exclusive.to_string(), n);
+            }
+        }
+    }
+    if let Some(Value::Object(pass_through)) = map.remove(OAS31_PASS_THROUGH) {
+        map.extend(pass_through);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_31() {
+        let yaml = r##"
+openapi: 3.1.0
+info:
+  title: Pets
+  summary: All about pets
+  version: 1.0.0
+jsonSchemaDialect: https://spec.openapis.org/oas/3.1/dialect/base
+webhooks:
+  newPet:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "200":
+          description: OK
+components:
+  schemas:
+    Pet:
+      type: object
+"##;
+        let versioned: VersionedOpenAPI = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(versioned.version(), "3.1.0");
+        let VersionedOpenAPI::V31(spec) = &versioned else { panic!("Expected a 3.1 document") };
+        assert_eq!(spec.info.summary.as_deref(), Some("All about pets"));
+        assert_eq!(spec.json_schema_dialect.as_deref(), Some("https://spec.openapis.org/oas/3.1/dialect/base"));
+        let ops = spec.callable_operations();
+        assert_eq!(ops[0].origin, v3::OperationOrigin::Webhook { name: "newPet" });
+        assert_eq!(ops[0].pointer, "#/webhooks/newPet/post");
+
+        let json = serde_json::to_value(&versioned).unwrap();
+        assert_eq!(json["webhooks"]["newPet"]["post"]["responses"]["200"]["description"], "OK");
+        assert_eq!(json["jsonSchemaDialect"], "https://spec.openapis.org/oas/3.1/dialect/base");
+        assert_eq!(serde_json::from_value::<VersionedOpenAPI>(json).unwrap(), versioned);
+
+        let v30: VersionedOpenAPI = serde_json::from_str(r#"{"openapi": "3.0.3", "info": {"title": "t", "version": "1"}, "paths": {}}"#).unwrap();
+        assert!(matches!(v30, VersionedOpenAPI::V3(_)));
+        assert!(serde_json::from_str::<VersionedOpenAPI>(r#"{"openapi": "4.0.0", "info": {"title": "t", "version": "1"}}"#).is_err());
+    }
+}
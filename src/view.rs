@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+
+use crate::usage::{visit_security_schemes, References};
+use crate::*;
+
+/// A borrowed subset of a spec: some of its operations and the components they need.
+///
+/// Creating a view clones nothing from the spec. Use [SpecView::to_owned] to produce a
+/// standalone document.
+#[derive(Debug)]
+pub struct SpecView<'a> {
+    spec: &'a OpenAPI,
+    /// Selected operations, as (path, lowercase method).
+    operations: HashSet<(&'a str, &'a str)>,
+    components: HashSet<(ComponentKind, String)>,
+}
+
+impl<'a> SpecView<'a> {
+    fn new(spec: &'a OpenAPI, operations: HashSet<(&'a str, &'a str)>) -> Self {
+        let mut view = SpecView { spec, operations, components: HashSet::new() };
+        view.components = view.reachable_components();
+        view
+    }
+
+    /// Follows references from the selected operations through the typed model, copying nothing.
+    fn reachable_components(&self) -> HashSet<(ComponentKind, String)> {
+        let mut roots = Vec::new();
+        let mut visit = |kind, name: &str| roots.push((kind, name.to_string()));
+        let mut items_seen = HashSet::new();
+        for (path, _, op, item) in self.operations() {
+            op.references(&mut visit);
+            if items_seen.insert(path) {
+                item.parameters.references(&mut visit);
+            }
+        }
+        self.spec.webhooks.references(&mut visit);
+        visit_security_schemes(&self.spec.security, &mut visit);
+        self.spec.reachable_from(roots)
+    }
+
+    /// The spec this view borrows from.
+    pub fn spec(&self) -> &'a OpenAPI {
+        self.spec
+    }
+
+    /// The selected operations, in document order, as (path, method, operation, path item).
+    pub fn operations(&self) -> impl Iterator<Item=(&'a str, &'a str, &'a Operation, &'a PathItem)> + '_ {
+        self.spec.operations().filter(move |(path, method, _, _)| self.operations.contains(&(*path, *method)))
+    }
+
+    /// Returns true if the component is used by the selected operations.
+    pub fn contains_component(&self, kind: ComponentKind, name: &str) -> bool {
+        self.components.contains(&(kind, name.to_string()))
+    }
+
+    /// The components used by the selected operations, in document order.
+    pub fn components(&self) -> impl Iterator<Item=(ComponentKind, &'a str, ComponentRef<'a>)> + '_ {
+        let components = &self.spec.components;
+        ComponentKind::ALL.iter().flat_map(move |kind| {
+            components.keys(*kind)
+                .filter(move |name| self.contains_component(*kind, name))
+                .filter_map(move |name| Some((*kind, name.as_str(), components.get(*kind, name)?)))
+        })
+    }
+
+    /// The schemas used by the selected operations, in document order.
+    pub fn schemas(&self) -> impl Iterator<Item=(&'a str, &'a RefOr<Schema>)> + '_ {
+        self.spec.schemas.iter()
+            .filter(move |(name, _)| self.contains_component(ComponentKind::Schema, name))
+            .map(|(name, schema)| (name.as_str(), schema))
+    }
+
+    /// Builds a standalone spec containing the selected operations and the components they use.
+    /// Tags are kept if a selected operation uses them. Webhooks are always kept.
+    /// Fails if an operation's method isn't one [PathItem::set] accepts.
+    pub fn to_owned(&self) -> Result<OpenAPI> {
+        let mut paths = Paths::default();
+        for (path, method, op, item) in self.operations() {
+            if !paths.contains_key(path) {
+                paths.insert(path.to_string(), PathItem {
+                    summary: item.summary.clone(),
+                    description: item.description.clone(),
+                    servers: item.servers.clone(),
+                    parameters: item.parameters.clone(),
+                    extensions: item.extensions.clone(),
+                    ..PathItem::default()
+                });
+            }
+            let method = PathMethod::from_bytes(method.to_uppercase().as_bytes())?;
+            if !PathItem::supports_method(&method) {
+                return Err(anyhow!("Unsupported method {} on {}", method, path));
+            }
+            if let Some(RefOr::Item(owned)) = paths.get_mut(path) {
+                owned.set(method, op.clone());
+            }
+        }
+        let mut components = Components {
+            extensions: self.spec.components.extensions.clone(),
+            ..Components::default()
+        };
+        for (_, name, component) in self.components() {
+            components.insert(name, component.cloned());
+        }
+        let tags = self.spec.tags.iter()
+            .filter(|t| self.operations().any(|(_, _, op, _)| op.tags.contains(&t.name)))
+            .cloned()
+            .collect();
+        Ok(OpenAPI {
+            openapi: self.spec.openapi.clone(),
+            info: self.spec.info.clone(),
+            json_schema_dialect: self.spec.json_schema_dialect.clone(),
+            servers: self.spec.servers.clone(),
+            paths,
+            webhooks: self.spec.webhooks.clone(),
+            components,
+            security: self.spec.security.clone(),
+            tags,
+            external_docs: self.spec.external_docs.clone(),
+            extensions: self.spec.extensions.clone(),
+        })
+    }
+}
+
+impl OpenAPI {
+    /// Returns a borrowed view of the operations matching the filter. See [SpecView].
+    pub fn view(&self, filter: &OperationFilter) -> SpecView<'_> {
+        let operations = self.operations()
+            .filter(|(path, method, op, _)| filter.matches(path, method, op))
+            .map(|(path, method, _, _)| (path, method))
+            .collect();
+        SpecView::new(self, operations)
+    }
+
+    /// Returns a borrowed view of the operation with the given operationId.
+    /// Unlike [OpenAPI::extract_operation], this doesn't clone the spec.
+    pub fn view_operation(&self, operation_id: &str) -> Result<SpecView<'_>> {
+        let (path, method, _, _) = self.operations()
+            .find(|(_, _, op, _)| op.operation_id.as_deref() == Some(operation_id))
+            .ok_or_else(|| anyhow!("Operation {} not found.", operation_id))?;
+        Ok(SpecView::new(self, vec![(path, method)].into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view() {
+        let mut spec = OpenAPI::default();
+        let mut pet = Schema::new_object();
+        pet.properties_mut().insert("owner", RefOr::schema_ref("Owner"));
+        spec.schemas.insert("Pet", pet);
+        spec.schemas.insert("Owner", Schema::new_object());
+        spec.schemas.insert("Store", Schema::new_object());
+        let mut list = Operation { operation_id: Some("listPets".to_string()), tags: vec!["pets".to_string()], ..Operation::default() };
+        list.add_response_success_json(Some(RefOr::schema_ref("Pet")));
+        spec.add_operation(PathMethod::GET, "/pets", list).unwrap();
+        let mut store = Operation { operation_id: Some("getStore".to_string()), ..Operation::default() };
+        store.add_response_success_json(Some(RefOr::schema_ref("Store")));
+        spec.add_operation(PathMethod::GET, "/store", store).unwrap();
+
+        let view = spec.view(&OperationFilter::all().tag("pets"));
+        assert_eq!(view.operations().map(|(path, ..)| path).collect::<Vec<_>>(), vec!["/pets"]);
+        assert_eq!(view.schemas().map(|(name, _)| name).collect::<Vec<_>>(), vec!["Pet", "Owner"]);
+        assert!(!view.contains_component(ComponentKind::Schema, "Store"));
+
+        let owned = spec.view_operation("listPets").unwrap().to_owned().unwrap();
+        assert_eq!(owned, spec.extract_operation("listPets").unwrap());
+    }
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "cli")]
+use std::process::Command;
+
+fn openapiv3(args: &[&str]) -> (i32, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_openapiv3"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    (output.status.code().unwrap(), stdout, stderr)
+}
+
+#[test]
+fn test_convert() {
+    let (code, stdout, _) = openapiv3(&["convert", "fixtures/petstore.yaml", "--to", "3.1", "--format", "json"]);
+    assert_eq!(code, 0);
+    let doc: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(doc["openapi"], "3.1.0");
+
+    let (code, stdout, _) = openapiv3(&["convert", "fixtures/petstore-3.1.yaml", "--to", "3.0"]);
+    assert_eq!(code, 0);
+    let doc: serde_yaml::Value = serde_yaml::from_str(&stdout).unwrap();
+    assert_eq!(doc["openapi"], "3.0.3");
+
+    let (code, _, stderr) = openapiv3(&["convert", "fixtures/petstore.yaml", "--to", "4.0"]);
+    assert_eq!(code, 2);
+    assert_eq!(stderr, "error: unsupported version: 4.0\n");
+}
+
+#[test]
+fn test_validate() {
+    let (code, _, _) = openapiv3(&["validate", "fixtures/petstore.yaml"]);
+    assert_eq!(code, 0);
+    let (code, _, stderr) = openapiv3(&["validate", "fixtures/petstore-3.1.yaml"]);
+    assert_eq!(code, 0, "{}", stderr);
+    let (code, stdout, _) = openapiv3(&["stats", "fixtures/petstore-3.1.yaml"]);
+    assert_eq!(code, 0);
+    assert!(!stdout.is_empty());
+    let (code, _, stderr) = openapiv3(&["frobnicate"]);
+    assert_eq!(code, 2);
+    assert!(stderr.starts_with("error: unknown command: frobnicate"));
+}
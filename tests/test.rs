@@ -349,4 +349,27 @@ fn test_nested_refs_circular_panics() {
     let api: OpenAPI = serde_yaml::from_str(s).expect("Could not deserialize file");
     let s: RefOr<Schema> = RefOr::schema_ref("UserId");
     s.resolve(&api);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_openapi_31_roundtrip() {
+    let yaml = include_str!("../fixtures/petstore-3.1.yaml");
+    let versioned: VersionedOpenAPI = serde_yaml::from_str(yaml).unwrap();
+    let VersionedOpenAPI::V31(spec) = &versioned else { panic!("Expected a 3.1 document") };
+    let pet = spec.schemas.get2("Pet").unwrap();
+    let tag = pet.properties().get("tag").unwrap().as_item().unwrap();
+    assert!(tag.nullable);
+    assert!(matches!(tag.kind, SchemaKind::Type(Type::String(_))));
+    let age = pet.properties().get("age").unwrap().as_item().unwrap();
+    let SchemaKind::Type(Type::Integer(age)) = &age.kind else { panic!("Expected an integer") };
+    assert_eq!((age.minimum, age.exclusive_minimum), (Some(0), true));
+    assert_eq!(spec.webhooks.len(), 1);
+
+    let expected: serde_json::Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(serde_json::to_value(&versioned).unwrap(), expected);
+
+    // 3.0 documents still need paths.
+    assert!(serde_json::from_str::<VersionedOpenAPI>(r#"{"openapi": "3.0.3", "info": {"title": "t", "version": "1"}}"#).is_err());
+    let webhooks_only: VersionedOpenAPI = serde_json::from_str(r#"{"openapi": "3.1.0", "info": {"title": "t", "version": "1"}}"#).unwrap();
+    assert!(webhooks_only.upgrade().paths.is_empty());
+}